@@ -0,0 +1,219 @@
+#![feature(test)]
+
+#[cfg(all(test, feature = "cache"))]
+mod benches {
+    extern crate serde_json;
+    extern crate serenity;
+    extern crate test;
+
+    use self::serenity::{
+        cache::{Cache, Settings},
+        model::{
+            event::{
+                GuildCreateEvent,
+                GuildMemberUpdateEvent,
+                MessageCreateEvent,
+                PresenceUpdateEvent,
+            },
+            id::{GuildId, MessageId, UserId},
+        },
+        prelude::RwLock,
+    };
+    use self::test::Bencher;
+    use std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        thread::{self, JoinHandle},
+    };
+
+    const WRITER_THREADS: usize = 4;
+
+    // The number of distinct users the background threads update, so that the
+    // cache does not grow while measuring.
+    const USERS: u64 = 1000;
+
+    // The updates which the background threads apply to the cache.
+    //
+    // Only messages are stored in independently locked shards, and guilds
+    // behind their own lock, so only their events can be applied while the
+    // cache is locked for reading. Events modifying the cache's users,
+    // presences, or channels - such as presence and member updates - still
+    // lock the whole cache, and are measured to compare against.
+    #[derive(Clone, Copy)]
+    enum Updates {
+        ConcurrentMessages,
+        ExclusiveMessages,
+        Members,
+        Presences,
+    }
+
+    // A cache holding a guild, which is being updated by background threads
+    // until dropped.
+    struct BusyCache {
+        cache: Arc<RwLock<Cache>>,
+        guild_id: GuildId,
+        running: Arc<AtomicBool>,
+        threads: Vec<JoinHandle<()>>,
+    }
+
+    impl BusyCache {
+        fn new(updates: Updates) -> Self {
+            let mut settings = Settings::new();
+            settings.max_messages(100);
+
+            let mut cache = Cache::new_with_settings(settings);
+            let mut guild: GuildCreateEvent = serde_json::from_str(
+                include_str!("../tests/resources/guild_create_1.json"),
+            ).unwrap();
+            let guild_id = guild.guild.id;
+            cache.update(&mut guild);
+
+            let cache = Arc::new(RwLock::new(cache));
+            let running = Arc::new(AtomicBool::new(true));
+
+            let threads = (0..WRITER_THREADS).map(|thread| {
+                let cache = Arc::clone(&cache);
+                let running = Arc::clone(&running);
+                let mut message = message_event();
+                let mut member = member_event(guild_id);
+                let mut presence = presence_event(guild_id);
+
+                thread::spawn(move || {
+                    let mut id = thread as u64 * 1_000_000;
+
+                    while running.load(Ordering::Relaxed) {
+                        id += 1;
+
+                        match updates {
+                            Updates::ConcurrentMessages => {
+                                message.message.id = MessageId(id);
+                                cache.read().update_concurrently(&mut message);
+                            },
+                            Updates::ExclusiveMessages => {
+                                message.message.id = MessageId(id);
+                                cache.write().update(&mut message);
+                            },
+                            Updates::Members => {
+                                member.user.id = UserId(id % USERS);
+                                cache.write().update(&mut member);
+                            },
+                            Updates::Presences => {
+                                presence.presence.user_id = UserId(id % USERS);
+                                cache.write().update(&mut presence);
+                            },
+                        }
+                    }
+                })
+            }).collect();
+
+            BusyCache {
+                cache,
+                guild_id,
+                running,
+                threads,
+            }
+        }
+    }
+
+    impl Drop for BusyCache {
+        fn drop(&mut self) {
+            self.running.store(false, Ordering::Relaxed);
+
+            for thread in self.threads.drain(..) {
+                let _ = thread.join();
+            }
+        }
+    }
+
+    fn message_event() -> MessageCreateEvent {
+        serde_json::from_str(include_str!("../tests/resources/message_create_1.json")).unwrap()
+    }
+
+    fn member_event(guild_id: GuildId) -> GuildMemberUpdateEvent {
+        let mut event: GuildMemberUpdateEvent = serde_json::from_str(
+            include_str!("../tests/resources/guild_member_update_1.json"),
+        ).unwrap();
+        event.guild_id = guild_id;
+
+        event
+    }
+
+    fn presence_event(guild_id: GuildId) -> PresenceUpdateEvent {
+        let mut event: PresenceUpdateEvent = serde_json::from_str(
+            include_str!("../tests/resources/presence_update_rich_1.json"),
+        ).unwrap();
+        event.guild_id = Some(guild_id);
+
+        event
+    }
+
+    fn read_guild(busy: &BusyCache) -> u64 {
+        let cache = busy.cache.read();
+        let guild = cache.guild(busy.guild_id).unwrap();
+        let count = guild.read().member_count;
+
+        count
+    }
+
+    #[bench]
+    fn guild_read_during_exclusive_message_updates(b: &mut Bencher) {
+        let busy = BusyCache::new(Updates::ExclusiveMessages);
+
+        b.iter(|| read_guild(&busy));
+    }
+
+    #[bench]
+    fn guild_read_during_concurrent_message_updates(b: &mut Bencher) {
+        let busy = BusyCache::new(Updates::ConcurrentMessages);
+
+        b.iter(|| read_guild(&busy));
+    }
+
+    #[bench]
+    fn message_create_during_exclusive_message_updates(b: &mut Bencher) {
+        let busy = BusyCache::new(Updates::ExclusiveMessages);
+        let mut event = message_event();
+
+        b.iter(|| busy.cache.write().update(&mut event));
+    }
+
+    #[bench]
+    fn message_create_during_concurrent_message_updates(b: &mut Bencher) {
+        let busy = BusyCache::new(Updates::ConcurrentMessages);
+        let mut event = message_event();
+
+        b.iter(|| busy.cache.read().update_concurrently(&mut event));
+    }
+
+    #[bench]
+    fn guild_read_during_member_updates(b: &mut Bencher) {
+        let busy = BusyCache::new(Updates::Members);
+
+        b.iter(|| read_guild(&busy));
+    }
+
+    #[bench]
+    fn guild_read_during_presence_updates(b: &mut Bencher) {
+        let busy = BusyCache::new(Updates::Presences);
+
+        b.iter(|| read_guild(&busy));
+    }
+
+    #[bench]
+    fn member_update_during_member_updates(b: &mut Bencher) {
+        let busy = BusyCache::new(Updates::Members);
+        let mut event = member_event(busy.guild_id);
+
+        b.iter(|| busy.cache.write().update(&mut event));
+    }
+
+    #[bench]
+    fn presence_update_during_presence_updates(b: &mut Bencher) {
+        let busy = BusyCache::new(Updates::Presences);
+        let mut event = presence_event(busy.guild_id);
+
+        b.iter(|| busy.cache.write().update(&mut event));
+    }
+}
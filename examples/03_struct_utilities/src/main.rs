@@ -10,7 +10,7 @@ use serenity::{
 struct Handler;
 
 impl EventHandler for Handler {
-    fn message(&self, _: Context, msg: Message) {
+    fn message(&self, ctx: Context, msg: Message) {
         if msg.content == "!messageme" {
             // If the `utils`-feature is enabled, then model structs will
             // have a lot of useful methods implemented, to avoid using an
@@ -19,8 +19,9 @@ impl EventHandler for Handler {
             //
             // In this case, you can direct message a User directly by simply
             // calling a method on its instance, with the content of the
-            // message.
-            if let Err(why) = msg.author.dm(|m| m.content("Hello!")) {
+            // message. The context is given so that an already open direct
+            // message channel can be found in its cache.
+            if let Err(why) = msg.author.dm(&ctx, |m| m.content("Hello!")) {
                 println!("Error when direct messaging user: {:?}", why);
             }
         }
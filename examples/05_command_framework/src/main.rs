@@ -262,7 +262,7 @@ command!(commands(ctx, msg, _args) {
 // Repeats what the user passed as argument but ensures that user and role
 // mentions are replaced with a safe textual alternative.
 // In this example channel mentions are excluded via the `ContentSafeOptions`.
-command!(say(_ctx, msg, args) {
+command!(say(ctx, msg, args) {
     let mut settings = if let Some(guild_id) = msg.guild_id {
        // By default roles, users, and channel mentions are cleaned.
        ContentSafeOptions::default()
@@ -278,7 +278,7 @@ command!(say(_ctx, msg, args) {
             .clean_role(false)
     };
 
-    let mut content = content_safe(&args.full(), &settings);
+    let mut content = content_safe(&ctx.cache, &args.full(), &settings);
 
     if let Err(why) = msg.channel_id.say(&content) {
         println!("Error sending message: {:?}", why);
@@ -299,10 +299,10 @@ fn owner_check(_: &mut Context, msg: &Message, _: &mut Args, _: &CommandOptions)
 //
 // This check analyses whether a guild member permissions has
 // administrator-permissions.
-fn admin_check(_: &mut Context, msg: &Message, _: &mut Args, _: &CommandOptions) -> bool {
-    if let Some(member) = msg.member() {
+fn admin_check(ctx: &mut Context, msg: &Message, _: &mut Args, _: &CommandOptions) -> bool {
+    if let Some(member) = msg.member(&ctx.cache) {
 
-        if let Ok(permissions) = member.permissions() {
+        if let Ok(permissions) = member.permissions(&ctx.cache) {
             return permissions.administrator();
         }
     }
@@ -316,10 +316,10 @@ command!(some_long_command(_ctx, msg, args) {
     }
 });
 
-command!(about_role(_ctx, msg, args) {
+command!(about_role(ctx, msg, args) {
     let potential_role_name = args.full();
 
-    if let Some(guild) = msg.guild() {
+    if let Some(guild) = msg.guild(&ctx.cache) {
         // `role_by_name()` allows us to attempt attaining a reference to a role
         // via its name.
         if let Some(role) = guild.read().role_by_name(&potential_role_name) {
@@ -382,7 +382,7 @@ command!(latency(ctx, msg, _args) {
     let shard_manager = match data.get::<ShardManagerContainer>() {
         Some(v) => v,
         None => {
-            let _ = msg.reply("There was a problem getting the shard manager");
+            let _ = msg.reply(ctx, "There was a problem getting the shard manager");
 
             return Ok(());
         },
@@ -397,13 +397,13 @@ command!(latency(ctx, msg, _args) {
     let runner = match runners.get(&ShardId(ctx.shard_id)) {
         Some(runner) => runner,
         None => {
-            let _ = msg.reply("No shard found");
+            let _ = msg.reply(ctx, "No shard found");
 
             return Ok(());
         },
     };
 
-    let _ = msg.reply(&format!("The shard latency is {:?}", runner.latency));
+    let _ = msg.reply(ctx, &format!("The shard latency is {:?}", runner.latency));
 });
 
 command!(ping(_ctx, msg, _args) {
@@ -25,7 +25,7 @@ use serenity::client::bridge::voice::ClientVoiceManager;
 use serenity::{client::{Context}, prelude::Mutex};
 
 use serenity::{
-    client::{CACHE, Client, EventHandler},
+    client::{Client, EventHandler},
     framework::StandardFramework,
     model::{channel::Message, gateway::Ready, misc::Mentionable},
     Result as SerenityResult,
@@ -80,7 +80,7 @@ fn main() {
 }
 
 command!(deafen(ctx, msg) {
-    let guild_id = match CACHE.read().guild_channel(msg.channel_id) {
+    let guild_id = match ctx.cache.read().guild_channel(msg.channel_id) {
         Some(channel) => channel.read().guild_id,
         None => {
             check_msg(msg.channel_id.say("Groups and DMs not supported"));
@@ -95,7 +95,7 @@ command!(deafen(ctx, msg) {
     let handler = match manager.get_mut(guild_id) {
         Some(handler) => handler,
         None => {
-            check_msg(msg.reply("Not in a voice channel"));
+            check_msg(msg.reply(ctx, "Not in a voice channel"));
 
             return Ok(());
         },
@@ -111,7 +111,7 @@ command!(deafen(ctx, msg) {
 });
 
 command!(join(ctx, msg) {
-    let guild = match msg.guild() {
+    let guild = match msg.guild(&ctx.cache) {
         Some(guild) => guild,
         None => {
             check_msg(msg.channel_id.say("Groups and DMs not supported"));
@@ -131,7 +131,7 @@ command!(join(ctx, msg) {
     let connect_to = match channel_id {
         Some(channel) => channel,
         None => {
-            check_msg(msg.reply("Not in a voice channel"));
+            check_msg(msg.reply(ctx, "Not in a voice channel"));
 
             return Ok(());
         }
@@ -148,7 +148,7 @@ command!(join(ctx, msg) {
 });
 
 command!(leave(ctx, msg) {
-    let guild_id = match CACHE.read().guild_channel(msg.channel_id) {
+    let guild_id = match ctx.cache.read().guild_channel(msg.channel_id) {
         Some(channel) => channel.read().guild_id,
         None => {
             check_msg(msg.channel_id.say("Groups and DMs not supported"));
@@ -166,12 +166,12 @@ command!(leave(ctx, msg) {
 
         check_msg(msg.channel_id.say("Left voice channel"));
     } else {
-        check_msg(msg.reply("Not in a voice channel"));
+        check_msg(msg.reply(ctx, "Not in a voice channel"));
     }
 });
 
 command!(mute(ctx, msg) {
-    let guild_id = match CACHE.read().guild_channel(msg.channel_id) {
+    let guild_id = match ctx.cache.read().guild_channel(msg.channel_id) {
         Some(channel) => channel.read().guild_id,
         None => {
             check_msg(msg.channel_id.say("Groups and DMs not supported"));
@@ -186,7 +186,7 @@ command!(mute(ctx, msg) {
     let handler = match manager.get_mut(guild_id) {
         Some(handler) => handler,
         None => {
-            check_msg(msg.reply("Not in a voice channel"));
+            check_msg(msg.reply(ctx, "Not in a voice channel"));
 
             return Ok(());
         },
@@ -221,7 +221,7 @@ command!(play(ctx, msg, args) {
         return Ok(());
     }
 
-    let guild_id = match CACHE.read().guild_channel(msg.channel_id) {
+    let guild_id = match ctx.cache.read().guild_channel(msg.channel_id) {
         Some(channel) => channel.read().guild_id,
         None => {
             check_msg(msg.channel_id.say("Error finding channel info"));
@@ -254,7 +254,7 @@ command!(play(ctx, msg, args) {
 });
 
 command!(undeafen(ctx, msg) {
-    let guild_id = match CACHE.read().guild_channel(msg.channel_id) {
+    let guild_id = match ctx.cache.read().guild_channel(msg.channel_id) {
         Some(channel) => channel.read().guild_id,
         None => {
             check_msg(msg.channel_id.say("Error finding channel info"));
@@ -276,7 +276,7 @@ command!(undeafen(ctx, msg) {
 });
 
 command!(unmute(ctx, msg) {
-    let guild_id = match CACHE.read().guild_channel(msg.channel_id) {
+    let guild_id = match ctx.cache.read().guild_channel(msg.channel_id) {
         Some(channel) => channel.read().guild_id,
         None => {
             check_msg(msg.channel_id.say("Error finding channel info"));
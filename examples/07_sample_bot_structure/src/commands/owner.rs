@@ -1,5 +1,5 @@
 command!(quit(ctx, msg, _args) {
     ctx.quit();
 
-    let _ = msg.reply("Shutting down!");
+    let _ = msg.reply(ctx, "Shutting down!");
 });
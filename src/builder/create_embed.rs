@@ -264,7 +264,7 @@ impl CreateEmbed {
     /// struct Handler;
     ///
     /// impl EventHandler for Handler {
-    ///     fn guild_member_addition(&self, _: Context, guild_id: GuildId, member: Member) {
+    ///     fn guild_member_addition(&self, ctx: Context, guild_id: GuildId, member: Member) {
     ///
     ///         if let Ok(guild) = guild_id.to_partial_guild() {
     ///
@@ -277,7 +277,7 @@ impl CreateEmbed {
     ///             if let Some(channel) = channel_search {
     ///                 let user = member.user.read();
     ///
-    ///                 let _ = channel.send_message(|m| m
+    ///                 let _ = channel.send_message(&ctx, |m| m
     ///                     .embed(|e| {
     ///                         let mut e = e
     ///                             .author(|a| a.icon_url(&user.face()).name(&user.name))
@@ -21,9 +21,9 @@ use utils::VecMap;
 /// struct Handler;
 ///
 /// impl EventHandler for Handler {
-///     fn message(&self, _: Context, msg: Message) {
+///     fn message(&self, ctx: Context, msg: Message) {
 ///         if msg.content == "!createinvite" {
-///             let channel = match msg.channel_id.to_channel() {
+///             let channel = match msg.channel_id.to_channel(&ctx) {
 ///                 Ok(channel) => channel,
 ///                 Err(why) => {
 ///                     let _ = msg.channel_id.say(&format!("Error creating invite: {:?}", why));
@@ -35,7 +35,7 @@ use utils::VecMap;
 ///             if let Channel::Guild(channel) = channel {
 ///                 let channel = channel.read();
 ///
-///             let invite = match channel.create_invite(|i| i.max_age(3600).max_uses(10)) {
+///             let invite = match channel.create_invite(&ctx, |i| i.max_age(3600).max_uses(10)) {
 ///                 Ok(invite) => invite,
 ///                 Err(why) => {
 ///                     println!("Err creating invite: {:?}", why);
@@ -80,23 +80,24 @@ impl CreateInvite {
     /// Create an invite with a max age of `3600` seconds, or 1 hour:
     ///
     /// ```rust,no_run
+    /// # use serenity::model::CacheSource;
     /// # use serenity::model::id::ChannelId;
     /// # use serenity::model::channel::Channel;
     /// # use std::error::Error;
     /// #
-    /// # fn try_main() -> Result<(), Box<Error>> {
-    /// #     let channel = ChannelId(81384788765712384).to_channel().unwrap();
+    /// # fn try_main(cache: &CacheSource) -> Result<(), Box<Error>> {
+    /// #     let channel = ChannelId(81384788765712384).to_channel(cache).unwrap();
     /// #
     /// #     if let Channel::Guild(guild_channel) = channel {
     /// #         let guild_channel = guild_channel.read();
-    /// let invite = guild_channel.create_invite(|i| i.max_age(3600))?;
+    /// let invite = guild_channel.create_invite(cache, |i| i.max_age(3600))?;
     /// #     }
     /// #
     /// #     Ok(())
     /// # }
     /// #
     /// # fn main() {
-    /// #     try_main().unwrap();
+    /// #     try_main(&()).unwrap();
     /// # }
     /// ```
     pub fn max_age(mut self, max_age: u64) -> Self {
@@ -116,22 +117,23 @@ impl CreateInvite {
     /// Create an invite with a max use limit of `5`:
     ///
     /// ```rust,no_run
+    /// # use serenity::model::CacheSource;
     /// # use serenity::model::id::ChannelId;
     /// # use serenity::model::channel::Channel;
     /// # use std::error::Error;
     /// #
-    /// # fn try_main() -> Result<(), Box<Error>> {
-    /// #     let channel = ChannelId(81384788765712384).to_channel().unwrap();
+    /// # fn try_main(cache: &CacheSource) -> Result<(), Box<Error>> {
+    /// #     let channel = ChannelId(81384788765712384).to_channel(cache).unwrap();
     /// #
     /// #       if let Channel::Guild(channel) = channel {
     /// #           let channel = channel.read();
-    /// let invite = channel.create_invite(|i| i.max_uses(5))?;
+    /// let invite = channel.create_invite(cache, |i| i.max_uses(5))?;
     /// #       }
     /// #     Ok(())
     /// # }
     /// #
     /// # fn main() {
-    /// #     try_main().unwrap();
+    /// #     try_main(&()).unwrap();
     /// # }
     /// ```
     pub fn max_uses(mut self, max_uses: u64) -> Self {
@@ -149,24 +151,25 @@ impl CreateInvite {
     /// Create an invite which is temporary:
     ///
     /// ```rust,no_run
+    /// # use serenity::model::CacheSource;
     /// # use serenity::model::id::ChannelId;
     /// # use serenity::model::channel::Channel;
     /// # use std::error::Error;
     /// #
-    /// # fn try_main() -> Result<(), Box<Error>> {
-    /// #     let channel = ChannelId(81384788765712384).to_channel().unwrap();
+    /// # fn try_main(cache: &CacheSource) -> Result<(), Box<Error>> {
+    /// #     let channel = ChannelId(81384788765712384).to_channel(cache).unwrap();
     /// #
     /// #     if let Channel::Guild(channel) = channel {
     /// #         let channel = channel.read();
     /// #
-    /// let invite = channel.create_invite(|i| i.temporary(true))?;
+    /// let invite = channel.create_invite(cache, |i| i.temporary(true))?;
     /// #     }
     /// #
     /// #     Ok(())
     /// # }
     /// #
     /// # fn main() {
-    /// #     try_main().unwrap();
+    /// #     try_main(&()).unwrap();
     /// # }
     /// ```
     pub fn temporary(mut self, temporary: bool) -> Self {
@@ -184,22 +187,23 @@ impl CreateInvite {
     /// Create an invite which is unique:
     ///
     /// ```rust,no_run
+    /// # use serenity::model::CacheSource;
     /// # use serenity::model::id::ChannelId;
     /// # use std::error::Error;
     /// # use serenity::model::channel::Channel;
     /// #
-    /// # fn try_main() -> Result<(), Box<Error>> {
-    /// #     let channel = ChannelId(81384788765712384).to_channel().unwrap();
+    /// # fn try_main(cache: &CacheSource) -> Result<(), Box<Error>> {
+    /// #     let channel = ChannelId(81384788765712384).to_channel(cache).unwrap();
     /// #
     /// # if let Channel::Guild(channel) = channel {
     /// #    let channel = channel.read();
-    /// let invite = channel.create_invite(|i| i.unique(true))?;
+    /// let invite = channel.create_invite(cache, |i| i.unique(true))?;
     /// # }
     /// #     Ok(())
     /// # }
     /// #
     /// # fn main() {
-    /// #     try_main().unwrap();
+    /// #     try_main(&()).unwrap();
     /// # }
     /// ```
     pub fn unique(mut self, unique: bool) -> Self {
@@ -10,12 +10,19 @@ use utils::{self, VecMap};
 /// Editing the content of a [`Message`] to `"hello"`:
 ///
 /// ```rust,no_run
+/// # use serenity::model::CacheSource;
 /// # use serenity::model::id::{ChannelId, MessageId};
 /// #
+/// # fn run(cache: &CacheSource) {
 /// # let mut message = ChannelId(7).message(MessageId(8)).unwrap();
 /// #
 ///
-/// let _ = message.edit(|m| m.content("hello"));
+/// let _ = message.edit(cache, |m| m.content("hello"));
+/// # }
+/// #
+/// # fn main() {
+/// #     run(&());
+/// # }
 /// ```
 ///
 /// [`Message`]: ../model/channel/struct.Message.html
@@ -0,0 +1,522 @@
+use model::prelude::*;
+use parking_lot::RwLock;
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    mem,
+    sync::Arc,
+};
+use super::{
+    index::{ChannelKeys, GuildKeys},
+    insert_bounded,
+    is_full,
+    retain_bounded,
+    Cache,
+};
+
+/// The storage operations which the library's [`CacheUpdate`]
+/// implementations perform when updating a cache with events.
+///
+/// The [`Cache`] - which keeps everything in memory - is the default
+/// implementation. Implementing this trait allows every event to be applied
+/// to another store, such as an embedded key-value store, via
+/// [`CacheUpdate::update`].
+///
+/// Implementations are free to skip storing data, such as to bound their
+/// size, in which case retrievals return `None`.
+///
+/// The operations on guild data, roles, voice states, and messages take a
+/// shared reference, so that the events which only modify them can be applied
+/// through a shared backend via [`ConcurrentCacheUpdate`]. Implementations
+/// need to synchronize them internally.
+///
+/// [`Cache`]: struct.Cache.html
+/// [`CacheUpdate`]: trait.CacheUpdate.html
+/// [`ConcurrentCacheUpdate`]: trait.ConcurrentCacheUpdate.html
+/// [`CacheUpdate::update`]: trait.CacheUpdate.html#tymethod.update
+pub trait CacheBackend {
+    /// Retrieves the current user.
+    fn current_user(&self) -> CurrentUser;
+
+    /// Replaces the current user, returning the previous one.
+    fn set_current_user(&mut self, user: CurrentUser) -> CurrentUser;
+
+    /// Sets the total number of shards in use.
+    fn set_shard_count(&mut self, shard_count: u64);
+
+    /// Retrieves a copy of a guild.
+    fn guild(&self, guild_id: GuildId) -> Option<Guild>;
+
+    /// Inserts a guild along with its channels, members, and roles, returning
+    /// the guild it replaced.
+    fn insert_guild(&mut self, guild: Guild) -> Option<Guild>;
+
+    /// Removes a guild along with its channels and their messages.
+    fn remove_guild(&mut self, guild_id: GuildId) -> Option<Guild>;
+
+    /// Removes a guild which became unavailable.
+    ///
+    /// Unlike [`remove_guild`], the guild's channels and their messages are
+    /// kept, as the guild is only temporarily unavailable during an outage.
+    ///
+    /// [`remove_guild`]: #tymethod.remove_guild
+    fn remove_unavailable_guild(&mut self, guild_id: GuildId) -> Option<Guild>;
+
+    /// Marks whether a guild is unavailable.
+    fn set_guild_unavailable(&mut self, guild_id: GuildId, unavailable: bool);
+
+    /// Retrieves the guilds which were restored from a snapshot and not yet
+    /// received from the gateway.
+    fn stale_guilds(&self) -> Vec<GuildId>;
+
+    /// Marks whether a guild's data is stale.
+    fn set_guild_stale(&mut self, guild_id: GuildId, stale: bool);
+
+    /// Updates the data of a guild which is included in a partial guild, such
+    /// as its name, owner, and roles.
+    ///
+    /// Nothing is updated if the guild is not present.
+    fn update_guild(&self, guild: &PartialGuild);
+
+    /// Replaces the emojis of a guild.
+    ///
+    /// Nothing is updated if the guild is not present.
+    fn set_guild_emojis(&self, guild_id: GuildId, emojis: HashMap<EmojiId, Emoji>);
+
+    /// Adds to the member count of a guild, returning the new count.
+    ///
+    /// Returns `None` if the guild is not present.
+    fn add_member_count(&self, guild_id: GuildId, count: i64) -> Option<u64>;
+
+    /// Retrieves a channel of any kind.
+    fn channel(&self, channel_id: ChannelId) -> Option<Channel>;
+
+    /// Inserts a channel, returning the channel it replaced.
+    ///
+    /// Guild channels are also added to their guild.
+    fn insert_channel(&mut self, channel: Channel) -> Option<Channel>;
+
+    /// Removes a channel along with its messages.
+    ///
+    /// Guild channels are also removed from their guild.
+    fn remove_channel(&mut self, channel_id: ChannelId) -> Option<Channel>;
+
+    /// Retrieves a member of a guild.
+    fn member(&self, guild_id: GuildId, user_id: UserId) -> Option<Member>;
+
+    /// Inserts a member into a guild, returning the member it replaced.
+    ///
+    /// Nothing is inserted if the guild is not present.
+    fn insert_member(&mut self, guild_id: GuildId, member: Member) -> Option<Member>;
+
+    /// Removes a member from a guild.
+    fn remove_member(&mut self, guild_id: GuildId, user_id: UserId) -> Option<Member>;
+
+    /// Retrieves a role of a guild.
+    fn role(&self, guild_id: GuildId, role_id: RoleId) -> Option<Role>;
+
+    /// Inserts a role into a guild, returning the role it replaced.
+    ///
+    /// Nothing is inserted if the guild is not present.
+    fn insert_role(&self, guild_id: GuildId, role: Role) -> Option<Role>;
+
+    /// Removes a role from a guild.
+    fn remove_role(&self, guild_id: GuildId, role_id: RoleId) -> Option<Role>;
+
+    /// Retrieves a copy of a user.
+    fn user(&self, user_id: UserId) -> Option<User>;
+
+    /// Inserts or updates a user, returning the instance which members,
+    /// presences, and channels should hold.
+    ///
+    /// The models hold their users as an `Arc<RwLock<User>>`. Backends which
+    /// don't share one instance between them can return a new one.
+    fn insert_user(&mut self, user: &User) -> Arc<RwLock<User>>;
+
+    /// Retrieves a message of a channel.
+    fn message(&self, channel_id: ChannelId, message_id: MessageId) -> Option<Message>;
+
+    /// Inserts a new message, returning a message that was evicted to make
+    /// room for it.
+    fn insert_message(&self, message: Message) -> Option<Message>;
+
+    /// Replaces an already stored message, returning the message as it was
+    /// before.
+    ///
+    /// Nothing is inserted if the message is not present.
+    fn update_message(&self, message: Message) -> Option<Message>;
+
+    /// Removes a message from a channel.
+    fn remove_message(&self, channel_id: ChannelId, message_id: MessageId) -> Option<Message>;
+
+    /// Retrieves the presence of a user, either in a guild or - for presences
+    /// not tied to a guild - globally.
+    fn presence(&self, guild_id: Option<GuildId>, user_id: UserId) -> Option<Presence>;
+
+    /// Inserts the presence of a user, returning the presence it replaced.
+    fn insert_presence(&mut self, guild_id: Option<GuildId>, presence: Presence) -> Option<Presence>;
+
+    /// Removes the presence of a user.
+    fn remove_presence(&mut self, guild_id: Option<GuildId>, user_id: UserId) -> Option<Presence>;
+
+    /// Inserts the voice state of a user in a guild, returning the voice state
+    /// it replaced.
+    ///
+    /// Nothing is inserted if the guild is not present.
+    fn insert_voice_state(&self, guild_id: GuildId, voice_state: VoiceState) -> Option<VoiceState>;
+
+    /// Removes the voice state of a user in a guild.
+    fn remove_voice_state(&self, guild_id: GuildId, user_id: UserId) -> Option<VoiceState>;
+}
+
+impl CacheBackend for Cache {
+    fn current_user(&self) -> CurrentUser {
+        self.user.clone()
+    }
+
+    fn set_current_user(&mut self, user: CurrentUser) -> CurrentUser {
+        mem::replace(&mut self.user, user)
+    }
+
+    fn set_shard_count(&mut self, shard_count: u64) {
+        self.shard_count = shard_count;
+    }
+
+    fn guild(&self, guild_id: GuildId) -> Option<Guild> {
+        self.guilds.get(&guild_id).map(|guild| guild.read().clone())
+    }
+
+    fn insert_guild(&mut self, mut guild: Guild) -> Option<Guild> {
+        retain_bounded(&mut guild.members, self.settings.max_members, Some(self.user.id));
+        retain_bounded(&mut guild.presences, self.settings.max_presences, None);
+
+        for member in guild.members.values_mut() {
+            let user = self.insert_user(&member.user.read());
+
+            member.user = user;
+        }
+
+        let keys = GuildKeys::from(&guild);
+
+        self.insert_guild_channels(&guild);
+        let old = self.guilds.insert(guild.id, Arc::new(RwLock::new(guild)));
+        let old_keys = old.as_ref().map(|old| GuildKeys::from(&*old.read()));
+        self.index.guild(old_keys.as_ref(), Some(&keys));
+
+        old.map(into_owned)
+    }
+
+    fn remove_guild(&mut self, guild_id: GuildId) -> Option<Guild> {
+        let guild = self.guilds.remove(&guild_id)?;
+        let keys = {
+            let guild = guild.read();
+
+            for channel_id in guild.channels.keys() {
+                self.channels.remove(channel_id);
+                self.messages.remove_channel(*channel_id);
+            }
+
+            GuildKeys::from(&*guild)
+        };
+        self.index.guild(Some(&keys), None);
+
+        Some(into_owned(guild))
+    }
+
+    fn remove_unavailable_guild(&mut self, guild_id: GuildId) -> Option<Guild> {
+        let guild = self.guilds.remove(&guild_id)?;
+        let keys = GuildKeys::from(&*guild.read()).without_channels();
+        self.index.guild(Some(&keys), None);
+
+        Some(into_owned(guild))
+    }
+
+    fn set_guild_unavailable(&mut self, guild_id: GuildId, unavailable: bool) {
+        if unavailable {
+            self.unavailable_guilds.insert(guild_id);
+        } else {
+            self.unavailable_guilds.remove(&guild_id);
+        }
+    }
+
+    fn stale_guilds(&self) -> Vec<GuildId> {
+        self.stale_guilds.iter().cloned().collect()
+    }
+
+    fn set_guild_stale(&mut self, guild_id: GuildId, stale: bool) {
+        if stale {
+            self.stale_guilds.insert(guild_id);
+        } else {
+            self.stale_guilds.remove(&guild_id);
+        }
+    }
+
+    fn update_guild(&self, partial: &PartialGuild) {
+        let guild = match self.guilds.get(&partial.id) {
+            Some(guild) => guild,
+            None => return,
+        };
+
+        let (old_owner_id, removed_roles) = {
+            let mut guild = guild.write();
+            let old_owner_id = guild.owner_id;
+            let removed_roles = guild.roles
+                .keys()
+                .filter(|id| !partial.roles.contains_key(id))
+                .cloned()
+                .collect::<Vec<RoleId>>();
+
+            guild.afk_timeout = partial.afk_timeout;
+            guild.afk_channel_id.clone_from(&partial.afk_channel_id);
+            guild.icon.clone_from(&partial.icon);
+            guild.name.clone_from(&partial.name);
+            guild.owner_id.clone_from(&partial.owner_id);
+            guild.region.clone_from(&partial.region);
+            guild.roles.clone_from(&partial.roles);
+            guild.verification_level = partial.verification_level;
+
+            (old_owner_id, removed_roles)
+        };
+
+        self.index.owner(partial.id, Some(old_owner_id), Some(partial.owner_id));
+
+        for role_id in removed_roles {
+            self.index.remove_role(role_id);
+        }
+    }
+
+    fn set_guild_emojis(&self, guild_id: GuildId, emojis: HashMap<EmojiId, Emoji>) {
+        if let Some(guild) = self.guilds.get(&guild_id) {
+            guild.write().emojis = emojis;
+        }
+    }
+
+    fn add_member_count(&self, guild_id: GuildId, count: i64) -> Option<u64> {
+        let mut guild = self.guilds.get(&guild_id)?.write();
+        guild.member_count = (guild.member_count as i64 + count) as u64;
+
+        Some(guild.member_count)
+    }
+
+    fn channel(&self, channel_id: ChannelId) -> Option<Channel> {
+        self._channel(channel_id).or_else(|| {
+            self.categories.get(&channel_id).cloned().map(Channel::Category)
+        })
+    }
+
+    fn insert_channel(&mut self, channel: Channel) -> Option<Channel> {
+        match channel {
+            Channel::Group(group) => {
+                let id = group.read().channel_id;
+
+                self.groups.insert(id, group).map(Channel::Group)
+            },
+            Channel::Guild(channel) => {
+                let keys = ChannelKeys::from(&*channel.read());
+                let (guild_id, id) = (keys.guild_id, keys.id);
+
+                if let Some(guild) = self.guilds.get(&guild_id) {
+                    guild.write().channels.insert(id, Arc::clone(&channel));
+                }
+
+                let old = self.channels.insert(id, channel);
+                let old_keys = old.as_ref().map(|old| ChannelKeys::from(&*old.read()));
+                self.index.channel(old_keys, Some(keys));
+
+                old.map(Channel::Guild)
+            },
+            Channel::Private(channel) => {
+                let max = self.settings.max_private_channels;
+                let id = channel.read().id;
+
+                insert_bounded(&mut self.private_channels, max, id, channel).map(Channel::Private)
+            },
+            Channel::Category(category) => {
+                let max = self.settings.max_categories;
+                let id = category.read().id;
+
+                insert_bounded(&mut self.categories, max, id, category).map(Channel::Category)
+            },
+        }
+    }
+
+    fn remove_channel(&mut self, channel_id: ChannelId) -> Option<Channel> {
+        self.messages.remove_channel(channel_id);
+
+        if let Some(channel) = self.channels.remove(&channel_id) {
+            let keys = ChannelKeys::from(&*channel.read());
+
+            if let Some(guild) = self.guilds.get(&keys.guild_id) {
+                guild.write().channels.remove(&channel_id);
+            }
+
+            self.index.channel(Some(keys), None);
+
+            return Some(Channel::Guild(channel));
+        }
+
+        self.categories.remove(&channel_id).map(Channel::Category)
+            .or_else(|| self.private_channels.remove(&channel_id).map(Channel::Private))
+            .or_else(|| self.groups.remove(&channel_id).map(Channel::Group))
+    }
+
+    fn member(&self, guild_id: GuildId, user_id: UserId) -> Option<Member> {
+        self._member(guild_id, user_id)
+    }
+
+    fn insert_member(&mut self, guild_id: GuildId, mut member: Member) -> Option<Member> {
+        // The member's user may already be the cached instance, which can't be
+        // read while it is updated.
+        let user = member.user.read().clone();
+        let max = self.max_members_for(user.id);
+
+        member.user = self.insert_user(&user);
+
+        let roles = member.roles.clone();
+        let (old, inserted) = {
+            let mut guild = self.guilds.get(&guild_id)?.write();
+            let old = insert_bounded(&mut guild.members, max, user.id, member);
+
+            (old, guild.members.contains_key(&user.id))
+        };
+
+        if inserted {
+            let old_roles = old.as_ref().map_or(&[][..], |old| &old.roles[..]);
+
+            self.index.member(user.id, old_roles, &roles);
+        }
+
+        old
+    }
+
+    fn remove_member(&mut self, guild_id: GuildId, user_id: UserId) -> Option<Member> {
+        let member = self.guilds
+            .get(&guild_id)
+            .and_then(|guild| guild.write().members.remove(&user_id))?;
+        self.index.member(user_id, &member.roles, &[]);
+
+        Some(member)
+    }
+
+    fn role(&self, guild_id: GuildId, role_id: RoleId) -> Option<Role> {
+        self._role(guild_id, role_id)
+    }
+
+    fn insert_role(&self, guild_id: GuildId, role: Role) -> Option<Role> {
+        self.guilds
+            .get(&guild_id)
+            .and_then(|guild| guild.write().roles.insert(role.id, role))
+    }
+
+    fn remove_role(&self, guild_id: GuildId, role_id: RoleId) -> Option<Role> {
+        let role = self.guilds
+            .get(&guild_id)
+            .and_then(|guild| guild.write().roles.remove(&role_id))?;
+        self.index.remove_role(role_id);
+
+        Some(role)
+    }
+
+    fn user(&self, user_id: UserId) -> Option<User> {
+        self._user(user_id).map(|user| user.read().clone())
+    }
+
+    fn insert_user(&mut self, user: &User) -> Arc<RwLock<User>> {
+        let full = is_full(&self.users, self.settings.max_users);
+
+        match self.users.entry(user.id) {
+            Entry::Vacant(e) => {
+                let user = Arc::new(RwLock::new(user.clone()));
+
+                if !full {
+                    e.insert(Arc::clone(&user));
+                }
+
+                user
+            },
+            Entry::Occupied(mut e) => {
+                e.get_mut().write().clone_from(user);
+
+                Arc::clone(e.get())
+            },
+        }
+    }
+
+    fn message(&self, channel_id: ChannelId, message_id: MessageId) -> Option<Message> {
+        // Events retrieve messages to update them, which isn't a use of them.
+        self.peek_message(channel_id, message_id)
+    }
+
+    fn insert_message(&self, message: Message) -> Option<Message> {
+        self.messages.insert(message, &self.settings)
+    }
+
+    fn update_message(&self, message: Message) -> Option<Message> {
+        self.messages.update(message)
+    }
+
+    fn remove_message(&self, channel_id: ChannelId, message_id: MessageId) -> Option<Message> {
+        self.messages.remove(channel_id, message_id)
+    }
+
+    fn presence(&self, guild_id: Option<GuildId>, user_id: UserId) -> Option<Presence> {
+        match guild_id {
+            Some(guild_id) => self.guilds
+                .get(&guild_id)
+                .and_then(|guild| guild.read().presences.get(&user_id).cloned()),
+            None => self.presences.get(&user_id).cloned(),
+        }
+    }
+
+    fn insert_presence(&mut self, guild_id: Option<GuildId>, presence: Presence) -> Option<Presence> {
+        let max = self.settings.max_presences;
+        let user_id = presence.user_id;
+
+        match guild_id {
+            Some(guild_id) => self.guilds.get(&guild_id).and_then(|guild| {
+                insert_bounded(&mut guild.write().presences, max, user_id, presence)
+            }),
+            None => insert_bounded(&mut self.presences, max, user_id, presence),
+        }
+    }
+
+    fn remove_presence(&mut self, guild_id: Option<GuildId>, user_id: UserId) -> Option<Presence> {
+        match guild_id {
+            Some(guild_id) => self.guilds
+                .get(&guild_id)
+                .and_then(|guild| guild.write().presences.remove(&user_id)),
+            None => self.presences.remove(&user_id),
+        }
+    }
+
+    fn insert_voice_state(&self, guild_id: GuildId, voice_state: VoiceState) -> Option<VoiceState> {
+        let user_id = voice_state.user_id;
+        let channel_id = voice_state.channel_id;
+        let old = self.guilds
+            .get(&guild_id)?
+            .write()
+            .voice_states
+            .insert(user_id, voice_state);
+        let old_channel_id = old.as_ref().and_then(|old| old.channel_id);
+        self.index.voice_state(guild_id, user_id, old_channel_id, channel_id);
+
+        old
+    }
+
+    fn remove_voice_state(&self, guild_id: GuildId, user_id: UserId) -> Option<VoiceState> {
+        let old = self.guilds
+            .get(&guild_id)?
+            .write()
+            .voice_states
+            .remove(&user_id)?;
+        self.index.voice_state(guild_id, user_id, old.channel_id, None);
+
+        Some(old)
+    }
+}
+
+// Takes a removed value out of its lock, cloning it if it is still shared.
+fn into_owned<T: Clone>(value: Arc<RwLock<T>>) -> T {
+    Arc::try_unwrap(value)
+        .map(RwLock::into_inner)
+        .unwrap_or_else(|value| value.read().clone())
+}
@@ -93,13 +93,59 @@ use super::Cache;
 /// cache.update(&mut update_message);
 /// ```
 ///
+/// Updating a [`CacheBackend`] other than the [`Cache`] works the same way,
+/// by implementing `CacheUpdate` for the backend's type. The library's events
+/// implement it for every backend, so they can be applied to one directly:
+///
+/// ```rust
+/// use serenity::cache::{CacheBackend, CacheUpdate};
+/// use serenity::model::event::GuildCreateEvent;
+///
+/// fn on_guild_create<B: CacheBackend>(backend: &mut B, event: &mut GuildCreateEvent) {
+///     CacheUpdate::update(event, backend);
+/// }
+/// ```
+///
+/// [`Cache`]: struct.Cache.html
+/// [`CacheBackend`]: trait.CacheBackend.html
 /// [`Cache::update`]: struct.Cache.html#method.update
-pub trait CacheUpdate {
+pub trait CacheUpdate<B: ?Sized = Cache> {
     /// The return type of an update.
     ///
     /// If there is nothing to return, specify this type as an unit (`()`).
     type Output;
 
     /// Updates the cache with the implementation.
-    fn update(&mut self, &mut Cache) -> Option<Self::Output>;
+    fn update(&mut self, &mut B) -> Option<Self::Output>;
+}
+
+/// Trait used for updating the cache with a type while the cache is only
+/// locked for reading.
+///
+/// This may be implemented for updates which only modify data that is locked
+/// independently of the cache, such as a [`Guild`] or a channel's cached
+/// [`messages`], and used to update the cache via
+/// [`Cache::update_concurrently`].
+///
+/// The library's dispatcher uses this for events such as
+/// [`MessageCreateEvent`], so that reading from the cache - such as data of
+/// another guild - is not blocked while they are applied.
+///
+/// Only the cached messages are split into independently locked shards, while
+/// the data of each guild is behind its own lock. The maps of guilds,
+/// channels, users, and presences are still only modified through the cache's
+/// lock, so the events which add to or remove from them - such as
+/// [`GuildMemberUpdateEvent`], [`PresenceUpdateEvent`], and
+/// [`ChannelCreateEvent`] - still require the cache to be locked for writing.
+///
+/// [`Cache::update_concurrently`]: struct.Cache.html#method.update_concurrently
+/// [`ChannelCreateEvent`]: ../model/event/struct.ChannelCreateEvent.html
+/// [`Guild`]: ../model/guild/struct.Guild.html
+/// [`GuildMemberUpdateEvent`]: ../model/event/struct.GuildMemberUpdateEvent.html
+/// [`MessageCreateEvent`]: ../model/event/struct.MessageCreateEvent.html
+/// [`PresenceUpdateEvent`]: ../model/event/struct.PresenceUpdateEvent.html
+/// [`messages`]: struct.Cache.html#structfield.messages
+pub trait ConcurrentCacheUpdate<B: ?Sized = Cache>: CacheUpdate<B> {
+    /// Updates the cache with the implementation through a shared reference.
+    fn update_concurrently(&mut self, cache: &B) -> Option<Self::Output>;
 }
@@ -0,0 +1,42 @@
+use model::id::GuildId;
+use std::{
+    error::Error as StdError,
+    fmt::{
+        Display,
+        Formatter,
+        Result as FmtResult
+    }
+};
+
+/// An error that occurred while using the [`Cache`].
+///
+/// This is always wrapped within the library's generic [`Error::Cache`]
+/// variant.
+///
+/// [`Cache`]: struct.Cache.html
+/// [`Error::Cache`]: ../enum.Error.html#variant.Cache
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Error {
+    /// The guild is not in the cache.
+    ///
+    /// Provides the Id of the guild.
+    GuildNotCached(GuildId),
+    /// A snapshot was written in a format version that this version of the
+    /// library can not read.
+    ///
+    /// Provides the version of the snapshot.
+    UnsupportedSnapshotVersion(u32),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult { f.write_str(self.description()) }
+}
+
+impl StdError for Error {
+    fn description(&self) -> &str {
+        match *self {
+            Error::GuildNotCached(_) => "The guild is not in the cache",
+            Error::UnsupportedSnapshotVersion(_) => "The cache snapshot's version is not supported",
+        }
+    }
+}
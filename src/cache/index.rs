@@ -0,0 +1,284 @@
+use model::prelude::*;
+use parking_lot::RwLock;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{Debug, Formatter, Result as FmtResult},
+    hash::Hash,
+};
+
+// Secondary indexes of the cache's guild data, used to answer queries without
+// scanning every guild.
+//
+// The index is locked independently of the cache, so that it can be updated
+// along with data that is updated through a shared reference. No guild or
+// channel is locked while the index is locked, so the indexed data is
+// extracted beforehand and the index is updated after the data was modified.
+#[derive(Default)]
+pub(crate) struct Index {
+    maps: RwLock<IndexMaps>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct IndexMaps {
+    channels_by_kind: HashMap<(GuildId, ChannelType), HashSet<ChannelId>>,
+    channels_by_parent: HashMap<ChannelId, HashSet<ChannelId>>,
+    guilds_by_owner: HashMap<UserId, HashSet<GuildId>>,
+    members_by_role: HashMap<RoleId, HashSet<UserId>>,
+    voice_states_by_channel: HashMap<ChannelId, HashSet<(GuildId, UserId)>>,
+}
+
+// The data of a guild channel which is indexed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct ChannelKeys {
+    pub category_id: Option<ChannelId>,
+    pub guild_id: GuildId,
+    pub id: ChannelId,
+    pub kind: ChannelType,
+}
+
+impl<'a> From<&'a GuildChannel> for ChannelKeys {
+    fn from(channel: &'a GuildChannel) -> Self {
+        ChannelKeys {
+            category_id: channel.category_id,
+            guild_id: channel.guild_id,
+            id: channel.id,
+            kind: channel.kind,
+        }
+    }
+}
+
+// The data of a guild and its channels, members, and voice states which is
+// indexed.
+//
+// This is extracted before the index is locked, as the guild's channels need
+// to be locked to do so.
+#[derive(Clone, Debug)]
+pub(crate) struct GuildKeys {
+    channels: Vec<ChannelKeys>,
+    id: GuildId,
+    members: Vec<(UserId, Vec<RoleId>)>,
+    owner_id: UserId,
+    voice_states: Vec<(UserId, ChannelId)>,
+}
+
+impl<'a> From<&'a Guild> for GuildKeys {
+    fn from(guild: &'a Guild) -> Self {
+        GuildKeys {
+            channels: guild.channels
+                .values()
+                .map(|channel| ChannelKeys::from(&*channel.read()))
+                .collect(),
+            id: guild.id,
+            members: guild.members
+                .iter()
+                .map(|(user_id, member)| (*user_id, member.roles.clone()))
+                .collect(),
+            owner_id: guild.owner_id,
+            voice_states: guild.voice_states
+                .iter()
+                .filter_map(|(user_id, state)| state.channel_id.map(|id| (*user_id, id)))
+                .collect(),
+        }
+    }
+}
+
+impl GuildKeys {
+    // Drops the keys of the guild's channels, for a guild which is removed
+    // while its channels are kept.
+    pub fn without_channels(mut self) -> Self {
+        self.channels.clear();
+
+        self
+    }
+}
+
+impl IndexMaps {
+    fn channel(&mut self, old: Option<ChannelKeys>, new: Option<ChannelKeys>) {
+        if old == new {
+            return;
+        }
+
+        if let Some(old) = old {
+            remove_from(&mut self.channels_by_kind, &(old.guild_id, old.kind), &old.id);
+
+            if let Some(category_id) = old.category_id {
+                remove_from(&mut self.channels_by_parent, &category_id, &old.id);
+            }
+        }
+
+        if let Some(new) = new {
+            add_to(&mut self.channels_by_kind, (new.guild_id, new.kind), new.id);
+
+            if let Some(category_id) = new.category_id {
+                add_to(&mut self.channels_by_parent, category_id, new.id);
+            }
+        }
+    }
+
+    fn member(&mut self, user_id: UserId, old: &[RoleId], new: &[RoleId]) {
+        for role_id in old.iter().filter(|id| !new.contains(id)) {
+            remove_from(&mut self.members_by_role, role_id, &user_id);
+        }
+
+        for role_id in new.iter().filter(|id| !old.contains(id)) {
+            add_to(&mut self.members_by_role, *role_id, user_id);
+        }
+    }
+
+    fn owner(&mut self, guild_id: GuildId, old: Option<UserId>, new: Option<UserId>) {
+        if old == new {
+            return;
+        }
+
+        if let Some(old) = old {
+            remove_from(&mut self.guilds_by_owner, &old, &guild_id);
+        }
+
+        if let Some(new) = new {
+            add_to(&mut self.guilds_by_owner, new, guild_id);
+        }
+    }
+
+    fn voice_state(
+        &mut self,
+        guild_id: GuildId,
+        user_id: UserId,
+        old: Option<ChannelId>,
+        new: Option<ChannelId>,
+    ) {
+        if old == new {
+            return;
+        }
+
+        if let Some(channel_id) = old {
+            remove_from(&mut self.voice_states_by_channel, &channel_id, &(guild_id, user_id));
+        }
+
+        if let Some(channel_id) = new {
+            add_to(&mut self.voice_states_by_channel, channel_id, (guild_id, user_id));
+        }
+    }
+
+    fn guild(&mut self, old: Option<&GuildKeys>, new: Option<&GuildKeys>) {
+        if let Some(guild) = old {
+            self.owner(guild.id, Some(guild.owner_id), None);
+
+            for channel in &guild.channels {
+                self.channel(Some(*channel), None);
+            }
+
+            for &(user_id, ref roles) in &guild.members {
+                self.member(user_id, roles, &[]);
+            }
+
+            for &(user_id, channel_id) in &guild.voice_states {
+                self.voice_state(guild.id, user_id, Some(channel_id), None);
+            }
+        }
+
+        if let Some(guild) = new {
+            self.owner(guild.id, None, Some(guild.owner_id));
+
+            for channel in &guild.channels {
+                self.channel(None, Some(*channel));
+            }
+
+            for &(user_id, ref roles) in &guild.members {
+                self.member(user_id, &[], roles);
+            }
+
+            for &(user_id, channel_id) in &guild.voice_states {
+                self.voice_state(guild.id, user_id, None, Some(channel_id));
+            }
+        }
+    }
+}
+
+impl Index {
+    pub fn channel(&self, old: Option<ChannelKeys>, new: Option<ChannelKeys>) {
+        self.maps.write().channel(old, new);
+    }
+
+    pub fn member(&self, user_id: UserId, old: &[RoleId], new: &[RoleId]) {
+        self.maps.write().member(user_id, old, new);
+    }
+
+    pub fn owner(&self, guild_id: GuildId, old: Option<UserId>, new: Option<UserId>) {
+        self.maps.write().owner(guild_id, old, new);
+    }
+
+    pub fn voice_state(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        old: Option<ChannelId>,
+        new: Option<ChannelId>,
+    ) {
+        self.maps.write().voice_state(guild_id, user_id, old, new);
+    }
+
+    pub fn guild(&self, old: Option<&GuildKeys>, new: Option<&GuildKeys>) {
+        self.maps.write().guild(old, new);
+    }
+
+    pub fn remove_role(&self, role_id: RoleId) {
+        self.maps.write().members_by_role.remove(&role_id);
+    }
+
+    pub fn channels_by_kind(&self, guild_id: GuildId, kind: ChannelType) -> Vec<ChannelId> {
+        collect(&self.maps.read().channels_by_kind, &(guild_id, kind))
+    }
+
+    pub fn channels_by_parent(&self, category_id: ChannelId) -> Vec<ChannelId> {
+        collect(&self.maps.read().channels_by_parent, &category_id)
+    }
+
+    pub fn guilds_by_owner(&self, user_id: UserId) -> Vec<GuildId> {
+        collect(&self.maps.read().guilds_by_owner, &user_id)
+    }
+
+    pub fn members_by_role(&self, role_id: RoleId) -> Vec<UserId> {
+        collect(&self.maps.read().members_by_role, &role_id)
+    }
+
+    pub fn voice_states_by_channel(&self, channel_id: ChannelId) -> Vec<(GuildId, UserId)> {
+        collect(&self.maps.read().voice_states_by_channel, &channel_id)
+    }
+}
+
+impl Clone for Index {
+    fn clone(&self) -> Self {
+        Index {
+            maps: RwLock::new(self.maps.read().clone()),
+        }
+    }
+}
+
+impl Debug for Index {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        Debug::fmt(&*self.maps.read(), f)
+    }
+}
+
+fn add_to<K: Eq + Hash, V: Eq + Hash>(map: &mut HashMap<K, HashSet<V>>, key: K, value: V) {
+    map.entry(key).or_default().insert(value);
+}
+
+fn remove_from<K: Eq + Hash, V: Eq + Hash>(map: &mut HashMap<K, HashSet<V>>, key: &K, value: &V) {
+    let empty = match map.get_mut(key) {
+        Some(values) => {
+            values.remove(value);
+
+            values.is_empty()
+        },
+        None => return,
+    };
+
+    if empty {
+        map.remove(key);
+    }
+}
+
+fn collect<K: Eq + Hash, V: Copy + Eq + Hash>(map: &HashMap<K, HashSet<V>>, key: &K) -> Vec<V> {
+    map.get(key).map_or_else(Vec::new, |values| values.iter().cloned().collect())
+}
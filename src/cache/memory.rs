@@ -0,0 +1,433 @@
+use model::prelude::*;
+use parking_lot::RwLock;
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    hash::{BuildHasher, Hash},
+    mem,
+    sync::Arc,
+};
+use super::Cache;
+
+/// An estimation of the memory used by a value.
+///
+/// The estimations are approximate: they account for the capacity of strings
+/// and collections, but not for the bookkeeping of the allocator or of hash
+/// tables.
+///
+/// Users which are shared between members, presences, and channels are only
+/// counted as part of the cache's [`users`], and not as part of the values
+/// which share them.
+///
+/// [`users`]: struct.Cache.html#structfield.users
+pub trait EstimateSize {
+    /// The approximate number of bytes the value owns on the heap.
+    fn estimated_heap_size(&self) -> usize;
+
+    /// The approximate number of bytes used by the value, both inline and on
+    /// the heap.
+    fn estimated_size(&self) -> usize where Self: Sized {
+        mem::size_of::<Self>() + self.estimated_heap_size()
+    }
+}
+
+/// The number of entries in a collection of the cache, and the approximate
+/// memory they use.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CollectionUsage {
+    /// The approximate number of bytes used by the entries, including their
+    /// keys.
+    pub bytes: usize,
+    /// The number of entries.
+    pub entries: usize,
+}
+
+impl CollectionUsage {
+    fn of<K, V: EstimateSize>(map: &HashMap<K, V>) -> Self {
+        let mut usage = CollectionUsage::default();
+
+        for value in map.values() {
+            usage.add_entry::<K, V>(value);
+        }
+
+        usage
+    }
+
+    pub(crate) fn add_entry<K, V: EstimateSize>(&mut self, value: &V) {
+        self.bytes += mem::size_of::<K>() + value.estimated_size();
+        self.entries += 1;
+    }
+
+    pub(crate) fn add(&mut self, other: CollectionUsage) {
+        self.bytes += other.bytes;
+        self.entries += other.entries;
+    }
+}
+
+/// The approximate memory used by the data of a single guild.
+///
+/// Refer to [`Cache::memory_usage`] for more information.
+///
+/// [`Cache::memory_usage`]: struct.Cache.html#method.memory_usage
+#[derive(Clone, Debug, Default)]
+pub struct GuildMemoryUsage {
+    /// The guild's channels.
+    pub channels: CollectionUsage,
+    /// The guild's custom emojis.
+    pub emojis: CollectionUsage,
+    /// The guild's members, excluding their users.
+    pub members: CollectionUsage,
+    /// The cached messages of the guild's channels.
+    pub messages: CollectionUsage,
+    /// The presences of the guild's members.
+    pub presences: CollectionUsage,
+    /// The guild's roles.
+    pub roles: CollectionUsage,
+}
+
+impl GuildMemoryUsage {
+    /// The sum of the guild's collections.
+    pub fn total(&self) -> CollectionUsage {
+        let mut total = CollectionUsage::default();
+
+        for usage in &[
+            self.channels,
+            self.emojis,
+            self.members,
+            self.messages,
+            self.presences,
+            self.roles,
+        ] {
+            total.add(*usage);
+        }
+
+        total
+    }
+}
+
+/// The approximate memory used by the collections of a [`Cache`], and by
+/// each of its guilds.
+///
+/// Refer to [`Cache::memory_usage`] for more information.
+///
+/// [`Cache`]: struct.Cache.html
+/// [`Cache::memory_usage`]: struct.Cache.html#method.memory_usage
+#[derive(Clone, Debug, Default)]
+pub struct MemoryUsage {
+    /// All channels, including channel categories, private channels, and
+    /// groups.
+    pub channels: CollectionUsage,
+    /// The custom emojis of all guilds.
+    pub emojis: CollectionUsage,
+    /// The usage of each guild.
+    pub guilds: HashMap<GuildId, GuildMemoryUsage>,
+    /// The members of all guilds, excluding their users.
+    pub members: CollectionUsage,
+    /// All cached messages.
+    pub messages: CollectionUsage,
+    /// All presences, both of guilds and of users not tied to a guild.
+    pub presences: CollectionUsage,
+    /// The roles of all guilds.
+    pub roles: CollectionUsage,
+    /// All users.
+    pub users: CollectionUsage,
+}
+
+impl MemoryUsage {
+    /// The sum of the cache's collections.
+    pub fn total(&self) -> CollectionUsage {
+        let mut total = CollectionUsage::default();
+
+        for usage in &[
+            self.channels,
+            self.emojis,
+            self.members,
+            self.messages,
+            self.presences,
+            self.roles,
+            self.users,
+        ] {
+            total.add(*usage);
+        }
+
+        total
+    }
+}
+
+impl Cache {
+    /// Reports the number of entries in each of the cache's collections, and
+    /// an approximation of the memory they use, in total and for each guild.
+    ///
+    /// The figures are estimated through the [`EstimateSize`] implementations
+    /// of the cached models, and can be used to monitor the growth of the
+    /// cache or to choose the limits of its [`Settings`].
+    ///
+    /// Each guild is locked for reading in turn while it is measured.
+    ///
+    /// # Examples
+    ///
+    /// Print the number of members and the memory they use:
+    ///
+    /// ```rust,no_run
+    /// use serenity::CACHE;
+    ///
+    /// let usage = CACHE.read().memory_usage();
+    ///
+    /// println!(
+    ///     "{} members using ~{} bytes",
+    ///     usage.members.entries,
+    ///     usage.members.bytes,
+    /// );
+    /// ```
+    ///
+    /// [`EstimateSize`]: trait.EstimateSize.html
+    /// [`Settings`]: struct.Settings.html
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let messages = self.messages.usage();
+        let mut usage = MemoryUsage::default();
+
+        for (guild_id, guild) in &self.guilds {
+            let guild = guild.read();
+            let mut guild_usage = GuildMemoryUsage {
+                channels: CollectionUsage::of(&guild.channels),
+                emojis: CollectionUsage::of(&guild.emojis),
+                members: CollectionUsage::of(&guild.members),
+                messages: CollectionUsage::default(),
+                presences: CollectionUsage::of(&guild.presences),
+                roles: CollectionUsage::of(&guild.roles),
+            };
+
+            for channel_id in guild.channels.keys() {
+                if let Some(channel_usage) = messages.get(channel_id) {
+                    guild_usage.messages.add(*channel_usage);
+                }
+            }
+
+            usage.emojis.add(guild_usage.emojis);
+            usage.members.add(guild_usage.members);
+            usage.presences.add(guild_usage.presences);
+            usage.roles.add(guild_usage.roles);
+            usage.guilds.insert(*guild_id, guild_usage);
+        }
+
+        for channel_usage in messages.values() {
+            usage.messages.add(*channel_usage);
+        }
+
+        usage.channels.add(CollectionUsage::of(&self.channels));
+        usage.channels.add(CollectionUsage::of(&self.categories));
+        usage.channels.add(CollectionUsage::of(&self.groups));
+        usage.channels.add(CollectionUsage::of(&self.private_channels));
+        usage.presences.add(CollectionUsage::of(&self.presences));
+        usage.users = CollectionUsage::of(&self.users);
+
+        usage
+    }
+}
+
+impl EstimateSize for String {
+    fn estimated_heap_size(&self) -> usize {
+        self.capacity()
+    }
+}
+
+impl<T: EstimateSize> EstimateSize for Option<T> {
+    fn estimated_heap_size(&self) -> usize {
+        self.as_ref().map_or(0, EstimateSize::estimated_heap_size)
+    }
+}
+
+impl<T: EstimateSize> EstimateSize for Vec<T> {
+    fn estimated_heap_size(&self) -> usize {
+        self.capacity() * mem::size_of::<T>()
+            + self.iter().map(EstimateSize::estimated_heap_size).sum::<usize>()
+    }
+}
+
+impl<K, V, S> EstimateSize for HashMap<K, V, S>
+    where K: EstimateSize + Eq + Hash, V: EstimateSize, S: BuildHasher {
+    fn estimated_heap_size(&self) -> usize {
+        self.capacity() * mem::size_of::<(K, V)>()
+            + self.iter()
+                .map(|(key, value)| key.estimated_heap_size() + value.estimated_heap_size())
+                .sum::<usize>()
+    }
+}
+
+impl<T: EstimateSize> EstimateSize for Arc<RwLock<T>> {
+    fn estimated_heap_size(&self) -> usize {
+        mem::size_of::<RwLock<T>>() + self.read().estimated_heap_size()
+    }
+}
+
+impl EstimateSize for Value {
+    fn estimated_heap_size(&self) -> usize {
+        match *self {
+            Value::String(ref string) => string.estimated_heap_size(),
+            Value::Array(ref values) => values.estimated_heap_size(),
+            Value::Object(ref map) => map
+                .iter()
+                .map(|(key, value)| {
+                    mem::size_of::<(String, Value)>()
+                        + key.estimated_heap_size()
+                        + value.estimated_heap_size()
+                })
+                .sum(),
+            Value::Null | Value::Bool(_) | Value::Number(_) => 0,
+        }
+    }
+}
+
+impl EstimateSize for ReactionType {
+    fn estimated_heap_size(&self) -> usize {
+        match *self {
+            ReactionType::Custom { ref name, .. } => name.estimated_heap_size(),
+            ReactionType::Unicode(ref name) => name.estimated_heap_size(),
+        }
+    }
+}
+
+impl EstimateSize for Group {
+    fn estimated_heap_size(&self) -> usize {
+        // The recipients are shared with the cache's users.
+        self.icon.estimated_heap_size()
+            + self.name.estimated_heap_size()
+            + self.recipients.capacity() * mem::size_of::<(UserId, Arc<RwLock<User>>)>()
+    }
+}
+
+// Implements `EstimateSize` for types by summing the heap sizes of the given
+// fields. Types listed without fields own nothing on the heap.
+macro_rules! estimate_fields {
+    ($($ty:ty => [$($field:ident),*];)*) => {
+        $(
+            impl EstimateSize for $ty {
+                fn estimated_heap_size(&self) -> usize {
+                    0 $(+ self.$field.estimated_heap_size())*
+                }
+            }
+        )*
+    };
+}
+
+estimate_fields! {
+    bool => [];
+    u64 => [];
+    ChannelId => [];
+    EmojiId => [];
+    GuildId => [];
+    MessageId => [];
+    PermissionOverwrite => [];
+    RoleId => [];
+    UserId => [];
+
+    ActivityAssets => [large_image, large_text, small_image, small_text];
+    ActivityParty => [id];
+    ActivitySecrets => [join, match_, spectate];
+    Attachment => [id, filename, proxy_url, url];
+    ChannelCategory => [name, permission_overwrites];
+    Embed => [
+        author,
+        description,
+        fields,
+        footer,
+        image,
+        kind,
+        provider,
+        thumbnail,
+        timestamp,
+        title,
+        url,
+        video
+    ];
+    EmbedAuthor => [icon_url, name, proxy_icon_url, url];
+    EmbedField => [name, value];
+    EmbedFooter => [icon_url, proxy_icon_url, text];
+    EmbedImage => [proxy_url, url];
+    EmbedProvider => [name, url];
+    EmbedThumbnail => [proxy_url, url];
+    EmbedVideo => [url];
+    Emoji => [name, roles];
+    Game => [assets, details, name, party, secrets, state, url];
+    Guild => [
+        channels,
+        emojis,
+        features,
+        icon,
+        members,
+        name,
+        presences,
+        region,
+        roles,
+        splash,
+        voice_states
+    ];
+    GuildChannel => [name, permission_overwrites, topic];
+    // The user is shared with the cache's users.
+    Member => [nick, roles];
+    Message => [
+        attachments,
+        author,
+        content,
+        embeds,
+        member,
+        mention_roles,
+        mentions,
+        nonce,
+        reactions
+    ];
+    MessageReaction => [reaction_type];
+    PartialMember => [roles];
+    // The user is shared with the cache's users.
+    Presence => [activities, game, nick];
+    // The recipient is shared with the cache's users.
+    PrivateChannel => [];
+    Role => [name];
+    User => [avatar, name];
+    VoiceState => [session_id, token];
+}
+
+#[cfg(test)]
+mod test {
+    use model::prelude::*;
+    use std::mem;
+    use super::EstimateSize;
+    use {
+        cache::Cache,
+        model::event::GuildCreateEvent,
+    };
+
+    #[test]
+    fn test_estimate_size() {
+        let mut name = String::with_capacity(16);
+        name.push_str("name");
+
+        assert_eq!(name.estimated_heap_size(), 16);
+        let name = Some(name);
+        assert_eq!(name.estimated_heap_size(), 16);
+
+        let names = vec![name.unwrap()];
+        let expected = names.capacity() * mem::size_of::<String>() + 16;
+        assert_eq!(names.estimated_heap_size(), expected);
+        assert_eq!(RoleId(1).estimated_heap_size(), 0);
+    }
+
+    #[test]
+    fn test_memory_usage() {
+        let mut event: GuildCreateEvent = fixture!("guild_create_1");
+        let guild_id = event.guild.id;
+        let mut cache = Cache::new();
+        cache.update(&mut event);
+
+        let usage = cache.memory_usage();
+        let guild = &usage.guilds[&guild_id];
+
+        assert_eq!(guild.members.entries, event.guild.members.len());
+        assert_eq!(guild.roles.entries, event.guild.roles.len());
+        assert_eq!(guild.channels.entries, event.guild.channels.len());
+        assert_eq!(usage.members, guild.members);
+        assert_eq!(usage.users.entries, cache.users.len());
+        assert!(guild.members.bytes > guild.members.entries * mem::size_of::<Member>());
+        assert!(usage.total().bytes > guild.total().bytes);
+    }
+}
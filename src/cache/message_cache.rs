@@ -0,0 +1,525 @@
+use model::prelude::*;
+use parking_lot::Mutex;
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    fmt::{Debug, Formatter, Result as FmtResult},
+    mem,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+use super::{CollectionUsage, EstimateSize, Settings, ShardedMap};
+
+/// The cached messages of all channels.
+///
+/// The number of messages kept in each channel, across all channels, and for
+/// how long they are kept is configured through the cache's [`Settings`].
+///
+/// Channels are locked independently of each other, so messages can be
+/// cached and retrieved through a shared reference.
+///
+/// [`Settings`]: struct.Settings.html
+pub struct MessageCache {
+    channels: ShardedMap<ChannelId, ChannelMessages>,
+    // The order in which messages were last used, when the total number of
+    // messages is bounded.
+    //
+    // This is only locked while a channel is locked when a message is used,
+    // and no channel is locked while this is locked, so that the two can't
+    // deadlock.
+    recency: Mutex<Recency>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+#[derive(Clone, Default)]
+struct ChannelMessages {
+    messages: HashMap<MessageId, CachedMessage>,
+    // The IDs of the messages in the order they were cached.
+    queue: VecDeque<MessageId>,
+}
+
+impl ChannelMessages {
+    fn remove(&mut self, message_id: MessageId) -> Option<CachedMessage> {
+        let removed = self.messages.remove(&message_id)?;
+        self.queue.retain(|id| *id != message_id);
+
+        Some(removed)
+    }
+}
+
+struct CachedMessage {
+    cached_at: Instant,
+    message: Message,
+    // The position of the message in the recency order, or 0 if it is not
+    // tracked.
+    tick: AtomicUsize,
+}
+
+impl CachedMessage {
+    fn is_expired(&self, settings: &Settings) -> bool {
+        settings.message_ttl.map_or(false, |ttl| self.cached_at.elapsed() >= ttl)
+    }
+}
+
+impl EstimateSize for CachedMessage {
+    fn estimated_heap_size(&self) -> usize {
+        self.message.estimated_heap_size()
+    }
+}
+
+impl Clone for CachedMessage {
+    fn clone(&self) -> Self {
+        CachedMessage {
+            cached_at: self.cached_at,
+            message: self.message.clone(),
+            tick: AtomicUsize::new(self.tick.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct Recency {
+    last_tick: usize,
+    order: BTreeMap<usize, (ChannelId, MessageId)>,
+}
+
+impl Recency {
+    fn next_tick(&mut self, key: (ChannelId, MessageId)) -> usize {
+        self.last_tick += 1;
+        self.order.insert(self.last_tick, key);
+
+        self.last_tick
+    }
+
+    fn forget(&mut self, tick: usize) {
+        if tick != 0 {
+            self.order.remove(&tick);
+        }
+    }
+}
+
+impl MessageCache {
+    /// Creates a new, empty message cache.
+    pub fn new() -> Self {
+        MessageCache {
+            channels: ShardedMap::new(),
+            recency: Mutex::new(Recency::default()),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+        }
+    }
+
+    /// Whether messages of the channel are cached.
+    pub fn contains_key(&self, channel_id: &ChannelId) -> bool {
+        self.channels.with(channel_id, |channel| {
+            channel.map_or(false, |channel| !channel.messages.is_empty())
+        })
+    }
+
+    /// Retrieves a copy of the cached messages of a channel.
+    ///
+    /// This does not count as a use of the messages, and includes expired
+    /// messages which have not been removed yet.
+    pub fn get(&self, channel_id: &ChannelId) -> Option<HashMap<MessageId, Message>> {
+        self.channels.with(channel_id, |channel| {
+            channel.map(|channel| {
+                channel.messages
+                    .iter()
+                    .map(|(id, cached)| (*id, cached.message.clone()))
+                    .collect()
+            })
+        })
+    }
+
+    /// The total number of cached messages.
+    pub fn len(&self) -> usize {
+        let mut len = 0;
+        self.channels.for_each(|_, channel| len += channel.messages.len());
+
+        len
+    }
+
+    /// Whether no messages are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of times a message was found when retrieving it from the
+    /// cache.
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// The number of times a message was not found - or had expired - when
+    /// retrieving it from the cache.
+    pub fn misses(&self) -> usize {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    // The number of cached messages of each channel and the approximate memory
+    // they use.
+    pub(crate) fn usage(&self) -> HashMap<ChannelId, CollectionUsage> {
+        let mut usage = HashMap::new();
+
+        self.channels.for_each(|channel_id, channel| {
+            let mut channel_usage = CollectionUsage::default();
+
+            // Each message is keyed by its Id, which is also queued.
+            for cached in channel.messages.values() {
+                channel_usage.add_entry::<(MessageId, MessageId), CachedMessage>(cached);
+            }
+
+            usage.insert(*channel_id, channel_usage);
+        });
+
+        usage
+    }
+
+    // Retrieves a message, counting it as used.
+    pub(crate) fn message(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        settings: &Settings,
+    ) -> Option<Message> {
+        let (found, expired) = self.channels.with(&channel_id, |channel| {
+            let cached = match channel.and_then(|c| c.messages.get(&message_id)) {
+                Some(cached) => cached,
+                None => return (None, false),
+            };
+
+            if cached.is_expired(settings) {
+                return (None, true);
+            }
+
+            if settings.max_total_messages.is_some() {
+                let mut recency = self.recency.lock();
+                let tick = recency.next_tick((channel_id, message_id));
+                let last = cached.tick.swap(tick, Ordering::Relaxed);
+
+                recency.forget(last);
+            }
+
+            (Some(cached.message.clone()), false)
+        });
+
+        if expired {
+            self.remove(channel_id, message_id);
+        }
+
+        let counter = if found.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+
+        found
+    }
+
+    // Retrieves a message without counting it as used, for lookups of the
+    // library itself, such as when applying an update to the message.
+    pub(crate) fn peek(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        settings: &Settings,
+    ) -> Option<Message> {
+        self.channels.with(&channel_id, |channel| {
+            channel
+                .and_then(|channel| channel.messages.get(&message_id))
+                .filter(|cached| !cached.is_expired(settings))
+                .map(|cached| cached.message.clone())
+        })
+    }
+
+    // Caches a new message, returning the oldest message of the channel if it
+    // had to be removed to make room.
+    pub(crate) fn insert(&self, message: Message, settings: &Settings) -> Option<Message> {
+        let channel_id = message.channel_id;
+        let message_id = message.id;
+        let max = settings.max_messages_for(channel_id, message.guild_id);
+
+        if max == 0 {
+            return None;
+        }
+
+        // Reserve the message's place in the recency order first, so that no
+        // channel is locked at the same time.
+        let (tick, mut evicted) = match settings.max_total_messages {
+            Some(max_total) => {
+                let mut recency = self.recency.lock();
+                let tick = recency.next_tick((channel_id, message_id));
+                let mut evicted = Vec::new();
+
+                while recency.order.len() > max_total {
+                    let oldest = match recency.order.keys().next() {
+                        Some(oldest) => *oldest,
+                        None => break,
+                    };
+
+                    if let Some(key) = recency.order.remove(&oldest) {
+                        evicted.push((oldest, key));
+                    }
+                }
+
+                (tick, evicted)
+            },
+            None => (0, Vec::new()),
+        };
+
+        let (removed_msg, forgotten) = self.channels.with_entry(channel_id, Default::default, |channel| {
+            let mut forgotten = Vec::new();
+            let mut removed_msg = None;
+
+            // Expired messages are at the front of the queue.
+            while let Some(id) = channel.queue.front().cloned() {
+                if !channel.messages.get(&id).map_or(true, |c| c.is_expired(settings)) {
+                    break;
+                }
+
+                channel.queue.pop_front();
+
+                if let Some(expired) = channel.messages.remove(&id) {
+                    forgotten.push(expired.tick.load(Ordering::Relaxed));
+                }
+            }
+
+            while channel.messages.len() >= max && !channel.messages.contains_key(&message_id) {
+                let removed = match channel.queue.pop_front() {
+                    Some(id) => channel.messages.remove(&id),
+                    None => break,
+                };
+
+                if let Some(removed) = removed {
+                    forgotten.push(removed.tick.load(Ordering::Relaxed));
+                    removed_msg = Some(removed.message);
+                }
+            }
+
+            let cached = CachedMessage {
+                cached_at: Instant::now(),
+                message,
+                tick: AtomicUsize::new(tick),
+            };
+
+            match channel.messages.insert(message_id, cached) {
+                Some(replaced) => forgotten.push(replaced.tick.load(Ordering::Relaxed)),
+                None => channel.queue.push_back(message_id),
+            }
+
+            (removed_msg, forgotten)
+        });
+
+        if !forgotten.is_empty() {
+            let mut recency = self.recency.lock();
+
+            for tick in forgotten {
+                recency.forget(tick);
+            }
+        }
+
+        // Remove the least recently used messages, unless they were used again
+        // in the meantime.
+        for (tick, (channel_id, message_id)) in evicted.drain(..) {
+            self.channels.with_mut(&channel_id, |channel| {
+                if let Some(channel) = channel {
+                    let current = channel.messages.get(&message_id).map(|c| c.tick.load(Ordering::Relaxed));
+
+                    if current == Some(tick) {
+                        channel.remove(message_id);
+                    }
+                }
+            });
+        }
+
+        removed_msg
+    }
+
+    // Replaces the content of a cached message, returning the message as it
+    // was before.
+    pub(crate) fn update(&self, message: Message) -> Option<Message> {
+        let channel_id = message.channel_id;
+
+        self.channels.with_mut(&channel_id, |channel| {
+            let cached = channel?.messages.get_mut(&message.id)?;
+
+            Some(mem::replace(&mut cached.message, message))
+        })
+    }
+
+    pub(crate) fn remove(&self, channel_id: ChannelId, message_id: MessageId) -> Option<Message> {
+        let removed = self.channels.with_mut(&channel_id, |channel| {
+            channel?.remove(message_id)
+        })?;
+
+        self.recency.lock().forget(removed.tick.load(Ordering::Relaxed));
+
+        Some(removed.message)
+    }
+
+    pub(crate) fn remove_channel(&self, channel_id: ChannelId) {
+        if let Some(channel) = self.channels.remove(&channel_id) {
+            let mut recency = self.recency.lock();
+
+            for cached in channel.messages.values() {
+                recency.forget(cached.tick.load(Ordering::Relaxed));
+            }
+        }
+    }
+
+    // Removes all expired messages, returning the number of removed messages.
+    pub(crate) fn purge_expired(&self, settings: &Settings) -> usize {
+        if settings.message_ttl.is_none() {
+            return 0;
+        }
+
+        let mut forgotten = Vec::new();
+
+        self.channels.retain(|_, channel| {
+            let expired = channel.messages
+                .iter()
+                .filter(|&(_, cached)| cached.is_expired(settings))
+                .map(|(id, _)| *id)
+                .collect::<Vec<MessageId>>();
+
+            for id in expired {
+                if let Some(removed) = channel.remove(id) {
+                    forgotten.push(removed.tick.load(Ordering::Relaxed));
+                }
+            }
+
+            !channel.messages.is_empty()
+        });
+
+        let mut recency = self.recency.lock();
+
+        for tick in &forgotten {
+            recency.forget(*tick);
+        }
+
+        forgotten.len()
+    }
+}
+
+impl Clone for MessageCache {
+    fn clone(&self) -> Self {
+        MessageCache {
+            channels: self.channels.clone(),
+            recency: Mutex::new(self.recency.lock().clone()),
+            hits: AtomicUsize::new(self.hits()),
+            misses: AtomicUsize::new(self.misses()),
+        }
+    }
+}
+
+impl Debug for MessageCache {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_struct("MessageCache")
+            .field("len", &self.len())
+            .field("hits", &self.hits())
+            .field("misses", &self.misses())
+            .finish()
+    }
+}
+
+impl Default for MessageCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use internal::test_util::message;
+    use model::prelude::*;
+    use std::time::Duration;
+    use super::super::Settings;
+    use super::MessageCache;
+
+    #[test]
+    fn test_max_total_messages() {
+        let mut settings = Settings::new();
+        settings.max_messages(10).max_total_messages(Some(3));
+        let cache = MessageCache::new();
+
+        cache.insert(message(1, 1), &settings);
+        cache.insert(message(2, 2), &settings);
+        cache.insert(message(1, 3), &settings);
+
+        // Using the first message makes the second the least recently used.
+        assert!(cache.message(ChannelId(1), MessageId(1), &settings).is_some());
+        cache.insert(message(3, 4), &settings);
+
+        assert_eq!(cache.len(), 3);
+        assert!(cache.message(ChannelId(2), MessageId(2), &settings).is_none());
+        assert!(cache.message(ChannelId(1), MessageId(1), &settings).is_some());
+        assert!(!cache.contains_key(&ChannelId(2)));
+
+        assert_eq!(cache.hits(), 2);
+        assert_eq!(cache.misses(), 1);
+    }
+
+    #[test]
+    fn test_peek() {
+        let mut settings = Settings::new();
+        settings.max_messages(10).max_total_messages(Some(2));
+        let cache = MessageCache::new();
+
+        cache.insert(message(1, 1), &settings);
+        cache.insert(message(1, 2), &settings);
+
+        // Peeking doesn't count as a use, so the first message is evicted.
+        assert!(cache.peek(ChannelId(1), MessageId(1), &settings).is_some());
+        cache.insert(message(1, 3), &settings);
+
+        assert!(cache.peek(ChannelId(1), MessageId(1), &settings).is_none());
+        assert_eq!(cache.hits(), 0);
+        assert_eq!(cache.misses(), 0);
+    }
+
+    #[test]
+    fn test_max_messages_overrides() {
+        let mut settings = Settings::new();
+        settings
+            .max_messages(10)
+            .guild_max_messages(1, 2)
+            .channel_max_messages(3, 1)
+            .channel_max_messages(4, 0);
+        let cache = MessageCache::new();
+
+        // The guild's override applies to its channels.
+        cache.insert(message(2, 1), &settings);
+        cache.insert(message(2, 2), &settings);
+        assert_eq!(cache.insert(message(2, 3), &settings).unwrap().id, MessageId(1));
+        assert_eq!(cache.get(&ChannelId(2)).unwrap().len(), 2);
+
+        // A channel's override takes precedence over its guild's.
+        cache.insert(message(3, 10), &settings);
+        assert_eq!(cache.insert(message(3, 11), &settings).unwrap().id, MessageId(10));
+        assert_eq!(cache.get(&ChannelId(3)).unwrap().len(), 1);
+
+        cache.insert(message(4, 20), &settings);
+        assert!(!cache.contains_key(&ChannelId(4)));
+    }
+
+    #[test]
+    fn test_message_ttl() {
+        let mut settings = Settings::new();
+        settings.max_messages(10);
+        let cache = MessageCache::new();
+
+        cache.insert(message(1, 1), &settings);
+        cache.insert(message(2, 2), &settings);
+        cache.insert(message(2, 3), &settings);
+
+        settings.message_ttl(Some(Duration::from_secs(0)));
+
+        // An expired message is a miss, and is removed.
+        assert!(cache.message(ChannelId(1), MessageId(1), &settings).is_none());
+        assert_eq!(cache.misses(), 1);
+        assert_eq!(cache.len(), 2);
+
+        assert_eq!(cache.purge_expired(&settings), 2);
+        assert!(cache.is_empty());
+
+        settings.message_ttl(None);
+        assert_eq!(cache.purge_expired(&settings), 0);
+    }
+}
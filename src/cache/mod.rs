@@ -7,21 +7,32 @@
 //! allows data to be "corrupted", and _may or may not_ cause misfunctions
 //! within the library. Mutate data at your own discretion.
 //!
-//! A "globally available" instance of the Cache is available at
-//! [`CACHE`]. This is the instance that is updated by the library,
+//! Every [`Client`] owns an instance of the Cache, which is updated by the
+//! library and available via [`Client::cache`] and [`Context::cache`],
 //! meaning you should _not_ need to maintain updating it yourself in any case.
+//! Multiple clients in one process each keep their own cache.
+//!
+//! The "globally available" instance at [`CACHE`] is only kept for
+//! compatibility, and is only updated by clients created with it via
+//! [`Client::new_with_cache`].
 //!
 //! # Use by Models
 //!
-//! Most models of Discord objects, such as the [`Message`], [`GuildChannel`],
-//! or [`Emoji`], have methods for interacting with that single instance. This
-//! feature is only compiled if the `methods` feature is enabled. An example of
-//! this is [`Guild::edit`], which performs a check to ensure that the current
-//! user is the owner of the guild, prior to actually performing the HTTP
-//! request. The cache is involved due to the function's use of unlocking the
-//! cache and retrieving the Id of the current user, and comparing it to the Id
-//! of the user that owns the guild. This is an inexpensive method of being able
-//! to access data required by these sugary methods.
+//! Models of Discord objects, such as the [`Message`], [`GuildChannel`], or
+//! [`Emoji`], have methods which read from a cache given to them, such as
+//! [`Message::is_own`] or [`ChannelId::to_channel_cached`].
+//!
+//! Methods which perform requests, such as [`Guild::edit`], check the global
+//! [`CACHE`] to ensure that the current user has the required permissions
+//! before actually performing the HTTP request. These checks are skipped while
+//! the global instance is not updated by a client.
+//!
+//! # Backends
+//!
+//! The events received over the gateway are applied to the cache through the
+//! operations of the [`CacheBackend`] trait, which the in-memory `Cache`
+//! implements. Other stores can implement the trait to be updated with the
+//! same events via [`CacheUpdate`].
 //!
 //! # Do I need the Cache?
 //!
@@ -32,37 +43,61 @@
 //! while needing to hit the REST API as little as possible, then the answer
 //! is "yes".
 //!
+//! [`ChannelId::to_channel_cached`]: ../model/id/struct.ChannelId.html#method.to_channel_cached
+//! [`Client`]: ../client/struct.Client.html
+//! [`Client::cache`]: ../client/struct.Client.html#structfield.cache
+//! [`Client::new_with_cache`]: ../client/struct.Client.html#method.new_with_cache
+//! [`Context::cache`]: ../client/struct.Context.html#structfield.cache
 //! [`Emoji`]: ../model/guild/struct.Emoji.html
 //! [`Group`]: ../model/channel/struct.Group.html
 //! [`Guild`]: ../model/guild/struct.Guild.html
 //! [`Guild::edit`]: ../model/guild/struct.Guild.html#method.edit
 //! [`Message`]: ../model/channel/struct.Message.html
+//! [`Message::is_own`]: ../model/channel/struct.Message.html#method.is_own
 //! [`GuildChannel`]: ../model/channel/struct.GuildChannel.html
 //! [`Role`]: ../model/guild/struct.Role.html
 //! [`CACHE`]: ../struct.CACHE.html
+//! [`CacheBackend`]: trait.CacheBackend.html
+//! [`CacheUpdate`]: trait.CacheUpdate.html
 //! [`http`]: ../http/index.html
 
 use model::prelude::*;
 use parking_lot::RwLock;
 use std::collections::{
-    hash_map::Entry,
     HashMap,
     HashSet,
-    VecDeque,
 };
 use std::{
     default::Default,
+    hash::Hash,
     sync::Arc,
     time::Duration,
 };
 
+mod backend;
 mod cache_update;
+mod error;
+mod index;
+mod memory;
+mod message_cache;
+#[cfg(feature = "http")]
+mod reconcile;
 mod settings;
+mod sharded;
+mod snapshot;
 
-pub use self::cache_update::CacheUpdate;
-pub use self::settings::Settings;
+use self::index::{ChannelKeys, Index};
 
-type MessageCache = HashMap<ChannelId, HashMap<MessageId, Message>>;
+pub use self::backend::CacheBackend;
+pub use self::cache_update::{CacheUpdate, ConcurrentCacheUpdate};
+pub use self::error::Error as CacheError;
+pub use self::memory::{CollectionUsage, EstimateSize, GuildMemoryUsage, MemoryUsage};
+pub use self::message_cache::MessageCache;
+#[cfg(feature = "http")]
+pub use self::reconcile::{reconcile_guild, CollectionDrift, Drift};
+pub use self::settings::Settings;
+pub use self::sharded::{ShardedMap, DEFAULT_SHARD_COUNT};
+pub use self::snapshot::SNAPSHOT_VERSION;
 
 /// A cache of all events received over a [`Shard`], where storing at least
 /// some data from the event is possible.
@@ -103,11 +138,18 @@ pub struct Cache {
     /// [`Emoji`]: ../model/guild/struct.Emoji.html
     /// [`Role`]: ../model/guild/struct.Role.html
     pub guilds: HashMap<GuildId, Arc<RwLock<Guild>>>,
-    /// A map of channels to messages.
+    /// The cached messages of each channel.
     ///
-    /// This is a map of channel IDs to another map of message IDs to messages.
+    /// How many messages are kept, and for how long, is configured through the
+    /// message settings of the cache's [`Settings`]. The messages of a channel
+    /// are removed when the channel or its guild is deleted.
     ///
-    /// This keeps only the ten most recent messages.
+    /// Channels are locked independently, so that messages can be updated
+    /// while the cache is only locked for reading. Refer to
+    /// [`ConcurrentCacheUpdate`] for more information.
+    ///
+    /// [`ConcurrentCacheUpdate`]: trait.ConcurrentCacheUpdate.html
+    /// [`Settings`]: struct.Settings.html
     pub messages: MessageCache,
     /// A map of notes that a user has made for individual users.
     ///
@@ -125,6 +167,16 @@ pub struct Cache {
     pub private_channels: HashMap<ChannelId, Arc<RwLock<PrivateChannel>>>,
     /// The total number of shards being used by the bot.
     pub shard_count: u64,
+    /// A list of guilds which were loaded from a snapshot via
+    /// [`load_snapshot`], and have not yet been received from the gateway
+    /// since.
+    ///
+    /// The data of these guilds may be outdated. A guild is removed from this
+    /// list when an [`Event::GuildCreate`] is received for it.
+    ///
+    /// [`Event::GuildCreate`]: ../model/event/enum.Event.html#variant.GuildCreate
+    /// [`load_snapshot`]: #method.load_snapshot
+    pub stale_guilds: HashSet<GuildId>,
     /// A list of guilds which are "unavailable". Refer to the documentation for
     /// [`Event::GuildUnavailable`] for more information on when this can occur.
     ///
@@ -170,14 +222,9 @@ pub struct Cache {
     /// [`PresenceUpdateEvent`]: ../model/event/struct.PresenceUpdateEvent.html
     /// [`ReadyEvent`]: ../model/event/struct.ReadyEvent.html
     pub users: HashMap<UserId, Arc<RwLock<User>>>,
-    /// Queue of message IDs for each channel.
-    ///
-    /// This is simply a vecdeque so we can keep track of the order of messages
-    /// inserted into the cache. When a maximum number of messages are in a
-    /// channel's cache, we can pop the front and remove that ID from the cache.
-    pub(crate) message_queue: HashMap<ChannelId, VecDeque<MessageId>>,
     /// The settings for the cache.
     settings: Settings,
+    index: Index,
     __nonexhaustive: (),
 }
 
@@ -222,7 +269,6 @@ impl Cache {
     /// #
     /// # #[cfg(feature = "client")]
     /// # fn main() {
-    /// use serenity::CACHE;
     /// use std::thread;
     /// use std::time::Duration;
     ///
@@ -241,7 +287,7 @@ impl Cache {
     ///         // seconds.
     ///         thread::sleep(Duration::from_secs(5));
     ///
-    ///         println!("{} unknown members", CACHE.read().unknown_members());
+    ///         println!("{} unknown members", ctx.cache.read().unknown_members());
     ///     }
     /// }
     ///
@@ -316,13 +362,11 @@ impl Cache {
     /// # use serenity::model::prelude::*;
     /// # use serenity::prelude::*;
     /// #
-    /// use serenity::CACHE;
-    ///
     /// struct Handler;
     ///
     /// impl EventHandler for Handler {
-    ///     fn ready(&self, _: Context, _: Ready) {
-    ///         let guilds = CACHE.read().guilds.len();
+    ///     fn ready(&self, ctx: Context, _: Ready) {
+    ///         let guilds = ctx.cache.read().guilds.len();
     ///
     ///         println!("Guilds in the Cache: {}", guilds);
     ///     }
@@ -438,13 +482,11 @@ impl Cache {
     /// # use serenity::model::prelude::*;
     /// # use serenity::prelude::*;
     /// #
-    /// use serenity::CACHE;
-    ///
     /// struct Handler;
     ///
     /// impl EventHandler for Handler {
     ///     fn message(&self, ctx: Context, message: Message) {
-    ///         let cache = CACHE.read();
+    ///         let cache = ctx.cache.read();
     ///
     ///         let channel = match cache.guild_channel(message.channel_id) {
     ///             Some(channel) => channel,
@@ -577,6 +619,57 @@ impl Cache {
         })
     }
 
+    /// Retrieves a [`Message`] from the cache's [`messages`] map, if it
+    /// exists and has not expired.
+    ///
+    /// Retrievals are counted as [hits] or [misses] of the message cache.
+    ///
+    /// # Examples
+    ///
+    /// Print the content of a cached message:
+    ///
+    /// ```rust,no_run
+    /// use serenity::CACHE;
+    ///
+    /// if let Some(message) = CACHE.read().message(7, 8) {
+    ///     println!("Message content: {}", message.content);
+    /// }
+    /// ```
+    ///
+    /// [`Message`]: ../model/channel/struct.Message.html
+    /// [`messages`]: #structfield.messages
+    /// [hits]: struct.MessageCache.html#method.hits
+    /// [misses]: struct.MessageCache.html#method.misses
+    #[inline]
+    pub fn message<C, M>(&self, channel_id: C, message_id: M) -> Option<Message>
+        where C: Into<ChannelId>, M: Into<MessageId> {
+        self._message(channel_id.into(), message_id.into())
+    }
+
+    fn _message(&self, channel_id: ChannelId, message_id: MessageId) -> Option<Message> {
+        self.messages.message(channel_id, message_id, &self.settings)
+    }
+
+    // Retrieves a message without counting it as used, so that the library's
+    // own lookups don't affect the hit counters or which messages are evicted.
+    pub(crate) fn peek_message(&self, channel_id: ChannelId, message_id: MessageId) -> Option<Message> {
+        self.messages.peek(channel_id, message_id, &self.settings)
+    }
+
+    /// Removes the cached messages which have expired, returning the number of
+    /// removed messages.
+    ///
+    /// Expired messages are otherwise only removed when they are retrieved or
+    /// a new message is cached in their channel, so this may be called
+    /// periodically to free their memory.
+    ///
+    /// Refer to [`Settings::message_ttl`] for more information.
+    ///
+    /// [`Settings::message_ttl`]: struct.Settings.html#structfield.message_ttl
+    pub fn purge_expired_messages(&self) -> usize {
+        self.messages.purge_expired(&self.settings)
+    }
+
     /// Retrieves a [`PrivateChannel`] from the cache's [`private_channels`]
     /// map, if it exists.
     ///
@@ -742,6 +835,164 @@ impl Cache {
         self.categories.get(&channel_id).cloned()
     }
 
+    /// Retrieves the members of a [`Guild`] which have a [`Role`].
+    ///
+    /// The members are looked up through an index of the cache, and are
+    /// copied out of the guild, so no lock is held while iterating over them.
+    ///
+    /// **Note**: The index is maintained by the [`CacheUpdate`] implementations
+    /// of events. Data inserted into the cache's maps directly is not found.
+    /// As no member has the `@everyone` role in their list of roles, none are
+    /// returned for it.
+    ///
+    /// # Examples
+    ///
+    /// Print the names of the members with a role:
+    ///
+    /// ```rust,no_run
+    /// # #[cfg(feature = "client")]
+    /// # fn main() {
+    /// # use serenity::model::prelude::*;
+    /// # use serenity::prelude::*;
+    /// #
+    /// struct Handler;
+    ///
+    /// impl EventHandler for Handler {
+    ///     fn message(&self, ctx: Context, message: Message) {
+    ///         let guild_id = match message.guild_id {
+    ///             Some(guild_id) => guild_id,
+    ///             None => return,
+    ///         };
+    ///
+    ///         for member in ctx.cache.read().members_with_role(guild_id, 77) {
+    ///             println!("{} has the role", member.display_name());
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let mut client = Client::new("token", Handler).unwrap();
+    ///
+    /// client.start().unwrap();
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "client"))]
+    /// # fn main() { }
+    /// ```
+    ///
+    /// [`CacheUpdate`]: trait.CacheUpdate.html
+    /// [`Guild`]: ../model/guild/struct.Guild.html
+    /// [`Role`]: ../model/guild/struct.Role.html
+    #[inline]
+    pub fn members_with_role<G, R>(&self, guild_id: G, role_id: R) -> Vec<Member>
+        where G: Into<GuildId>, R: Into<RoleId> {
+        self._members_with_role(guild_id.into(), role_id.into())
+    }
+
+    fn _members_with_role(&self, guild_id: GuildId, role_id: RoleId) -> Vec<Member> {
+        let user_ids = self.index.members_by_role(role_id);
+
+        self.guilds.get(&guild_id).map_or_else(Vec::new, |guild| {
+            let guild = guild.read();
+
+            user_ids
+                .iter()
+                .filter_map(|user_id| guild.members.get(user_id).cloned())
+                .collect()
+        })
+    }
+
+    /// Retrieves the channels of a [`Guild`] which are of a [`ChannelType`].
+    ///
+    /// The channels are copied, so no lock is held while iterating over them.
+    ///
+    /// Refer to [`members_with_role`] for notes on the index used.
+    ///
+    /// [`ChannelType`]: ../model/channel/enum.ChannelType.html
+    /// [`Guild`]: ../model/guild/struct.Guild.html
+    /// [`members_with_role`]: #method.members_with_role
+    #[inline]
+    pub fn channels_of_kind<G: Into<GuildId>>(&self, guild_id: G, kind: ChannelType)
+        -> Vec<GuildChannel> {
+        self.guild_channels(self.index.channels_by_kind(guild_id.into(), kind))
+    }
+
+    /// Retrieves the channels which are in a channel category.
+    ///
+    /// The channels are copied, so no lock is held while iterating over them.
+    ///
+    /// Refer to [`members_with_role`] for notes on the index used.
+    ///
+    /// [`members_with_role`]: #method.members_with_role
+    #[inline]
+    pub fn channels_in_category<C: Into<ChannelId>>(&self, category_id: C) -> Vec<GuildChannel> {
+        self.guild_channels(self.index.channels_by_parent(category_id.into()))
+    }
+
+    fn guild_channels(&self, channel_ids: Vec<ChannelId>) -> Vec<GuildChannel> {
+        channel_ids
+            .iter()
+            .filter_map(|id| self.channels.get(id).map(|channel| channel.read().clone()))
+            .collect()
+    }
+
+    /// Retrieves the voice states of the users connected to a voice channel.
+    ///
+    /// The voice states are copied, so no lock is held while iterating over
+    /// them.
+    ///
+    /// Refer to [`members_with_role`] for notes on the index used.
+    ///
+    /// [`members_with_role`]: #method.members_with_role
+    #[inline]
+    pub fn voice_states_in_channel<C: Into<ChannelId>>(&self, channel_id: C) -> Vec<VoiceState> {
+        self._voice_states_in_channel(channel_id.into())
+    }
+
+    fn _voice_states_in_channel(&self, channel_id: ChannelId) -> Vec<VoiceState> {
+        self.index
+            .voice_states_by_channel(channel_id)
+            .iter()
+            .filter_map(|&(guild_id, user_id)| {
+                self.guilds
+                    .get(&guild_id)
+                    .and_then(|guild| guild.read().voice_states.get(&user_id).cloned())
+            })
+            .collect()
+    }
+
+    /// Retrieves the members connected to a voice channel.
+    ///
+    /// The members are copied, so no lock is held while iterating over them.
+    /// Users whose member is not cached, such as when the number of cached
+    /// members is bounded, are skipped.
+    ///
+    /// Refer to [`members_with_role`] for notes on the index used.
+    ///
+    /// [`members_with_role`]: #method.members_with_role
+    #[inline]
+    pub fn voice_channel_members<C: Into<ChannelId>>(&self, channel_id: C) -> Vec<Member> {
+        self._voice_channel_members(channel_id.into())
+    }
+
+    fn _voice_channel_members(&self, channel_id: ChannelId) -> Vec<Member> {
+        self.index
+            .voice_states_by_channel(channel_id)
+            .iter()
+            .filter_map(|&(guild_id, user_id)| self._member(guild_id, user_id))
+            .collect()
+    }
+
+    /// Retrieves the Ids of the [`Guild`]s which are owned by a user.
+    ///
+    /// Refer to [`members_with_role`] for notes on the index used.
+    ///
+    /// [`Guild`]: ../model/guild/struct.Guild.html
+    /// [`members_with_role`]: #method.members_with_role
+    #[inline]
+    pub fn guilds_owned_by<U: Into<UserId>>(&self, user_id: U) -> Vec<GuildId> {
+        self.index.guilds_by_owner(user_id.into())
+    }
+
     /// Updates the cache with the update implementation for an event or other
     /// custom update implementation.
     ///
@@ -757,6 +1008,21 @@ impl Cache {
         e.update(self)
     }
 
+    /// Updates the cache with an update implementation which only modifies
+    /// independently locked data, such as a guild or the cached messages.
+    ///
+    /// Unlike [`update`], this only needs the cache to be locked for reading,
+    /// so other readers of the cache are not blocked.
+    ///
+    /// Refer to the documentation for [`ConcurrentCacheUpdate`] for more
+    /// information.
+    ///
+    /// [`ConcurrentCacheUpdate`]: trait.ConcurrentCacheUpdate.html
+    /// [`update`]: #method.update
+    pub fn update_concurrently<E: ConcurrentCacheUpdate>(&self, e: &mut E) -> Option<E::Output> {
+        e.update_concurrently(self)
+    }
+
     /// Gets the duration it will try for when acquiring a write lock.
     ///
     /// Refer to the documentation for [`cache_lock_time`] for more information.
@@ -766,16 +1032,71 @@ impl Cache {
         self.settings.cache_lock_time
     }
 
-    pub(crate) fn update_user_entry(&mut self, user: &User) {
-        match self.users.entry(user.id) {
-            Entry::Vacant(e) => {
-                e.insert(Arc::new(RwLock::new(user.clone())));
-            },
-            Entry::Occupied(mut e) => {
-                e.get_mut().write().clone_from(user);
-            },
+    // The maximum number of members to store in a guild when storing the
+    // member of the given user.
+    //
+    // The member of the current user is always stored.
+    pub(crate) fn max_members_for(&self, user_id: UserId) -> Option<usize> {
+        if user_id == self.user.id {
+            None
+        } else {
+            self.settings.max_members
         }
     }
+
+    // Caches the channels of a guild, removing the indexed data of the
+    // channels they replace.
+    fn insert_guild_channels(&mut self, guild: &Guild) {
+        for (id, channel) in &guild.channels {
+            if let Some(old) = self.channels.insert(*id, Arc::clone(channel)) {
+                self.index.channel(Some(ChannelKeys::from(&*old.read())), None);
+            }
+        }
+    }
+}
+
+fn is_full<K: Eq + Hash, V>(map: &HashMap<K, V>, max: Option<usize>) -> bool {
+    max.map_or(false, |max| map.len() >= max)
+}
+
+// Inserts the value into the map, unless the key is new and the map already
+// holds the maximum number of entries.
+pub(crate) fn insert_bounded<K: Eq + Hash, V>(
+    map: &mut HashMap<K, V>,
+    max: Option<usize>,
+    key: K,
+    value: V,
+) -> Option<V> {
+    if is_full(map, max) && !map.contains_key(&key) {
+        return None;
+    }
+
+    map.insert(key, value)
+}
+
+// Removes arbitrary entries from the map until it holds at most the maximum
+// number of entries, never removing the entry of `keep`.
+pub(crate) fn retain_bounded<K: Copy + Eq + Hash, V>(
+    map: &mut HashMap<K, V>,
+    max: Option<usize>,
+    keep: Option<K>,
+) {
+    let max = match max {
+        Some(max) if map.len() > max => max,
+        _ => return,
+    };
+
+    let excess = map.len() - max;
+    let removed = map
+        .keys()
+        .filter(|key| Some(**key) != keep)
+        .take(excess)
+        .cloned()
+        .collect::<Vec<K>>();
+
+    for key in removed {
+        map.remove(&key);
+    }
 }
 
 impl Default for Cache {
@@ -785,16 +1106,17 @@ impl Default for Cache {
             categories: HashMap::default(),
             groups: HashMap::with_capacity(128),
             guilds: HashMap::default(),
-            messages: HashMap::default(),
+            index: Index::default(),
+            messages: MessageCache::default(),
             notes: HashMap::default(),
             presences: HashMap::default(),
             private_channels: HashMap::with_capacity(128),
             settings: Settings::default(),
             shard_count: 1,
+            stale_guilds: HashSet::default(),
             unavailable_guilds: HashSet::default(),
             user: CurrentUser::default(),
             users: HashMap::default(),
-            message_queue: HashMap::default(),
             __nonexhaustive: (),
         }
     }
@@ -807,11 +1129,13 @@ mod test {
     use std::{
         collections::HashMap,
         sync::Arc,
+        thread,
     };
     use {
-        cache::{Cache, CacheUpdate, Settings},
+        cache::{Cache, CacheBackend, CacheError, CacheUpdate, Settings, SNAPSHOT_VERSION},
         model::prelude::*,
         prelude::RwLock,
+        Error,
     };
 
     #[test]
@@ -880,6 +1204,45 @@ mod test {
             assert!(!channel.contains_key(&MessageId(3)));
         }
 
+        // Update a cached message, the message as it was before the update
+        // should be returned.
+        let mut update = MessageUpdateEvent {
+            id: MessageId(4),
+            channel_id: ChannelId(2),
+            kind: None,
+            content: Some("edited".to_owned()),
+            nonce: None,
+            tts: None,
+            pinned: None,
+            timestamp: None,
+            edited_timestamp: None,
+            author: None,
+            mention_everyone: None,
+            mentions: None,
+            mention_roles: None,
+            attachments: None,
+            embeds: None,
+        };
+        assert_eq!(cache.update(&mut update).unwrap().content, "");
+        assert_eq!(cache.message(ChannelId(2), MessageId(4)).unwrap().content, "edited");
+
+        // Delete the updated message, the deleted message should be returned
+        // only the first time.
+        let mut message_delete = MessageDeleteEvent {
+            channel_id: ChannelId(2),
+            message_id: MessageId(4),
+        };
+        assert_eq!(cache.update(&mut message_delete).unwrap().content, "edited");
+        assert!(cache.update(&mut message_delete).is_none());
+
+        // Bulk delete the rest, only the cached messages should be returned.
+        let mut message_delete_bulk = MessageDeleteBulkEvent {
+            channel_id: ChannelId(2),
+            ids: vec![MessageId(4), MessageId(5)],
+        };
+        assert_eq!(cache.update(&mut message_delete_bulk).unwrap().len(), 1);
+        assert!(cache.messages.get(&ChannelId(2)).unwrap().is_empty());
+
         let guild_channel = GuildChannel {
             id: event.message.channel_id,
             bitrate: None,
@@ -970,4 +1333,312 @@ mod test {
         // Assert that the channel's message cache no longer exists.
         assert!(!cache.messages.contains_key(&ChannelId(2)));
     }
+
+    #[test]
+    fn test_cache_limits() {
+        let mut settings = Settings::new();
+        settings.max_presences(Some(1)).max_users(Some(0));
+        let mut cache = Cache::new_with_settings(settings);
+
+        let user = |id| User {
+            id: UserId(id),
+            avatar: None,
+            bot: false,
+            discriminator: 1,
+            name: format!("user {}", id),
+        };
+        let presence = |id| Presence {
+            activities: vec![],
+            game: None,
+            last_modified: None,
+            nick: None,
+            status: OnlineStatus::Online,
+            user_id: UserId(id),
+            user: Some(Arc::new(RwLock::new(user(id)))),
+        };
+
+        let mut first = PresenceUpdateEvent {
+            guild_id: None,
+            presence: presence(1),
+            roles: None,
+        };
+        let mut second = PresenceUpdateEvent {
+            guild_id: None,
+            presence: presence(2),
+            roles: None,
+        };
+        cache.update(&mut first);
+        cache.update(&mut second);
+
+        // Only the first presence fits, and users are not cached at all.
+        assert_eq!(cache.presences.len(), 1);
+        assert!(cache.presences.contains_key(&UserId(1)));
+        assert!(cache.users.is_empty());
+
+        // Existing presences are still updated once the map is full.
+        first.presence.status = OnlineStatus::Idle;
+        cache.update(&mut first);
+        assert_eq!(cache.presences[&UserId(1)].status, OnlineStatus::Idle);
+
+        // An uncached user is still given to the presence.
+        assert_eq!(first.presence.user.unwrap().read().id, UserId(1));
+    }
+
+    #[test]
+    fn test_cache_snapshot() {
+        let mut event: GuildCreateEvent = fixture!("guild_create_1");
+        let guild_id = event.guild.id;
+
+        let mut cache = Cache::default();
+        cache.update(&mut event);
+
+        let mut snapshot = Vec::new();
+        cache.save_snapshot(&mut snapshot).unwrap();
+
+        // Loaded guilds are available, but stale until received again.
+        let mut loaded = Cache::default();
+        loaded.load_snapshot(&snapshot[..]).unwrap();
+
+        {
+            let guild = loaded.guilds[&guild_id].read();
+
+            assert_eq!(guild.channels.len(), event.guild.channels.len());
+            assert_eq!(guild.members.len(), event.guild.members.len());
+            assert_eq!(guild.roles.len(), event.guild.roles.len());
+            assert_eq!(loaded.channels.len(), cache.channels.len());
+            assert_eq!(loaded.users.len(), cache.users.len());
+
+            // Members share their user with the cache's users.
+            for member in guild.members.values() {
+                let user_id = member.user.read().id;
+
+                assert!(Arc::ptr_eq(&member.user, &loaded.users[&user_id]));
+            }
+        }
+
+        assert!(loaded.stale_guilds.contains(&guild_id));
+
+        loaded.update(&mut event);
+        assert!(loaded.stale_guilds.is_empty());
+
+        // Channels still cached while their guild is unavailable are replaced
+        // along with their indexed data.
+        let channel_id = *event.guild.channels.keys().next().unwrap();
+        let mut channel = event.guild.channels[&channel_id].read().clone();
+        channel.category_id = Some(ChannelId(3));
+        loaded.update(&mut ChannelUpdateEvent {
+            channel: Channel::Guild(Arc::new(RwLock::new(channel))),
+        });
+        loaded.update(&mut GuildUnavailableEvent {
+            guild_id,
+        });
+        assert_eq!(loaded.channels_in_category(3).len(), 1);
+
+        loaded.load_snapshot(&snapshot[..]).unwrap();
+        assert!(loaded.channels_in_category(3).is_empty());
+
+        // Snapshots of other versions are refused.
+        let mut value: Value = ::serde_json::from_slice(&snapshot).unwrap();
+        value["version"] = Value::from(SNAPSHOT_VERSION + 1);
+        let snapshot = ::serde_json::to_vec(&value).unwrap();
+
+        match Cache::default().load_snapshot(&snapshot[..]) {
+            Err(Error::Cache(CacheError::UnsupportedSnapshotVersion(version))) => {
+                assert_eq!(version, SNAPSHOT_VERSION + 1);
+            },
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_cache_backend() {
+        let mut event: GuildCreateEvent = fixture!("guild_create_1");
+        let guild_id = event.guild.id;
+        let channel_id = *event.guild.channels.keys().next().unwrap();
+
+        // Events update a backend through the trait alone.
+        let mut settings = Settings::new();
+        settings.max_messages(10);
+        let mut cache = Cache::new_with_settings(settings);
+
+        {
+            let backend: &mut CacheBackend = &mut cache;
+
+            CacheUpdate::update(&mut event, backend);
+
+            assert!(backend.guild(guild_id).is_some());
+            assert!(backend.channel(channel_id).is_some());
+
+            let mut role = GuildRoleCreateEvent {
+                guild_id,
+                role: event.guild.roles.values().next().unwrap().clone(),
+            };
+            role.role.id = RoleId(1);
+            CacheUpdate::update(&mut role, backend);
+            assert!(backend.role(guild_id, RoleId(1)).is_some());
+
+            // The guild's channels and their messages are kept while it is
+            // unavailable, and removed along with it.
+            let mut message: Message = fixture!("message_create_1");
+            message.channel_id = channel_id;
+            let message_id = message.id;
+            backend.insert_message(message);
+            assert!(backend.message(channel_id, message_id).is_some());
+
+            let mut unavailable = GuildUnavailableEvent {
+                guild_id,
+            };
+            CacheUpdate::update(&mut unavailable, backend);
+            assert!(backend.guild(guild_id).is_none());
+            assert!(backend.channel(channel_id).is_some());
+            assert!(backend.message(channel_id, message_id).is_some());
+
+            CacheUpdate::update(&mut event, backend);
+            assert!(backend.remove_guild(guild_id).is_some());
+            assert!(backend.channel(channel_id).is_none());
+        }
+
+        assert!(cache.guilds.is_empty());
+        assert!(cache.channels.is_empty());
+        assert!(cache.messages.is_empty());
+    }
+
+    #[test]
+    fn test_cache_queries() {
+        let mut event: GuildCreateEvent = fixture!("guild_create_1");
+        let guild_id = event.guild.id;
+        let owner_id = event.guild.owner_id;
+        let user = event.guild.members.values().next().unwrap().user.read().clone();
+        let mut cache = Cache::new();
+        cache.update(&mut event);
+
+        assert_eq!(cache.guilds_owned_by(owner_id), vec![guild_id]);
+
+        let voice_channels = cache.channels_of_kind(guild_id, ChannelType::Voice);
+        let expected = event.guild.channels
+            .values()
+            .filter(|channel| channel.read().kind == ChannelType::Voice)
+            .count();
+        assert_eq!(voice_channels.len(), expected);
+
+        // Members are indexed by their current roles.
+        let mut update = GuildMemberUpdateEvent {
+            guild_id,
+            nick: None,
+            roles: vec![RoleId(1), RoleId(2)],
+            user: user.clone(),
+        };
+        cache.update(&mut update);
+        assert_eq!(cache.members_with_role(guild_id, 1).len(), 1);
+
+        update.roles = vec![RoleId(2)];
+        cache.update(&mut update);
+        assert!(cache.members_with_role(guild_id, 1).is_empty());
+        assert_eq!(cache.members_with_role(guild_id, 2).len(), 1);
+
+        // Channels are indexed by their category.
+        let mut channel = voice_channels[0].clone();
+        channel.category_id = Some(ChannelId(3));
+        let mut channel_update = ChannelUpdateEvent {
+            channel: Channel::Guild(Arc::new(RwLock::new(channel.clone()))),
+        };
+        cache.update(&mut channel_update);
+        let in_category = cache.channels_in_category(3);
+        assert_eq!(in_category.len(), 1);
+        assert_eq!(in_category[0].id, channel.id);
+
+        // Voice states follow the user between channels.
+        let mut voice_state: VoiceStateUpdateEvent = fixture!("voice_state_update_1");
+        voice_state.guild_id = Some(guild_id);
+        voice_state.voice_state.channel_id = Some(channel.id);
+        assert!(cache.update(&mut voice_state).is_none());
+        assert_eq!(cache.voice_states_in_channel(channel.id).len(), 1);
+
+        voice_state.voice_state.channel_id = None;
+        let old = cache.update(&mut voice_state).unwrap();
+        assert_eq!(old.channel_id, Some(channel.id));
+        assert!(cache.voice_states_in_channel(channel.id).is_empty());
+        assert!(cache.voice_channel_members(channel.id).is_empty());
+
+        // Members are no longer found by roles removed from the guild.
+        let role_id = event.guild.members
+            .values()
+            .filter_map(|member| member.roles.first())
+            .next()
+            .cloned()
+            .unwrap();
+        assert!(!cache.members_with_role(guild_id, role_id).is_empty());
+
+        let mut guild_update: GuildUpdateEvent = fixture!("guild_update_1");
+        guild_update.guild.id = guild_id;
+        guild_update.guild.owner_id = owner_id;
+        guild_update.guild.roles.clear();
+        cache.update(&mut guild_update);
+        assert!(cache.members_with_role(guild_id, role_id).is_empty());
+
+        let mut unavailable = GuildUnavailableEvent {
+            guild_id,
+        };
+        cache.update(&mut unavailable);
+        assert!(cache.guilds_owned_by(owner_id).is_empty());
+        // The channels of an unavailable guild are kept.
+        assert_eq!(cache.channels_of_kind(guild_id, ChannelType::Voice).len(), expected);
+        assert!(cache.members_with_role(guild_id, 2).is_empty());
+    }
+
+    #[test]
+    fn test_concurrent_update() {
+        let mut settings = Settings::new();
+        settings.max_messages(10);
+        let cache = Arc::new(RwLock::new(Cache::new_with_settings(settings)));
+        let mut event: MessageCreateEvent = fixture!("message_create_1");
+        let channel_id = event.message.channel_id;
+        let message_id = event.message.id;
+
+        // Messages are cached while another reader holds the cache.
+        let reader = cache.read();
+
+        let thread = {
+            let cache = Arc::clone(&cache);
+
+            thread::spawn(move || {
+                cache.read().update_concurrently(&mut event);
+            })
+        };
+        thread.join().unwrap();
+
+        assert!(reader.message(channel_id, message_id).is_some());
+    }
+
+    #[cfg(feature = "model")]
+    #[test]
+    fn test_independent_caches() {
+        let first = RwLock::new(Cache::default());
+        let second = RwLock::new(Cache::default());
+
+        let mut event = PresenceUpdateEvent {
+            guild_id: None,
+            presence: Presence {
+                activities: vec![],
+                game: None,
+                last_modified: None,
+                nick: None,
+                status: OnlineStatus::Online,
+                user_id: UserId(1),
+                user: Some(Arc::new(RwLock::new(User {
+                    id: UserId(1),
+                    avatar: None,
+                    bot: false,
+                    discriminator: 1,
+                    name: "user 1".to_owned(),
+                }))),
+            },
+            roles: None,
+        };
+        first.write().update(&mut event);
+
+        // Only the cache that received the event knows about the user.
+        assert!(UserId(1).to_user_cached(&first).is_some());
+        assert!(UserId(1).to_user_cached(&second).is_none());
+    }
 }
@@ -0,0 +1,341 @@
+use http;
+use internal::prelude::*;
+use model::prelude::*;
+use parking_lot::RwLock;
+use serde::Serialize;
+use serde_json;
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::Arc,
+};
+use super::{Cache, CacheBackend, CacheError};
+
+// The maximum number of members which can be retrieved per request.
+const MEMBERS_PER_REQUEST: u64 = 1000;
+
+/// The differences between the cached data of a collection and the data
+/// retrieved over the REST API, which were corrected by [`reconcile_guild`].
+///
+/// [`reconcile_guild`]: fn.reconcile_guild.html
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CollectionDrift<K> {
+    /// The Ids of entries which were missing from the cache, and were added.
+    pub added: Vec<K>,
+    /// The Ids of entries which no longer exist, and were removed from the
+    /// cache.
+    pub removed: Vec<K>,
+    /// The Ids of entries which were outdated in the cache, and were replaced.
+    pub updated: Vec<K>,
+}
+
+impl<K> CollectionDrift<K> {
+    /// Whether the cached collection did not differ.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.updated.is_empty()
+    }
+}
+
+/// The differences between the cached data of a guild and the data retrieved
+/// over the REST API, which were corrected by [`reconcile_guild`].
+///
+/// [`reconcile_guild`]: fn.reconcile_guild.html
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Drift {
+    /// The drift of the guild's channels.
+    pub channels: CollectionDrift<ChannelId>,
+    /// The drift of the guild's custom emojis.
+    pub emojis: CollectionDrift<EmojiId>,
+    /// The drift of the guild's members.
+    pub members: CollectionDrift<UserId>,
+    /// The drift of the guild's roles.
+    pub roles: CollectionDrift<RoleId>,
+}
+
+impl Drift {
+    /// Whether the cached data of the guild did not differ.
+    pub fn is_empty(&self) -> bool {
+        self.channels.is_empty()
+            && self.emojis.is_empty()
+            && self.members.is_empty()
+            && self.roles.is_empty()
+    }
+}
+
+/// Retrieves the channels, roles, emojis, and members of a cached guild over
+/// the REST API, corrects the cached data where it differs, and reports the
+/// differences.
+///
+/// Events can be missed by the gateway, such as when members update their
+/// roles, so the cached data of a guild may drift from its actual state over
+/// long uptimes. This can be called on demand, or periodically from a
+/// separate thread.
+///
+/// The requests are made through the [`http`] module, and are ratelimited as
+/// usual. Members are retrieved in batches of 1000, so large guilds need many
+/// requests.
+///
+/// The cache is not locked while the data is retrieved, and is only locked
+/// for reading while the cached data is copied to be compared. It is then
+/// locked for writing while the differences are corrected. When the number of
+/// cached members is bounded by [`Settings::max_members`], cached members are
+/// updated or removed, but missing members are not added.
+///
+/// Fields which change without an event for the model, such as the last
+/// message of a channel, are not compared.
+///
+/// # Examples
+///
+/// Reconcile every guild in the client's cache once an hour:
+///
+/// ```rust,no_run
+/// # #[cfg(feature = "client")]
+/// # fn main() {
+/// # use serenity::prelude::*;
+/// #
+/// # struct Handler;
+/// #
+/// # impl EventHandler for Handler {}
+/// #
+/// use serenity::cache::reconcile_guild;
+/// use std::{sync::Arc, thread, time::Duration};
+///
+/// let mut client = Client::new("token", Handler).unwrap();
+/// let cache = Arc::clone(&client.cache);
+///
+/// thread::spawn(move || loop {
+///     thread::sleep(Duration::from_secs(60 * 60));
+///
+///     let guild_ids = cache.read().guilds.keys().cloned().collect::<Vec<_>>();
+///
+///     for guild_id in guild_ids {
+///         match reconcile_guild(&cache, guild_id) {
+///             Ok(ref drift) if !drift.is_empty() => {
+///                 println!("Corrected {}: {:?}", guild_id, drift);
+///             },
+///             Ok(_) => {},
+///             Err(why) => println!("Error reconciling {}: {:?}", guild_id, why),
+///         }
+///     }
+/// });
+///
+/// client.start().unwrap();
+/// # }
+/// #
+/// # #[cfg(not(feature = "client"))]
+/// # fn main() { }
+/// ```
+///
+/// # Errors
+///
+/// Returns a [`CacheError::GuildNotCached`] if the guild is not in the cache.
+///
+/// Returns an [`Error::Http`] if a request fails, in which case the cache is
+/// not modified.
+///
+/// [`CacheError::GuildNotCached`]: enum.CacheError.html#variant.GuildNotCached
+/// [`Error::Http`]: ../enum.Error.html#variant.Http
+/// [`Settings::max_members`]: struct.Settings.html#structfield.max_members
+/// [`http`]: ../http/index.html
+pub fn reconcile_guild<G: Into<GuildId>>(cache: &RwLock<Cache>, guild_id: G) -> Result<Drift> {
+    let guild_id = guild_id.into();
+
+    if !cache.read().guilds.contains_key(&guild_id) {
+        return Err(Error::Cache(CacheError::GuildNotCached(guild_id)));
+    }
+
+    let partial = http::get_guild(guild_id.0)?;
+    let channels = http::get_channels(guild_id.0)?
+        .into_iter()
+        .map(|channel| (channel.id, channel))
+        .collect::<HashMap<_, _>>();
+    let members = fetch_members(guild_id)?;
+
+    let (cached_channels, cached_emojis, cached_members, cached_roles, bounded) = {
+        let cache = cache.read();
+        let guild = match cache.guilds.get(&guild_id) {
+            Some(guild) => guild.read(),
+            None => return Err(Error::Cache(CacheError::GuildNotCached(guild_id))),
+        };
+        let channels = guild.channels
+            .iter()
+            .map(|(id, channel)| (*id, channel.read().clone()))
+            .collect::<HashMap<_, _>>();
+        let bounded = cache.settings().max_members.is_some();
+
+        (channels, guild.emojis.clone(), guild.members.clone(), guild.roles.clone(), bounded)
+    };
+
+    let mut drift = Drift {
+        channels: diff(&cached_channels, &channels),
+        emojis: diff(&cached_emojis, &partial.emojis),
+        members: diff(&cached_members, &members),
+        roles: diff(&cached_roles, &partial.roles),
+    };
+
+    if bounded {
+        drift.members.added.clear();
+    }
+
+    let mut cache = cache.write();
+
+    // The guild may have been removed while the drift was determined.
+    if !cache.guilds.contains_key(&guild_id) {
+        return Err(Error::Cache(CacheError::GuildNotCached(guild_id)));
+    }
+
+    for channel_id in &drift.channels.removed {
+        cache.remove_channel(*channel_id);
+    }
+
+    for channel_id in drift.channels.added.iter().chain(&drift.channels.updated) {
+        let channel = channels[channel_id].clone();
+
+        cache.insert_channel(Channel::Guild(Arc::new(RwLock::new(channel))));
+    }
+
+    for role_id in &drift.roles.removed {
+        cache.remove_role(guild_id, *role_id);
+    }
+
+    for role_id in drift.roles.added.iter().chain(&drift.roles.updated) {
+        cache.insert_role(guild_id, partial.roles[role_id].clone());
+    }
+
+    if !drift.emojis.is_empty() {
+        cache.update(&mut GuildEmojisUpdateEvent {
+            emojis: partial.emojis,
+            guild_id,
+        });
+    }
+
+    for user_id in &drift.members.removed {
+        cache.remove_member(guild_id, *user_id);
+    }
+
+    for user_id in drift.members.added.iter().chain(&drift.members.updated) {
+        cache.insert_member(guild_id, members[user_id].clone());
+    }
+
+    Ok(drift)
+}
+
+fn fetch_members(guild_id: GuildId) -> Result<HashMap<UserId, Member>> {
+    let mut members = HashMap::new();
+    let mut after = None;
+
+    loop {
+        let batch = http::get_guild_members(guild_id.0, Some(MEMBERS_PER_REQUEST), after)?;
+        let count = batch.len() as u64;
+
+        for member in batch {
+            let user_id = member.user.read().id;
+
+            after = Some(user_id.0);
+            members.insert(user_id, member);
+        }
+
+        if count < MEMBERS_PER_REQUEST {
+            return Ok(members);
+        }
+    }
+}
+
+// A model which is compared by its serialized form when reconciling it, as
+// the models can not be compared directly.
+trait Reconciled: Serialize {
+    // The fields which change without an event updating the model, and so are
+    // expected to differ from the cache.
+    const VOLATILE_FIELDS: &'static [&'static str] = &[];
+}
+
+impl Reconciled for Emoji {}
+
+impl Reconciled for GuildChannel {
+    const VOLATILE_FIELDS: &'static [&'static str] = &["last_message_id", "last_pin_timestamp"];
+}
+
+impl Reconciled for Member {}
+
+impl Reconciled for Role {}
+
+fn compared<V: Reconciled>(value: &V) -> Option<Value> {
+    let mut value = serde_json::to_value(value).ok()?;
+
+    if let Some(map) = value.as_object_mut() {
+        for field in V::VOLATILE_FIELDS {
+            map.remove(*field);
+        }
+    }
+
+    Some(value)
+}
+
+// Compares the cached entries of a collection with the retrieved entries.
+fn diff<K, V>(cached: &HashMap<K, V>, retrieved: &HashMap<K, V>) -> CollectionDrift<K>
+    where K: Copy + Eq + Hash, V: Reconciled {
+    let mut drift = CollectionDrift {
+        added: Vec::new(),
+        removed: Vec::new(),
+        updated: Vec::new(),
+    };
+
+    for (id, value) in retrieved {
+        match cached.get(id) {
+            Some(cached) => if compared(cached) != compared(value) {
+                drift.updated.push(*id);
+            },
+            None => drift.added.push(*id),
+        }
+    }
+
+    drift.removed.extend(cached.keys().filter(|id| !retrieved.contains_key(id)));
+
+    drift
+}
+
+#[cfg(test)]
+mod test {
+    use model::prelude::*;
+    use std::collections::HashMap;
+    use super::{diff, Reconciled};
+
+    impl Reconciled for &'static str {}
+
+    #[test]
+    fn test_diff() {
+        let cached = vec![(1, "a"), (2, "b"), (3, "c")].into_iter().collect::<HashMap<_, _>>();
+        let retrieved = vec![(2, "b"), (3, "d"), (4, "e")].into_iter().collect::<HashMap<_, _>>();
+
+        let drift = diff(&cached, &retrieved);
+
+        assert_eq!(drift.added, vec![4]);
+        assert_eq!(drift.removed, vec![1]);
+        assert_eq!(drift.updated, vec![3]);
+        assert!(diff(&cached, &cached).is_empty());
+    }
+
+    #[test]
+    fn test_diff_models() {
+        let guild: Guild = fixture!("guild_create_1");
+        let cached_channels = guild.channels
+            .iter()
+            .map(|(id, channel)| (*id, channel.read().clone()))
+            .collect::<HashMap<_, _>>();
+        let channel_id = *cached_channels.keys().next().unwrap();
+
+        // The last message of a channel changes without an update event.
+        let mut channels = cached_channels.clone();
+        channels.get_mut(&channel_id).unwrap().last_message_id = Some(MessageId(1));
+        assert!(diff(&cached_channels, &channels).is_empty());
+
+        channels.get_mut(&channel_id).unwrap().name = "renamed".to_owned();
+        assert_eq!(diff(&cached_channels, &channels).updated, vec![channel_id]);
+
+        let user_id = *guild.members.keys().next().unwrap();
+        let mut members = guild.members.clone();
+        members.get_mut(&user_id).unwrap().roles.push(RoleId(1));
+        assert_eq!(diff(&guild.members, &members).updated, vec![user_id]);
+        assert!(diff(&guild.members, &guild.members).is_empty());
+    }
+}
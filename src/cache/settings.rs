@@ -1,7 +1,16 @@
-use std::time::Duration;
+use model::id::{ChannelId, GuildId};
+use std::{
+    collections::HashMap,
+    time::Duration,
+};
 
 /// Settings for the cache.
 ///
+/// Most collections of the cache can be bounded via a `max_*` setting. Once a
+/// collection is full, new entries are no longer added to it while existing
+/// entries are still updated. A maximum of `Some(0)` disables a collection
+/// entirely. Events are dispatched as usual regardless of these settings.
+///
 /// # Examples
 ///
 /// Create new settings, specifying the maximum number of messages:
@@ -12,20 +21,114 @@ use std::time::Duration;
 /// let mut settings = CacheSettings::new();
 /// settings.max_messages(10);
 /// ```
+///
+/// Disable caching presences and bound the number of members per guild:
+///
+/// ```rust
+/// use serenity::cache::Settings as CacheSettings;
+///
+/// let mut settings = CacheSettings::new();
+/// settings
+///     .max_presences(Some(0))
+///     .max_members(Some(1000));
+/// ```
 #[derive(Clone, Debug)]
 pub struct Settings {
     /// The maximum number of messages to store in a channel's message cache.
     ///
+    /// Once a channel's message cache is full, its oldest message is removed
+    /// to make room for a new one.
+    ///
+    /// This can be overridden for the channels of a guild via
+    /// [`guild_max_messages`], and for a single channel via
+    /// [`channel_max_messages`].
+    ///
     /// Defaults to 0.
+    ///
+    /// [`channel_max_messages`]: #structfield.channel_max_messages
+    /// [`guild_max_messages`]: #structfield.guild_max_messages
     pub max_messages: usize,
 
-    /// The Duration cache updates will try to acquire write-locks for.
+    /// The maximum number of messages to store in each of a guild's channels,
+    /// taking precedence over [`max_messages`].
+    ///
+    /// [`max_messages`]: #structfield.max_messages
+    pub guild_max_messages: HashMap<GuildId, usize>,
+
+    /// The maximum number of messages to store in a channel, taking precedence
+    /// over [`guild_max_messages`] and [`max_messages`].
+    ///
+    /// [`guild_max_messages`]: #structfield.guild_max_messages
+    /// [`max_messages`]: #structfield.max_messages
+    pub channel_max_messages: HashMap<ChannelId, usize>,
+
+    /// The maximum number of messages to store across all channels.
+    ///
+    /// Once the message cache is full, the least recently used messages are
+    /// removed to make room for new ones. Messages are used when they are
+    /// cached or retrieved from the cache.
+    ///
+    /// Only messages cached while this is set are tracked for removal.
+    ///
+    /// Defaults to `None`, only bounding the messages of each channel.
+    pub max_total_messages: Option<usize>,
+
+    /// The duration after which a cached message expires.
+    ///
+    /// Expired messages are no longer retrieved from the cache. They are
+    /// removed once they are retrieved, when a new message is cached in their
+    /// channel, or via [`Cache::purge_expired_messages`].
+    ///
+    /// Defaults to `None`, never expiring messages.
+    ///
+    /// [`Cache::purge_expired_messages`]: struct.Cache.html#method.purge_expired_messages
+    pub message_ttl: Option<Duration>,
+
+    /// The maximum number of channel categories to store.
+    ///
+    /// Defaults to `None`, storing every category.
+    pub max_categories: Option<usize>,
+
+    /// The maximum number of members to store in each guild.
+    ///
+    /// The member of the current user is always stored, as it is needed to
+    /// calculate permissions.
+    ///
+    /// Defaults to `None`, storing every member.
+    pub max_members: Option<usize>,
+
+    /// The maximum number of presences to store in each guild, as well as in
+    /// the cache's own map of presences.
+    ///
+    /// Defaults to `None`, storing every presence.
+    pub max_presences: Option<usize>,
+
+    /// The maximum number of private channels to store.
+    ///
+    /// Defaults to `None`, storing every private channel.
+    pub max_private_channels: Option<usize>,
+
+    /// The maximum number of users to store in the cache's map of users.
+    ///
+    /// Users which are not stored are still given to members and channels,
+    /// but are no longer shared between them.
+    ///
+    /// Defaults to `None`, storing every user.
+    pub max_users: Option<usize>,
+
+    /// The Duration cache updates will try to acquire locks for.
+    ///
+    /// Updates with events which only modify independently locked data, such
+    /// as messages, only acquire a read-lock. Refer to
+    /// [`ConcurrentCacheUpdate`] for more information.
     ///
     /// Defaults to 10 milliseconds.
     ///
     /// **Note**:
-    /// If set to `None`, cache updates will acquire write-lock until available,
+    /// If set to `None`, cache updates will acquire locks until available,
     /// potentially deadlocking.
+    ///
+    /// [`ConcurrentCacheUpdate`]: trait.ConcurrentCacheUpdate.html
     pub cache_lock_time: Option<Duration>,
     __nonexhaustive: (),
 }
@@ -34,6 +137,15 @@ impl Default for Settings {
     fn default() -> Self {
         Settings {
             max_messages: usize::default(),
+            guild_max_messages: HashMap::new(),
+            channel_max_messages: HashMap::new(),
+            max_total_messages: None,
+            message_ttl: None,
+            max_categories: None,
+            max_members: None,
+            max_presences: None,
+            max_private_channels: None,
+            max_users: None,
             cache_lock_time: Some(Duration::from_millis(10)),
             __nonexhaustive: (),
         }
@@ -69,14 +181,137 @@ impl Settings {
         self
     }
 
+    /// Sets the maximum number of messages to cache in each channel of a guild.
+    ///
+    /// Refer to [`guild_max_messages`] for more information.
+    ///
+    /// [`guild_max_messages`]: #structfield.guild_max_messages
+    pub fn guild_max_messages<G: Into<GuildId>>(&mut self, guild_id: G, max: usize) -> &mut Self {
+        self.guild_max_messages.insert(guild_id.into(), max);
+
+        self
+    }
+
+    /// Sets the maximum number of messages to cache in a channel.
+    ///
+    /// Refer to [`channel_max_messages`] for more information.
+    ///
+    /// # Examples
+    ///
+    /// Cache more messages of one channel, and none of another:
+    ///
+    /// ```rust
+    /// use serenity::cache::Settings;
+    ///
+    /// let mut settings = Settings::new();
+    /// settings
+    ///     .max_messages(10)
+    ///     .channel_max_messages(381880193700069377, 100)
+    ///     .channel_max_messages(381880193700069378, 0);
+    /// ```
+    ///
+    /// [`channel_max_messages`]: #structfield.channel_max_messages
+    pub fn channel_max_messages<C: Into<ChannelId>>(&mut self, channel_id: C, max: usize) -> &mut Self {
+        self.channel_max_messages.insert(channel_id.into(), max);
+
+        self
+    }
+
+    /// Sets the maximum number of messages to cache across all channels.
+    ///
+    /// Refer to [`max_total_messages`] for more information.
+    ///
+    /// [`max_total_messages`]: #structfield.max_total_messages
+    pub fn max_total_messages(&mut self, max: Option<usize>) -> &mut Self {
+        self.max_total_messages = max;
+
+        self
+    }
+
+    /// Sets the duration after which a cached message expires.
+    ///
+    /// Refer to [`message_ttl`] for more information.
+    ///
+    /// # Examples
+    ///
+    /// Keep messages for at most an hour:
+    ///
+    /// ```rust
+    /// use serenity::cache::Settings;
+    /// use std::time::Duration;
+    ///
+    /// let mut settings = Settings::new();
+    /// settings
+    ///     .max_messages(50)
+    ///     .message_ttl(Some(Duration::from_secs(60 * 60)));
+    /// ```
+    ///
+    /// [`message_ttl`]: #structfield.message_ttl
+    pub fn message_ttl(&mut self, ttl: Option<Duration>) -> &mut Self {
+        self.message_ttl = ttl;
+
+        self
+    }
+
+    /// Sets the maximum number of channel categories to store.
+    ///
+    /// Refer to [`max_categories`] for more information.
+    ///
+    /// [`max_categories`]: #structfield.max_categories
+    pub fn max_categories(&mut self, max: Option<usize>) -> &mut Self {
+        self.max_categories = max;
+
+        self
+    }
+
+    /// Sets the maximum number of members to store in each guild.
+    ///
+    /// Refer to [`max_members`] for more information.
+    ///
+    /// [`max_members`]: #structfield.max_members
+    pub fn max_members(&mut self, max: Option<usize>) -> &mut Self {
+        self.max_members = max;
+
+        self
+    }
+
+    /// Sets the maximum number of presences to store in each guild and in the cache.
+    ///
+    /// Refer to [`max_presences`] for more information.
+    ///
+    /// [`max_presences`]: #structfield.max_presences
+    pub fn max_presences(&mut self, max: Option<usize>) -> &mut Self {
+        self.max_presences = max;
+
+        self
+    }
+
+    /// Sets the maximum number of private channels to store.
+    ///
+    /// Refer to [`max_private_channels`] for more information.
+    ///
+    /// [`max_private_channels`]: #structfield.max_private_channels
+    pub fn max_private_channels(&mut self, max: Option<usize>) -> &mut Self {
+        self.max_private_channels = max;
+
+        self
+    }
+
+    /// Sets the maximum number of users to store.
+    ///
+    /// Refer to [`max_users`] for more information.
+    ///
+    /// [`max_users`]: #structfield.max_users
+    pub fn max_users(&mut self, max: Option<usize>) -> &mut Self {
+        self.max_users = max;
+
+        self
+    }
+
     /// Sets the duration that the cache will try to aquire a write lock.
     ///
     /// Refer to [`cache_lock_time`] for more information.
     ///
-    /// **Note**:
-    /// Should be set before the client gets started, as it can not be
-    /// changed after the first read of the duration.
-    ///
     /// # Examples
     ///
     /// Set the time that it will try to aquire a lock.
@@ -93,10 +328,10 @@ impl Settings {
     /// fn main() {
     ///     let token = env::var("DISCORD_TOKEN")
     ///        .expect("Expected a token in the environment");
-    ///     serenity::CACHE
+    ///     let mut client = Client::new(&token, Handler).unwrap();
+    ///     client.cache
     ///        .write().settings_mut()
     ///        .cache_lock_time(Some(Duration::from_secs(1)));
-    ///     let mut client = Client::new(&token, Handler).unwrap();
     ///
     ///     if let Err(why) = client.start() {
     ///        println!("Client error: {:?}", why);
@@ -110,4 +345,17 @@ impl Settings {
 
         self
     }
+
+    // The maximum number of messages to store in a channel, which is in the
+    // given guild.
+    pub(crate) fn max_messages_for(&self, channel_id: ChannelId, guild_id: Option<GuildId>) -> usize {
+        if let Some(max) = self.channel_max_messages.get(&channel_id) {
+            return *max;
+        }
+
+        guild_id
+            .and_then(|guild_id| self.guild_max_messages.get(&guild_id))
+            .cloned()
+            .unwrap_or(self.max_messages)
+    }
 }
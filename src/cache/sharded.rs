@@ -0,0 +1,234 @@
+use parking_lot::RwLock;
+use std::{
+    collections::{
+        hash_map::RandomState,
+        HashMap,
+    },
+    fmt::{Debug, Formatter, Result as FmtResult},
+    hash::{BuildHasher, Hash, Hasher},
+};
+
+/// The number of shards a [`ShardedMap`] is split into by default.
+///
+/// [`ShardedMap`]: struct.ShardedMap.html
+pub const DEFAULT_SHARD_COUNT: usize = 16;
+
+/// A map which is split into a number of independently locked shards, so that
+/// it can be read from and written to through a shared reference.
+///
+/// Operations on keys in different shards do not block each other. Each
+/// operation only locks a single shard, and only for its own duration.
+///
+/// This is used for data of the [`Cache`] which is updated often, so that it
+/// can be updated while the cache itself is only locked for reading.
+///
+/// [`Cache`]: struct.Cache.html
+pub struct ShardedMap<K, V> {
+    hasher: RandomState,
+    shards: Vec<RwLock<HashMap<K, V>>>,
+}
+
+impl<K: Eq + Hash, V> ShardedMap<K, V> {
+    /// Creates a new map with the [default number of shards].
+    ///
+    /// [default number of shards]: constant.DEFAULT_SHARD_COUNT.html
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_shard_count(DEFAULT_SHARD_COUNT)
+    }
+
+    /// Creates a new map which is split into the given number of shards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the shard count is 0.
+    pub fn with_shard_count(shard_count: usize) -> Self {
+        assert!(shard_count > 0, "A sharded map needs at least one shard");
+
+        Self {
+            hasher: RandomState::new(),
+            shards: (0..shard_count).map(|_| RwLock::new(HashMap::new())).collect(),
+        }
+    }
+
+    /// Whether the map contains a value for the key.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.shard(key).read().contains_key(key)
+    }
+
+    /// Retrieves a copy of the value for the key.
+    pub fn get(&self, key: &K) -> Option<V> where V: Clone {
+        self.shard(key).read().get(key).cloned()
+    }
+
+    /// Calls the function with a reference to the value for the key, while
+    /// the key's shard is locked for reading.
+    pub fn with<F: FnOnce(Option<&V>) -> R, R>(&self, key: &K, f: F) -> R {
+        f(self.shard(key).read().get(key))
+    }
+
+    /// Calls the function with a mutable reference to the value for the key,
+    /// while the key's shard is locked for writing.
+    pub fn with_mut<F: FnOnce(Option<&mut V>) -> R, R>(&self, key: &K, f: F) -> R {
+        f(self.shard(key).write().get_mut(key))
+    }
+
+    /// Calls the function with a mutable reference to the value for the key,
+    /// inserting the value returned by `default` first if the key is not
+    /// present.
+    pub fn with_entry<D, F, R>(&self, key: K, default: D, f: F) -> R
+        where D: FnOnce() -> V, F: FnOnce(&mut V) -> R {
+        let mut shard = self.shard(&key).write();
+
+        f(shard.entry(key).or_insert_with(default))
+    }
+
+    /// Inserts a value for the key, returning the value it replaced.
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        self.shard(&key).write().insert(key, value)
+    }
+
+    /// Removes the value for the key.
+    pub fn remove(&self, key: &K) -> Option<V> {
+        self.shard(key).write().remove(key)
+    }
+
+    /// Removes all values from the map.
+    pub fn clear(&self) {
+        for shard in &self.shards {
+            shard.write().clear();
+        }
+    }
+
+    /// Calls the function with every key and value in the map, while their
+    /// shard is locked for reading.
+    pub fn for_each<F: FnMut(&K, &V)>(&self, mut f: F) {
+        for shard in &self.shards {
+            for (key, value) in shard.read().iter() {
+                f(key, value);
+            }
+        }
+    }
+
+    /// Retains only the values for which the function returns `true`, while
+    /// their shard is locked for writing.
+    pub fn retain<F: FnMut(&K, &mut V) -> bool>(&self, mut f: F) {
+        for shard in &self.shards {
+            shard.write().retain(|key, value| f(key, value));
+        }
+    }
+
+    /// Retrieves a copy of every key in the map.
+    pub fn keys(&self) -> Vec<K> where K: Clone {
+        self.shards
+            .iter()
+            .flat_map(|shard| shard.read().keys().cloned().collect::<Vec<K>>())
+            .collect()
+    }
+
+    /// The number of values in the map.
+    ///
+    /// As the shards are locked one after another, this may be outdated if the
+    /// map is written to at the same time.
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|shard| shard.read().len()).sum()
+    }
+
+    /// Whether the map contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(|shard| shard.read().is_empty())
+    }
+
+    fn shard(&self, key: &K) -> &RwLock<HashMap<K, V>> {
+        let mut hasher = self.hasher.build_hasher();
+        key.hash(&mut hasher);
+
+        &self.shards[hasher.finish() as usize % self.shards.len()]
+    }
+}
+
+impl<K: Clone, V: Clone> Clone for ShardedMap<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            hasher: self.hasher.clone(),
+            shards: self.shards
+                .iter()
+                .map(|shard| RwLock::new(shard.read().clone()))
+                .collect(),
+        }
+    }
+}
+
+impl<K: Debug, V: Debug> Debug for ShardedMap<K, V> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let mut map = f.debug_map();
+
+        for shard in &self.shards {
+            for (key, value) in shard.read().iter() {
+                map.entry(key, value);
+            }
+        }
+
+        map.finish()
+    }
+}
+
+impl<K: Eq + Hash, V> Default for ShardedMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::Arc,
+        thread,
+    };
+    use super::ShardedMap;
+
+    #[test]
+    fn test_sharded_map() {
+        let map = ShardedMap::with_shard_count(4);
+
+        for key in 0..100u64 {
+            assert!(map.insert(key, key * 2).is_none());
+        }
+
+        assert_eq!(map.len(), 100);
+        assert_eq!(map.get(&21), Some(42));
+        assert_eq!(map.remove(&21), Some(42));
+        assert!(!map.contains_key(&21));
+
+        map.with_mut(&1, |value| *value.unwrap() = 0);
+        assert_eq!(map.get(&1), Some(0));
+
+        map.with_entry(200, || 0, |value| *value += 1);
+        map.with_entry(200, || 0, |value| *value += 1);
+        assert_eq!(map.get(&200), Some(2));
+
+        map.clear();
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn test_sharded_map_concurrent_writes() {
+        let map = Arc::new(ShardedMap::new());
+
+        let threads = (0..4u64).map(|thread| {
+            let map = Arc::clone(&map);
+
+            thread::spawn(move || {
+                for key in 0..250u64 {
+                    map.insert(thread * 1000 + key, ());
+                }
+            })
+        }).collect::<Vec<_>>();
+
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        assert_eq!(map.len(), 1000);
+    }
+}
@@ -0,0 +1,246 @@
+use internal::prelude::*;
+use model::prelude::*;
+use parking_lot::RwLock;
+use serde::ser::{Serialize, SerializeSeq, SerializeStruct, Serializer};
+use serde_json;
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    io::{Read, Write},
+    sync::Arc,
+};
+use super::{
+    index::GuildKeys,
+    insert_bounded,
+    retain_bounded,
+    Cache,
+    CacheBackend,
+    CacheError,
+};
+
+/// The format version of snapshots written by [`Cache::save_snapshot`].
+///
+/// Snapshots of any other version are refused by [`Cache::load_snapshot`].
+///
+/// [`Cache::load_snapshot`]: struct.Cache.html#method.load_snapshot
+/// [`Cache::save_snapshot`]: struct.Cache.html#method.save_snapshot
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+#[derive(Deserialize)]
+struct Snapshot {
+    categories: Vec<ChannelCategory>,
+    guilds: Vec<Guild>,
+    private_channels: Vec<PrivateChannel>,
+    user: CurrentUser,
+    users: Vec<User>,
+}
+
+// Serializes the cache's data in the format of a `Snapshot` without cloning
+// it.
+struct SnapshotRef<'a>(&'a Cache);
+
+impl<'a> Serialize for SnapshotRef<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> StdResult<S::Ok, S::Error> {
+        let cache = self.0;
+        let mut state = serializer.serialize_struct("Snapshot", 6)?;
+        state.serialize_field("version", &SNAPSHOT_VERSION)?;
+        state.serialize_field("categories", &LockedValues(&cache.categories))?;
+        state.serialize_field("guilds", &LockedValues(&cache.guilds))?;
+        state.serialize_field("private_channels", &LockedValues(&cache.private_channels))?;
+        state.serialize_field("user", &cache.user)?;
+        state.serialize_field("users", &LockedValues(&cache.users))?;
+
+        state.end()
+    }
+}
+
+// Serializes the values of a map of locked values as a sequence.
+struct LockedValues<'a, K: 'a, V: 'a>(&'a HashMap<K, Arc<RwLock<V>>>);
+
+impl<'a, K: Eq + Hash, V: Serialize> Serialize for LockedValues<'a, K, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> StdResult<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+
+        for value in self.0.values() {
+            seq.serialize_element(&*value.read())?;
+        }
+
+        seq.end()
+    }
+}
+
+impl Cache {
+    /// Writes a snapshot of the cache's guilds - including their channels,
+    /// roles, and members - channel categories, private channels, and users
+    /// to the writer.
+    ///
+    /// Messages and the cache's presences are not included, as they are
+    /// outdated quickly.
+    ///
+    /// The snapshot can be loaded into a cache again via [`load_snapshot`],
+    /// such as when restarting the bot.
+    ///
+    /// # Examples
+    ///
+    /// Save the cache of a client which is shutting down:
+    ///
+    /// ```rust,no_run
+    /// # use serenity::prelude::*;
+    /// # use std::error::Error;
+    /// #
+    /// # struct Handler;
+    /// #
+    /// # impl EventHandler for Handler {}
+    /// #
+    /// # fn try_main() -> Result<(), Box<Error>> {
+    /// # let client = Client::new("token", Handler)?;
+    /// #
+    /// use std::fs::File;
+    ///
+    /// let file = File::create("cache.json")?;
+    /// client.cache.read().save_snapshot(file)?;
+    /// #     Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #     try_main().unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Json`] if the snapshot could not be written.
+    ///
+    /// [`Error::Json`]: ../enum.Error.html#variant.Json
+    /// [`load_snapshot`]: #method.load_snapshot
+    pub fn save_snapshot<W: Write>(&self, writer: W) -> Result<()> {
+        serde_json::to_writer(writer, &SnapshotRef(self)).map_err(From::from)
+    }
+
+    /// Loads a snapshot written by [`save_snapshot`] into the cache.
+    ///
+    /// The loaded guilds are added to [`stale_guilds`], as their data may have
+    /// changed while the bot was offline. They are available immediately, and
+    /// are marked as up to date once the gateway sends them again through a
+    /// [`GuildCreate`] event. Loaded guilds the current user is no longer in
+    /// are removed once the shard they belong to receives its [`Ready`] event.
+    ///
+    /// The presences of the loaded guilds are discarded, as they are outdated
+    /// quickly. The limits of the cache's [`Settings`] are applied to the
+    /// loaded data.
+    ///
+    /// # Examples
+    ///
+    /// Warm up the cache of a client before starting it:
+    ///
+    /// ```rust,no_run
+    /// # use serenity::prelude::*;
+    /// # use std::error::Error;
+    /// #
+    /// # struct Handler;
+    /// #
+    /// # impl EventHandler for Handler {}
+    /// #
+    /// # fn try_main() -> Result<(), Box<Error>> {
+    /// use std::fs::File;
+    ///
+    /// let mut client = Client::new("token", Handler)?;
+    ///
+    /// if let Ok(file) = File::open("cache.json") {
+    ///     client.cache.write().load_snapshot(file)?;
+    /// }
+    ///
+    /// client.start()?;
+    /// #     Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #     try_main().unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CacheError::UnsupportedSnapshotVersion`] if the snapshot was
+    /// written in a different format version.
+    ///
+    /// Returns an [`Error::Json`] if the snapshot could not be read.
+    ///
+    /// [`CacheError::UnsupportedSnapshotVersion`]: enum.CacheError.html#variant.UnsupportedSnapshotVersion
+    /// [`Error::Json`]: ../enum.Error.html#variant.Json
+    /// [`GuildCreate`]: ../model/event/enum.Event.html#variant.GuildCreate
+    /// [`Ready`]: ../model/event/enum.Event.html#variant.Ready
+    /// [`Settings`]: struct.Settings.html
+    /// [`save_snapshot`]: #method.save_snapshot
+    /// [`stale_guilds`]: #structfield.stale_guilds
+    pub fn load_snapshot<R: Read>(&mut self, reader: R) -> Result<()> {
+        let value: Value = serde_json::from_reader(reader)?;
+        let version = value
+            .get("version")
+            .and_then(Value::as_u64)
+            .ok_or(Error::Decode("Expected snapshot version", Value::Null))?;
+
+        if version != u64::from(SNAPSHOT_VERSION) {
+            return Err(Error::Cache(CacheError::UnsupportedSnapshotVersion(version as u32)));
+        }
+
+        let snapshot = serde_json::from_value::<Snapshot>(value)?;
+
+        if self.user.id.0 == 0 {
+            self.user = snapshot.user;
+        }
+
+        for user in snapshot.users {
+            self.load_user(&user);
+        }
+
+        let max = self.settings.max_categories;
+
+        for category in snapshot.categories {
+            insert_bounded(&mut self.categories, max, category.id, Arc::new(RwLock::new(category)));
+        }
+
+        let max = self.settings.max_private_channels;
+
+        for mut channel in snapshot.private_channels {
+            let recipient = self.load_user(&channel.recipient.read());
+
+            channel.recipient = recipient;
+
+            insert_bounded(&mut self.private_channels, max, channel.id, Arc::new(RwLock::new(channel)));
+        }
+
+        for mut guild in snapshot.guilds {
+            // Guilds already received from the gateway are more recent.
+            if self.guilds.contains_key(&guild.id) {
+                continue;
+            }
+
+            guild.presences.clear();
+            retain_bounded(&mut guild.members, self.settings.max_members, Some(self.user.id));
+
+            for member in guild.members.values_mut() {
+                let user = self.load_user(&member.user.read());
+
+                member.user = user;
+            }
+
+            self.insert_guild_channels(&guild);
+            self.index.guild(None, Some(&GuildKeys::from(&guild)));
+            self.stale_guilds.insert(guild.id);
+            self.guilds.insert(guild.id, Arc::new(RwLock::new(guild)));
+        }
+
+        Ok(())
+    }
+
+    // Retrieves the entry of a loaded user, keeping the cached data of the user
+    // if it is already present, as it is more recent.
+    fn load_user(&mut self, user: &User) -> Arc<RwLock<User>> {
+        if let Some(cached) = self.users.get(&user.id) {
+            return Arc::clone(cached);
+        }
+
+        self.insert_user(user)
+    }
+}
@@ -0,0 +1,557 @@
+use internal::prelude::*;
+use parking_lot::Mutex;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    io::BufReader,
+    os::unix::net::{UnixListener, UnixStream},
+    path::Path,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc
+    },
+    thread,
+    time::{Duration, Instant}
+};
+use super::{
+    ClusterQuery,
+    CoordinatorMessage,
+    QUERY_TIMEOUT_IN_SECONDS,
+    WAIT_BETWEEN_IDENTIFIES_IN_SECONDS,
+    WorkerMessage,
+    recv,
+    send,
+};
+
+/// The coordinator of a cluster, responsible for assigning shards to workers
+/// and serialising their IDENTIFYs.
+///
+/// Refer to the [module-level documentation][module docs] for more
+/// information.
+///
+/// [module docs]: index.html
+pub struct ClusterCoordinator {
+    deadline_tx: Sender<(u64, Instant)>,
+    identify_tx: Sender<(u64, u64)>,
+    listener: UnixListener,
+    state: Arc<Mutex<CoordinatorState>>,
+}
+
+impl ClusterCoordinator {
+    /// Creates a new coordinator listening on a Unix socket at the given path,
+    /// splitting `shard_total` shards into `workers` ranges.
+    ///
+    /// **Note**: The path must not already exist.
+    pub fn new<P: AsRef<Path>>(path: P, shard_total: u64, workers: u64)
+        -> Result<Self> {
+        let listener = UnixListener::bind(path)?;
+        let state = Arc::new(Mutex::new(CoordinatorState::new(
+            split_shards(shard_total, workers),
+            shard_total,
+        )));
+        let (deadline_tx, deadline_rx) = mpsc::channel();
+        let (identify_tx, identify_rx) = mpsc::channel();
+
+        let deadline_state = Arc::clone(&state);
+        let identify_state = Arc::clone(&state);
+
+        thread::spawn(move || {
+            run_query_deadlines(&deadline_rx, &deadline_state);
+        });
+
+        thread::spawn(move || {
+            run_identifies(&identify_rx, &identify_state);
+        });
+
+        Ok(Self {
+            deadline_tx,
+            identify_tx,
+            listener,
+            state,
+        })
+    }
+
+    /// Sets how long to wait for every worker to answer a query, after which
+    /// the answers received so far are given to the worker which started it,
+    /// along with the shards of the workers which did not answer.
+    ///
+    /// Defaults to [`QUERY_TIMEOUT_IN_SECONDS`].
+    ///
+    /// [`QUERY_TIMEOUT_IN_SECONDS`]: constant.QUERY_TIMEOUT_IN_SECONDS.html
+    pub fn set_query_timeout(&self, timeout: Duration) {
+        self.state.lock().query_timeout = timeout;
+    }
+
+    /// Accepts workers, blocking the current thread.
+    ///
+    /// Each worker is handled in its own thread.
+    pub fn run(&self) -> Result<()> {
+        for stream in self.listener.incoming() {
+            let stream = stream?;
+            let reader = BufReader::new(stream.try_clone()?);
+            let stream = Arc::new(Mutex::new(stream));
+
+            // The stream is locked until the assignment is sent, so that it is
+            // the first message the worker receives.
+            let mut guard = stream.lock();
+            let (worker_id, msg) = self.state.lock().add_worker(&stream);
+
+            if let Err(why) = send(&mut *guard, &msg) {
+                warn!("[Cluster] Err assigning worker {}: {:?}", worker_id, why);
+            }
+
+            drop(guard);
+
+            let deadline_tx = self.deadline_tx.clone();
+            let identify_tx = self.identify_tx.clone();
+            let state = Arc::clone(&self.state);
+
+            thread::spawn(move || {
+                handle_worker(worker_id, reader, &deadline_tx, &identify_tx, &state);
+            });
+        }
+
+        Ok(())
+    }
+}
+
+// Messages to send once the state is unlocked, so that a worker which is slow
+// to read does not block the coordinator.
+type Outbox = Vec<(Arc<Mutex<UnixStream>>, CoordinatorMessage)>;
+
+struct CoordinatorState {
+    next_nonce: u64,
+    next_worker_id: u64,
+    // Ranges of shards that are not assigned to any worker.
+    pending: VecDeque<Vec<u64>>,
+    queries: HashMap<u64, PendingQuery>,
+    query_timeout: Duration,
+    shard_total: u64,
+    workers: HashMap<u64, WorkerConnection>,
+}
+
+struct PendingQuery {
+    // The nonce given by the worker that started the query.
+    nonce: u64,
+    remaining: HashSet<u64>,
+    requester: u64,
+    value: u64,
+}
+
+struct WorkerConnection {
+    shards: Vec<u64>,
+    stream: Arc<Mutex<UnixStream>>,
+}
+
+impl CoordinatorState {
+    fn new(pending: VecDeque<Vec<u64>>, shard_total: u64) -> Self {
+        Self {
+            next_nonce: 0,
+            next_worker_id: 0,
+            pending,
+            queries: HashMap::new(),
+            query_timeout: Duration::from_secs(QUERY_TIMEOUT_IN_SECONDS),
+            shard_total,
+            workers: HashMap::new(),
+        }
+    }
+
+    fn add_worker(&mut self, stream: &Arc<Mutex<UnixStream>>)
+        -> (u64, CoordinatorMessage) {
+        let worker_id = self.next_worker_id;
+        self.next_worker_id += 1;
+
+        let shards = self.pending.pop_front().unwrap_or_else(Vec::new);
+
+        info!("[Cluster] Worker {} connected; assigning {:?}", worker_id, shards);
+
+        let msg = CoordinatorMessage::Assign {
+            shards: shards.clone(),
+            shard_total: self.shard_total,
+        };
+
+        self.workers.insert(worker_id, WorkerConnection {
+            shards,
+            stream: Arc::clone(stream),
+        });
+
+        (worker_id, msg)
+    }
+
+    fn remove_worker(&mut self, worker_id: u64) -> Outbox {
+        let worker = match self.workers.remove(&worker_id) {
+            Some(worker) => worker,
+            None => return vec![],
+        };
+
+        info!("[Cluster] Worker {} disconnected", worker_id);
+
+        let finished = self.queries
+            .iter_mut()
+            .filter_map(|(nonce, query)| {
+                query.remaining.remove(&worker_id);
+
+                if query.remaining.is_empty() {
+                    Some(*nonce)
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut outbox = finished
+            .into_iter()
+            .filter_map(|nonce| self.finish_query(nonce))
+            .collect::<Vec<_>>();
+
+        if worker.shards.is_empty() {
+            return outbox;
+        }
+
+        let target = self.workers
+            .iter()
+            .min_by_key(|&(_, worker)| worker.shards.len())
+            .map(|(worker_id, _)| *worker_id);
+
+        match target.and_then(|id| self.workers.get_mut(&id).map(|w| (id, w))) {
+            Some((target_id, target)) => {
+                info!(
+                    "[Cluster] Moving shards {:?} to worker {}",
+                    worker.shards,
+                    target_id,
+                );
+
+                target.shards.extend(worker.shards.iter().cloned());
+
+                let msg = CoordinatorMessage::StartShards {
+                    shards: worker.shards,
+                };
+
+                outbox.push((Arc::clone(&target.stream), msg));
+            },
+            None => {
+                info!(
+                    "[Cluster] No worker to take shards {:?}; queueing",
+                    worker.shards,
+                );
+
+                self.pending.push_back(worker.shards);
+            },
+        }
+
+        outbox
+    }
+
+    // A worker which can not be sent the query is removed from it once its
+    // connection is found to be closed.
+    //
+    // Returns the Id of the query along with the messages to send.
+    fn start_query(&mut self, requester: u64, nonce: u64, query: ClusterQuery)
+        -> (u64, Outbox) {
+        let id = self.next_nonce;
+        self.next_nonce += 1;
+
+        let msg = CoordinatorMessage::Query {
+            nonce: id,
+            query,
+        };
+        let outbox = self.workers
+            .values()
+            .map(|worker| (Arc::clone(&worker.stream), msg.clone()))
+            .collect();
+
+        self.queries.insert(id, PendingQuery {
+            nonce,
+            remaining: self.workers.keys().cloned().collect(),
+            requester,
+            value: 0,
+        });
+
+        (id, outbox)
+    }
+
+    fn answer_query(&mut self, worker_id: u64, nonce: u64, value: u64)
+        -> Option<(Arc<Mutex<UnixStream>>, CoordinatorMessage)> {
+        let finished = match self.queries.get_mut(&nonce) {
+            Some(query) => {
+                if query.remaining.remove(&worker_id) {
+                    query.value += value;
+                }
+
+                query.remaining.is_empty()
+            },
+            None => return None,
+        };
+
+        if finished {
+            self.finish_query(nonce)
+        } else {
+            None
+        }
+    }
+
+    // Finishes a query which was not answered by every worker in time, if it
+    // is still pending.
+    fn expire_query(&mut self, id: u64)
+        -> Option<(Arc<Mutex<UnixStream>>, CoordinatorMessage)> {
+        if let Some(query) = self.queries.get(&id) {
+            warn!(
+                "[Cluster] Query {} timed out waiting for workers {:?}",
+                id,
+                query.remaining,
+            );
+        }
+
+        self.finish_query(id)
+    }
+
+    fn finish_query(&mut self, id: u64)
+        -> Option<(Arc<Mutex<UnixStream>>, CoordinatorMessage)> {
+        let query = self.queries.remove(&id)?;
+        let worker = self.workers.get(&query.requester)?;
+
+        let mut missing_shards = query.remaining
+            .iter()
+            .filter_map(|worker_id| self.workers.get(worker_id))
+            .flat_map(|worker| worker.shards.iter().cloned())
+            .collect::<Vec<_>>();
+        missing_shards.sort();
+
+        Some((Arc::clone(&worker.stream), CoordinatorMessage::QueryResult {
+            nonce: query.nonce,
+            value: query.value,
+            missing_shards,
+        }))
+    }
+}
+
+// Sends messages to workers. A failure means the worker is dead, which is
+// handled by its own connection thread.
+fn deliver(outbox: Outbox) {
+    for (stream, msg) in outbox {
+        if let Err(why) = send(&mut *stream.lock(), &msg) {
+            debug!("[Cluster] Err sending {:?}: {:?}", msg, why);
+        }
+    }
+}
+
+fn handle_worker(
+    worker_id: u64,
+    mut reader: BufReader<UnixStream>,
+    deadline_tx: &Sender<(u64, Instant)>,
+    identify_tx: &Sender<(u64, u64)>,
+    state: &Arc<Mutex<CoordinatorState>>,
+) {
+    loop {
+        let msg = match recv(&mut reader) {
+            Ok(Some(msg)) => msg,
+            Ok(None) => break,
+            Err(why) => {
+                warn!("[Cluster] Err receiving from worker {}: {:?}", worker_id, why);
+
+                break;
+            },
+        };
+
+        match msg {
+            WorkerMessage::Identify { shard_id } => {
+                let _ = identify_tx.send((worker_id, shard_id));
+            },
+            WorkerMessage::Query { nonce, query } => {
+                let (id, timeout, outbox) = {
+                    let mut state = state.lock();
+                    let (id, outbox) = state.start_query(worker_id, nonce, query);
+
+                    (id, state.query_timeout, outbox)
+                };
+
+                let _ = deadline_tx.send((id, Instant::now() + timeout));
+                deliver(outbox);
+            },
+            WorkerMessage::QueryResponse { nonce, value } => {
+                let outbox = state.lock().answer_query(worker_id, nonce, value);
+                deliver(outbox.into_iter().collect());
+            },
+        }
+    }
+
+    let outbox = state.lock().remove_worker(worker_id);
+    deliver(outbox);
+}
+
+// Finishes the queries which were not answered by every worker by their
+// deadline, in the order they were started.
+fn run_query_deadlines(rx: &Receiver<(u64, Instant)>, state: &Arc<Mutex<CoordinatorState>>) {
+    for (id, deadline) in rx {
+        let now = Instant::now();
+
+        if deadline > now {
+            thread::sleep(deadline - now);
+        }
+
+        let outbox = state.lock().expire_query(id);
+        deliver(outbox.into_iter().collect());
+    }
+}
+
+// Grants IDENTIFYs in the order they were requested, waiting between each.
+fn run_identifies(rx: &Receiver<(u64, u64)>, state: &Arc<Mutex<CoordinatorState>>) {
+    let wait = Duration::from_secs(WAIT_BETWEEN_IDENTIFIES_IN_SECONDS);
+    let mut last_grant: Option<Instant> = None;
+
+    for (worker_id, shard_id) in rx {
+        if let Some(instant) = last_grant {
+            let elapsed = instant.elapsed();
+
+            if elapsed < wait {
+                thread::sleep(wait - elapsed);
+            }
+        }
+
+        let stream = state
+            .lock()
+            .workers
+            .get(&worker_id)
+            .map(|worker| Arc::clone(&worker.stream));
+
+        let granted = match stream {
+            Some(stream) => {
+                let msg = CoordinatorMessage::IdentifyGranted { shard_id };
+
+                send(&mut *stream.lock(), &msg).is_ok()
+            },
+            None => false,
+        };
+
+        if granted {
+            debug!(
+                "[Cluster] Granted IDENTIFY of shard {} to worker {}",
+                shard_id,
+                worker_id,
+            );
+
+            last_grant = Some(Instant::now());
+        }
+    }
+}
+
+// Splits the shards into contiguous ranges of as equal size as possible.
+fn split_shards(shard_total: u64, workers: u64) -> VecDeque<Vec<u64>> {
+    let workers = workers.max(1).min(shard_total.max(1));
+    let size = shard_total / workers;
+    let extra = shard_total % workers;
+
+    let mut start = 0;
+
+    (0..workers).map(|idx| {
+        let len = size + if idx < extra { 1 } else { 0 };
+        let range = (start..start + len).collect();
+        start += len;
+
+        range
+    }).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use internal::prelude::*;
+    use std::{
+        env,
+        fs,
+        io::{BufRead, BufReader},
+        os::unix::net::UnixStream,
+        process,
+        sync::mpsc::Receiver,
+        thread,
+        time::Duration
+    };
+    use super::super::super::gateway::ShardId;
+    use super::super::{ClusterError, ClusterQuery, ClusterWorker, CoordinatorMessage};
+    use super::{split_shards, ClusterCoordinator};
+
+    fn recv_shards(rx: &Receiver<CoordinatorMessage>) -> Vec<u64> {
+        match rx.recv_timeout(Duration::from_secs(5)).unwrap() {
+            CoordinatorMessage::Assign { shards, .. } |
+            CoordinatorMessage::StartShards { shards } => shards,
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_coordinator_protocol() {
+        let path = env::temp_dir().join(format!("serenity-cluster-{}.sock", process::id()));
+        let _ = fs::remove_file(&path);
+
+        let coordinator = ClusterCoordinator::new(&path, 4, 2).unwrap();
+        thread::spawn(move || coordinator.run());
+
+        let first = ClusterWorker::connect(&path).unwrap();
+        let first_rx = first.take_assignments().unwrap();
+        assert_eq!(recv_shards(&first_rx), vec![0, 1]);
+
+        let second = ClusterWorker::connect(&path).unwrap();
+        let second_rx = second.take_assignments().unwrap();
+        assert_eq!(recv_shards(&second_rx), vec![2, 3]);
+
+        first.wait_for_identify(ShardId(0)).unwrap();
+        assert_eq!(first.query(ClusterQuery::ShardCount).unwrap(), 0);
+
+        // The shards of a worker which disconnects are moved to another.
+        drop(second);
+        assert_eq!(recv_shards(&first_rx), vec![2, 3]);
+        assert_eq!(first.query(ClusterQuery::ShardCount).unwrap(), 0);
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_query_timeouts() {
+        let path = env::temp_dir().join(format!("serenity-cluster-query-{}.sock", process::id()));
+        let _ = fs::remove_file(&path);
+
+        let coordinator = ClusterCoordinator::new(&path, 4, 2).unwrap();
+        coordinator.set_query_timeout(Duration::from_millis(100));
+        thread::spawn(move || coordinator.run());
+
+        let worker = ClusterWorker::connect(&path).unwrap();
+        let worker_rx = worker.take_assignments().unwrap();
+        assert_eq!(recv_shards(&worker_rx), vec![0, 1]);
+
+        // A worker which is connected, but never answers. Its assignment is
+        // read so that it is known to be registered.
+        let hung = UnixStream::connect(&path).unwrap();
+        let mut assignment = String::new();
+        BufReader::new(&hung).read_line(&mut assignment).unwrap();
+        assert!(assignment.contains("Assign"));
+
+        match worker.query(ClusterQuery::ShardCount) {
+            Err(Error::Cluster(ClusterError::QueryIncomplete { missing_shards, value })) => {
+                assert_eq!(missing_shards, vec![2, 3]);
+                assert_eq!(value, 0);
+            },
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        // The worker stops waiting before the coordinator gives up.
+        worker.set_query_timeout(Duration::from_millis(10));
+
+        match worker.query(ClusterQuery::ShardCount) {
+            Err(Error::Cluster(ClusterError::QueryTimedOut)) => {},
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_split_shards() {
+        let ranges = split_shards(10, 3);
+
+        assert_eq!(ranges.len(), 3);
+        assert_eq!(ranges[0], vec![0, 1, 2, 3]);
+        assert_eq!(ranges[1], vec![4, 5, 6]);
+        assert_eq!(ranges[2], vec![7, 8, 9]);
+
+        assert_eq!(split_shards(2, 5).len(), 2);
+        assert_eq!(split_shards(1, 0), vec![vec![0]]);
+    }
+}
@@ -0,0 +1,55 @@
+use std::{
+    error::Error as StdError,
+    fmt::{
+        Display,
+        Formatter,
+        Result as FmtResult
+    }
+};
+
+/// An error that occurred while communicating within a cluster.
+///
+/// This is always wrapped within the library's generic [`Error::Cluster`]
+/// variant.
+///
+/// [`Error::Cluster`]: ../../../enum.Error.html#variant.Cluster
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Error {
+    /// The shard assignments of a [`ClusterWorker`] were already taken, such
+    /// as by a previous call to [`Client::start_cluster`].
+    ///
+    /// [`Client::start_cluster`]: ../../struct.Client.html#method.start_cluster
+    /// [`ClusterWorker`]: struct.ClusterWorker.html
+    AssignmentsTaken,
+    /// The connection between a worker and the coordinator was closed.
+    Disconnected,
+    /// Not every worker answered a query within the coordinator's query
+    /// timeout.
+    QueryIncomplete {
+        /// The shards of the workers which did not answer.
+        missing_shards: Vec<u64>,
+        /// The sum of the answers which were received.
+        value: u64,
+    },
+    /// The coordinator did not send the result of a query within the worker's
+    /// query timeout.
+    QueryTimedOut,
+    /// A message that was not expected at this point was received.
+    UnexpectedMessage,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult { f.write_str(self.description()) }
+}
+
+impl StdError for Error {
+    fn description(&self) -> &str {
+        match *self {
+            Error::AssignmentsTaken => "The worker's shard assignments were already taken",
+            Error::Disconnected => "The cluster connection was closed",
+            Error::QueryIncomplete { .. } => "Not every worker answered the query in time",
+            Error::QueryTimedOut => "The result of the query was not received in time",
+            Error::UnexpectedMessage => "Received an unexpected cluster message",
+        }
+    }
+}
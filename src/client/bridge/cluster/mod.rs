@@ -0,0 +1,205 @@
+//! Support for running the shards of a bot across multiple processes on the
+//! same machine.
+//!
+//! A cluster is made of one [`ClusterCoordinator`] and any number of
+//! [`ClusterWorker`]s, communicating over a Unix socket.
+//!
+//! ### [`ClusterCoordinator`]
+//!
+//! The coordinator does not connect to the gateway itself. It splits the total
+//! number of shards into ranges and hands a range to each worker that
+//! connects. It also serialises the IDENTIFYs of every shard in the cluster,
+//! so that no two shards identify within 5 seconds of each other, regardless
+//! of which process they are in.
+//!
+//! When a worker dies, its shards are started by the worker running the fewest
+//! shards. If no worker is left, they are given to the next worker to connect.
+//!
+//! ### [`ClusterWorker`]
+//!
+//! A worker is a connection to the coordinator, given to
+//! [`Client::start_cluster`] in place of a shard range. It can also be used
+//! to run aggregate [`ClusterQuery`]s, such as the total number of guilds
+//! across the cluster. Both the coordinator and the worker stop waiting for a
+//! query after a timeout, so that a hung worker does not block its callers.
+//!
+//! # Examples
+//!
+//! Run a coordinator for 16 shards split across 4 workers:
+//!
+//! ```rust,no_run
+//! # use std::error::Error;
+//! #
+//! # fn try_main() -> Result<(), Box<Error>> {
+//! use serenity::client::bridge::cluster::ClusterCoordinator;
+//!
+//! let coordinator = ClusterCoordinator::new("/tmp/my-bot.sock", 16, 4)?;
+//! coordinator.run()?;
+//! #     Ok(())
+//! # }
+//! #
+//! # fn main() {
+//! #     try_main().unwrap();
+//! # }
+//! ```
+//!
+//! And, in each worker process:
+//!
+//! ```rust,no_run
+//! # use std::error::Error;
+//! #
+//! # fn try_main() -> Result<(), Box<Error>> {
+//! use serenity::client::bridge::cluster::ClusterWorker;
+//! use serenity::client::{Client, EventHandler};
+//! use std::env;
+//!
+//! struct Handler;
+//!
+//! impl EventHandler for Handler { }
+//!
+//! let mut client = Client::new(&env::var("DISCORD_TOKEN")?, Handler)?;
+//! let worker = ClusterWorker::connect("/tmp/my-bot.sock")?;
+//!
+//! client.start_cluster(&worker)?;
+//! #     Ok(())
+//! # }
+//! #
+//! # fn main() {
+//! #     try_main().unwrap();
+//! # }
+//! ```
+//!
+//! [`Client::start_cluster`]: ../../struct.Client.html#method.start_cluster
+//! [`ClusterCoordinator`]: struct.ClusterCoordinator.html
+//! [`ClusterQuery`]: enum.ClusterQuery.html
+//! [`ClusterWorker`]: struct.ClusterWorker.html
+
+mod coordinator;
+mod error;
+mod worker;
+
+pub use self::coordinator::ClusterCoordinator;
+pub use self::error::Error as ClusterError;
+pub use self::worker::ClusterWorker;
+
+use internal::prelude::*;
+use serde::{de::DeserializeOwned, ser::Serialize};
+use serde_json;
+use std::{
+    io::{BufRead, Write},
+    os::unix::net::UnixStream
+};
+
+/// The number of seconds to wait between two IDENTIFYs within the cluster.
+pub const WAIT_BETWEEN_IDENTIFIES_IN_SECONDS: u64 = 5;
+
+/// The default number of seconds the coordinator waits for every worker to
+/// answer a query.
+pub const QUERY_TIMEOUT_IN_SECONDS: u64 = 5;
+
+/// An aggregate query run across every worker of a cluster.
+///
+/// The answers of the workers are summed.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum ClusterQuery {
+    /// The number of channels in the workers' caches.
+    #[cfg(feature = "cache")]
+    ChannelCount,
+    /// The number of guilds in the workers' caches.
+    #[cfg(feature = "cache")]
+    GuildCount,
+    /// The number of shards instantiated by the workers.
+    ShardCount,
+    /// The number of users in the workers' caches.
+    #[cfg(feature = "cache")]
+    UserCount,
+}
+
+/// A message sent from the [`ClusterCoordinator`] to a [`ClusterWorker`].
+///
+/// [`ClusterCoordinator`]: struct.ClusterCoordinator.html
+/// [`ClusterWorker`]: struct.ClusterWorker.html
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "op", content = "d")]
+pub enum CoordinatorMessage {
+    /// The initial assignment of a worker, sent when it connects.
+    ///
+    /// The list of shards may be empty if every shard is already assigned.
+    Assign {
+        shards: Vec<u64>,
+        shard_total: u64,
+    },
+    /// Indicator that a worker should additionally start the given shards,
+    /// usually because the worker running them died.
+    StartShards {
+        shards: Vec<u64>,
+    },
+    /// Indicator that the shard may now send its IDENTIFY.
+    IdentifyGranted {
+        shard_id: u64,
+    },
+    /// A query which the worker should answer with a
+    /// [`WorkerMessage::QueryResponse`].
+    ///
+    /// [`WorkerMessage::QueryResponse`]: enum.WorkerMessage.html#variant.QueryResponse
+    Query {
+        nonce: u64,
+        query: ClusterQuery,
+    },
+    /// The aggregated result of a query started by the worker.
+    ///
+    /// If not every worker answered in time, the shards of those which did not
+    /// are given.
+    QueryResult {
+        nonce: u64,
+        value: u64,
+        #[serde(default)]
+        missing_shards: Vec<u64>,
+    },
+}
+
+/// A message sent from a [`ClusterWorker`] to the [`ClusterCoordinator`].
+///
+/// [`ClusterCoordinator`]: struct.ClusterCoordinator.html
+/// [`ClusterWorker`]: struct.ClusterWorker.html
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "op", content = "d")]
+pub enum WorkerMessage {
+    /// A request for the shard to be allowed to IDENTIFY.
+    Identify {
+        shard_id: u64,
+    },
+    /// A query to run across every worker of the cluster.
+    Query {
+        nonce: u64,
+        query: ClusterQuery,
+    },
+    /// The worker's answer to a [`CoordinatorMessage::Query`].
+    ///
+    /// [`CoordinatorMessage::Query`]: enum.CoordinatorMessage.html#variant.Query
+    QueryResponse {
+        nonce: u64,
+        value: u64,
+    },
+}
+
+// Messages are sent as JSON, one per line.
+fn send<T: Serialize>(stream: &mut UnixStream, message: &T) -> Result<()> {
+    let mut bytes = serde_json::to_vec(message)?;
+    bytes.push(b'\n');
+
+    stream.write_all(&bytes)?;
+
+    Ok(())
+}
+
+// Returns `Ok(None)` once the other end has closed the connection.
+fn recv<R: BufRead, T: DeserializeOwned>(reader: &mut R) -> Result<Option<T>> {
+    let mut line = String::new();
+
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+
+    serde_json::from_str(&line).map(Some).map_err(From::from)
+}
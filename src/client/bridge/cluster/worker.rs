@@ -0,0 +1,354 @@
+use internal::prelude::*;
+use parking_lot::Mutex;
+use std::{
+    collections::HashMap,
+    io::BufReader,
+    net::Shutdown,
+    os::unix::net::UnixStream,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
+        Arc,
+        Weak
+    },
+    thread,
+    time::Duration
+};
+use super::super::gateway::{ShardId, ShardManager};
+use super::{
+    ClusterError,
+    ClusterQuery,
+    CoordinatorMessage,
+    QUERY_TIMEOUT_IN_SECONDS,
+    WorkerMessage,
+    recv,
+    send,
+};
+
+#[cfg(feature = "cache")]
+use cache::Cache;
+#[cfg(feature = "cache")]
+use parking_lot::RwLock;
+
+// The sum of the answers to a query, and the shards of the workers which did
+// not answer.
+type QueryAnswer = (u64, Vec<u64>);
+
+/// A connection from a worker process to the [`ClusterCoordinator`].
+///
+/// This is cheap to clone, and can be stored in [`Client::data`] to run
+/// [`ClusterQuery`]s from event handlers and commands.
+///
+/// Refer to the [module-level documentation][module docs] for more
+/// information.
+///
+/// [`Client::data`]: ../../struct.Client.html#structfield.data
+/// [`ClusterCoordinator`]: struct.ClusterCoordinator.html
+/// [`ClusterQuery`]: enum.ClusterQuery.html
+/// [module docs]: index.html
+#[derive(Clone, Debug)]
+pub struct ClusterWorker {
+    inner: Arc<WorkerInner>,
+}
+
+#[derive(Debug)]
+struct WorkerInner {
+    assignments: Mutex<Option<Receiver<CoordinatorMessage>>>,
+    #[cfg(feature = "cache")]
+    cache: Mutex<Option<Weak<RwLock<Cache>>>>,
+    connected: AtomicBool,
+    // Waiters are keyed by shard ID for IDENTIFYs and by nonce for queries.
+    identifies: Mutex<HashMap<u64, Sender<()>>>,
+    next_nonce: Mutex<u64>,
+    queries: Mutex<HashMap<u64, Sender<QueryAnswer>>>,
+    query_timeout: Mutex<Duration>,
+    shard_manager: Mutex<Option<Weak<Mutex<ShardManager>>>>,
+    stream: Mutex<UnixStream>,
+}
+
+impl ClusterWorker {
+    /// Connects to a coordinator listening on a Unix socket at the given path.
+    pub fn connect<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let stream = UnixStream::connect(path)?;
+        let reader = BufReader::new(stream.try_clone()?);
+        let (assignments_tx, assignments_rx) = mpsc::channel();
+
+        let inner = Arc::new(WorkerInner {
+            assignments: Mutex::new(Some(assignments_rx)),
+            #[cfg(feature = "cache")]
+            cache: Mutex::new(None),
+            connected: AtomicBool::new(true),
+            identifies: Mutex::new(HashMap::new()),
+            next_nonce: Mutex::new(0),
+            queries: Mutex::new(HashMap::new()),
+            // The worker waits longer than the coordinator, so that it
+            // receives the partial result of a query which timed out.
+            query_timeout: Mutex::new(Duration::from_secs(QUERY_TIMEOUT_IN_SECONDS * 2)),
+            shard_manager: Mutex::new(None),
+            stream: Mutex::new(stream),
+        });
+
+        let thread_inner = Arc::downgrade(&inner);
+
+        thread::spawn(move || {
+            handle_coordinator(&thread_inner, reader, &assignments_tx);
+        });
+
+        Ok(Self {
+            inner,
+        })
+    }
+
+    /// Whether the connection to the coordinator is still open.
+    pub fn is_connected(&self) -> bool {
+        self.inner.connected.load(Ordering::SeqCst)
+    }
+
+    /// Runs a query across every worker of the cluster, blocking until each
+    /// worker has answered and returning the sum of their answers.
+    ///
+    /// The coordinator gives up on workers which do not answer within its
+    /// [query timeout], and this gives up on the coordinator after the
+    /// worker's own [query timeout][`set_query_timeout`].
+    ///
+    /// # Examples
+    ///
+    /// Retrieve the number of guilds across the cluster:
+    ///
+    /// ```rust,no_run
+    /// # use std::error::Error;
+    /// #
+    /// # #[cfg(feature = "cache")]
+    /// # fn try_main() -> Result<(), Box<Error>> {
+    /// use serenity::client::bridge::cluster::{ClusterQuery, ClusterWorker};
+    ///
+    /// let worker = ClusterWorker::connect("/tmp/my-bot.sock")?;
+    ///
+    /// println!("Guilds: {}", worker.query(ClusterQuery::GuildCount)?);
+    /// #     Ok(())
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "cache"))]
+    /// # fn try_main() -> Result<(), Box<Error>> { Ok(()) }
+    /// #
+    /// # fn main() {
+    /// #     try_main().unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ClusterError::Disconnected`] if the connection to the
+    /// coordinator closed before the result was received.
+    ///
+    /// Returns a [`ClusterError::QueryIncomplete`] with the sum of the answers
+    /// received if not every worker answered in time, or a
+    /// [`ClusterError::QueryTimedOut`] if the coordinator did not send the
+    /// result in time.
+    ///
+    /// [`ClusterError::Disconnected`]: enum.ClusterError.html#variant.Disconnected
+    /// [`ClusterError::QueryIncomplete`]: enum.ClusterError.html#variant.QueryIncomplete
+    /// [`ClusterError::QueryTimedOut`]: enum.ClusterError.html#variant.QueryTimedOut
+    /// [`set_query_timeout`]: #method.set_query_timeout
+    /// [query timeout]: struct.ClusterCoordinator.html#method.set_query_timeout
+    pub fn query(&self, query: ClusterQuery) -> Result<u64> {
+        let nonce = {
+            let mut next_nonce = self.inner.next_nonce.lock();
+            *next_nonce += 1;
+
+            *next_nonce
+        };
+
+        let timeout = *self.inner.query_timeout.lock();
+        let rx = self.wait(&self.inner.queries, nonce)?;
+        self.send(&WorkerMessage::Query { nonce, query })?;
+
+        match rx.recv_timeout(timeout) {
+            Ok((value, ref missing_shards)) if missing_shards.is_empty() => Ok(value),
+            Ok((value, missing_shards)) => Err(Error::Cluster(ClusterError::QueryIncomplete {
+                missing_shards,
+                value,
+            })),
+            Err(RecvTimeoutError::Timeout) => {
+                self.inner.queries.lock().remove(&nonce);
+
+                Err(Error::Cluster(ClusterError::QueryTimedOut))
+            },
+            Err(RecvTimeoutError::Disconnected) => Err(Error::Cluster(ClusterError::Disconnected)),
+        }
+    }
+
+    /// Sets how long [`query`] waits for the coordinator to send the result of
+    /// a query.
+    ///
+    /// Defaults to twice [`QUERY_TIMEOUT_IN_SECONDS`], so that the result of a
+    /// query which timed out in the coordinator is still received.
+    ///
+    /// [`QUERY_TIMEOUT_IN_SECONDS`]: constant.QUERY_TIMEOUT_IN_SECONDS.html
+    /// [`query`]: #method.query
+    pub fn set_query_timeout(&self, timeout: Duration) {
+        *self.inner.query_timeout.lock() = timeout;
+    }
+
+    /// Asks the coordinator for permission for a shard to IDENTIFY, blocking
+    /// until it is granted.
+    ///
+    /// This is used by the [`ShardQueuer`] before starting a shard, and should
+    /// not need to be called manually.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ClusterError::Disconnected`] if the connection to the
+    /// coordinator closed before permission was granted.
+    ///
+    /// [`ClusterError::Disconnected`]: enum.ClusterError.html#variant.Disconnected
+    /// [`ShardQueuer`]: ../gateway/struct.ShardQueuer.html
+    pub fn wait_for_identify(&self, shard_id: ShardId) -> Result<()> {
+        let rx = self.wait(&self.inner.identifies, shard_id.0)?;
+        self.send(&WorkerMessage::Identify { shard_id: shard_id.0 })?;
+
+        rx.recv().map_err(|_| Error::Cluster(ClusterError::Disconnected))
+    }
+
+    /// Takes the receiver of shard assignments sent by the coordinator.
+    ///
+    /// The first message received is always a [`CoordinatorMessage::Assign`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ClusterError::AssignmentsTaken`] if the receiver was
+    /// already taken.
+    ///
+    /// [`ClusterError::AssignmentsTaken`]: enum.ClusterError.html#variant.AssignmentsTaken
+    /// [`CoordinatorMessage::Assign`]: enum.CoordinatorMessage.html#variant.Assign
+    pub fn take_assignments(&self) -> Result<Receiver<CoordinatorMessage>> {
+        self.inner
+            .assignments
+            .lock()
+            .take()
+            .ok_or(Error::Cluster(ClusterError::AssignmentsTaken))
+    }
+
+    #[cfg(feature = "cache")]
+    pub(crate) fn set_cache(&self, cache: &Arc<RwLock<Cache>>) {
+        *self.inner.cache.lock() = Some(Arc::downgrade(cache));
+    }
+
+    pub(crate) fn set_shard_manager(&self, manager: &Arc<Mutex<ShardManager>>) {
+        *self.inner.shard_manager.lock() = Some(Arc::downgrade(manager));
+    }
+
+    fn send(&self, message: &WorkerMessage) -> Result<()> {
+        send(&mut *self.inner.stream.lock(), message)
+    }
+
+    // Registers a waiter. The connection flag is checked while holding the
+    // lock, so that the waiter can not be registered after the reader thread
+    // has dropped every waiter.
+    fn wait<T>(&self, waiters: &Mutex<HashMap<u64, Sender<T>>>, key: u64)
+        -> Result<Receiver<T>> {
+        let mut waiters = waiters.lock();
+
+        if !self.is_connected() {
+            return Err(Error::Cluster(ClusterError::Disconnected));
+        }
+
+        let (tx, rx) = mpsc::channel();
+        waiters.insert(key, tx);
+
+        Ok(rx)
+    }
+}
+
+impl Drop for WorkerInner {
+    /// Closes the connection once every handle to the worker has been
+    /// dropped, so that the coordinator can move its shards elsewhere.
+    fn drop(&mut self) {
+        let _ = self.stream.lock().shutdown(Shutdown::Both);
+    }
+}
+
+// The reader only holds a weak reference to the worker, so that the
+// connection is closed when the last handle is dropped.
+fn handle_coordinator(
+    inner: &Weak<WorkerInner>,
+    mut reader: BufReader<UnixStream>,
+    assignments_tx: &Sender<CoordinatorMessage>,
+) {
+    loop {
+        let msg = match recv(&mut reader) {
+            Ok(Some(msg)) => msg,
+            Ok(None) => break,
+            Err(why) => {
+                warn!("[Cluster] Err receiving from coordinator: {:?}", why);
+
+                break;
+            },
+        };
+
+        let inner = match inner.upgrade() {
+            Some(inner) => inner,
+            None => return,
+        };
+
+        match msg {
+            msg @ CoordinatorMessage::Assign { .. } |
+            msg @ CoordinatorMessage::StartShards { .. } => {
+                let _ = assignments_tx.send(msg);
+            },
+            CoordinatorMessage::IdentifyGranted { shard_id } => {
+                if let Some(tx) = inner.identifies.lock().remove(&shard_id) {
+                    let _ = tx.send(());
+                }
+            },
+            CoordinatorMessage::Query { nonce, query } => {
+                let value = answer(&inner, query);
+                let msg = WorkerMessage::QueryResponse { nonce, value };
+
+                let _ = send(&mut *inner.stream.lock(), &msg);
+            },
+            CoordinatorMessage::QueryResult { nonce, value, missing_shards } => {
+                if let Some(tx) = inner.queries.lock().remove(&nonce) {
+                    let _ = tx.send((value, missing_shards));
+                }
+            },
+        }
+    }
+
+    error!("[Cluster] Connection to coordinator closed");
+
+    if let Some(inner) = inner.upgrade() {
+        inner.connected.store(false, Ordering::SeqCst);
+        inner.identifies.lock().clear();
+        inner.queries.lock().clear();
+    }
+}
+
+fn answer(inner: &WorkerInner, query: ClusterQuery) -> u64 {
+    match query {
+        #[cfg(feature = "cache")]
+        ClusterQuery::ChannelCount => count_cached(inner, |cache| cache.channels.len()),
+        #[cfg(feature = "cache")]
+        ClusterQuery::GuildCount => count_cached(inner, |cache| cache.guilds.len()),
+        ClusterQuery::ShardCount => {
+            let manager = inner.shard_manager
+                .lock()
+                .as_ref()
+                .and_then(Weak::upgrade);
+
+            manager.map_or(0, |manager| {
+                manager.lock().shards_instantiated().len() as u64
+            })
+        },
+        #[cfg(feature = "cache")]
+        ClusterQuery::UserCount => count_cached(inner, |cache| cache.users.len()),
+    }
+}
+
+#[cfg(feature = "cache")]
+fn count_cached<F: FnOnce(&Cache) -> usize>(inner: &WorkerInner, f: F) -> u64 {
+    let cache = inner.cache.lock().as_ref().and_then(Weak::upgrade);
+
+    cache.map_or(0, |cache| f(&cache.read()) as u64)
+}
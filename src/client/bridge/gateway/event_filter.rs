@@ -0,0 +1,178 @@
+use model::event::{EventType, UnknownEvent};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// A filter deciding which dispatched events are fully deserialized and
+/// processed by a shard.
+///
+/// Events that are filtered out skip deserialization into their model, are
+/// not given to the cache, and are not dispatched to the [`EventHandler`].
+/// If [`raw`] is enabled, they are instead dispatched to
+/// [`EventHandler::unknown`] with their name and raw payload.
+///
+/// The [`Ready`] and [`Resumed`] events are always processed, as the shard
+/// relies on them to manage its session. Note that the cache and voice
+/// manager may go out of sync if the events they rely on are filtered out.
+///
+/// # Examples
+///
+/// Only process messages and guild events, ignoring presence and typing
+/// floods:
+///
+/// ```rust,no_run
+/// # use std::error::Error;
+/// #
+/// # fn try_main() -> Result<(), Box<Error>> {
+/// use serenity::client::bridge::gateway::EventFilter;
+/// use serenity::client::{Client, EventHandler};
+/// use serenity::model::event::EventType;
+/// use std::env;
+///
+/// struct Handler;
+///
+/// impl EventHandler for Handler { }
+///
+/// let mut client = Client::new(&env::var("DISCORD_TOKEN")?, Handler)?;
+///
+/// client.set_event_filter(EventFilter::only(vec![
+///     EventType::GuildCreate,
+///     EventType::GuildDelete,
+///     EventType::MessageCreate,
+/// ]));
+///
+/// client.start()?;
+/// #     Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #     try_main().unwrap();
+/// # }
+/// ```
+///
+/// [`EventHandler`]: ../../trait.EventHandler.html
+/// [`EventHandler::unknown`]: ../../trait.EventHandler.html#method.unknown
+/// [`Ready`]: ../../../model/event/enum.EventType.html#variant.Ready
+/// [`Resumed`]: ../../../model/event/enum.EventType.html#variant.Resumed
+/// [`raw`]: #method.raw
+#[derive(Clone, Debug, Default)]
+pub struct EventFilter {
+    kinds: Option<HashSet<EventType>>,
+    raw: bool,
+}
+
+impl EventFilter {
+    /// Creates a filter which processes every event.
+    ///
+    /// This is the default.
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Creates a filter which only processes the given types of events.
+    pub fn only<I: IntoIterator<Item = EventType>>(kinds: I) -> Self {
+        Self {
+            kinds: Some(kinds.into_iter().collect()),
+            raw: false,
+        }
+    }
+
+    /// Sets whether events that are filtered out are dispatched to
+    /// [`EventHandler::unknown`] with their raw payload, rather than dropped.
+    ///
+    /// [`EventHandler::unknown`]: ../../trait.EventHandler.html#method.unknown
+    pub fn raw(mut self, raw: bool) -> Self {
+        self.raw = raw;
+
+        self
+    }
+
+    /// Whether events that are filtered out are dispatched raw.
+    pub fn is_raw(&self) -> bool {
+        self.raw
+    }
+
+    /// Whether the given type of event is fully processed.
+    pub fn allows(&self, kind: &EventType) -> bool {
+        match *kind {
+            EventType::Ready | EventType::Resumed => true,
+            _ => self.kinds.as_ref().map_or(true, |kinds| kinds.contains(kind)),
+        }
+    }
+
+    // Checks whether the gateway payload is a dispatch which should not be
+    // processed, returning its sequence, type, and raw payload if so.
+    //
+    // This only inspects the top level of the payload, so that the event
+    // itself is not deserialized.
+    pub(crate) fn skip(&self, value: &mut Value) -> Option<(u64, EventType, UnknownEvent)> {
+        if self.kinds.is_none() {
+            return None;
+        }
+
+        let map = match *value {
+            Value::Object(ref mut map) => map,
+            _ => return None,
+        };
+
+        if map.get("op").and_then(Value::as_u64) != Some(0) {
+            return None;
+        }
+
+        let seq = map.get("s").and_then(Value::as_u64)?;
+        let name = map.get("t").and_then(Value::as_str)?.to_owned();
+        let kind = EventType::deserialize(Value::String(name.clone())).ok()?;
+
+        if self.allows(&kind) {
+            return None;
+        }
+
+        let payload = map.remove("d").unwrap_or(Value::Null);
+
+        Some((seq, kind, UnknownEvent {
+            kind: name,
+            value: payload,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use model::event::EventType;
+    use super::EventFilter;
+
+    #[test]
+    fn test_allows() {
+        let filter = EventFilter::only(vec![EventType::MessageCreate]);
+
+        assert!(filter.allows(&EventType::MessageCreate));
+        assert!(filter.allows(&EventType::Ready));
+        assert!(filter.allows(&EventType::Resumed));
+        assert!(!filter.allows(&EventType::PresenceUpdate));
+        assert!(EventFilter::all().allows(&EventType::PresenceUpdate));
+    }
+
+    #[test]
+    fn test_skip() {
+        let filter = EventFilter::only(vec![EventType::MessageCreate]);
+
+        let mut typing = json!({
+            "op": 0,
+            "s": 7,
+            "t": "TYPING_START",
+            "d": {"channel_id": "1", "timestamp": 2, "user_id": "3"},
+        });
+        let (seq, kind, event) = filter.skip(&mut typing).unwrap();
+
+        assert_eq!(seq, 7);
+        assert_eq!(kind, EventType::TypingStart);
+        assert_eq!(event.kind, "TYPING_START");
+        assert_eq!(event.value["timestamp"], 2);
+
+        let mut ready = json!({"op": 0, "s": 1, "t": "READY", "d": {}});
+        assert!(filter.skip(&mut ready).is_none());
+
+        let mut heartbeat = json!({"op": 1, "d": 5});
+        assert!(filter.skip(&mut heartbeat).is_none());
+    }
+}
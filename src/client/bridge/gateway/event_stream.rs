@@ -0,0 +1,369 @@
+use model::event::Event;
+use parking_lot::Mutex;
+use std::{
+    fmt::{Debug, Formatter, Result as FmtResult},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError, Sender, SyncSender, TryRecvError, TrySendError},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// What a shard does when a bounded [`EventStream`] is full.
+///
+/// [`EventStream`]: struct.EventStream.html
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Backpressure {
+    /// The shard waits until the stream has room for the event.
+    ///
+    /// While waiting, the shard does not receive events or send heartbeats,
+    /// so a stream which is not drained for a long time causes the shard to
+    /// reconnect.
+    Block,
+    /// The event is dropped from the stream, and counted by
+    /// [`EventStream::dropped`].
+    ///
+    /// [`EventStream::dropped`]: struct.EventStream.html#method.dropped
+    Drop,
+}
+
+/// Options for creating an [`EventStream`].
+///
+/// [`EventStream`]: struct.EventStream.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct EventStreamOptions {
+    backpressure: Backpressure,
+    capacity: Option<usize>,
+}
+
+impl EventStreamOptions {
+    /// Creates options for a stream which buffers any number of events.
+    ///
+    /// This is the default.
+    pub fn unbounded() -> Self {
+        Self::default()
+    }
+
+    /// Creates options for a stream which buffers up to `capacity` events,
+    /// applying the given backpressure when it is full.
+    pub fn bounded(capacity: usize, backpressure: Backpressure) -> Self {
+        Self {
+            backpressure,
+            capacity: Some(capacity),
+        }
+    }
+
+    /// The backpressure applied when the stream is full.
+    pub fn backpressure(&self) -> Backpressure {
+        self.backpressure
+    }
+
+    /// The number of events the stream buffers, if it is bounded.
+    pub fn capacity(&self) -> Option<usize> {
+        self.capacity
+    }
+
+    pub(crate) fn build(self) -> (EventStreamSender, EventStream) {
+        let dropped = Arc::new(AtomicUsize::new(0));
+
+        let (kind, rx) = match self.capacity {
+            Some(capacity) => {
+                let (tx, rx) = mpsc::sync_channel(capacity);
+
+                (SenderKind::Bounded(tx, self.backpressure), rx)
+            },
+            None => {
+                let (tx, rx) = mpsc::channel();
+
+                (SenderKind::Unbounded(tx), rx)
+            },
+        };
+
+        let sender = EventStreamSender {
+            dropped: Arc::clone(&dropped),
+            kind,
+        };
+
+        (sender, EventStream { dropped, rx })
+    }
+}
+
+impl Default for EventStreamOptions {
+    fn default() -> Self {
+        Self {
+            backpressure: Backpressure::Block,
+            capacity: None,
+        }
+    }
+}
+
+/// An ordered stream of the events received by a client's shards, along with
+/// the Id of the shard which received them.
+///
+/// This is an alternative to receiving events through an [`EventHandler`],
+/// for services which prefer pulling events, such as bridges forwarding them
+/// to a queue. Events are sent to the stream by the shards themselves after
+/// the cache was updated with them, rather than from the threadpool, so the
+/// events of a shard are received in the order the shard received them.
+///
+/// The events are additionally dispatched to the [`EventHandler`]. To only
+/// receive events through the stream, give the client a handler which does
+/// not implement any methods. Events that are filtered out by the
+/// [`EventFilter`] are not sent to the stream.
+///
+/// Only one stream is used at a time, and the shards stop sending to a stream
+/// once it is dropped.
+///
+/// # Examples
+///
+/// Forward every message from a separate thread:
+///
+/// ```rust,no_run
+/// # use std::error::Error;
+/// #
+/// # fn try_main() -> Result<(), Box<Error>> {
+/// use serenity::client::bridge::gateway::{Backpressure, EventStreamOptions};
+/// use serenity::client::{Client, EventHandler};
+/// use serenity::model::event::Event;
+/// use std::{env, thread};
+///
+/// struct Handler;
+///
+/// impl EventHandler for Handler { }
+///
+/// let mut client = Client::new(&env::var("DISCORD_TOKEN")?, Handler)?;
+/// let stream = client.event_stream(EventStreamOptions::bounded(
+///     1000,
+///     Backpressure::Block,
+/// ));
+///
+/// thread::spawn(move || {
+///     for (shard_id, event) in stream {
+///         if let Event::MessageCreate(event) = event {
+///             println!("[shard {}] {}", shard_id, event.message.content);
+///         }
+///     }
+/// });
+///
+/// client.start()?;
+/// #     Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #     try_main().unwrap();
+/// # }
+/// ```
+///
+/// [`EventFilter`]: struct.EventFilter.html
+/// [`EventHandler`]: ../../trait.EventHandler.html
+pub struct EventStream {
+    dropped: Arc<AtomicUsize>,
+    rx: Receiver<(u64, Event)>,
+}
+
+impl EventStream {
+    /// The number of events which were dropped because the stream was full.
+    ///
+    /// This is only non-zero for streams using [`Backpressure::Drop`].
+    ///
+    /// [`Backpressure::Drop`]: enum.Backpressure.html#variant.Drop
+    pub fn dropped(&self) -> usize {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Waits for the next event.
+    ///
+    /// Returns `None` once the stream was replaced or the client was dropped.
+    pub fn recv(&self) -> Option<(u64, Event)> {
+        self.rx.recv().ok()
+    }
+
+    /// Waits for the next event for at most the given duration.
+    ///
+    /// Returns `None` if no event was received in time, or once the stream was
+    /// replaced or the client was dropped.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<(u64, Event)> {
+        match self.rx.recv_timeout(timeout) {
+            Ok(event) => Some(event),
+            Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => None,
+        }
+    }
+
+    /// Returns the next event if one is buffered, without waiting.
+    pub fn try_recv(&self) -> Option<(u64, Event)> {
+        match self.rx.try_recv() {
+            Ok(event) => Some(event),
+            Err(TryRecvError::Empty) | Err(TryRecvError::Disconnected) => None,
+        }
+    }
+}
+
+impl Debug for EventStream {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_struct("EventStream")
+            .field("dropped", &self.dropped())
+            .finish()
+    }
+}
+
+impl Iterator for EventStream {
+    type Item = (u64, Event);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.recv()
+    }
+}
+
+#[derive(Clone)]
+enum SenderKind {
+    Bounded(SyncSender<(u64, Event)>, Backpressure),
+    Unbounded(Sender<(u64, Event)>),
+}
+
+// The sending half of an `EventStream`, shared by the shard runners.
+#[derive(Clone)]
+pub(crate) struct EventStreamSender {
+    dropped: Arc<AtomicUsize>,
+    kind: SenderKind,
+}
+
+impl EventStreamSender {
+    // Sends an event received by a shard, returning whether the stream is
+    // still being received from.
+    pub fn send(&self, shard_id: u64, event: Event) -> bool {
+        match self.kind {
+            SenderKind::Bounded(ref tx, Backpressure::Block) => tx.send((shard_id, event)).is_ok(),
+            SenderKind::Bounded(ref tx, Backpressure::Drop) => match tx.try_send((shard_id, event)) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+
+                    true
+                },
+                Err(TrySendError::Disconnected(_)) => false,
+            },
+            SenderKind::Unbounded(ref tx) => tx.send((shard_id, event)).is_ok(),
+        }
+    }
+}
+
+// Sends an event received by a shard to the client's stream, if there is one,
+// returning whether the stream is still being received from. The stream is
+// removed once it is no longer received from.
+//
+// The sender is cloned out of the lock before sending, so that shards blocked
+// on a full stream don't block each other from reaching the stream.
+pub(crate) fn send_to_stream(
+    stream: &Mutex<Option<EventStreamSender>>,
+    shard_id: u64,
+    event: Event,
+) -> bool {
+    let sender = match *stream.lock() {
+        Some(ref sender) => sender.clone(),
+        None => return true,
+    };
+
+    if sender.send(shard_id, event) {
+        return true;
+    }
+
+    let mut stream = stream.lock();
+
+    // The stream may have been replaced while sending.
+    let replaced = match *stream {
+        Some(ref current) => !Arc::ptr_eq(&current.dropped, &sender.dropped),
+        None => true,
+    };
+
+    if !replaced {
+        *stream = None;
+    }
+
+    false
+}
+
+impl Debug for EventStreamSender {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_struct("EventStreamSender")
+            .field("dropped", &self.dropped.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use model::event::{Event, PresencesReplaceEvent};
+    use parking_lot::Mutex;
+    use std::{
+        sync::Arc,
+        thread,
+        time::Duration,
+    };
+    use super::{send_to_stream, Backpressure, EventStreamOptions};
+
+    fn event() -> Event {
+        Event::PresencesReplace(PresencesReplaceEvent {
+            presences: vec![],
+        })
+    }
+
+    #[test]
+    fn test_event_stream_order() {
+        let (sender, stream) = EventStreamOptions::unbounded().build();
+
+        for shard_id in 0..3 {
+            assert!(sender.send(shard_id, event()));
+        }
+
+        let shard_ids = stream.take(3).map(|(id, _)| id).collect::<Vec<_>>();
+
+        assert_eq!(shard_ids, vec![0, 1, 2]);
+        assert!(!sender.send(0, event()));
+    }
+
+    #[test]
+    fn test_event_stream_drop() {
+        let (sender, stream) = EventStreamOptions::bounded(1, Backpressure::Drop).build();
+
+        assert!(sender.send(0, event()));
+        assert!(sender.send(1, event()));
+        assert_eq!(stream.dropped(), 1);
+        assert_eq!(stream.try_recv().map(|(id, _)| id), Some(0));
+        assert!(stream.try_recv().is_none());
+
+        drop(stream);
+
+        assert!(!sender.send(2, event()));
+    }
+
+    #[test]
+    fn test_event_stream_full() {
+        let (sender, stream) = EventStreamOptions::bounded(1, Backpressure::Block).build();
+        let shared = Arc::new(Mutex::new(Some(sender)));
+        assert!(send_to_stream(&shared, 0, event()));
+
+        // Both runners wait for room in the full stream without holding the
+        // shared sender.
+        let runners = (1..3).map(|shard_id| {
+            let shared = Arc::clone(&shared);
+
+            thread::spawn(move || send_to_stream(&shared, shard_id, event()))
+        }).collect::<Vec<_>>();
+
+        thread::sleep(Duration::from_millis(50));
+        assert!(shared.try_lock_for(Duration::from_millis(50)).is_some());
+
+        let mut shard_ids = stream.take(3).map(|(id, _)| id).collect::<Vec<_>>();
+        shard_ids.sort();
+        assert_eq!(shard_ids, vec![0, 1, 2]);
+
+        for runner in runners {
+            assert!(runner.join().unwrap());
+        }
+
+        // The stream is removed once it was dropped.
+        assert!(!send_to_stream(&shared, 0, event()));
+        assert!(shared.lock().is_none());
+    }
+}
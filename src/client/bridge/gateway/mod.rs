@@ -50,19 +50,32 @@
 
 pub mod event;
 
+mod event_filter;
+mod event_stream;
 mod shard_manager;
 mod shard_manager_monitor;
 mod shard_messenger;
 mod shard_queuer;
 mod shard_runner;
 mod shard_runner_message;
+mod shard_stats;
 
+pub use self::event_filter::EventFilter;
+pub use self::event_stream::{Backpressure, EventStream, EventStreamOptions};
 pub use self::shard_manager::{ShardManager, ShardManagerOptions};
 pub use self::shard_manager_monitor::ShardManagerMonitor;
 pub use self::shard_messenger::ShardMessenger;
 pub use self::shard_queuer::ShardQueuer;
 pub use self::shard_runner::{ShardRunner, ShardRunnerOptions};
 pub use self::shard_runner_message::ShardRunnerMessage;
+pub use self::shard_stats::{
+    EVENT_RATE_WINDOW_IN_SECONDS,
+    LATENCY_HISTORY_SIZE,
+    RECONNECT_HISTORY_SIZE,
+    ReconnectReason,
+    ReconnectRecord,
+    ShardStats,
+};
 
 use std::{
     fmt::{
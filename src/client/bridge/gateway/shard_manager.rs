@@ -1,16 +1,32 @@
 use gateway::InterMessage;
 use internal::prelude::*;
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
 use std::{
     collections::{HashMap, VecDeque},
     sync::{
+        atomic::{AtomicBool, Ordering},
         mpsc::{self, Sender},
         Arc
     },
-    thread
+    thread,
+    time::Duration as StdDuration
+};
+use super::super::super::{
+    handler_error::ErrorReporter,
+    scheduler::Scheduler,
+    sequencer::Sequencer,
+    Collectors,
+    DataStore,
+    DispatchOrder,
+    ErrorHook,
+    EventHandler,
+    RawEventHandler,
 };
-use super::super::super::EventHandler;
 use super::{
+    event_stream::EventStreamSender,
+    EventFilter,
+    EventStream,
+    EventStreamOptions,
     ShardClientMessage,
     ShardId,
     ShardManagerMessage,
@@ -18,14 +34,19 @@ use super::{
     ShardQueuer,
     ShardQueuerMessage,
     ShardRunnerInfo,
+    ShardStats,
 };
 use threadpool::ThreadPool;
 use typemap::ShareMap;
 
+#[cfg(feature = "cache")]
+use cache::Cache;
 #[cfg(feature = "framework")]
 use framework::Framework;
 #[cfg(feature = "voice")]
 use client::bridge::voice::ClientVoiceManager;
+#[cfg(all(feature = "cluster", unix))]
+use client::bridge::cluster::ClusterWorker;
 
 /// A manager for handling the status of shards by starting them, restarting
 /// them, and stopping them when required.
@@ -50,13 +71,16 @@ use client::bridge::voice::ClientVoiceManager;
 /// # use serenity::client::bridge::voice::ClientVoiceManager;
 /// # #[cfg(feature = "voice")]
 /// # use serenity::model::id::UserId;
+/// # #[cfg(feature = "cache")]
+/// # use serenity::cache::Cache;
 /// #
 /// # #[cfg(feature = "framework")]
 /// # fn try_main() -> Result<(), Box<Error>> {
 /// #
 /// use parking_lot::Mutex;
 /// use serenity::client::bridge::gateway::{ShardManager, ShardManagerOptions};
-/// use serenity::client::EventHandler;
+/// use serenity::client::{Collectors, DataStore, EventHandler};
+/// use serenity::client::scheduler::Scheduler;
 /// use serenity::http;
 /// // Of note, this imports `typemap`'s `ShareMap` type.
 /// use serenity::prelude::*;
@@ -73,15 +97,25 @@ use client::bridge::voice::ClientVoiceManager;
 /// let token = Arc::new(Mutex::new(token));
 ///
 /// let gateway_url = Arc::new(Mutex::new(http::get_gateway()?.url));
+/// let collectors = Collectors::default();
 /// let data = Arc::new(Mutex::new(ShareMap::custom()));
+/// let data_store = DataStore::default();
 /// let event_handler = Arc::new(Handler);
 /// let framework = Arc::new(Mutex::new(None));
+/// let scheduler = Scheduler::default();
 /// let threadpool = ThreadPool::with_name("my threadpool".to_owned(), 5);
+/// # #[cfg(feature = "cache")]
+/// let cache = Arc::new(RwLock::new(Cache::default()));
 ///
 /// ShardManager::new(ShardManagerOptions {
+///     # #[cfg(feature = "cache")]
+///     cache: &cache,
+///     collectors: &collectors,
 ///     data: &data,
+///     data_store: &data_store,
 ///     event_handler: &event_handler,
 ///     framework: &framework,
+///     scheduler: &scheduler,
 ///     // the shard index to start initiating from
 ///     shard_index: 0,
 ///     // the number of shards to initiate (this initiates 0, 1, and 2)
@@ -110,13 +144,20 @@ use client::bridge::voice::ClientVoiceManager;
 /// [`Client`]: ../../struct.Client.html
 #[derive(Debug)]
 pub struct ShardManager {
+    #[cfg(all(feature = "cluster", unix))]
+    cluster: Arc<Mutex<Option<ClusterWorker>>>,
+    pub(crate) error_reporter: ErrorReporter,
+    event_filter: Arc<RwLock<EventFilter>>,
+    event_stream: Arc<Mutex<Option<EventStreamSender>>>,
     monitor_tx: Sender<ShardManagerMessage>,
+    raw_event_handlers: Arc<RwLock<Vec<Box<RawEventHandler>>>>,
     /// The shard runners currently managed.
     ///
     /// **Note**: It is highly unrecommended to mutate this yourself unless you
     /// need to. Instead prefer to use methods on this struct that are provided
     /// where possible.
     pub runners: Arc<Mutex<HashMap<ShardId, ShardRunnerInfo>>>,
+    sequencer: Sequencer,
     /// The index of the first shard to initialize, 0-indexed.
     shard_index: u64,
     /// The number of shards to initialize.
@@ -124,6 +165,8 @@ pub struct ShardManager {
     /// The total shards in use, 1-indexed.
     shard_total: u64,
     shard_queuer: Sender<ShardQueuerMessage>,
+    shutting_down: Arc<AtomicBool>,
+    stats: Arc<Mutex<HashMap<ShardId, Arc<Mutex<ShardStats>>>>>,
 }
 
 impl ShardManager {
@@ -135,18 +178,41 @@ impl ShardManager {
         let (thread_tx, thread_rx) = mpsc::channel();
         let (shard_queue_tx, shard_queue_rx) = mpsc::channel();
 
+        let error_reporter = ErrorReporter::default();
+        let event_filter = Arc::new(RwLock::new(EventFilter::default()));
+        let event_stream = Arc::new(Mutex::new(None));
+        let raw_event_handlers = Arc::new(RwLock::new(Vec::new()));
+        let sequencer = Sequencer::default();
         let runners = Arc::new(Mutex::new(HashMap::new()));
+        let shutting_down = Arc::new(AtomicBool::new(false));
+        let stats = Arc::new(Mutex::new(HashMap::new()));
+        #[cfg(all(feature = "cluster", unix))]
+        let cluster = Arc::new(Mutex::new(None));
 
         let mut shard_queuer = ShardQueuer {
+            #[cfg(feature = "cache")]
+            cache: Arc::clone(opt.cache),
+            #[cfg(all(feature = "cluster", unix))]
+            cluster: Arc::clone(&cluster),
+            collectors: opt.collectors.clone(),
             data: Arc::clone(opt.data),
+            data_store: opt.data_store.clone(),
+            error_reporter: error_reporter.clone(),
+            event_filter: Arc::clone(&event_filter),
+            event_stream: Arc::clone(&event_stream),
             event_handler: Arc::clone(opt.event_handler),
             #[cfg(feature = "framework")]
             framework: Arc::clone(opt.framework),
             last_start: None,
             manager_tx: thread_tx.clone(),
             queue: VecDeque::new(),
+            raw_event_handlers: Arc::clone(&raw_event_handlers),
             runners: Arc::clone(&runners),
             rx: shard_queue_rx,
+            scheduler: opt.scheduler.clone(),
+            sequencer: sequencer.clone(),
+            shutting_down: Arc::clone(&shutting_down),
+            stats: Arc::clone(&stats),
             threadpool: opt.threadpool,
             token: Arc::clone(opt.token),
             #[cfg(feature = "voice")]
@@ -159,12 +225,21 @@ impl ShardManager {
         });
 
         let manager = Arc::new(Mutex::new(Self {
+            #[cfg(all(feature = "cluster", unix))]
+            cluster,
+            error_reporter,
+            event_filter,
+            event_stream,
             monitor_tx: thread_tx,
+            raw_event_handlers,
             shard_index: opt.shard_index,
             shard_init: opt.shard_init,
             shard_queuer: shard_queue_tx,
             shard_total: opt.shard_total,
             runners,
+            sequencer,
+            shutting_down,
+            stats,
         }));
 
         (Arc::clone(&manager), ShardManagerMonitor {
@@ -258,6 +333,100 @@ impl ShardManager {
         self.boot([shard_id, ShardId(shard_total)]);
     }
 
+    /// Starts a shard runner for a shard that is not currently running.
+    ///
+    /// The start is queued via the [`ShardQueuer`] like with [`initialize`],
+    /// using the total number of shards given to [`set_shards`].
+    ///
+    /// [`ShardQueuer`]: struct.ShardQueuer.html
+    /// [`initialize`]: #method.initialize
+    /// [`set_shards`]: #method.set_shards
+    pub fn start(&mut self, shard_id: ShardId) {
+        let shard_total = self.shard_total;
+
+        self.boot([shard_id, ShardId(shard_total)]);
+    }
+
+    /// Sets the cluster worker that the [`ShardQueuer`] must ask for
+    /// permission before starting a shard.
+    ///
+    /// This is set by [`Client::start_cluster`].
+    ///
+    /// [`Client::start_cluster`]: ../../struct.Client.html#method.start_cluster
+    /// [`ShardQueuer`]: struct.ShardQueuer.html
+    #[cfg(all(feature = "cluster", unix))]
+    pub fn set_cluster(&mut self, cluster: Option<ClusterWorker>) {
+        *self.cluster.lock() = cluster;
+    }
+
+    /// Returns a copy of the filter deciding which events are processed by the
+    /// shards.
+    pub fn event_filter(&self) -> EventFilter {
+        self.event_filter.read().clone()
+    }
+
+    /// Sets the filter deciding which events are processed by the shards.
+    ///
+    /// This applies to running shards as well as those started later. Refer to
+    /// [`EventFilter`] for more information.
+    ///
+    /// [`EventFilter`]: struct.EventFilter.html
+    pub fn set_event_filter(&self, filter: EventFilter) {
+        *self.event_filter.write() = filter;
+    }
+
+    /// Creates a stream of the events received by the shards, replacing the
+    /// previous stream, if any.
+    ///
+    /// This applies to running shards as well as those started later. Refer to
+    /// [`EventStream`] for more information.
+    ///
+    /// [`EventStream`]: struct.EventStream.html
+    pub fn event_stream(&self, options: EventStreamOptions) -> EventStream {
+        let (sender, stream) = options.build();
+
+        *self.event_stream.lock() = Some(sender);
+
+        stream
+    }
+
+    /// Adds a handler which is given every event before and after the cache
+    /// is updated with it, after the handlers added previously.
+    ///
+    /// This applies to running shards as well as those started later. Refer to
+    /// [`RawEventHandler`] for more information.
+    ///
+    /// [`RawEventHandler`]: ../../trait.RawEventHandler.html
+    pub fn add_raw_event_handler<R: RawEventHandler + 'static>(&self, handler: R) {
+        self.raw_event_handlers.write().push(Box::new(handler));
+    }
+
+    /// Sets the hook which is given every failure of the event handler,
+    /// replacing the previous hook, if any.
+    ///
+    /// This applies to running shards as well as those started later. Refer to
+    /// [`ErrorHook`] for more information.
+    ///
+    /// [`ErrorHook`]: ../../trait.ErrorHook.html
+    pub fn set_error_hook<E: ErrorHook + 'static>(&self, hook: E) {
+        self.error_reporter.set_hook(hook);
+    }
+
+    /// Returns the order in which events are handled by the event handler.
+    pub fn dispatch_order(&self) -> DispatchOrder {
+        self.sequencer.order()
+    }
+
+    /// Sets the order in which events are handled by the event handler.
+    ///
+    /// This applies to running shards as well as those started later. Refer to
+    /// [`DispatchOrder`] for more information.
+    ///
+    /// [`DispatchOrder`]: ../../enum.DispatchOrder.html
+    pub fn set_dispatch_order(&self, order: DispatchOrder) {
+        self.sequencer.set_order(order);
+    }
+
     /// Returns the [`ShardId`]s of the shards that have been instantiated and
     /// currently have a valid [`ShardRunner`].
     ///
@@ -267,6 +436,89 @@ impl ShardManager {
         self.runners.lock().keys().cloned().collect()
     }
 
+    /// Returns a copy of the statistics recorded for a shard, if it has been
+    /// started at least once.
+    ///
+    /// # Examples
+    ///
+    /// Print the average heartbeat latency and event rate of shard 0:
+    ///
+    /// ```rust,no_run
+    /// use serenity::client::bridge::gateway::ShardId;
+    /// use serenity::client::{Client, EventHandler};
+    /// use std::env;
+    ///
+    /// struct Handler;
+    ///
+    /// impl EventHandler for Handler { }
+    ///
+    /// let token = env::var("DISCORD_TOKEN").unwrap();
+    /// let client = Client::new(&token, Handler).unwrap();
+    ///
+    /// let stats = client.shard_manager.lock().stats(ShardId(0));
+    ///
+    /// if let Some(stats) = stats {
+    ///     println!(
+    ///         "Latency: {:?}; events/s: {}",
+    ///         stats.average_latency(),
+    ///         stats.events_per_second(),
+    ///     );
+    /// }
+    /// ```
+    pub fn stats(&self, shard_id: ShardId) -> Option<ShardStats> {
+        self.stats.lock().get(&shard_id).map(|stats| stats.lock().clone())
+    }
+
+    /// Returns a copy of the statistics recorded for every shard that has
+    /// been started at least once.
+    pub fn stats_all(&self) -> HashMap<ShardId, ShardStats> {
+        collect_stats(&self.stats)
+    }
+
+    /// Spawns a thread which calls the given function with a snapshot of the
+    /// statistics of every shard once per `interval`.
+    ///
+    /// This is useful for exporting the health of shards to a monitoring
+    /// service. The thread stops once the shard manager has shutdown.
+    ///
+    /// # Examples
+    ///
+    /// Log the number of events received by each shard every minute:
+    ///
+    /// ```rust,no_run
+    /// use serenity::client::{Client, EventHandler};
+    /// use std::env;
+    /// use std::time::Duration;
+    ///
+    /// struct Handler;
+    ///
+    /// impl EventHandler for Handler { }
+    ///
+    /// let token = env::var("DISCORD_TOKEN").unwrap();
+    /// let client = Client::new(&token, Handler).unwrap();
+    ///
+    /// client.shard_manager.lock().stats_callback(Duration::from_secs(60), |stats| {
+    ///     for (shard_id, stats) in stats {
+    ///         println!("Shard {}: {} events", shard_id, stats.events_total());
+    ///     }
+    /// });
+    /// ```
+    pub fn stats_callback<F>(&self, interval: StdDuration, f: F)
+        where F: Fn(&HashMap<ShardId, ShardStats>) + Send + 'static {
+        let stats = Arc::downgrade(&self.stats);
+
+        thread::spawn(move || loop {
+            thread::sleep(interval);
+
+            let stats = match stats.upgrade() {
+                Some(stats) => stats,
+                None => break,
+            };
+
+            f(&collect_stats(&stats));
+        });
+    }
+
     /// Attempts to shut down the shard runner by Id.
     ///
     /// Returns a boolean indicating whether a shard runner was present. This is
@@ -325,6 +577,31 @@ impl ShardManager {
         let _ = self.monitor_tx.send(ShardManagerMessage::ShutdownInitiated);
     }
 
+    /// Begins a graceful shutdown of the manager.
+    ///
+    /// Events stop being dispatched and no new shards are started. Every
+    /// running shard is then closed and the [`ShardManagerMonitor`] stops,
+    /// allowing [`Client::start`] to return.
+    ///
+    /// This can not be undone.
+    ///
+    /// [`Client::start`]: ../../struct.Client.html#method.start
+    /// [`ShardManagerMonitor`]: struct.ShardManagerMonitor.html
+    pub fn begin_shutdown(&mut self) {
+        info!("Beginning graceful shutdown");
+
+        self.shutting_down.store(true, Ordering::SeqCst);
+
+        let _ = self.monitor_tx.send(ShardManagerMessage::ShutdownAll);
+    }
+
+    /// Whether a graceful shutdown was started via [`begin_shutdown`].
+    ///
+    /// [`begin_shutdown`]: #method.begin_shutdown
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutting_down.load(Ordering::SeqCst)
+    }
+
     fn boot(&mut self, shard_info: [ShardId; 2]) {
         info!("Telling shard queuer to start shard {}", shard_info[0]);
 
@@ -350,11 +627,26 @@ impl Drop for ShardManager {
     }
 }
 
+fn collect_stats(
+    stats: &Mutex<HashMap<ShardId, Arc<Mutex<ShardStats>>>>,
+) -> HashMap<ShardId, ShardStats> {
+    stats
+        .lock()
+        .iter()
+        .map(|(shard_id, stats)| (*shard_id, stats.lock().clone()))
+        .collect()
+}
+
 pub struct ShardManagerOptions<'a, H: EventHandler + Send + Sync + 'static> {
+    #[cfg(feature = "cache")]
+    pub cache: &'a Arc<RwLock<Cache>>,
+    pub collectors: &'a Collectors,
     pub data: &'a Arc<Mutex<ShareMap>>,
+    pub data_store: &'a DataStore,
     pub event_handler: &'a Arc<H>,
     #[cfg(feature = "framework")]
     pub framework: &'a Arc<Mutex<Option<Box<Framework + Send>>>>,
+    pub scheduler: &'a Scheduler,
     pub shard_index: u64,
     pub shard_init: u64,
     pub shard_total: u64,
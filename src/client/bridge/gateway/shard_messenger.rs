@@ -158,6 +158,7 @@ impl ShardMessenger {
     ///     kind: GameType::Playing,
     ///     name: "Heroes of the Storm".to_owned(),
     ///     url: None,
+    ///     ..Default::default()
     /// }));
     /// #     Ok(())
     /// # }
@@ -224,6 +225,23 @@ impl ShardMessenger {
         let _ = self.send(ShardRunnerMessage::SetPresence(status, game));
     }
 
+    /// Sets the user's presence to multiple activities at once, along with
+    /// their online status.
+    ///
+    /// The first activity is also sent as the presence's game, for clients
+    /// which only display one. Note that [`Offline`] is not a valid online
+    /// status, so it is automatically converted to [`Invisible`].
+    ///
+    /// [`Invisible`]: ../../../model/user/enum.OnlineStatus.html#variant.Invisible
+    /// [`Offline`]: ../../../model/user/enum.OnlineStatus.html#variant.Offline
+    pub fn set_activities(&self, activities: Vec<Game>, mut status: OnlineStatus) {
+        if status == OnlineStatus::Offline {
+            status = OnlineStatus::Invisible;
+        }
+
+        let _ = self.send(ShardRunnerMessage::SetActivities(status, activities));
+    }
+
     /// Sets the user's current online status.
     ///
     /// Note that [`Offline`] is not a valid online status, so it is
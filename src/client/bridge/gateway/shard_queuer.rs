@@ -1,9 +1,10 @@
 use gateway::Shard;
 use internal::prelude::*;
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
 use std::{
     collections::{HashMap, VecDeque},
     sync::{
+        atomic::{AtomicBool, Ordering},
         mpsc::{
             Receiver,
             RecvTimeoutError,
@@ -13,23 +14,38 @@ use std::{
     thread,
     time::{Duration, Instant}
 };
-use super::super::super::EventHandler;
+use super::super::super::{
+    handler_error::ErrorReporter,
+    scheduler::Scheduler,
+    sequencer::Sequencer,
+    Collectors,
+    DataStore,
+    EventHandler,
+    RawEventHandler,
+};
 use super::{
+    event_stream::EventStreamSender,
+    EventFilter,
     ShardId,
     ShardManagerMessage,
     ShardQueuerMessage,
     ShardRunner,
     ShardRunnerInfo,
     ShardRunnerOptions,
+    ShardStats,
 };
 use threadpool::ThreadPool;
 use typemap::ShareMap;
 use ::gateway::ConnectionStage;
 
+#[cfg(feature = "cache")]
+use cache::Cache;
 #[cfg(feature = "voice")]
 use client::bridge::voice::ClientVoiceManager;
 #[cfg(feature = "framework")]
 use framework::Framework;
+#[cfg(all(feature = "cluster", unix))]
+use client::bridge::cluster::ClusterWorker;
 
 const WAIT_BETWEEN_BOOTS_IN_SECONDS: u64 = 5;
 
@@ -40,11 +56,37 @@ const WAIT_BETWEEN_BOOTS_IN_SECONDS: u64 = 5;
 /// blocking nature of the loop itself as well as a 5 second thread sleep
 /// between shard starts.
 pub struct ShardQueuer<H: EventHandler + Send + Sync + 'static> {
+    /// A copy of [`Client::cache`] to be given to runners for updating and
+    /// contextual dispatching.
+    ///
+    /// [`Client::cache`]: ../../struct.Client.html#structfield.cache
+    #[cfg(feature = "cache")]
+    pub cache: Arc<RwLock<Cache>>,
+    /// The cluster worker to ask for permission before starting a shard, if
+    /// the shards are run as part of a cluster.
+    #[cfg(all(feature = "cluster", unix))]
+    pub cluster: Arc<Mutex<Option<ClusterWorker>>>,
+    /// A copy of [`Client::collectors`] to be given to runners for feeding
+    /// them with events.
+    ///
+    /// [`Client::collectors`]: ../../struct.Client.html#structfield.collectors
+    pub collectors: Collectors,
     /// A copy of [`Client::data`] to be given to runners for contextual
     /// dispatching.
     ///
     /// [`Client::data`]: ../../struct.Client.html#structfield.data
     pub data: Arc<Mutex<ShareMap>>,
+    /// A copy of [`Client::data_store`] to be given to runners for contextual
+    /// dispatching.
+    ///
+    /// [`Client::data_store`]: ../../struct.Client.html#structfield.data_store
+    pub data_store: DataStore,
+    /// A copy of the reporter of event handler failures.
+    pub(crate) error_reporter: ErrorReporter,
+    /// A copy of the filter deciding which events are processed by runners.
+    pub event_filter: Arc<RwLock<EventFilter>>,
+    /// A copy of the sender of the stream which runners send events to.
+    pub(crate) event_stream: Arc<Mutex<Option<EventStreamSender>>>,
     /// A reference to an `EventHandler`, such as the one given to the
     /// [`Client`].
     ///
@@ -66,10 +108,29 @@ pub struct ShardQueuer<H: EventHandler + Send + Sync + 'static> {
     ///
     /// This will typically be filled with previously failed boots.
     pub queue: VecDeque<(u64, u64)>,
+    /// A copy of the handlers given every event before and after the cache is
+    /// updated with it.
+    pub raw_event_handlers: Arc<RwLock<Vec<Box<RawEventHandler>>>>,
     /// A copy of the map of shard runners.
     pub runners: Arc<Mutex<HashMap<ShardId, ShardRunnerInfo>>>,
     /// A receiver channel for the shard queuer to be told to start shards.
     pub rx: Receiver<ShardQueuerMessage>,
+    /// A copy of [`Client::scheduler`] to be given to runners for contextual
+    /// dispatching.
+    ///
+    /// [`Client::scheduler`]: ../../struct.Client.html#structfield.scheduler
+    pub scheduler: Scheduler,
+    /// A copy of the sequencer deciding the order in which runners dispatch
+    /// events.
+    pub(crate) sequencer: Sequencer,
+    /// Whether the manager is shutting down gracefully, in which case no more
+    /// shards are started.
+    pub shutting_down: Arc<AtomicBool>,
+    /// A copy of the map of shard statistics.
+    ///
+    /// The statistics of a shard are kept when it is restarted, so that the
+    /// new runner continues recording to them.
+    pub stats: Arc<Mutex<HashMap<ShardId, Arc<Mutex<ShardStats>>>>>,
     /// A copy of a threadpool to give shard runners.
     ///
     /// For example, when using the [`Client`], this will be a copy of
@@ -156,9 +217,44 @@ impl<H: EventHandler + Send + Sync + 'static> ShardQueuer<H> {
         thread::sleep(to_sleep);
     }
 
+    // Waits for the coordinator to allow the shard to IDENTIFY, returning
+    // whether it may be started.
+    #[cfg(all(feature = "cluster", unix))]
+    fn check_cluster(&self, id: u64) -> bool {
+        let cluster = match *self.cluster.lock() {
+            Some(ref cluster) => cluster.clone(),
+            None => return true,
+        };
+
+        match cluster.wait_for_identify(ShardId(id)) {
+            Ok(()) => true,
+            Err(why) => {
+                // The coordinator moves the shards of a worker whose
+                // connection closed to another worker, so the shard must not
+                // be started here as well.
+                error!("Not starting shard {}; cluster refused IDENTIFY: {:?}", id, why);
+
+                false
+            },
+        }
+    }
+
     fn checked_start(&mut self, id: u64, total: u64) {
         self.check_last_start();
 
+        if self.shutting_down.load(Ordering::SeqCst) {
+            info!("Not starting shard {} due to shutdown", id);
+
+            return;
+        }
+
+        #[cfg(all(feature = "cluster", unix))]
+        {
+            if !self.check_cluster(id) {
+                return;
+            }
+        }
+
         if let Err(why) = self.start(id, total) {
             warn!("Err starting shard {}: {:?}", id, why);
             info!("Re-queueing start of shard {}", id);
@@ -178,12 +274,29 @@ impl<H: EventHandler + Send + Sync + 'static> ShardQueuer<H> {
             shard_info,
         )?;
 
+        let stats = Arc::clone(self.stats
+            .lock()
+            .entry(ShardId(shard_id))
+            .or_insert_with(|| Arc::new(Mutex::new(ShardStats::new()))));
+
         let mut runner = ShardRunner::new(ShardRunnerOptions {
+            #[cfg(feature = "cache")]
+            cache: Arc::clone(&self.cache),
+            collectors: self.collectors.clone(),
             data: Arc::clone(&self.data),
+            data_store: self.data_store.clone(),
+            error_reporter: self.error_reporter.clone(),
+            event_filter: Arc::clone(&self.event_filter),
+            event_stream: Arc::clone(&self.event_stream),
             event_handler: Arc::clone(&self.event_handler),
             #[cfg(feature = "framework")]
             framework: Arc::clone(&self.framework),
             manager_tx: self.manager_tx.clone(),
+            raw_event_handlers: Arc::clone(&self.raw_event_handlers),
+            scheduler: self.scheduler.clone(),
+            sequencer: self.sequencer.clone(),
+            shutting_down: Arc::clone(&self.shutting_down),
+            stats,
             threadpool: self.threadpool.clone(),
             #[cfg(feature = "voice")]
             voice_manager: Arc::clone(&self.voice_manager),
@@ -1,10 +1,11 @@
-use gateway::{InterMessage, ReconnectType, Shard, ShardAction};
+use gateway::{GatewayError, InterMessage, ReconnectType, Shard, ShardAction};
 use internal::prelude::*;
 use internal::ws_impl::{ReceiverExt, SenderExt};
 use model::event::{Event, GatewayEvent};
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
 use serde::Deserialize;
 use std::sync::{
+    atomic::{AtomicBool, Ordering},
     mpsc::{
         self,
         Receiver,
@@ -13,10 +14,30 @@ use std::sync::{
     },
     Arc
 };
+use std::time::Instant;
 use super::super::super::dispatch::{DispatchEvent, dispatch};
-use super::super::super::EventHandler;
+use super::super::super::{
+    handler_error::ErrorReporter,
+    scheduler::Scheduler,
+    sequencer::Sequencer,
+    Collectors,
+    Context,
+    DataStore,
+    EventHandler,
+    RawEventHandler,
+    RawEventStage,
+};
 use super::event::{ClientEvent, ShardStageUpdateEvent};
-use super::{ShardClientMessage, ShardId, ShardManagerMessage, ShardRunnerMessage};
+use super::{
+    event_stream::{self, EventStreamSender},
+    EventFilter,
+    ReconnectReason,
+    ShardClientMessage,
+    ShardId,
+    ShardManagerMessage,
+    ShardRunnerMessage,
+    ShardStats,
+};
 use threadpool::ThreadPool;
 use typemap::ShareMap;
 use websocket::{
@@ -24,6 +45,8 @@ use websocket::{
     WebSocketError
 };
 
+#[cfg(feature = "cache")]
+use cache::Cache;
 #[cfg(feature = "framework")]
 use framework::Framework;
 #[cfg(feature = "voice")]
@@ -33,16 +56,30 @@ use super::super::voice::ClientVoiceManager;
 ///
 /// [`Shard`]: ../../../gateway/struct.Shard.html
 pub struct ShardRunner<H: EventHandler + Send + Sync + 'static> {
+    #[cfg(feature = "cache")]
+    cache: Arc<RwLock<Cache>>,
+    collectors: Collectors,
     data: Arc<Mutex<ShareMap>>,
+    data_store: DataStore,
+    error_reporter: ErrorReporter,
+    event_filter: Arc<RwLock<EventFilter>>,
     event_handler: Arc<H>,
+    event_stream: Arc<Mutex<Option<EventStreamSender>>>,
     #[cfg(feature = "framework")]
     framework: Arc<Mutex<Option<Box<Framework + Send>>>>,
     manager_tx: Sender<ShardManagerMessage>,
+    // the heartbeat whose acknowledgement was last counted as missed
+    missed_heartbeat: Option<Instant>,
+    raw_event_handlers: Arc<RwLock<Vec<Box<RawEventHandler>>>>,
     // channel to receive messages from the shard manager and dispatches
     runner_rx: Receiver<InterMessage>,
     // channel to send messages to the shard runner from the shard manager
     runner_tx: Sender<InterMessage>,
+    scheduler: Scheduler,
+    sequencer: Sequencer,
     shard: Shard,
+    shutting_down: Arc<AtomicBool>,
+    stats: Arc<Mutex<ShardStats>>,
     threadpool: ThreadPool,
     #[cfg(feature = "voice")]
     voice_manager: Arc<Mutex<ClientVoiceManager>>,
@@ -56,12 +93,25 @@ impl<H: EventHandler + Send + Sync + 'static> ShardRunner<H> {
         Self {
             runner_rx: rx,
             runner_tx: tx,
+            #[cfg(feature = "cache")]
+            cache: opt.cache,
+            collectors: opt.collectors,
             data: opt.data,
+            data_store: opt.data_store,
+            error_reporter: opt.error_reporter,
+            event_filter: opt.event_filter,
             event_handler: opt.event_handler,
+            event_stream: opt.event_stream,
             #[cfg(feature = "framework")]
             framework: opt.framework,
             manager_tx: opt.manager_tx,
+            missed_heartbeat: None,
+            raw_event_handlers: opt.raw_event_handlers,
+            scheduler: opt.scheduler,
+            sequencer: opt.sequencer,
             shard: opt.shard,
+            shutting_down: opt.shutting_down,
+            stats: opt.stats,
             threadpool: opt.threadpool,
             #[cfg(feature = "voice")]
             voice_manager: opt.voice_manager,
@@ -109,11 +159,20 @@ impl<H: EventHandler + Send + Sync + 'static> ShardRunner<H> {
                     self.shard.shard_info(),
                 );
 
+                let reason = if self.shard.last_heartbeat_acknowledged() {
+                    ReconnectReason::ConnectionLost
+                } else {
+                    self.record_missed_heartbeat_ack();
+
+                    ReconnectReason::HeartbeatAckMissed
+                };
+                self.stats.lock().record_reconnect(ReconnectType::Reidentify, reason);
+
                 return self.request_restart();
             }
 
             let pre = self.shard.stage();
-            let (event, action, successful) = self.recv_event();
+            let (event, action, received) = self.recv_event();
             let post = self.shard.stage();
 
             if post != pre {
@@ -141,8 +200,12 @@ impl<H: EventHandler + Send + Sync + 'static> ShardRunner<H> {
                 self.dispatch(DispatchEvent::Model(event));
             }
 
-            if !successful && !self.shard.stage().is_connecting() {
-                return self.request_restart();
+            if let Err(reason) = received {
+                if !self.shard.stage().is_connecting() {
+                    self.stats.lock().record_reconnect(ReconnectType::Reidentify, reason);
+
+                    return self.request_restart();
+                }
             }
         }
     }
@@ -197,18 +260,74 @@ impl<H: EventHandler + Send + Sync + 'static> ShardRunner<H> {
         false
     }
 
+    // Events received during a graceful shutdown are dropped, so that only
+    // the handlers already running need to finish.
+    //
+    // Model events are given to the raw event handlers before and after the
+    // cache is updated with them, and then sent to the event stream.
     #[inline]
     fn dispatch(&self, event: DispatchEvent) {
+        if self.shutting_down.load(Ordering::SeqCst) {
+            return;
+        }
+
+        let context = self.context();
+        let raw_event_handlers = self.raw_event_handlers.read();
+
+        let processed = match event {
+            DispatchEvent::Model(ref event) => {
+                dispatch_raw(&raw_event_handlers, &context, event, RawEventStage::BeforeCache);
+
+                if raw_event_handlers.is_empty() && self.event_stream.lock().is_none() {
+                    None
+                } else {
+                    Some(event.clone())
+                }
+            },
+            DispatchEvent::Client(_) => None,
+        };
+
         dispatch(
             event,
             #[cfg(feature = "framework")]
             &self.framework,
-            &self.data,
+            &context,
             &self.event_handler,
-            &self.runner_tx,
             &self.threadpool,
-            self.shard.shard_info()[0],
+            &self.sequencer,
         );
+
+        if let Some(event) = processed {
+            dispatch_raw(&raw_event_handlers, &context, &event, RawEventStage::AfterCache);
+
+            self.stream(event);
+        }
+    }
+
+    // Sends an event to the event stream once it was dispatched, so that the
+    // cache is already updated with it. The stream is removed once it is no
+    // longer received from.
+    fn stream(&self, event: Event) {
+        if !event_stream::send_to_stream(&self.event_stream, self.shard.shard_info()[0], event) {
+            debug!("[ShardRunner {:?}] Event stream dropped", self.shard.shard_info());
+        }
+    }
+
+    // Creates a context for an event dispatched by this runner.
+    fn context(&self) -> Context {
+        let collectors = self.collectors.clone();
+        let data = Arc::clone(&self.data);
+        let data_store = self.data_store.clone();
+        let error_reporter = self.error_reporter.clone();
+        let runner_tx = self.runner_tx.clone();
+        let scheduler = self.scheduler.clone();
+        let shard_id = self.shard.shard_info()[0];
+
+        feature_cache! {{
+            Context::new(Arc::clone(&self.cache), collectors, data, data_store, error_reporter, runner_tx, scheduler, shard_id)
+        } else {
+            Context::new(collectors, data, data_store, error_reporter, runner_tx, scheduler, shard_id)
+        }}
     }
 
     // Handles a received value over the shard runner rx channel.
@@ -258,6 +377,12 @@ impl<H: EventHandler + Send + Sync + 'static> ShardRunner<H> {
                 ShardRunnerMessage::Message(msg) => {
                     self.shard.client.send_message(&msg).is_ok()
                 },
+                ShardRunnerMessage::SetActivities(status, activities) => {
+                    self.shard.set_activities(activities);
+                    self.shard.set_status(status);
+
+                    self.shard.update_presence().is_ok()
+                },
                 ShardRunnerMessage::SetGame(game) => {
                     // To avoid a clone of `game`, we do a little bit of
                     // trickery here:
@@ -363,12 +488,43 @@ impl<H: EventHandler + Send + Sync + 'static> ShardRunner<H> {
         Ok(true)
     }
 
+    // Counts the unacknowledged heartbeat last sent as missed, unless it was
+    // already counted by an earlier check.
+    fn record_missed_heartbeat_ack(&mut self) {
+        let sent = self.shard.last_heartbeat_sent().cloned();
+
+        if sent.is_some() && sent == self.missed_heartbeat {
+            return;
+        }
+
+        self.missed_heartbeat = sent;
+        self.stats.lock().record_missed_heartbeat_ack();
+    }
+
     /// Returns a received event, as well as whether reading the potentially
-    /// present event was successful.
-    fn recv_event(&mut self) -> (Option<Event>, Option<ShardAction>, bool) {
+    /// present event was successful, or why the shard has to reconnect if it
+    /// was not.
+    fn recv_event(
+        &mut self,
+    ) -> (Option<Event>, Option<ShardAction>, StdResult<(), ReconnectReason>) {
+        // The type of a dispatch skipped by the event filter, and whether it
+        // should still be dispatched raw.
+        let mut skipped = None;
+
         let gw_event = match self.shard.client.recv_json() {
-            Ok(Some(value)) => {
-                GatewayEvent::deserialize(value).map(Some).map_err(From::from)
+            Ok(Some(mut value)) => {
+                let filter = self.event_filter.read();
+
+                match filter.skip(&mut value) {
+                    Some((seq, kind, event)) => {
+                        skipped = Some((kind, filter.is_raw()));
+
+                        Ok(Some(GatewayEvent::Dispatch(seq, Event::Unknown(event))))
+                    },
+                    None => {
+                        GatewayEvent::deserialize(value).map(Some).map_err(From::from)
+                    },
+                }
             },
             Ok(None) => Ok(None),
             Err(Error::WebSocket(WebSocketError::IoError(_))) => {
@@ -387,53 +543,80 @@ impl<H: EventHandler + Send + Sync + 'static> ShardRunner<H> {
                         let interval_in_secs = interval / 1000;
 
                         if seconds_passed <= interval_in_secs * 2 {
-                            return (None, None, true);
+                            return (None, None, Ok(()));
                         }
                     } else {
-                        return (None, None, true);
+                        return (None, None, Ok(()));
                     }
                 }
 
                 debug!("Attempting to auto-reconnect");
 
+                self.record_missed_heartbeat_ack();
+
                 match self.shard.reconnection_type() {
-                    ReconnectType::Reidentify => return (None, None, false),
+                    ReconnectType::Reidentify => return (None, None, Err(ReconnectReason::HeartbeatAckMissed)),
                     ReconnectType::Resume => {
+                        self.stats.lock().record_reconnect(
+                            ReconnectType::Resume,
+                            ReconnectReason::HeartbeatAckMissed,
+                        );
+
                         if let Err(why) = self.shard.resume() {
                             warn!("Failed to resume: {:?}", why);
 
-                            return (None, None, false);
+                            return (None, None, Err(ReconnectReason::HeartbeatAckMissed));
                         }
                     },
                 }
 
-                return (None, None, true);
+                return (None, None, Ok(()));
             },
             Err(Error::WebSocket(WebSocketError::NoDataAvailable)) => {
                 // This is hit when the websocket client dies this will be
                 // hit every iteration.
-                return (None, None, false);
+                return (None, None, Err(ReconnectReason::ConnectionLost));
             },
             Err(why) => Err(why),
         };
 
         let event = match gw_event {
             Ok(Some(event)) => Ok(event),
-            Ok(None) => return (None, None, true),
+            Ok(None) => return (None, None, Ok(())),
             Err(why) => Err(why),
         };
 
+        let reason = match event {
+            Ok(GatewayEvent::InvalidateSession(_)) => {
+                ReconnectReason::InvalidSession
+            },
+            Ok(GatewayEvent::Reconnect) => ReconnectReason::GatewayRequested,
+            Err(Error::Gateway(GatewayError::Closed(ref data))) => {
+                ReconnectReason::Closed(data.as_ref().map(|d| d.status_code))
+            },
+            Err(Error::WebSocket(_)) => ReconnectReason::ConnectionLost,
+            _ => ReconnectReason::Other,
+        };
+
         let action = match self.shard.handle_event(&event) {
             Ok(Some(action)) => Some(action),
             Ok(None) => None,
             Err(why) => {
                 error!("Shard handler received err: {:?}", why);
 
-                return (None, None, true);
+                return (None, None, Ok(()));
             },
         };
 
+        if let Some(ShardAction::Reconnect(kind)) = action {
+            self.stats.lock().record_reconnect(kind, reason);
+        }
+
         if let Ok(GatewayEvent::HeartbeatAck) = event {
+            if let Some(latency) = self.shard.latency() {
+                self.stats.lock().record_latency(latency);
+            }
+
             self.update_manager();
         }
 
@@ -445,11 +628,26 @@ impl<H: EventHandler + Send + Sync + 'static> ShardRunner<H> {
         }
 
         let event = match event {
-            Ok(GatewayEvent::Dispatch(_, event)) => Some(event),
+            Ok(GatewayEvent::Dispatch(_, event)) => match skipped {
+                Some((kind, raw)) => {
+                    self.stats.lock().record_event(kind);
+
+                    if raw {
+                        Some(event)
+                    } else {
+                        None
+                    }
+                },
+                None => {
+                    self.stats.lock().record_event(event.event_type());
+
+                    Some(event)
+                },
+            },
             _ => None,
         };
 
-        (event, action, true)
+        (event, action, Ok(()))
     }
 
     fn request_restart(&self) -> Result<()> {
@@ -480,17 +678,97 @@ impl<H: EventHandler + Send + Sync + 'static> ShardRunner<H> {
     }
 }
 
+// Gives an event to the raw event handlers, reporting any handler which panics
+// so that the shard keeps running.
+fn dispatch_raw(
+    handlers: &[Box<RawEventHandler>],
+    context: &Context,
+    event: &Event,
+    stage: RawEventStage,
+) {
+    for handler in handlers {
+        context.error_reporter.catch("raw_event", context.shard_id, || {
+            handler.raw_event(context, event, stage);
+        });
+    }
+}
+
 /// Options to be passed to [`ShardRunner::new`].
 ///
 /// [`ShardRunner::new`]: struct.ShardRunner.html#method.new
 pub struct ShardRunnerOptions<H: EventHandler + Send + Sync + 'static> {
+    #[cfg(feature = "cache")]
+    pub cache: Arc<RwLock<Cache>>,
+    pub collectors: Collectors,
     pub data: Arc<Mutex<ShareMap>>,
+    pub data_store: DataStore,
+    pub(crate) error_reporter: ErrorReporter,
+    pub event_filter: Arc<RwLock<EventFilter>>,
     pub event_handler: Arc<H>,
+    pub(crate) event_stream: Arc<Mutex<Option<EventStreamSender>>>,
     #[cfg(feature = "framework")]
     pub framework: Arc<Mutex<Option<Box<Framework + Send>>>>,
     pub manager_tx: Sender<ShardManagerMessage>,
+    pub raw_event_handlers: Arc<RwLock<Vec<Box<RawEventHandler>>>>,
+    pub scheduler: Scheduler,
+    pub(crate) sequencer: Sequencer,
     pub shard: Shard,
+    pub shutting_down: Arc<AtomicBool>,
+    pub stats: Arc<Mutex<ShardStats>>,
     pub threadpool: ThreadPool,
     #[cfg(feature = "voice")]
     pub voice_manager: Arc<Mutex<ClientVoiceManager>>,
 }
+
+#[cfg(test)]
+mod test {
+    use internal::test_util;
+    use model::event::{Event, UnknownEvent};
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+    use super::super::super::super::{
+        Context,
+        HandlerError,
+        HandlerErrorKind,
+        RawEventHandler,
+        RawEventStage,
+    };
+    use super::dispatch_raw;
+
+    #[test]
+    fn test_raw_event_handler_panic() {
+        let context = test_util::context();
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&errors);
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let called = Arc::clone(&calls);
+
+        context.error_reporter.set_hook(move |error: HandlerError| {
+            let message = match error.kind {
+                HandlerErrorKind::Panic(message) => message,
+                HandlerErrorKind::Error(_) => None,
+            };
+
+            recorded.lock().push((error.event, message));
+        });
+
+        let handlers: Vec<Box<RawEventHandler>> = vec![
+            Box::new(|_: &Context, _: &Event, _: RawEventStage| panic!("raw handler panicked")),
+            Box::new(move |_: &Context, _: &Event, stage: RawEventStage| called.lock().push(stage)),
+        ];
+        let event = Event::Unknown(UnknownEvent {
+            kind: "TEST".to_owned(),
+            value: json!({}),
+        });
+
+        dispatch_raw(&handlers, &context, &event, RawEventStage::BeforeCache);
+        dispatch_raw(&handlers, &context, &event, RawEventStage::AfterCache);
+
+        // The handlers after the panicking one still receive both stages.
+        assert_eq!(*calls.lock(), vec![RawEventStage::BeforeCache, RawEventStage::AfterCache]);
+        assert_eq!(*errors.lock(), vec![
+            ("raw_event", Some("raw handler panicked".to_owned())),
+            ("raw_event", Some("raw handler panicked".to_owned())),
+        ]);
+    }
+}
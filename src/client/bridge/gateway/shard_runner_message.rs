@@ -38,6 +38,9 @@ pub enum ShardRunnerMessage {
     Close(u16, Option<String>),
     /// Indicates that the client is to send a custom WebSocket message.
     Message(OwnedMessage),
+    /// Indicates that the client is to update the shard's presence to the
+    /// given activities, along with the online status.
+    SetActivities(OnlineStatus, Vec<Game>),
     /// Indicates that the client is to update the shard's presence's game.
     SetGame(Option<Game>),
     /// Indicates that the client is to update the shard's presence in its
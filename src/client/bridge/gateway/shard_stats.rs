@@ -0,0 +1,306 @@
+use gateway::ReconnectType;
+use model::event::EventType;
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration as StdDuration, Instant}
+};
+
+/// The number of heartbeat latencies kept in a shard's latency history.
+pub const LATENCY_HISTORY_SIZE: usize = 20;
+
+/// The number of reconnects kept in a shard's reconnect history.
+pub const RECONNECT_HISTORY_SIZE: usize = 20;
+
+/// The number of seconds over which the event rate of a shard is averaged.
+pub const EVENT_RATE_WINDOW_IN_SECONDS: u64 = 60;
+
+/// The reason that a shard had to reconnect to the gateway.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ReconnectReason {
+    /// The gateway closed the connection, with the close code if one was
+    /// given.
+    Closed(Option<u16>),
+    /// The gateway asked the shard to reconnect.
+    GatewayRequested,
+    /// The last heartbeat sent was not acknowledged in time.
+    HeartbeatAckMissed,
+    /// The gateway invalidated the session.
+    InvalidSession,
+    /// The WebSocket connection was lost or errored.
+    ConnectionLost,
+    /// The gateway sent an unexpected payload, such as a late Hello or a
+    /// heartbeat with an off sequence.
+    Other,
+}
+
+/// A record of a single reconnect performed by a shard.
+#[derive(Clone, Copy, Debug)]
+pub struct ReconnectRecord {
+    /// The instant at which the reconnect was started.
+    pub at: Instant,
+    /// Whether a new session was identified or the old one resumed.
+    pub kind: ReconnectType,
+    /// Why the shard reconnected.
+    pub reason: ReconnectReason,
+}
+
+/// Statistics about the health of a shard over its lifetime.
+///
+/// These are collected by the shard's [`ShardRunner`] and kept across
+/// restarts of the shard. Retrieve a copy of them via [`ShardManager::stats`]
+/// or periodically via [`ShardManager::stats_callback`].
+///
+/// [`ShardManager::stats`]: struct.ShardManager.html#method.stats
+/// [`ShardManager::stats_callback`]: struct.ShardManager.html#method.stats_callback
+/// [`ShardRunner`]: struct.ShardRunner.html
+#[derive(Clone, Debug)]
+pub struct ShardStats {
+    created: Instant,
+    events: HashMap<EventType, u64>,
+    events_total: u64,
+    // Number of events received in each second, keyed by the number of
+    // seconds since `created`.
+    event_rate: VecDeque<(u64, u64)>,
+    last_event: Option<Instant>,
+    latency_history: VecDeque<StdDuration>,
+    missed_heartbeat_acks: u64,
+    reconnect_history: VecDeque<ReconnectRecord>,
+    reconnects: u64,
+    resumes: u64,
+    session_started: Option<Instant>,
+}
+
+impl ShardStats {
+    /// Creates a new, empty set of statistics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the most recently recorded heartbeat latency.
+    pub fn latency(&self) -> Option<StdDuration> {
+        self.latency_history.back().cloned()
+    }
+
+    /// Returns the most recent heartbeat latencies, from oldest to newest.
+    ///
+    /// At most [`LATENCY_HISTORY_SIZE`] latencies are kept.
+    ///
+    /// [`LATENCY_HISTORY_SIZE`]: constant.LATENCY_HISTORY_SIZE.html
+    pub fn latency_history(&self) -> &VecDeque<StdDuration> {
+        &self.latency_history
+    }
+
+    /// Returns the average of the latencies in the [`latency_history`].
+    ///
+    /// [`latency_history`]: #method.latency_history
+    pub fn average_latency(&self) -> Option<StdDuration> {
+        let count = self.latency_history.len() as u32;
+
+        if count == 0 {
+            return None;
+        }
+
+        let sum = self.latency_history
+            .iter()
+            .fold(StdDuration::from_secs(0), |sum, latency| sum + *latency);
+
+        Some(sum / count)
+    }
+
+    /// Returns the number of heartbeats that were not acknowledged by the
+    /// gateway before the next heartbeat was due.
+    pub fn missed_heartbeat_acks(&self) -> u64 {
+        self.missed_heartbeat_acks
+    }
+
+    /// Returns the number of times that the shard reconnected by identifying
+    /// a new session.
+    pub fn reconnects(&self) -> u64 {
+        self.reconnects
+    }
+
+    /// Returns the number of times that the shard reconnected by resuming its
+    /// session.
+    pub fn resumes(&self) -> u64 {
+        self.resumes
+    }
+
+    /// Returns the most recent reconnects and resumes, from oldest to newest.
+    ///
+    /// At most [`RECONNECT_HISTORY_SIZE`] records are kept.
+    ///
+    /// [`RECONNECT_HISTORY_SIZE`]: constant.RECONNECT_HISTORY_SIZE.html
+    pub fn reconnect_history(&self) -> &VecDeque<ReconnectRecord> {
+        &self.reconnect_history
+    }
+
+    /// Returns the number of dispatched events received, per event type.
+    pub fn events(&self) -> &HashMap<EventType, u64> {
+        &self.events
+    }
+
+    /// Returns the total number of dispatched events received.
+    pub fn events_total(&self) -> u64 {
+        self.events_total
+    }
+
+    /// Returns the average number of events received per second, over the
+    /// last [`EVENT_RATE_WINDOW_IN_SECONDS`] seconds.
+    ///
+    /// [`EVENT_RATE_WINDOW_IN_SECONDS`]: constant.EVENT_RATE_WINDOW_IN_SECONDS.html
+    pub fn events_per_second(&self) -> f64 {
+        let now = self.created.elapsed().as_secs();
+        let window = EVENT_RATE_WINDOW_IN_SECONDS.min(now + 1);
+
+        let count = self.event_rate
+            .iter()
+            .filter(|&&(second, _)| second + window > now)
+            .fold(0, |sum, &(_, count)| sum + count);
+
+        count as f64 / window as f64
+    }
+
+    /// Returns the amount of time since the last dispatched event was
+    /// received.
+    pub fn since_last_event(&self) -> Option<StdDuration> {
+        self.last_event.map(|instant| instant.elapsed())
+    }
+
+    /// Returns the amount of time since the current session was identified.
+    ///
+    /// This is not reset when the session is resumed.
+    pub fn session_age(&self) -> Option<StdDuration> {
+        self.session_started.map(|instant| instant.elapsed())
+    }
+
+    pub(crate) fn record_event(&mut self, kind: EventType) {
+        let now = Instant::now();
+        let second = now.duration_since(self.created).as_secs();
+
+        if kind == EventType::Ready {
+            self.session_started = Some(now);
+        }
+
+        *self.events.entry(kind).or_insert(0) += 1;
+        self.events_total += 1;
+        self.last_event = Some(now);
+
+        match self.event_rate.back_mut() {
+            Some(&mut (last, ref mut count)) if last == second => *count += 1,
+            _ => self.event_rate.push_back((second, 1)),
+        }
+
+        while self.event_rate
+            .front()
+            .map_or(false, |&(first, _)| first + EVENT_RATE_WINDOW_IN_SECONDS <= second) {
+            self.event_rate.pop_front();
+        }
+    }
+
+    pub(crate) fn record_latency(&mut self, latency: StdDuration) {
+        if self.latency_history.len() >= LATENCY_HISTORY_SIZE {
+            self.latency_history.pop_front();
+        }
+
+        self.latency_history.push_back(latency);
+    }
+
+    pub(crate) fn record_missed_heartbeat_ack(&mut self) {
+        self.missed_heartbeat_acks += 1;
+    }
+
+    pub(crate) fn record_reconnect(
+        &mut self,
+        kind: ReconnectType,
+        reason: ReconnectReason,
+    ) {
+        match kind {
+            ReconnectType::Reidentify => self.reconnects += 1,
+            ReconnectType::Resume => self.resumes += 1,
+        }
+
+        if self.reconnect_history.len() >= RECONNECT_HISTORY_SIZE {
+            self.reconnect_history.pop_front();
+        }
+
+        self.reconnect_history.push_back(ReconnectRecord {
+            at: Instant::now(),
+            kind,
+            reason,
+        });
+    }
+}
+
+impl Default for ShardStats {
+    fn default() -> Self {
+        Self {
+            created: Instant::now(),
+            events: HashMap::new(),
+            events_total: 0,
+            event_rate: VecDeque::new(),
+            last_event: None,
+            latency_history: VecDeque::with_capacity(LATENCY_HISTORY_SIZE),
+            missed_heartbeat_acks: 0,
+            reconnect_history: VecDeque::with_capacity(RECONNECT_HISTORY_SIZE),
+            reconnects: 0,
+            resumes: 0,
+            session_started: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use gateway::ReconnectType;
+    use model::event::EventType;
+    use std::time::Duration;
+    use super::*;
+
+    #[test]
+    fn test_latency_history() {
+        let mut stats = ShardStats::new();
+        assert!(stats.latency().is_none());
+        assert!(stats.average_latency().is_none());
+
+        for millis in 0..LATENCY_HISTORY_SIZE as u64 + 5 {
+            stats.record_latency(Duration::from_millis(millis * 10));
+        }
+
+        assert_eq!(stats.latency_history().len(), LATENCY_HISTORY_SIZE);
+        assert_eq!(stats.latency_history().front(), Some(&Duration::from_millis(50)));
+        assert_eq!(stats.latency(), Some(Duration::from_millis(240)));
+        assert_eq!(stats.average_latency(), Some(Duration::from_millis(145)));
+    }
+
+    #[test]
+    fn test_events() {
+        let mut stats = ShardStats::new();
+        assert!(stats.session_age().is_none());
+
+        stats.record_event(EventType::Ready);
+        stats.record_event(EventType::MessageCreate);
+        stats.record_event(EventType::MessageCreate);
+
+        assert_eq!(stats.events_total(), 3);
+        assert_eq!(stats.events().get(&EventType::MessageCreate), Some(&2));
+        assert_eq!(stats.events().get(&EventType::Ready), Some(&1));
+        assert!(stats.session_age().is_some());
+        assert!(stats.since_last_event().is_some());
+        assert!(stats.events_per_second() > 0.0);
+    }
+
+    #[test]
+    fn test_reconnects() {
+        let mut stats = ShardStats::new();
+
+        stats.record_reconnect(ReconnectType::Resume, ReconnectReason::Closed(Some(4000)));
+        stats.record_reconnect(ReconnectType::Reidentify, ReconnectReason::InvalidSession);
+        stats.record_missed_heartbeat_ack();
+
+        assert_eq!(stats.resumes(), 1);
+        assert_eq!(stats.reconnects(), 1);
+        assert_eq!(stats.missed_heartbeat_acks(), 1);
+        assert_eq!(stats.reconnect_history().len(), 2);
+        assert_eq!(stats.reconnect_history()[0].reason, ReconnectReason::Closed(Some(4000)));
+    }
+}
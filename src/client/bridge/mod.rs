@@ -9,5 +9,8 @@
 
 pub mod gateway;
 
+#[cfg(all(feature = "cluster", unix))]
+pub mod cluster;
+
 #[cfg(feature = "voice")]
 pub mod voice;
@@ -0,0 +1,573 @@
+use model::prelude::*;
+use parking_lot::Mutex;
+use std::{
+    fmt::{Debug, Formatter, Result as FmtResult},
+    sync::{
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+type Filter<T> = Box<Fn(&T) -> bool + Send + Sync>;
+
+/// The registry of the collectors waiting for events received by a
+/// [`Client`].
+///
+/// Collectors are a way to wait for an event from within an event handler or
+/// command, such as a reply to a question, without implementing a separate
+/// event handler method and sharing state with it.
+///
+/// A collector is created via [`message`] or [`reaction`], narrowed down with
+/// its builder methods, and then blocks the current thread until it has
+/// collected enough events or its timeout elapsed. It is only registered
+/// while it is waiting, and is removed automatically afterwards.
+///
+/// The collectors are fed by the dispatcher before the events are passed to
+/// the [`EventHandler`]. Every waiting collector is sent each event of its
+/// kind, and filters it on the thread which is waiting for it, so that slow or
+/// panicking predicates do not affect the shard. As event handlers run on the
+/// client's threadpool, every handler which is blocked on a collector occupies
+/// one of its threads.
+///
+/// This is cheap to clone, and is available via [`Client::collectors`] and
+/// [`Context::collectors`].
+///
+/// # Examples
+///
+/// Ask a question, and wait up to 30 seconds for the author to answer it:
+///
+/// ```rust,no_run
+/// # use serenity::prelude::*;
+/// # use serenity::model::channel::Message;
+/// #
+/// use std::time::Duration;
+///
+/// struct Handler;
+///
+/// impl EventHandler for Handler {
+///     fn message(&self, ctx: Context, msg: Message) {
+///         if msg.content != "!quiz" {
+///             return;
+///         }
+///
+///         let _ = msg.channel_id.say("What is 2 + 2?");
+///
+///         let answer = ctx.collectors
+///             .message()
+///             .channel_id(msg.channel_id)
+///             .author_id(msg.author.id)
+///             .timeout(Duration::from_secs(30))
+///             .receive();
+///
+///         let reply = match answer {
+///             Some(ref answer) if answer.content == "4" => "Correct!",
+///             Some(_) => "Wrong!",
+///             None => "Too slow!",
+///         };
+///
+///         let _ = msg.channel_id.say(reply);
+///     }
+/// }
+///
+/// let mut client = Client::new("token", Handler).unwrap();
+///
+/// client.start().unwrap();
+/// ```
+///
+/// [`Client`]: struct.Client.html
+/// [`Client::collectors`]: struct.Client.html#structfield.collectors
+/// [`Context::collectors`]: struct.Context.html#structfield.collectors
+/// [`EventHandler`]: trait.EventHandler.html
+/// [`message`]: #method.message
+/// [`reaction`]: #method.reaction
+#[derive(Clone, Default)]
+pub struct Collectors {
+    registry: Arc<Mutex<Registry>>,
+}
+
+#[derive(Default)]
+struct Registry {
+    last_id: u64,
+    messages: Vec<Registration<Message>>,
+    reactions: Vec<Registration<Reaction>>,
+}
+
+struct Registration<T> {
+    id: u64,
+    sender: Sender<T>,
+}
+
+// An event which can be collected.
+trait Collectable: Clone + Send + 'static {
+    fn registrations(registry: &mut Registry) -> &mut Vec<Registration<Self>>;
+}
+
+impl Collectable for Message {
+    fn registrations(registry: &mut Registry) -> &mut Vec<Registration<Self>> {
+        &mut registry.messages
+    }
+}
+
+impl Collectable for Reaction {
+    fn registrations(registry: &mut Registry) -> &mut Vec<Registration<Self>> {
+        &mut registry.reactions
+    }
+}
+
+impl Collectors {
+    /// Creates a collector of messages.
+    pub fn message(&self) -> MessageCollector {
+        MessageCollector(Collector::new(self))
+    }
+
+    /// Creates a collector of added reactions.
+    pub fn reaction(&self) -> ReactionCollector {
+        ReactionCollector(Collector::new(self))
+    }
+
+    /// The number of collectors which are currently waiting.
+    pub fn len(&self) -> usize {
+        let registry = self.registry.lock();
+
+        registry.messages.len() + registry.reactions.len()
+    }
+
+    /// Whether no collectors are currently waiting.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Passes a received message to the collectors waiting for it.
+    pub(crate) fn feed_message(&self, message: &Message) {
+        self.feed(message);
+    }
+
+    // Passes an added reaction to the collectors waiting for it.
+    pub(crate) fn feed_reaction(&self, reaction: &Reaction) {
+        self.feed(reaction);
+    }
+
+    // Sends an event to every collector of its kind, removing the collectors
+    // which stopped waiting, such as when their predicate panicked.
+    fn feed<T: Collectable>(&self, item: &T) {
+        T::registrations(&mut self.registry.lock())
+            .retain(|registration| registration.sender.send(item.clone()).is_ok());
+    }
+
+    fn register<T: Collectable>(&self) -> (u64, Receiver<T>) {
+        let (sender, receiver) = mpsc::channel();
+        let mut registry = self.registry.lock();
+
+        registry.last_id += 1;
+        let id = registry.last_id;
+
+        T::registrations(&mut registry).push(Registration {
+            id,
+            sender,
+        });
+
+        (id, receiver)
+    }
+
+    fn unregister<T: Collectable>(&self, id: u64) {
+        T::registrations(&mut self.registry.lock()).retain(|registration| registration.id != id);
+    }
+}
+
+impl Debug for Collectors {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_struct("Collectors")
+            .field("waiting", &self.len())
+            .finish()
+    }
+}
+
+// The filters, limit, and timeout of a collector, shared by the collectors of
+// each kind of event.
+struct Collector<T> {
+    collectors: Collectors,
+    filters: Vec<Filter<T>>,
+    limit: Option<u32>,
+    timeout: Option<Duration>,
+}
+
+impl<T: Collectable> Collector<T> {
+    fn new(collectors: &Collectors) -> Self {
+        Collector {
+            collectors: collectors.clone(),
+            filters: Vec::new(),
+            limit: None,
+            timeout: None,
+        }
+    }
+
+    fn filter<F: Fn(&T) -> bool + Send + Sync + 'static>(mut self, filter: F) -> Self {
+        self.filters.push(Box::new(filter));
+
+        self
+    }
+
+    fn collect(self) -> Vec<T> {
+        if self.limit == Some(0) {
+            return Vec::new();
+        }
+
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        let (id, receiver) = self.collectors.register();
+        let mut collected = Vec::new();
+
+        while self.limit.map_or(true, |limit| collected.len() < limit as usize) {
+            let received = match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+
+                    if now >= deadline {
+                        break;
+                    }
+
+                    match receiver.recv_timeout(deadline - now) {
+                        Ok(item) => item,
+                        Err(RecvTimeoutError::Timeout) |
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    }
+                },
+                None => match receiver.recv() {
+                    Ok(item) => item,
+                    Err(_) => break,
+                },
+            };
+
+            if matches(&self.filters, &received) {
+                collected.push(received);
+            }
+        }
+
+        self.collectors.unregister::<T>(id);
+
+        collected
+    }
+
+    fn subscribe(self) -> Subscription<T> {
+        let (id, receiver) = self.collectors.register();
+
+        Subscription {
+            collectors: self.collectors,
+            filters: self.filters,
+            id,
+            receiver,
+            unregister: Collectors::unregister::<T>,
+        }
+    }
+}
+
+fn matches<T>(filters: &[Filter<T>], item: &T) -> bool {
+    filters.iter().all(|filter| filter(item))
+}
+
+// A collector which stays registered until it is dropped, so that no events
+// are missed in between receiving them one at a time.
+pub(crate) struct Subscription<T> {
+    collectors: Collectors,
+    filters: Vec<Filter<T>>,
+    id: u64,
+    receiver: Receiver<T>,
+    unregister: fn(&Collectors, u64),
+}
+
+impl<T> Subscription<T> {
+    // Blocks until a matching event is received, or until the timeout elapsed.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<T> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let now = Instant::now();
+
+            if now >= deadline {
+                return None;
+            }
+
+            match self.receiver.recv_timeout(deadline - now) {
+                Ok(item) => if matches(&self.filters, &item) {
+                    return Some(item);
+                },
+                Err(_) => return None,
+            }
+        }
+    }
+}
+
+impl<T> Drop for Subscription<T> {
+    fn drop(&mut self) {
+        (self.unregister)(&self.collectors, self.id);
+    }
+}
+
+/// A collector of messages, created via [`Collectors::message`].
+///
+/// Only messages received after the collector started waiting are collected.
+///
+/// [`Collectors::message`]: struct.Collectors.html#method.message
+pub struct MessageCollector(Collector<Message>);
+
+impl MessageCollector {
+    /// Only collects messages sent by a user.
+    pub fn author_id<U: Into<UserId>>(self, author_id: U) -> Self {
+        let author_id = author_id.into();
+
+        self.filter(move |message| message.author.id == author_id)
+    }
+
+    /// Only collects messages sent in a channel.
+    pub fn channel_id<C: Into<ChannelId>>(self, channel_id: C) -> Self {
+        let channel_id = channel_id.into();
+
+        self.filter(move |message| message.channel_id == channel_id)
+    }
+
+    /// Only collects messages sent in a guild.
+    pub fn guild_id<G: Into<GuildId>>(self, guild_id: G) -> Self {
+        let guild_id = guild_id.into();
+
+        self.filter(move |message| message.guild_id == Some(guild_id))
+    }
+
+    /// Only collects messages for which the predicate returns `true`.
+    ///
+    /// This can be called multiple times, in which case all predicates must
+    /// return `true`. Predicates are called on the thread which is waiting for
+    /// the collector.
+    pub fn filter<F>(self, filter: F) -> Self
+        where F: Fn(&Message) -> bool + Send + Sync + 'static {
+        MessageCollector(self.0.filter(filter))
+    }
+
+    /// Stops collecting after a number of messages were collected.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.0.limit = Some(limit);
+
+        self
+    }
+
+    /// Stops collecting after a duration has elapsed.
+    ///
+    /// Without a timeout, the collector waits until the [`limit`] is reached.
+    ///
+    /// [`limit`]: #method.limit
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.0.timeout = Some(timeout);
+
+        self
+    }
+
+    /// Blocks until a matching message is received, returning it, or until
+    /// the timeout elapsed.
+    pub fn receive(self) -> Option<Message> {
+        self.limit(1).collect().pop()
+    }
+
+    /// Blocks until the limit is reached or the timeout elapsed, returning the
+    /// matching messages in the order they were received.
+    pub fn collect(self) -> Vec<Message> {
+        self.0.collect()
+    }
+}
+
+/// A collector of added reactions, created via [`Collectors::reaction`].
+///
+/// Only reactions added after the collector started waiting are collected.
+///
+/// [`Collectors::reaction`]: struct.Collectors.html#method.reaction
+pub struct ReactionCollector(Collector<Reaction>);
+
+impl ReactionCollector {
+    /// Only collects reactions added to messages in a channel.
+    pub fn channel_id<C: Into<ChannelId>>(self, channel_id: C) -> Self {
+        let channel_id = channel_id.into();
+
+        self.filter(move |reaction| reaction.channel_id == channel_id)
+    }
+
+    /// Only collects reactions added to a message.
+    pub fn message_id<M: Into<MessageId>>(self, message_id: M) -> Self {
+        let message_id = message_id.into();
+
+        self.filter(move |reaction| reaction.message_id == message_id)
+    }
+
+    /// Only collects reactions added by a user.
+    pub fn user_id<U: Into<UserId>>(self, user_id: U) -> Self {
+        let user_id = user_id.into();
+
+        self.filter(move |reaction| reaction.user_id == user_id)
+    }
+
+    /// Only collects reactions for which the predicate returns `true`.
+    ///
+    /// This can be called multiple times, in which case all predicates must
+    /// return `true`. Predicates are called on the thread which is waiting for
+    /// the collector.
+    pub fn filter<F>(self, filter: F) -> Self
+        where F: Fn(&Reaction) -> bool + Send + Sync + 'static {
+        ReactionCollector(self.0.filter(filter))
+    }
+
+    /// Stops collecting after a number of reactions were collected.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.0.limit = Some(limit);
+
+        self
+    }
+
+    /// Stops collecting after a duration has elapsed.
+    ///
+    /// Without a timeout, the collector waits until the [`limit`] is reached.
+    ///
+    /// [`limit`]: #method.limit
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.0.timeout = Some(timeout);
+
+        self
+    }
+
+    /// Blocks until a matching reaction is added, returning it, or until the
+    /// timeout elapsed.
+    pub fn receive(self) -> Option<Reaction> {
+        self.limit(1).collect().pop()
+    }
+
+    /// Blocks until the limit is reached or the timeout elapsed, returning the
+    /// matching reactions in the order they were added.
+    pub fn collect(self) -> Vec<Reaction> {
+        self.0.collect()
+    }
+
+    // Registers the collector until the subscription is dropped, ignoring
+    // the limit and timeout.
+    pub(crate) fn subscribe(self) -> Subscription<Reaction> {
+        self.0.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use internal::test_util;
+    use model::prelude::*;
+    use std::{
+        thread,
+        time::Duration,
+    };
+    use super::Collectors;
+
+    fn message(channel_id: u64, content: &str) -> Message {
+        let mut message = test_util::message(channel_id, 1);
+        message.content = content.to_owned();
+
+        message
+    }
+
+    fn wait_for_registration(collectors: &Collectors) {
+        while collectors.is_empty() {
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn test_message_collector() {
+        let collectors = Collectors::default();
+        let waiting = collectors.clone();
+
+        let collecting = thread::spawn(move || {
+            waiting
+                .message()
+                .channel_id(1)
+                .filter(|message| message.content.starts_with('!'))
+                .limit(2)
+                .timeout(Duration::from_secs(10))
+                .collect()
+        });
+
+        wait_for_registration(&collectors);
+
+        collectors.feed_message(&message(2, "!other channel"));
+        collectors.feed_message(&message(1, "not a command"));
+        collectors.feed_message(&message(1, "!first"));
+        collectors.feed_message(&message(1, "!second"));
+        collectors.feed_message(&message(1, "!third"));
+
+        let collected = collecting.join().unwrap();
+        let contents = collected.iter().map(|m| &m.content[..]).collect::<Vec<_>>();
+
+        assert_eq!(contents, vec!["!first", "!second"]);
+        assert!(collectors.is_empty());
+    }
+
+    #[test]
+    fn test_collector_filter_panic() {
+        let collectors = Collectors::default();
+        let waiting = collectors.clone();
+
+        let collecting = thread::spawn(move || {
+            waiting.message().filter(|_| panic!("filter panicked")).receive()
+        });
+
+        wait_for_registration(&collectors);
+
+        // The predicate is called on the collecting thread, not the feeding
+        // one.
+        collectors.feed_message(&message(1, "!first"));
+        assert!(collecting.join().is_err());
+
+        // The collector is removed once it stopped receiving.
+        collectors.feed_message(&message(1, "!second"));
+        assert!(collectors.is_empty());
+    }
+
+    #[test]
+    fn test_reaction_collector_timeout() {
+        let collectors = Collectors::default();
+        let mut event: ReactionAddEvent = fixture!("message_reaction_add_1");
+        let message_id = event.reaction.message_id;
+        let waiting = collectors.clone();
+
+        let collecting = thread::spawn(move || {
+            waiting
+                .reaction()
+                .message_id(message_id)
+                .timeout(Duration::from_millis(200))
+                .collect()
+        });
+
+        wait_for_registration(&collectors);
+        collectors.feed_reaction(&event.reaction);
+        event.reaction.message_id = MessageId(message_id.0 + 1);
+        collectors.feed_reaction(&event.reaction);
+
+        let collected = collecting.join().unwrap();
+
+        assert_eq!(collected.len(), 1);
+        assert_eq!(collected[0].message_id, message_id);
+        // The collector is removed once its timeout elapsed.
+        assert!(collectors.is_empty());
+        assert!(collectors.reaction().timeout(Duration::from_millis(1)).receive().is_none());
+    }
+
+    #[test]
+    fn test_reaction_subscription() {
+        let collectors = Collectors::default();
+        let event: ReactionAddEvent = fixture!("message_reaction_add_1");
+        let subscription = collectors.reaction().message_id(event.reaction.message_id).subscribe();
+
+        // Reactions are kept while the subscription is not waiting.
+        collectors.feed_reaction(&event.reaction);
+        collectors.feed_reaction(&event.reaction);
+
+        assert!(subscription.recv_timeout(Duration::from_millis(10)).is_some());
+        assert!(subscription.recv_timeout(Duration::from_millis(10)).is_some());
+        assert!(subscription.recv_timeout(Duration::from_millis(10)).is_none());
+
+        drop(subscription);
+
+        assert!(collectors.is_empty());
+    }
+}
@@ -2,18 +2,29 @@ use client::bridge::gateway::ShardMessenger;
 use gateway::InterMessage;
 use model::prelude::*;
 use parking_lot::Mutex;
-use std::sync::{
-    mpsc::Sender,
-    Arc
+use std::{
+    error::Error as StdError,
+    sync::{
+        mpsc::Sender,
+        Arc
+    }
+};
+use super::{
+    handler_error::{ErrorReporter, HandlerError, HandlerErrorKind},
+    scheduler::Scheduler,
+    Collectors,
+    DataStore
 };
-use typemap::ShareMap;
+use typemap::{Key, ShareMap};
 
 #[cfg(feature = "builder")]
 use builder::EditProfile;
 #[cfg(feature = "builder")]
 use internal::prelude::*;
-#[cfg(all(feature = "builder", feature = "cache"))]
-use super::CACHE;
+#[cfg(feature = "cache")]
+use cache::Cache;
+#[cfg(feature = "cache")]
+use parking_lot::RwLock;
 #[cfg(feature = "builder")]
 use {Result, http};
 #[cfg(feature = "builder")]
@@ -36,11 +47,33 @@ use utils::{self, VecMap};
 /// [`set_game`]: #method.set_game
 #[derive(Clone)]
 pub struct Context {
+    /// A clone of [`Client::cache`], the cache updated by the client which
+    /// dispatched the event.
+    ///
+    /// [`Client::cache`]: struct.Client.html#structfield.cache
+    #[cfg(feature = "cache")]
+    pub cache: Arc<RwLock<Cache>>,
+    /// A clone of [`Client::collectors`], to wait for events from within an
+    /// event handler.
+    ///
+    /// [`Client::collectors`]: struct.Client.html#structfield.collectors
+    pub collectors: Collectors,
     /// A clone of [`Client::data`]. Refer to its documentation for more
     /// information.
     ///
     /// [`Client::data`]: struct.Client.html#structfield.data
     pub data: Arc<Mutex<ShareMap>>,
+    /// A clone of [`Client::data_store`]. Refer to its documentation for more
+    /// information.
+    ///
+    /// [`Client::data_store`]: struct.Client.html#structfield.data_store
+    pub data_store: DataStore,
+    pub(crate) error_reporter: ErrorReporter,
+    /// A clone of [`Client::scheduler`], to schedule jobs from within an event
+    /// handler.
+    ///
+    /// [`Client::scheduler`]: struct.Client.html#structfield.scheduler
+    pub scheduler: Scheduler,
     /// The messenger to communicate with the shard runner.
     pub shard: ShardMessenger,
     /// The ID of the shard this context is related to.
@@ -49,18 +82,93 @@ pub struct Context {
 
 impl Context {
     /// Create a new Context to be passed to an event handler.
+    #[cfg(feature = "cache")]
+    #[cfg_attr(feature = "cargo-clippy", allow(too_many_arguments))]
     pub(crate) fn new(
+        cache: Arc<RwLock<Cache>>,
+        collectors: Collectors,
         data: Arc<Mutex<ShareMap>>,
+        data_store: DataStore,
+        error_reporter: ErrorReporter,
         runner_tx: Sender<InterMessage>,
+        scheduler: Scheduler,
         shard_id: u64,
     ) -> Context {
         Context {
             shard: ShardMessenger::new(runner_tx),
             shard_id,
+            cache,
+            collectors,
             data,
+            data_store,
+            error_reporter,
+            scheduler,
         }
     }
 
+    /// Create a new Context to be passed to an event handler.
+    #[cfg(not(feature = "cache"))]
+    #[cfg_attr(feature = "cargo-clippy", allow(too_many_arguments))]
+    pub(crate) fn new(
+        collectors: Collectors,
+        data: Arc<Mutex<ShareMap>>,
+        data_store: DataStore,
+        error_reporter: ErrorReporter,
+        runner_tx: Sender<InterMessage>,
+        scheduler: Scheduler,
+        shard_id: u64,
+    ) -> Context {
+        Context {
+            shard: ShardMessenger::new(runner_tx),
+            shard_id,
+            collectors,
+            data,
+            data_store,
+            error_reporter,
+            scheduler,
+        }
+    }
+
+    /// Calls the given function with a shared reference to the value of a key
+    /// in the [`data_store`], returning its result.
+    ///
+    /// Returns `None` if no value is inserted for the key.
+    ///
+    /// [`data_store`]: #structfield.data_store
+    pub fn with_data<K, F, T>(&self, f: F) -> Option<T>
+        where K: Key, K::Value: Send + Sync, F: FnOnce(&K::Value) -> T {
+        self.data_store.get::<K>().map(|value| f(&*value.read()))
+    }
+
+    /// Calls the given function with a mutable reference to the value of a key
+    /// in the [`data_store`], returning its result.
+    ///
+    /// Returns `None` if no value is inserted for the key.
+    ///
+    /// [`data_store`]: #structfield.data_store
+    pub fn with_data_mut<K, F, T>(&self, f: F) -> Option<T>
+        where K: Key, K::Value: Send + Sync, F: FnOnce(&mut K::Value) -> T {
+        self.data_store.get::<K>().map(|value| f(&mut *value.write()))
+    }
+
+    /// Reports an error of the event handler to the [`ErrorHook`], along with
+    /// the name of the failed handler method and the Id of this context's
+    /// shard.
+    ///
+    /// This allows handlers which do not use a [`TryEventHandler`] to report
+    /// their errors the same way.
+    ///
+    /// [`ErrorHook`]: trait.ErrorHook.html
+    /// [`TryEventHandler`]: trait.TryEventHandler.html
+    pub fn report_error<E>(&self, event: &'static str, error: E)
+        where E: Into<Box<StdError>> {
+        self.error_reporter.report(HandlerError {
+            kind: HandlerErrorKind::Error(error.into()),
+            event,
+            shard_id: self.shard_id,
+        });
+    }
+
     /// Edits the current user's profile settings.
     ///
     /// Refer to `EditProfile`'s documentation for its methods.
@@ -93,7 +201,7 @@ impl Context {
 
         feature_cache! {
             {
-                let cache = CACHE.read();
+                let cache = self.cache.read();
 
                 map.insert("username", Value::String(cache.user.name.clone()));
 
@@ -417,6 +525,44 @@ impl Context {
     /// client.start().unwrap();
     /// ```
     ///
+    /// Setting the current user as playing a game with rich presence details:
+    ///
+    /// ```rust,no_run
+    /// # #[cfg(feature = "model")]
+    /// # fn main() {
+    /// # use serenity::prelude::*;
+    /// # use serenity::model::gateway::Ready;
+    /// #
+    /// struct Handler;
+    ///
+    /// impl EventHandler for Handler {
+    ///     fn ready(&self, context: Context, _: Ready) {
+    ///         use serenity::model::gateway::{ActivityParty, Game};
+    ///         use serenity::model::user::OnlineStatus;
+    ///
+    ///         let game = Game {
+    ///             details: Some("Ranked match".to_owned()),
+    ///             party: Some(ActivityParty {
+    ///                 id: None,
+    ///                 size: Some([2, 5]),
+    ///             }),
+    ///             state: Some("In a group".to_owned()),
+    ///             ..Game::playing("Heroes of the Storm")
+    ///         };
+    ///
+    ///         context.set_presence(Some(game), OnlineStatus::Online);
+    ///     }
+    /// }
+    ///
+    /// let mut client = Client::new("token", Handler).unwrap();
+    ///
+    /// client.start().unwrap();
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "model"))]
+    /// # fn main() {}
+    /// ```
+    ///
     /// [`DoNotDisturb`]: ../model/user/enum.OnlineStatus.html#variant.DoNotDisturb
     /// [`Idle`]: ../model/user/enum.OnlineStatus.html#variant.Idle
     #[inline]
@@ -424,6 +570,51 @@ impl Context {
         self.shard.set_presence(game, status);
     }
 
+    /// Sets the current user's presence to multiple activities at once, along
+    /// with their online status.
+    ///
+    /// The first activity is also sent as the presence's game, for clients
+    /// which only display one.
+    ///
+    /// # Examples
+    ///
+    /// Setting the current user as listening to a song while playing a game:
+    ///
+    /// ```rust,no_run
+    /// # #[cfg(feature = "model")]
+    /// # fn main() {
+    /// # use serenity::prelude::*;
+    /// # use serenity::model::gateway::Ready;
+    /// #
+    /// struct Handler;
+    ///
+    /// impl EventHandler for Handler {
+    ///     fn ready(&self, context: Context, _: Ready) {
+    ///         use serenity::model::gateway::Game;
+    ///         use serenity::model::user::OnlineStatus;
+    ///
+    ///         let activities = vec![
+    ///             Game::playing("Heroes of the Storm"),
+    ///             Game::listening("Lofi Beats"),
+    ///         ];
+    ///
+    ///         context.set_activities(activities, OnlineStatus::Online);
+    ///     }
+    /// }
+    ///
+    /// let mut client = Client::new("token", Handler).unwrap();
+    ///
+    /// client.start().unwrap();
+    /// # }
+    /// #
+    /// # #[cfg(not(feature = "model"))]
+    /// # fn main() {}
+    /// ```
+    #[inline]
+    pub fn set_activities(&self, activities: Vec<Game>, status: OnlineStatus) {
+        self.shard.set_activities(activities, status);
+    }
+
     /// Disconnects the shard from the websocket, essentially "quiting" it.
     /// Note however that this will only exit the one which the `Context` was given.
     /// If it's just one shard that's on, then serenity will stop any further actions
@@ -435,3 +626,9 @@ impl Context {
         self.shard.shutdown_clean();
     }
 }
+
+#[cfg(feature = "model")]
+impl CacheSource for Context {
+    #[cfg(feature = "cache")]
+    fn cache(&self) -> Option<&RwLock<Cache>> { Some(&self.cache) }
+}
@@ -0,0 +1,250 @@
+use parking_lot::RwLock;
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt::{Debug, Formatter, Result as FmtResult},
+    sync::Arc,
+};
+use typemap::{Key, ShareMap};
+
+/// A typed container of shared data, where every value is locked on its own.
+///
+/// Values are stored by a [`Key`], like with a `ShareMap`, but are retrieved
+/// as an `Arc<RwLock>` handle. The container itself is only locked while a
+/// handle is retrieved, so that a handler holding the lock of one value does
+/// not block handlers using other values, and values can be read
+/// concurrently.
+///
+/// This is cheap to clone, and is accessible through
+/// [`Client::data_store`] and [`Context::data_store`].
+///
+/// # Migrating from `ShareMap`
+///
+/// Values of the [`Client::data`] `ShareMap` can be moved into the store one
+/// key at a time through [`take_from`], and are then accessed through the
+/// store instead.
+///
+/// # Examples
+///
+/// Count the received messages without locking the other shared data:
+///
+/// ```rust,no_run
+/// # extern crate serenity;
+/// # extern crate typemap;
+/// #
+/// # use std::error::Error;
+/// #
+/// # fn try_main() -> Result<(), Box<Error>> {
+/// use serenity::client::{Client, Context, EventHandler};
+/// use serenity::model::channel::Message;
+/// use std::env;
+/// use typemap::Key;
+///
+/// struct MessageCount;
+///
+/// impl Key for MessageCount {
+///     type Value = u64;
+/// }
+///
+/// struct Handler;
+///
+/// impl EventHandler for Handler {
+///     fn message(&self, ctx: Context, _: Message) {
+///         ctx.with_data_mut::<MessageCount, _, _>(|count| *count += 1);
+///     }
+/// }
+///
+/// let mut client = Client::new(&env::var("DISCORD_TOKEN")?, Handler)?;
+/// client.data_store.insert::<MessageCount>(0);
+///
+/// client.start()?;
+/// #     Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #     try_main().unwrap();
+/// # }
+/// ```
+///
+/// [`Client::data`]: struct.Client.html#structfield.data
+/// [`Client::data_store`]: struct.Client.html#structfield.data_store
+/// [`Context::data_store`]: struct.Context.html#structfield.data_store
+/// [`Key`]: ../../typemap/trait.Key.html
+/// [`take_from`]: #method.take_from
+#[derive(Clone, Default)]
+pub struct DataStore {
+    values: Arc<RwLock<HashMap<TypeId, Box<Any + Send + Sync>>>>,
+}
+
+impl DataStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Retrieves a handle to the value of a key, if it was inserted.
+    pub fn get<K>(&self) -> Option<Arc<RwLock<K::Value>>>
+        where K: Key, K::Value: Send + Sync {
+        self.values
+            .read()
+            .get(&TypeId::of::<K>())
+            .and_then(|value| value.downcast_ref::<Arc<RwLock<K::Value>>>())
+            .map(Arc::clone)
+    }
+
+    /// Retrieves a handle to the value of a key, inserting the value returned
+    /// by the given function if there is none.
+    pub fn get_or_insert_with<K, F>(&self, f: F) -> Arc<RwLock<K::Value>>
+        where K: Key, K::Value: Send + Sync, F: FnOnce() -> K::Value {
+        if let Some(value) = self.get::<K>() {
+            return value;
+        }
+
+        let mut values = self.values.write();
+
+        // The value may have been inserted while the store was unlocked.
+        let value = values
+            .entry(TypeId::of::<K>())
+            .or_insert_with(|| Box::new(Arc::new(RwLock::new(f()))));
+
+        value
+            .downcast_ref::<Arc<RwLock<K::Value>>>()
+            .map(Arc::clone)
+            .expect("value of key has a different type")
+    }
+
+    /// Inserts the value of a key, returning the handle to the previous value.
+    ///
+    /// Existing handles to the previous value are not updated.
+    pub fn insert<K>(&self, value: K::Value) -> Option<Arc<RwLock<K::Value>>>
+        where K: Key, K::Value: Send + Sync {
+        let value = Box::new(Arc::new(RwLock::new(value)));
+
+        self.values
+            .write()
+            .insert(TypeId::of::<K>(), value)
+            .and_then(|value| value.downcast::<Arc<RwLock<K::Value>>>().ok())
+            .map(|value| *value)
+    }
+
+    /// Removes the value of a key, returning the handle to it.
+    pub fn remove<K>(&self) -> Option<Arc<RwLock<K::Value>>>
+        where K: Key, K::Value: Send + Sync {
+        self.values
+            .write()
+            .remove(&TypeId::of::<K>())
+            .and_then(|value| value.downcast::<Arc<RwLock<K::Value>>>().ok())
+            .map(|value| *value)
+    }
+
+    /// Whether a value is inserted for a key.
+    pub fn contains<K: Key>(&self) -> bool {
+        self.values.read().contains_key(&TypeId::of::<K>())
+    }
+
+    /// The number of inserted values.
+    pub fn len(&self) -> usize {
+        self.values.read().len()
+    }
+
+    /// Whether no values are inserted.
+    pub fn is_empty(&self) -> bool {
+        self.values.read().is_empty()
+    }
+
+    /// Moves the value of a key from a `ShareMap` into the store, returning
+    /// whether the map contained a value for it.
+    ///
+    /// This replaces the value of the key in the store, if any.
+    pub fn take_from<K>(&self, map: &mut ShareMap) -> bool
+        where K: Key, K::Value: Send + Sync {
+        match map.remove::<K>() {
+            Some(value) => {
+                self.insert::<K>(value);
+
+                true
+            },
+            None => false,
+        }
+    }
+}
+
+impl Debug for DataStore {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_struct("DataStore")
+            .field("values", &self.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{sync::Arc, thread};
+    use super::DataStore;
+    use typemap::{Key, ShareMap};
+
+    struct Counter;
+
+    impl Key for Counter {
+        type Value = u64;
+    }
+
+    struct Names;
+
+    impl Key for Names {
+        type Value = Vec<String>;
+    }
+
+    #[test]
+    fn test_data_store() {
+        let store = DataStore::new();
+
+        assert!(store.get::<Counter>().is_none());
+        assert!(store.insert::<Counter>(1).is_none());
+        assert_eq!(*store.get::<Counter>().unwrap().read(), 1);
+        assert_eq!(*store.get_or_insert_with::<Counter, _>(|| 5).read(), 1);
+        assert!(store.get_or_insert_with::<Names, _>(Vec::new).read().is_empty());
+        assert_eq!(store.len(), 2);
+
+        let previous = store.insert::<Counter>(2).unwrap();
+
+        assert_eq!(*previous.read(), 1);
+        assert_eq!(*store.remove::<Counter>().unwrap().read(), 2);
+        assert!(!store.contains::<Counter>());
+    }
+
+    #[test]
+    fn test_data_store_independent_locks() {
+        let store = DataStore::new();
+        store.insert::<Counter>(0);
+        store.insert::<Names>(vec![]);
+
+        let names = store.get::<Names>().unwrap();
+        let _guard = names.write();
+
+        let handles = (0..4).map(|_| {
+            let store = store.clone();
+
+            thread::spawn(move || *store.get::<Counter>().unwrap().write() += 1)
+        }).collect::<Vec<_>>();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(*store.get::<Counter>().unwrap().read(), 4);
+        assert_eq!(Arc::strong_count(&names), 2);
+    }
+
+    #[test]
+    fn test_data_store_take_from() {
+        let store = DataStore::new();
+        let mut map = ShareMap::custom();
+        map.insert::<Counter>(3);
+
+        assert!(store.take_from::<Counter>(&mut map));
+        assert!(!store.take_from::<Counter>(&mut map));
+        assert!(!map.contains::<Counter>());
+        assert_eq!(*store.get::<Counter>().unwrap().read(), 3);
+    }
+}
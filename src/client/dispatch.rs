@@ -1,67 +1,92 @@
-use gateway::InterMessage;
 use model::{
     channel::{Channel, Message},
     event::Event,
     guild::Member,
 };
 use std::sync::Arc;
-use parking_lot::Mutex;
 use super::{
     bridge::gateway::event::ClientEvent,
     event_handler::EventHandler,
+    sequencer::{Executor, Sequencer},
     Context
 };
-use std::sync::mpsc::Sender;
 use threadpool::ThreadPool;
-use typemap::ShareMap;
 
 #[cfg(feature = "framework")]
 use framework::Framework;
+#[cfg(feature = "framework")]
+use parking_lot::Mutex;
 #[cfg(feature = "cache")]
-use model::id::GuildId;
+use model::{
+    id::GuildId,
+    voice::{VoiceState, VoiceTransition},
+};
 #[cfg(feature = "cache")]
-use std::time::Duration;
-
+use cache::Cache;
 #[cfg(feature = "cache")]
-use super::CACHE;
-
+use parking_lot::RwLock;
 #[cfg(feature = "cache")]
-lazy_static! {
-    pub static ref CACHE_TRY_WRITE_DURATION: Option<Duration> =
-        CACHE.read().get_try_write_duration();
-}
+use std::{fmt::Debug, time::Duration};
 
 macro_rules! update {
-    ($event:expr) => {
+    ($cache:expr, $event:expr) => {
         {
             #[cfg(feature = "cache")]
             {
-                match *CACHE_TRY_WRITE_DURATION {
-                    Some(duration) => {
-                        if let Some(mut lock) = CACHE.try_write_for(duration) {
-                            lock.update(&mut $event)
-                        } else {
-                            warn!(
-                                "[dispatch] Possible deadlock: couldn't unlock cache to update with event: {:?}",
-                                $event,
-                            );
-                            None
-                        }},
-                    None => {
-                        CACHE.write().update(&mut $event)
-                    },
-                }
+                lock_cache(&$cache, &$event, RwLock::try_write_for, RwLock::write)
+                    .and_then(|mut cache| cache.update(&mut $event))
             }
         }
     };
 }
 
-fn context(
-    data: &Arc<Mutex<ShareMap>>,
-    runner_tx: &Sender<InterMessage>,
-    shard_id: u64,
-) -> Context {
-    Context::new(Arc::clone(data), runner_tx.clone(), shard_id)
+// Updates the cache with an event which only modifies independently locked
+// data, only locking the cache itself for reading.
+macro_rules! update_concurrently {
+    // Evaluates to `None` if the cache could not be locked in time, rather
+    // than to the result of the update.
+    (@applied $cache:expr, $event:expr) => {
+        lock_cache(&$cache, &$event, RwLock::try_read_for, RwLock::read)
+            .map(|cache| cache.update_concurrently(&mut $event))
+    };
+    ($cache:expr, $event:expr) => {
+        {
+            #[cfg(feature = "cache")]
+            {
+                update_concurrently!(@applied $cache, $event).and_then(|result| result)
+            }
+        }
+    };
+}
+
+// Locks the cache to update it with an event, giving up after the cache's
+// configured lock time to avoid deadlocking.
+#[cfg(feature = "cache")]
+fn lock_cache<'a, G, T, L>(
+    cache: &'a RwLock<Cache>,
+    event: &Debug,
+    try_lock_for: T,
+    lock: L,
+) -> Option<G>
+    where T: FnOnce(&'a RwLock<Cache>, Duration) -> Option<G>,
+          L: FnOnce(&'a RwLock<Cache>) -> G {
+    let duration = cache.read().get_try_write_duration();
+
+    match duration {
+        Some(duration) => {
+            let guard = try_lock_for(cache, duration);
+
+            if guard.is_none() {
+                warn!(
+                    "[dispatch] Possible deadlock: couldn't lock cache to update with event: {:?}",
+                    event,
+                );
+            }
+
+            guard
+        },
+        None => Some(lock(cache)),
+    }
 }
 
 pub(crate) enum DispatchEvent {
@@ -74,35 +99,43 @@ pub(crate) enum DispatchEvent {
 pub(crate) fn dispatch<H: EventHandler + Send + Sync + 'static>(
     event: DispatchEvent,
     framework: &Arc<Mutex<Option<Box<Framework + Send>>>>,
-    data: &Arc<Mutex<ShareMap>>,
+    context: &Context,
     event_handler: &Arc<H>,
-    runner_tx: &Sender<InterMessage>,
     threadpool: &ThreadPool,
-    shard_id: u64,
+    sequencer: &Sequencer,
 ) {
+    let executor = Executor {
+        error_reporter: &context.error_reporter,
+        key: sequencer.key(&event, context),
+        sequencer,
+        shard_id: context.shard_id,
+        threadpool,
+    };
+
     match event {
         DispatchEvent::Model(Event::MessageCreate(mut event)) => {
-            update!(event);
+            update_concurrently!(context.cache, event);
+            context.collectors.feed_message(&event.message);
 
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
             dispatch_message(
                 context.clone(),
                 event.message.clone(),
                 event_handler,
-                threadpool,
+                &executor,
             );
 
             if let Some(ref mut framework) = *framework.lock() {
-                framework.dispatch(context, event.message, threadpool);
+                executor.call("framework", || {
+                    framework.dispatch(context, event.message, threadpool);
+                });
             }
         },
         other => handle_event(
             other,
-            data,
+            context,
             event_handler,
-            runner_tx,
-            threadpool,
-            shard_id,
+            &executor,
         ),
     }
 }
@@ -111,26 +144,32 @@ pub(crate) fn dispatch<H: EventHandler + Send + Sync + 'static>(
 #[allow(unused_mut)]
 pub(crate) fn dispatch<H: EventHandler + Send + Sync + 'static>(
     event: DispatchEvent,
-    data: &Arc<Mutex<ShareMap>>,
+    context: &Context,
     event_handler: &Arc<H>,
-    runner_tx: &Sender<InterMessage>,
     threadpool: &ThreadPool,
-    shard_id: u64,
+    sequencer: &Sequencer,
 ) {
+    let executor = Executor {
+        error_reporter: &context.error_reporter,
+        key: sequencer.key(&event, context),
+        sequencer,
+        shard_id: context.shard_id,
+        threadpool,
+    };
+
     match event {
         DispatchEvent::Model(Event::MessageCreate(mut event)) => {
-            update!(event);
+            update_concurrently!(context.cache, event);
+            context.collectors.feed_message(&event.message);
 
-            let context = context(data, runner_tx, shard_id);
-            dispatch_message(context, event.message, event_handler, threadpool);
+            let context = context.clone();
+            dispatch_message(context, event.message, event_handler, &executor);
         },
         other => handle_event(
             other,
-            data,
+            context,
             event_handler,
-            runner_tx,
-            threadpool,
-            shard_id,
+            &executor,
         ),
     }
 }
@@ -140,7 +179,7 @@ fn dispatch_message<H>(
     context: Context,
     mut message: Message,
     event_handler: &Arc<H>,
-    threadpool: &ThreadPool,
+    executor: &Executor,
 ) where H: EventHandler + Send + Sync + 'static {
     #[cfg(feature = "model")]
     {
@@ -149,7 +188,7 @@ fn dispatch_message<H>(
 
     let event_handler = Arc::clone(event_handler);
 
-    threadpool.execute(move || {
+    executor.execute("message", move || {
         event_handler.message(context, message);
     });
 }
@@ -157,25 +196,23 @@ fn dispatch_message<H>(
 #[allow(cyclomatic_complexity, unused_assignments, unused_mut)]
 fn handle_event<H: EventHandler + Send + Sync + 'static>(
     event: DispatchEvent,
-    data: &Arc<Mutex<ShareMap>>,
+    context: &Context,
     event_handler: &Arc<H>,
-    runner_tx: &Sender<InterMessage>,
-    threadpool: &ThreadPool,
-    shard_id: u64,
+    executor: &Executor,
 ) {
     match event {
         DispatchEvent::Client(ClientEvent::ShardStageUpdate(event)) => {
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("shard_stage_update", move || {
                 event_handler.shard_stage_update(context, event);
             });
         }
         DispatchEvent::Model(Event::ChannelCreate(mut event)) => {
-            update!(event);
+            update!(context.cache, event);
 
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
 
             // Discord sends both a MessageCreate and a ChannelCreate upon a new message in a private channel.
             // This could potentially be annoying to handle when otherwise wanting to normally take care of a new channel.
@@ -184,7 +221,7 @@ fn handle_event<H: EventHandler + Send + Sync + 'static>(
                 Channel::Private(channel) => {
                     let event_handler = Arc::clone(event_handler);
 
-                    threadpool.execute(move || {
+                    executor.execute("private_channel_create", move || {
                         event_handler.private_channel_create(context, channel);
                     });
                 },
@@ -192,58 +229,58 @@ fn handle_event<H: EventHandler + Send + Sync + 'static>(
                 Channel::Guild(channel) => {
                     let event_handler = Arc::clone(event_handler);
 
-                    threadpool.execute(move || {
+                    executor.execute("channel_create", move || {
                         event_handler.channel_create(context, channel);
                     });
                 },
                 Channel::Category(channel) => {
                     let event_handler = Arc::clone(event_handler);
 
-                    threadpool.execute(move || {
+                    executor.execute("category_create", move || {
                         event_handler.category_create(context, channel);
                     });
                 },
             }
         },
         DispatchEvent::Model(Event::ChannelDelete(mut event)) => {
-            update!(event);
+            update!(context.cache, event);
 
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
 
             match event.channel {
                 Channel::Private(_) | Channel::Group(_) => {},
                 Channel::Guild(channel) => {
                     let event_handler = Arc::clone(event_handler);
 
-                    threadpool.execute(move || {
+                    executor.execute("channel_delete", move || {
                         event_handler.channel_delete(context, channel);
                     });
                 },
                 Channel::Category(channel) => {
                     let event_handler = Arc::clone(event_handler);
 
-                    threadpool.execute(move || {
+                    executor.execute("category_delete", move || {
                         event_handler.category_delete(context, channel);
                     });
                 },
             }
         },
         DispatchEvent::Model(Event::ChannelPinsUpdate(mut event)) => {
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("channel_pins_update", move || {
                 event_handler.channel_pins_update(context, event);
             });
         },
         DispatchEvent::Model(Event::ChannelRecipientAdd(mut event)) => {
-            update!(event);
+            update!(context.cache, event);
 
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
 
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("channel_recipient_addition", move || {
                 event_handler.channel_recipient_addition(
                     context,
                     event.channel_id,
@@ -252,12 +289,12 @@ fn handle_event<H: EventHandler + Send + Sync + 'static>(
             });
         },
         DispatchEvent::Model(Event::ChannelRecipientRemove(mut event)) => {
-            update!(event);
+            update_concurrently!(context.cache, event);
 
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("channel_recipient_removal", move || {
                 event_handler.channel_recipient_removal(
                     context,
                     event.channel_id,
@@ -266,14 +303,14 @@ fn handle_event<H: EventHandler + Send + Sync + 'static>(
             });
         },
         DispatchEvent::Model(Event::ChannelUpdate(mut event)) => {
-            update!(event);
+            update!(context.cache, event);
 
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("channel_update", move || {
                 feature_cache! {{
-                    let before = CACHE.read().channel(event.channel.id());
+                    let before = context.cache.read().channel(event.channel.id());
 
                     event_handler.channel_update(context, before, event.channel);
                 } else {
@@ -282,37 +319,37 @@ fn handle_event<H: EventHandler + Send + Sync + 'static>(
             });
         },
         DispatchEvent::Model(Event::GuildBanAdd(mut event)) => {
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("guild_ban_addition", move || {
                 event_handler.guild_ban_addition(context, event.guild_id, event.user);
             });
         },
         DispatchEvent::Model(Event::GuildBanRemove(mut event)) => {
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("guild_ban_removal", move || {
                 event_handler.guild_ban_removal(context, event.guild_id, event.user);
             });
         },
         DispatchEvent::Model(Event::GuildCreate(mut event)) => {
             #[cfg(feature = "cache")]
             let _is_new = {
-                let cache = CACHE.read();
+                let cache = context.cache.read();
 
                 !cache.unavailable_guilds.contains(&event.guild.id)
             };
 
-            update!(event);
+            update!(context.cache, event);
 
             #[cfg(feature = "cache")]
             {
-                let cache = CACHE.read();
+                let cache = context.cache.read();
 
                 if cache.unavailable_guilds.is_empty() {
-                    let context = context(data, runner_tx, shard_id);
+                    let context = context.clone();
 
                     let guild_amount = cache
                         .guilds
@@ -321,16 +358,16 @@ fn handle_event<H: EventHandler + Send + Sync + 'static>(
                         .collect::<Vec<GuildId>>();
                     let event_handler = Arc::clone(event_handler);
 
-                    threadpool.execute(move || {
+                    executor.execute("cached", move || {
                         event_handler.cached(context, guild_amount);
                     });
                 }
             }
 
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("guild_create", move || {
                 feature_cache! {{
                     event_handler.guild_create(context, event.guild, _is_new);
                 } else {
@@ -339,11 +376,11 @@ fn handle_event<H: EventHandler + Send + Sync + 'static>(
             });
         },
         DispatchEvent::Model(Event::GuildDelete(mut event)) => {
-            let _full = update!(event);
-            let context = context(data, runner_tx, shard_id);
+            let _full = update!(context.cache, event);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("guild_delete", move || {
                 feature_cache! {{
                     event_handler.guild_delete(context, event.guild, _full);
                 } else {
@@ -352,39 +389,39 @@ fn handle_event<H: EventHandler + Send + Sync + 'static>(
             });
         },
         DispatchEvent::Model(Event::GuildEmojisUpdate(mut event)) => {
-            update!(event);
+            update_concurrently!(context.cache, event);
 
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("guild_emojis_update", move || {
                 event_handler.guild_emojis_update(context, event.guild_id, event.emojis);
             });
         },
         DispatchEvent::Model(Event::GuildIntegrationsUpdate(mut event)) => {
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("guild_integrations_update", move || {
                 event_handler.guild_integrations_update(context, event.guild_id);
             });
         },
         DispatchEvent::Model(Event::GuildMemberAdd(mut event)) => {
-            update!(event);
+            update!(context.cache, event);
 
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("guild_member_addition", move || {
                 event_handler.guild_member_addition(context, event.guild_id, event.member);
             });
         },
         DispatchEvent::Model(Event::GuildMemberRemove(mut event)) => {
-            let _member = update!(event);
-            let context = context(data, runner_tx, shard_id);
+            let _member = update!(context.cache, event);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("guild_member_removal", move || {
                 feature_cache! {{
                     event_handler.guild_member_removal(context, event.guild_id, event.user, _member);
                 } else {
@@ -393,18 +430,28 @@ fn handle_event<H: EventHandler + Send + Sync + 'static>(
             });
         },
         DispatchEvent::Model(Event::GuildMemberUpdate(mut event)) => {
-            let _before = update!(event);
+            let _before = update!(context.cache, event);
 
+            // The member may not be cached if the cache's settings bound the
+            // number of members, in which case it is built from the event.
             let _after: Option<Member> = feature_cache! {{
-                CACHE.read().member(event.guild_id, event.user.id)
+                context.cache.read().member(event.guild_id, event.user.id).or_else(|| Some(Member {
+                    deaf: false,
+                    guild_id: event.guild_id,
+                    joined_at: None,
+                    mute: false,
+                    nick: event.nick.clone(),
+                    roles: event.roles.clone(),
+                    user: Arc::new(RwLock::new(event.user.clone())),
+                }))
             } else {
                 None
             }};
 
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("guild_member_update", move || {
                 feature_cache! {{
                     if let Some(after) = _after {
                         event_handler.guild_member_update(context, _before, after);
@@ -415,31 +462,31 @@ fn handle_event<H: EventHandler + Send + Sync + 'static>(
             });
         },
         DispatchEvent::Model(Event::GuildMembersChunk(mut event)) => {
-            update!(event);
+            update!(context.cache, event);
 
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("guild_members_chunk", move || {
                 event_handler.guild_members_chunk(context, event.guild_id, event.members);
             });
         },
         DispatchEvent::Model(Event::GuildRoleCreate(mut event)) => {
-            update!(event);
+            update_concurrently!(context.cache, event);
 
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("guild_role_create", move || {
                 event_handler.guild_role_create(context, event.guild_id, event.role);
             });
         },
         DispatchEvent::Model(Event::GuildRoleDelete(mut event)) => {
-            let _role = update!(event);
-            let context = context(data, runner_tx, shard_id);
+            let _role = update_concurrently!(context.cache, event);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("guild_role_delete", move || {
                 feature_cache! {{
                     event_handler.guild_role_delete(context, event.guild_id, event.role_id, _role);
                 } else {
@@ -448,11 +495,11 @@ fn handle_event<H: EventHandler + Send + Sync + 'static>(
             });
         },
         DispatchEvent::Model(Event::GuildRoleUpdate(mut event)) => {
-            let _before = update!(event);
-            let context = context(data, runner_tx, shard_id);
+            let _before = update_concurrently!(context.cache, event);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("guild_role_update", move || {
                 feature_cache! {{
                     event_handler.guild_role_update(context, event.guild_id, _before, event.role);
                 } else {
@@ -461,24 +508,24 @@ fn handle_event<H: EventHandler + Send + Sync + 'static>(
             });
         },
         DispatchEvent::Model(Event::GuildUnavailable(mut event)) => {
-            update!(event);
+            update!(context.cache, event);
 
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("guild_unavailable", move || {
                 event_handler.guild_unavailable(context, event.guild_id);
             });
         },
         DispatchEvent::Model(Event::GuildUpdate(mut event)) => {
-            update!(event);
+            update_concurrently!(context.cache, event);
 
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("guild_update", move || {
                 feature_cache! {{
-                    let before = CACHE.read()
+                    let before = context.cache.read()
                         .guilds
                         .get(&event.guild.id)
                         .cloned();
@@ -492,112 +539,138 @@ fn handle_event<H: EventHandler + Send + Sync + 'static>(
         // Already handled by the framework check macro
         DispatchEvent::Model(Event::MessageCreate(_)) => {},
         DispatchEvent::Model(Event::MessageDeleteBulk(mut event)) => {
-            let context = context(data, runner_tx, shard_id);
+            let _deleted = update_concurrently!(context.cache, event);
+
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
-                event_handler.message_delete_bulk(context, event.channel_id, event.ids);
+            executor.execute("message_delete_bulk", move || {
+                feature_cache! {{
+                    let deleted = _deleted.unwrap_or_else(Vec::new);
+
+                    event_handler.message_delete_bulk(context, event.channel_id, event.ids, deleted);
+                } else {
+                    event_handler.message_delete_bulk(context, event.channel_id, event.ids);
+                }}
             });
         },
         DispatchEvent::Model(Event::MessageDelete(mut event)) => {
-            let context = context(data, runner_tx, shard_id);
+            let _deleted = update_concurrently!(context.cache, event);
+
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
-                event_handler.message_delete(context, event.channel_id, event.message_id);
+            executor.execute("message_delete", move || {
+                feature_cache! {{
+                    event_handler.message_delete(context, event.channel_id, event.message_id, _deleted);
+                } else {
+                    event_handler.message_delete(context, event.channel_id, event.message_id);
+                }}
             });
         },
         DispatchEvent::Model(Event::MessageUpdate(mut event)) => {
-            update!(event);
+            let _before = update_concurrently!(context.cache, event);
 
-            let context = context(data, runner_tx, shard_id);
+            let _after: Option<Message> = feature_cache! {{
+                context.cache.read().peek_message(event.channel_id, event.id)
+            } else {
+                None
+            }};
+
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
-                event_handler.message_update(context, event);
+            executor.execute("message_update", move || {
+                feature_cache! {{
+                    event_handler.message_update(context, _before, _after, event);
+                } else {
+                    event_handler.message_update(context, event);
+                }}
             });
         },
         DispatchEvent::Model(Event::PresencesReplace(mut event)) => {
-            update!(event);
+            update!(context.cache, event);
 
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("presence_replace", move || {
                 event_handler.presence_replace(context, event.presences);
             });
         },
         DispatchEvent::Model(Event::PresenceUpdate(mut event)) => {
-            update!(event);
+            update!(context.cache, event);
 
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("presence_update", move || {
                 event_handler.presence_update(context, event);
             });
         },
         DispatchEvent::Model(Event::ReactionAdd(mut event)) => {
-            let context = context(data, runner_tx, shard_id);
+            context.collectors.feed_reaction(&event.reaction);
+
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("reaction_add", move || {
                 event_handler.reaction_add(context, event.reaction);
             });
         },
         DispatchEvent::Model(Event::ReactionRemove(mut event)) => {
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("reaction_remove", move || {
                 event_handler.reaction_remove(context, event.reaction);
             });
         },
         DispatchEvent::Model(Event::ReactionRemoveAll(mut event)) => {
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("reaction_remove_all", move || {
                 event_handler.reaction_remove_all(context, event.channel_id, event.message_id);
             });
         },
         DispatchEvent::Model(Event::Ready(mut event)) => {
-            update!(event);
+            update!(context.cache, event);
 
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
             let event_handler = Arc::clone(&event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("ready", move || {
                 event_handler.ready(context, event.ready);
             });
         },
         DispatchEvent::Model(Event::Resumed(mut event)) => {
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
 
-            event_handler.resume(context, event);
+            executor.call("resume", || event_handler.resume(context, event));
         },
         DispatchEvent::Model(Event::TypingStart(mut event)) => {
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("typing_start", move || {
                 event_handler.typing_start(context, event);
             });
         },
         DispatchEvent::Model(Event::Unknown(mut event)) => {
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("unknown", move || {
                 event_handler.unknown(context, event.kind, event.value);
             });
         },
         DispatchEvent::Model(Event::UserUpdate(mut event)) => {
-            let _before = update!(event);
-            let context = context(data, runner_tx, shard_id);
+            let _before = update!(context.cache, event);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("user_update", move || {
                 feature_cache! {{
                     event_handler.user_update(context, _before.unwrap(), event.current_user);
                 } else {
@@ -606,30 +679,80 @@ fn handle_event<H: EventHandler + Send + Sync + 'static>(
             });
         },
         DispatchEvent::Model(Event::VoiceServerUpdate(mut event)) => {
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("voice_server_update", move || {
                 event_handler.voice_server_update(context, event);
             });
         },
         DispatchEvent::Model(Event::VoiceStateUpdate(mut event)) => {
-            update!(event);
+            #[cfg(feature = "cache")]
+            {
+                // The transitions are unknown if the cache was not updated.
+                let before = update_concurrently!(@applied context.cache, event);
+
+                if let (Some(before), Some(guild_id)) = (before, event.guild_id) {
+                    let transitions = VoiceTransition::between(before.as_ref(), &event.voice_state);
+
+                    dispatch_voice_transitions(
+                        transitions,
+                        guild_id,
+                        &event.voice_state,
+                        context,
+                        event_handler,
+                        executor,
+                    );
+                }
+            }
 
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("voice_state_update", move || {
                 event_handler.voice_state_update(context, event.guild_id, event.voice_state);
             });
         },
         DispatchEvent::Model(Event::WebhookUpdate(mut event)) => {
-            let context = context(data, runner_tx, shard_id);
+            let context = context.clone();
             let event_handler = Arc::clone(event_handler);
 
-            threadpool.execute(move || {
+            executor.execute("webhook_update", move || {
                 event_handler.webhook_update(context, event.guild_id, event.channel_id);
             });
         },
     }
 }
+
+// Dispatches the transitions of a user's voice state to the dedicated handler
+// methods.
+#[cfg(feature = "cache")]
+fn dispatch_voice_transitions<H: EventHandler + Send + Sync + 'static>(
+    transitions: Vec<VoiceTransition>,
+    guild_id: GuildId,
+    state: &VoiceState,
+    context: &Context,
+    event_handler: &Arc<H>,
+    executor: &Executor,
+) {
+    for transition in transitions {
+        let context = context.clone();
+        let event_handler = Arc::clone(event_handler);
+        let state = state.clone();
+
+        match transition {
+            VoiceTransition::Join(_) => executor.execute("voice_channel_join", move || {
+                event_handler.voice_channel_join(context, guild_id, state);
+            }),
+            VoiceTransition::Leave(channel_id) => executor.execute("voice_channel_leave", move || {
+                event_handler.voice_channel_leave(context, guild_id, channel_id, state);
+            }),
+            VoiceTransition::Move { from, .. } => executor.execute("voice_channel_move", move || {
+                event_handler.voice_channel_move(context, guild_id, from, state);
+            }),
+            VoiceTransition::Change(change) => executor.execute("voice_state_change", move || {
+                event_handler.voice_state_change(context, guild_id, change, state);
+            }),
+        }
+    }
+}
@@ -19,6 +19,10 @@ use std::{
 #[allow(enum_variant_names)]
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum Error {
+    /// When a cron expression given for a [`CronSchedule`] is malformed.
+    ///
+    /// [`CronSchedule`]: scheduler/struct.CronSchedule.html
+    InvalidCronExpression,
     /// When the token provided is invalid. This is returned when validating a
     /// token through the [`validate_token`] function.
     ///
@@ -39,6 +43,7 @@ impl Display for Error {
 impl StdError for Error {
     fn description(&self) -> &str {
         match *self {
+            Error::InvalidCronExpression => "The cron expression was invalid",
             Error::InvalidToken => "The provided token was invalid",
             Error::ShardBootFailure => "Failed to (re-)boot a shard",
             Error::Shutdown => "The clients shards shutdown",
@@ -202,14 +202,30 @@ pub trait EventHandler {
     /// Provides the message's data.
     fn message(&self, _ctx: Context, _new_message: Message) {}
 
+    /// Dispatched when a message is deleted.
+    ///
+    /// Provides the channel's id, the message's id, and the deleted message
+    /// (if it was cached).
+    #[cfg(feature = "cache")]
+    fn message_delete(&self, _ctx: Context, _channel_id: ChannelId, _deleted_message_id: MessageId, _deleted_if_available: Option<Message>) {}
+
     /// Dispatched when a message is deleted.
     ///
     /// Provides the channel's id and the message's id.
+    #[cfg(not(feature = "cache"))]
     fn message_delete(&self, _ctx: Context, _channel_id: ChannelId, _deleted_message_id: MessageId) {}
 
+    /// Dispatched when multiple messages were deleted at once.
+    ///
+    /// Provides the channel's id, the deleted messages' ids, and those of the
+    /// deleted messages which were cached.
+    #[cfg(feature = "cache")]
+    fn message_delete_bulk(&self, _ctx: Context, _channel_id: ChannelId, _multiple_deleted_messages_ids: Vec<MessageId>, _deleted_if_available: Vec<Message>) {}
+
     /// Dispatched when multiple messages were deleted at once.
     ///
     /// Provides the channel's id and the deleted messages' ids.
+    #[cfg(not(feature = "cache"))]
     fn message_delete_bulk(&self, _ctx: Context, _channel_id: ChannelId, _multiple_deleted_messages_ids: Vec<MessageId>) {}
 
     /// Dispatched when a new reaction is attached to a message.
@@ -227,9 +243,17 @@ pub trait EventHandler {
     /// Provides the channel's id and the message's id.
     fn reaction_remove_all(&self, _ctx: Context, _channel_id: ChannelId, _removed_from_message_id: MessageId) {}
 
+    /// Dispatched when a message is updated.
+    ///
+    /// Provides the message's old data and new data (if it was cached), as
+    /// well as the update itself.
+    #[cfg(feature = "cache")]
+    fn message_update(&self, _ctx: Context, _old_if_available: Option<Message>, _new: Option<Message>, _event: MessageUpdateEvent) {}
+
     /// Dispatched when a message is updated.
     ///
     /// Provides the new data of the message.
+    #[cfg(not(feature = "cache"))]
     fn message_update(&self, _ctx: Context, _new_data: MessageUpdateEvent) {}
 
     fn presence_replace(&self, _ctx: Context, _: Vec<Presence>) {}
@@ -283,6 +307,39 @@ pub trait EventHandler {
     /// the new state of the guild's voice channels.
     fn voice_state_update(&self, _ctx: Context, _: Option<GuildId>, _: VoiceState) {}
 
+    /// Dispatched when a user joins a voice channel of a guild, as determined
+    /// from their cached voice state.
+    ///
+    /// Provides the guild's id and the user's new voice state, which contains
+    /// the joined channel.
+    #[cfg(feature = "cache")]
+    fn voice_channel_join(&self, _ctx: Context, _guild_id: GuildId, _state: VoiceState) {}
+
+    /// Dispatched when a user leaves a voice channel of a guild, as
+    /// determined from their cached voice state.
+    ///
+    /// Provides the guild's id, the left channel's id, and the user's new
+    /// voice state.
+    #[cfg(feature = "cache")]
+    fn voice_channel_leave(&self, _ctx: Context, _guild_id: GuildId, _left_channel_id: ChannelId, _state: VoiceState) {}
+
+    /// Dispatched when a user moves between voice channels of a guild, as
+    /// determined from their cached voice state.
+    ///
+    /// Provides the guild's id, the previous channel's id, and the user's new
+    /// voice state, which contains the new channel.
+    #[cfg(feature = "cache")]
+    fn voice_channel_move(&self, _ctx: Context, _guild_id: GuildId, _old_channel_id: ChannelId, _state: VoiceState) {}
+
+    /// Dispatched when a flag of a user's voice state changes while they stay
+    /// connected, such as being muted or starting to stream, as determined
+    /// from their cached voice state.
+    ///
+    /// Provides the guild's id, the change, and the user's new voice state.
+    /// Multiple flags changing at once are dispatched separately.
+    #[cfg(feature = "cache")]
+    fn voice_state_change(&self, _ctx: Context, _guild_id: GuildId, _change: VoiceStateChange, _state: VoiceState) {}
+
     /// Dispatched when a guild's webhook is updated.
     ///
     /// Provides the guild's id and the channel's id the webhook belongs in.
@@ -0,0 +1,250 @@
+use model::prelude::*;
+use parking_lot::RwLock;
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Formatter, Result as FmtResult},
+    sync::Arc
+};
+use super::{
+    bridge::gateway::event::*,
+    Context,
+    EventHandler
+};
+
+/// A chain of [`EventHandler`]s, which dispatches every event to each of them
+/// in the order they were added.
+///
+/// This allows splitting a bot into reusable plugins, such as for logging or
+/// moderation, that each implement their own handler. The chain is given to
+/// the [`Client`] in place of a single handler.
+///
+/// The handlers are called one after another within the same task of the
+/// threadpool, so a handler taking long delays the handlers after it for that
+/// event. The arguments of an event are cloned for every handler.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use std::error::Error;
+/// #
+/// # fn try_main() -> Result<(), Box<Error>> {
+/// use serenity::client::{Client, Context, EventHandler, HandlerChain};
+/// use serenity::model::channel::Message;
+/// use std::env;
+///
+/// struct Logger;
+///
+/// impl EventHandler for Logger {
+///     fn message(&self, _: Context, msg: Message) {
+///         println!("{}: {}", msg.author.name, msg.content);
+///     }
+/// }
+///
+/// struct Pong;
+///
+/// impl EventHandler for Pong {
+///     fn message(&self, _: Context, msg: Message) {
+///         if msg.content == "!ping" {
+///             let _ = msg.channel_id.say("Pong!");
+///         }
+///     }
+/// }
+///
+/// let handlers = HandlerChain::new()
+///     .with(Logger)
+///     .with(Pong);
+///
+/// let mut client = Client::new(&env::var("DISCORD_TOKEN")?, handlers)?;
+///
+/// client.start()?;
+/// #     Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #     try_main().unwrap();
+/// # }
+/// ```
+///
+/// [`Client`]: struct.Client.html
+/// [`EventHandler`]: trait.EventHandler.html
+#[derive(Default)]
+pub struct HandlerChain {
+    handlers: Vec<Box<EventHandler + Send + Sync>>,
+}
+
+impl HandlerChain {
+    /// Creates an empty chain.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a handler to the end of the chain.
+    pub fn push<H: EventHandler + Send + Sync + 'static>(&mut self, handler: H) {
+        self.handlers.push(Box::new(handler));
+    }
+
+    /// Adds a handler to the end of the chain, returning the chain.
+    pub fn with<H: EventHandler + Send + Sync + 'static>(mut self, handler: H) -> Self {
+        self.push(handler);
+
+        self
+    }
+
+    /// The number of handlers in the chain.
+    pub fn len(&self) -> usize {
+        self.handlers.len()
+    }
+
+    /// Whether the chain has no handlers.
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+}
+
+impl Debug for HandlerChain {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_struct("HandlerChain")
+            .field("handlers", &self.handlers.len())
+            .finish()
+    }
+}
+
+// Implements methods of `EventHandler` which call the method of every handler
+// in the chain with clones of the arguments.
+macro_rules! chain {
+    ($($(#[$attr:meta])* fn $name:ident(&self, ctx: Context $(, $arg:ident: $kind:ty)*);)*) => {
+        $(
+            $(#[$attr])*
+            fn $name(&self, ctx: Context $(, $arg: $kind)*) {
+                for handler in &self.handlers {
+                    handler.$name(ctx.clone() $(, $arg.clone())*);
+                }
+            }
+        )*
+    };
+}
+
+impl EventHandler for HandlerChain {
+    chain! {
+        #[cfg(feature = "cache")]
+        fn cached(&self, ctx: Context, guilds: Vec<GuildId>);
+        fn channel_create(&self, ctx: Context, channel: Arc<RwLock<GuildChannel>>);
+        fn category_create(&self, ctx: Context, category: Arc<RwLock<ChannelCategory>>);
+        fn category_delete(&self, ctx: Context, category: Arc<RwLock<ChannelCategory>>);
+        fn private_channel_create(&self, ctx: Context, channel: Arc<RwLock<PrivateChannel>>);
+        fn channel_delete(&self, ctx: Context, channel: Arc<RwLock<GuildChannel>>);
+        fn channel_pins_update(&self, ctx: Context, pin: ChannelPinsUpdateEvent);
+        fn channel_recipient_addition(&self, ctx: Context, group_id: ChannelId, user: User);
+        fn channel_recipient_removal(&self, ctx: Context, group_id: ChannelId, user: User);
+        #[cfg(feature = "cache")]
+        fn channel_update(&self, ctx: Context, old: Option<Channel>, new: Channel);
+        #[cfg(not(feature = "cache"))]
+        fn channel_update(&self, ctx: Context, new_data: Channel);
+        fn guild_ban_addition(&self, ctx: Context, guild_id: GuildId, banned_user: User);
+        fn guild_ban_removal(&self, ctx: Context, guild_id: GuildId, unbanned_user: User);
+        #[cfg(feature = "cache")]
+        fn guild_create(&self, ctx: Context, guild: Guild, is_new: bool);
+        #[cfg(not(feature = "cache"))]
+        fn guild_create(&self, ctx: Context, guild: Guild);
+        #[cfg(feature = "cache")]
+        fn guild_delete(&self, ctx: Context, incomplete: PartialGuild, full: Option<Arc<RwLock<Guild>>>);
+        #[cfg(not(feature = "cache"))]
+        fn guild_delete(&self, ctx: Context, incomplete: PartialGuild);
+        fn guild_emojis_update(&self, ctx: Context, guild_id: GuildId, current_state: HashMap<EmojiId, Emoji>);
+        fn guild_integrations_update(&self, ctx: Context, guild_id: GuildId);
+        fn guild_member_addition(&self, ctx: Context, guild_id: GuildId, new_member: Member);
+        #[cfg(feature = "cache")]
+        fn guild_member_removal(&self, ctx: Context, guild: GuildId, user: User, member_data_if_available: Option<Member>);
+        #[cfg(not(feature = "cache"))]
+        fn guild_member_removal(&self, ctx: Context, guild_id: GuildId, kicked: User);
+        #[cfg(feature = "cache")]
+        fn guild_member_update(&self, ctx: Context, old_if_available: Option<Member>, new: Member);
+        #[cfg(not(feature = "cache"))]
+        fn guild_member_update(&self, ctx: Context, new: GuildMemberUpdateEvent);
+        fn guild_members_chunk(&self, ctx: Context, guild_id: GuildId, offline_members: HashMap<UserId, Member>);
+        fn guild_role_create(&self, ctx: Context, guild_id: GuildId, new: Role);
+        #[cfg(feature = "cache")]
+        fn guild_role_delete(&self, ctx: Context, guild_id: GuildId, removed_role_id: RoleId, removed_role_data_if_available: Option<Role>);
+        #[cfg(not(feature = "cache"))]
+        fn guild_role_delete(&self, ctx: Context, guild_id: GuildId, removed_role_id: RoleId);
+        #[cfg(feature = "cache")]
+        fn guild_role_update(&self, ctx: Context, guild_id: GuildId, old_data_if_available: Option<Role>, new: Role);
+        #[cfg(not(feature = "cache"))]
+        fn guild_role_update(&self, ctx: Context, guild_id: GuildId, new_data: Role);
+        fn guild_unavailable(&self, ctx: Context, guild_id: GuildId);
+        #[cfg(feature = "cache")]
+        fn guild_update(&self, ctx: Context, old_data_if_available: Option<Arc<RwLock<Guild>>>, new_but_incomplete: PartialGuild);
+        #[cfg(not(feature = "cache"))]
+        fn guild_update(&self, ctx: Context, new_but_incomplete_data: PartialGuild);
+        fn message(&self, ctx: Context, new_message: Message);
+        #[cfg(feature = "cache")]
+        fn message_delete(&self, ctx: Context, channel_id: ChannelId, deleted_message_id: MessageId, deleted_if_available: Option<Message>);
+        #[cfg(not(feature = "cache"))]
+        fn message_delete(&self, ctx: Context, channel_id: ChannelId, deleted_message_id: MessageId);
+        #[cfg(feature = "cache")]
+        fn message_delete_bulk(&self, ctx: Context, channel_id: ChannelId, multiple_deleted_messages_ids: Vec<MessageId>, deleted_if_available: Vec<Message>);
+        #[cfg(not(feature = "cache"))]
+        fn message_delete_bulk(&self, ctx: Context, channel_id: ChannelId, multiple_deleted_messages_ids: Vec<MessageId>);
+        fn reaction_add(&self, ctx: Context, add_reaction: Reaction);
+        fn reaction_remove(&self, ctx: Context, removed_reaction: Reaction);
+        fn reaction_remove_all(&self, ctx: Context, channel_id: ChannelId, removed_from_message_id: MessageId);
+        #[cfg(feature = "cache")]
+        fn message_update(&self, ctx: Context, old_if_available: Option<Message>, new: Option<Message>, event: MessageUpdateEvent);
+        #[cfg(not(feature = "cache"))]
+        fn message_update(&self, ctx: Context, new_data: MessageUpdateEvent);
+        fn presence_replace(&self, ctx: Context, presences: Vec<Presence>);
+        fn presence_update(&self, ctx: Context, new_data: PresenceUpdateEvent);
+        fn ready(&self, ctx: Context, data_about_bot: Ready);
+        fn resume(&self, ctx: Context, event: ResumedEvent);
+        fn shard_stage_update(&self, ctx: Context, event: ShardStageUpdateEvent);
+        fn typing_start(&self, ctx: Context, event: TypingStartEvent);
+        fn unknown(&self, ctx: Context, name: String, raw: Value);
+        #[cfg(feature = "cache")]
+        fn user_update(&self, ctx: Context, old_data: CurrentUser, new: CurrentUser);
+        #[cfg(not(feature = "cache"))]
+        fn user_update(&self, ctx: Context, new_data: CurrentUser);
+        fn voice_server_update(&self, ctx: Context, event: VoiceServerUpdateEvent);
+        fn voice_state_update(&self, ctx: Context, guild_id: Option<GuildId>, state: VoiceState);
+        #[cfg(feature = "cache")]
+        fn voice_channel_join(&self, ctx: Context, guild_id: GuildId, state: VoiceState);
+        #[cfg(feature = "cache")]
+        fn voice_channel_leave(&self, ctx: Context, guild_id: GuildId, left_channel_id: ChannelId, state: VoiceState);
+        #[cfg(feature = "cache")]
+        fn voice_channel_move(&self, ctx: Context, guild_id: GuildId, old_channel_id: ChannelId, state: VoiceState);
+        #[cfg(feature = "cache")]
+        fn voice_state_change(&self, ctx: Context, guild_id: GuildId, change: VoiceStateChange, state: VoiceState);
+        fn webhook_update(&self, ctx: Context, guild_id: GuildId, belongs_to_channel_id: ChannelId);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use internal::test_util;
+    use model::id::GuildId;
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+    use super::super::{Context, EventHandler};
+    use super::HandlerChain;
+
+    struct Recorder(&'static str, Arc<Mutex<Vec<(&'static str, GuildId)>>>);
+
+    impl EventHandler for Recorder {
+        fn guild_integrations_update(&self, _: Context, guild_id: GuildId) {
+            self.1.lock().push((self.0, guild_id));
+        }
+    }
+
+    #[test]
+    fn test_handler_chain_order() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let chain = HandlerChain::new()
+            .with(Recorder("first", Arc::clone(&calls)))
+            .with(Recorder("second", Arc::clone(&calls)));
+
+        chain.guild_integrations_update(test_util::context(), GuildId(1));
+
+        assert_eq!(chain.len(), 2);
+        assert_eq!(*calls.lock(), vec![("first", GuildId(1)), ("second", GuildId(1))]);
+    }
+}
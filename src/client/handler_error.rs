@@ -0,0 +1,205 @@
+use parking_lot::RwLock;
+use std::{
+    any::Any,
+    error::Error as StdError,
+    fmt::{Debug, Display, Formatter, Result as FmtResult},
+    panic::{self, AssertUnwindSafe},
+    result::Result as StdResult,
+    sync::Arc,
+};
+
+/// The result returned by the methods of a [`TryEventHandler`].
+///
+/// [`TryEventHandler`]: trait.TryEventHandler.html
+pub type HandlerResult = StdResult<(), Box<StdError>>;
+
+/// The way in which an event handler failed.
+#[derive(Debug)]
+pub enum HandlerErrorKind {
+    /// The handler returned an error.
+    Error(Box<StdError>),
+    /// The handler panicked, with the message of the panic if it was a string.
+    Panic(Option<String>),
+}
+
+/// A failure of an event handler, given to the [`ErrorHook`].
+///
+/// [`ErrorHook`]: trait.ErrorHook.html
+#[derive(Debug)]
+pub struct HandlerError {
+    /// The name of the [`EventHandler`] method which failed, such as
+    /// `"message"`, `"framework"` for the commands and hooks of the
+    /// framework, or `"raw_event"` for a [`RawEventHandler`].
+    ///
+    /// [`EventHandler`]: trait.EventHandler.html
+    /// [`RawEventHandler`]: trait.RawEventHandler.html
+    pub event: &'static str,
+    /// The way in which the handler failed.
+    pub kind: HandlerErrorKind,
+    /// The Id of the shard which received the event.
+    pub shard_id: u64,
+}
+
+impl Display for HandlerError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self.kind {
+            HandlerErrorKind::Error(ref why) => write!(
+                f,
+                "Handler of {} on shard {} failed: {}",
+                self.event,
+                self.shard_id,
+                why,
+            ),
+            HandlerErrorKind::Panic(Some(ref message)) => write!(
+                f,
+                "Handler of {} on shard {} panicked: {}",
+                self.event,
+                self.shard_id,
+                message,
+            ),
+            HandlerErrorKind::Panic(None) => write!(
+                f,
+                "Handler of {} on shard {} panicked",
+                self.event,
+                self.shard_id,
+            ),
+        }
+    }
+}
+
+/// A hook which is given every failure of the event handler.
+///
+/// Every method of the [`EventHandler`] is called such that a panic is
+/// caught, so that it does not take down the thread it was called on, and is
+/// then given to the hook. Errors returned by a [`TryEventHandler`], or given
+/// to [`Context::report_error`], are given to the hook as well.
+///
+/// This is implemented for closures, and is set through
+/// [`Client::set_error_hook`]. Without a hook, failures are logged.
+///
+/// # Examples
+///
+/// Print every failure:
+///
+/// ```rust,no_run
+/// # use std::error::Error;
+/// #
+/// # fn try_main() -> Result<(), Box<Error>> {
+/// use serenity::client::{Client, EventHandler, HandlerError};
+/// use std::env;
+///
+/// struct Handler;
+///
+/// impl EventHandler for Handler { }
+///
+/// let mut client = Client::new(&env::var("DISCORD_TOKEN")?, Handler)?;
+///
+/// client.set_error_hook(|error: HandlerError| {
+///     println!("{}", error);
+/// });
+///
+/// client.start()?;
+/// #     Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #     try_main().unwrap();
+/// # }
+/// ```
+///
+/// [`Client::set_error_hook`]: struct.Client.html#method.set_error_hook
+/// [`Context::report_error`]: struct.Context.html#method.report_error
+/// [`EventHandler`]: trait.EventHandler.html
+/// [`TryEventHandler`]: trait.TryEventHandler.html
+pub trait ErrorHook: Send + Sync {
+    /// Dispatched with a failure of the event handler.
+    fn handler_error(&self, error: HandlerError);
+}
+
+impl Debug for ErrorHook {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str("ErrorHook")
+    }
+}
+
+impl<F> ErrorHook for F where F: Fn(HandlerError) + Send + Sync {
+    fn handler_error(&self, error: HandlerError) {
+        (*self)(error)
+    }
+}
+
+// Gives the failures of event handlers to the error hook, if one is set.
+#[derive(Clone, Default)]
+pub(crate) struct ErrorReporter {
+    hook: Arc<RwLock<Option<Box<ErrorHook>>>>,
+}
+
+impl ErrorReporter {
+    pub fn set_hook<E: ErrorHook + 'static>(&self, hook: E) {
+        *self.hook.write() = Some(Box::new(hook));
+    }
+
+    pub fn report(&self, error: HandlerError) {
+        match *self.hook.read() {
+            Some(ref hook) => hook.handler_error(error),
+            None => error!("[Handler] {}", error),
+        }
+    }
+
+    // Calls a handler, reporting it if it panics.
+    pub fn catch<F: FnOnce()>(&self, event: &'static str, shard_id: u64, f: F) {
+        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(f)) {
+            self.report(HandlerError {
+                kind: HandlerErrorKind::Panic(panic_message(&*payload)),
+                event,
+                shard_id,
+            });
+        }
+    }
+}
+
+impl Debug for ErrorReporter {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_struct("ErrorReporter")
+            .field("hook", &self.hook.read().is_some())
+            .finish()
+    }
+}
+
+fn panic_message(payload: &(Any + Send)) -> Option<String> {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+    use super::{ErrorReporter, HandlerError, HandlerErrorKind};
+
+    #[test]
+    fn test_catch_panic() {
+        let reporter = ErrorReporter::default();
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&errors);
+
+        reporter.set_hook(move |error: HandlerError| {
+            let message = match error.kind {
+                HandlerErrorKind::Panic(ref message) => message.clone(),
+                HandlerErrorKind::Error(_) => None,
+            };
+
+            recorded.lock().unwrap().push((error.event, error.shard_id, message, error.to_string()));
+        });
+
+        reporter.catch("message", 1, || {});
+        reporter.catch("message", 2, || panic!("handler panicked: {}", 5));
+        reporter.catch("ready", 3, || panic!("handler panicked"));
+
+        assert_eq!(*errors.lock().unwrap(), vec![
+            ("message", 2, Some("handler panicked: 5".to_owned()), "Handler of message on shard 2 panicked: handler panicked: 5".to_owned()),
+            ("ready", 3, Some("handler panicked".to_owned()), "Handler of ready on shard 3 panicked: handler panicked".to_owned()),
+        ]);
+    }
+}
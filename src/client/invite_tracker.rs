@@ -0,0 +1,538 @@
+use http::{self, HttpError};
+use hyper::status::StatusCode;
+use internal::prelude::*;
+use model::prelude::*;
+use parking_lot::Mutex;
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::{Debug, Formatter, Result as FmtResult},
+    sync::Arc,
+    thread,
+};
+use super::{Context, EventHandler};
+
+#[cfg(feature = "cache")]
+use parking_lot::RwLock;
+
+type Callback = Box<Fn(Context, GuildId, Member, InviteUse) + Send + Sync>;
+
+/// The invite inferred to have been used by a member to join a guild, as
+/// determined by an [`InviteTracker`].
+///
+/// [`InviteTracker`]: struct.InviteTracker.html
+#[derive(Debug)]
+pub enum InviteUse {
+    /// The invite was used, with its uses after the member joined.
+    ///
+    /// This may be an invite which was deleted as it reached its maximum
+    /// uses.
+    Invite(RichInvite),
+    /// The vanity URL of the guild was used.
+    Vanity,
+    /// The uses of multiple invites increased, as multiple members joined
+    /// before the invites could be retrieved.
+    Ambiguous {
+        /// The invites which may have been used.
+        invites: Vec<RichInvite>,
+        /// Whether the vanity URL may have been used.
+        vanity: bool,
+    },
+    /// No invite's uses increased, or the invites were not known before the
+    /// member joined.
+    ///
+    /// This happens when the member joined through other means, such as
+    /// server discovery, or when the invites were already retrieved by the
+    /// join of another member who joined at the same time.
+    Unknown,
+    /// The invites could not be retrieved, such as when the current user does
+    /// not have the [Manage Guild] permission.
+    ///
+    /// [Manage Guild]: ../model/permissions/struct.Permissions.html#associatedconstant.MANAGE_GUILD
+    Unavailable(Error),
+}
+
+/// An event handler which tracks the uses of the invites of every guild to
+/// infer which invite was used by each member who joins.
+///
+/// The invites of a guild are retrieved when it is created, and again
+/// whenever a member joins, after which the increased uses are given to the
+/// callback. Retrieving the invites requires the [Manage Guild] permission,
+/// without which every join is reported as [`InviteUse::Unavailable`].
+///
+/// As the invites can only be retrieved after a member joined, members
+/// joining at the same time can not be told apart, which is reported as
+/// [`InviteUse::Ambiguous`] or [`InviteUse::Unknown`]. The joins of a guild
+/// are queued and processed one at a time to keep this to a minimum, by
+/// whichever thread is already retrieving the guild's invites, so that joins
+/// do not wait on the threadpool for each other.
+///
+/// Once the invites of a guild could not be retrieved due to missing
+/// permissions, they are not retrieved again until the guild, its roles, or
+/// the current user's member is updated.
+///
+/// Tracking is opt-in, by adding the tracker to a [`HandlerChain`] along
+/// with the main event handler. The callback is called on the client's
+/// threadpool.
+///
+/// # Examples
+///
+/// Log the invite used by every member who joins:
+///
+/// ```rust,no_run
+/// # use std::error::Error;
+/// #
+/// # fn try_main() -> Result<(), Box<Error>> {
+/// use serenity::client::{Client, EventHandler, HandlerChain, InviteTracker, InviteUse};
+/// use std::env;
+///
+/// struct Handler;
+///
+/// impl EventHandler for Handler { }
+///
+/// let tracker = InviteTracker::new(|_, guild_id, member, invite| {
+///     match invite {
+///         InviteUse::Invite(invite) => {
+///             println!("{} joined {} via {}", member.user.read().name, guild_id, invite.code);
+///         },
+///         InviteUse::Vanity => println!("{} joined {} via the vanity URL", member.user.read().name, guild_id),
+///         other => println!("{} joined {}: {:?}", member.user.read().name, guild_id, other),
+///     }
+/// });
+///
+/// let handlers = HandlerChain::new()
+///     .with(Handler)
+///     .with(tracker);
+///
+/// let mut client = Client::new(&env::var("DISCORD_TOKEN")?, handlers)?;
+///
+/// client.start()?;
+/// #     Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #     try_main().unwrap();
+/// # }
+/// ```
+///
+/// [`HandlerChain`]: struct.HandlerChain.html
+/// [`InviteUse::Ambiguous`]: enum.InviteUse.html#variant.Ambiguous
+/// [`InviteUse::Unavailable`]: enum.InviteUse.html#variant.Unavailable
+/// [`InviteUse::Unknown`]: enum.InviteUse.html#variant.Unknown
+/// [Manage Guild]: ../model/permissions/struct.Permissions.html#associatedconstant.MANAGE_GUILD
+pub struct InviteTracker {
+    callback: Callback,
+    guilds: Mutex<HashMap<GuildId, Arc<Mutex<Tracked>>>>,
+}
+
+// The invites of a guild as of when they were last retrieved.
+#[derive(Default)]
+struct Tracked {
+    // Whether a thread is retrieving the invites for the queued joins.
+    fetching: bool,
+    // Whether the invites could not be retrieved due to missing permissions.
+    forbidden: bool,
+    has_vanity: bool,
+    // The joins to retrieve the invites for, or `None` to only refresh them.
+    queue: VecDeque<Option<(Context, Member)>>,
+    snapshot: Option<Snapshot>,
+}
+
+struct Snapshot {
+    invites: HashMap<String, RichInvite>,
+    vanity_uses: Option<u64>,
+}
+
+impl InviteTracker {
+    /// Creates a tracker which gives the invite used by every member who joins
+    /// a guild to the callback.
+    pub fn new<F>(callback: F) -> Self
+        where F: Fn(Context, GuildId, Member, InviteUse) + Send + Sync + 'static {
+        InviteTracker {
+            callback: Box::new(callback),
+            guilds: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn tracked(&self, guild_id: GuildId) -> Arc<Mutex<Tracked>> {
+        Arc::clone(self.guilds.lock().entry(guild_id).or_default())
+    }
+
+    fn track(&self, guild: &Guild) {
+        {
+            let tracked = self.tracked(guild.id);
+            let mut tracked = tracked.lock();
+
+            tracked.forbidden = false;
+            tracked.has_vanity = guild.features.iter().any(|feature| feature == "VANITY_URL");
+        }
+
+        self.enqueue(guild.id, None);
+    }
+
+    // Retrieves the invites again if they were forbidden, as the permissions
+    // of the current user may have changed.
+    fn permissions_changed(&self, guild_id: GuildId) {
+        let forbidden = match self.guilds.lock().get(&guild_id) {
+            Some(tracked) => {
+                let mut tracked = tracked.lock();
+
+                let forbidden = tracked.forbidden;
+                tracked.forbidden = false;
+
+                forbidden
+            },
+            None => false,
+        };
+
+        if forbidden {
+            self.enqueue(guild_id, None);
+        }
+    }
+
+    // Queues a retrieval of the invites of a guild, for the join of a member
+    // if any. The queue is processed by the current thread, unless another
+    // thread is already processing it.
+    fn enqueue(&self, guild_id: GuildId, join: Option<(Context, Member)>) {
+        let tracked = self.tracked(guild_id);
+
+        {
+            let mut tracked = tracked.lock();
+
+            tracked.queue.push_back(join);
+
+            if tracked.fetching {
+                return;
+            }
+
+            tracked.fetching = true;
+        }
+
+        let _fetching = Fetching(&tracked);
+
+        loop {
+            let (join, has_vanity, forbidden) = {
+                let mut tracked = tracked.lock();
+
+                match tracked.queue.pop_front() {
+                    Some(join) => (join, tracked.has_vanity, tracked.forbidden),
+                    None => {
+                        tracked.fetching = false;
+
+                        return;
+                    },
+                }
+            };
+
+            let result = if forbidden {
+                Err(Error::Model(ModelError::InvalidPermissions(Permissions::MANAGE_GUILD)))
+            } else {
+                fetch(guild_id, has_vanity)
+            };
+
+            let invite = {
+                let mut tracked = tracked.lock();
+
+                match result {
+                    Ok(after) => {
+                        let invite = match tracked.snapshot {
+                            Some(ref before) => infer(before, &after),
+                            None => InviteUse::Unknown,
+                        };
+                        tracked.snapshot = Some(after);
+
+                        invite
+                    },
+                    Err(why) => {
+                        if is_forbidden(&why) {
+                            tracked.forbidden = true;
+                            tracked.snapshot = None;
+                        }
+
+                        InviteUse::Unavailable(why)
+                    },
+                }
+            };
+
+            match join {
+                Some((ctx, member)) => (self.callback)(ctx, guild_id, member, invite),
+                None => if let InviteUse::Unavailable(why) = invite {
+                    debug!("[InviteTracker] Error retrieving invites of {}: {:?}", guild_id, why);
+                },
+            }
+        }
+    }
+
+    fn untrack(&self, guild_id: GuildId) {
+        self.guilds.lock().remove(&guild_id);
+    }
+}
+
+impl Debug for InviteTracker {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_struct("InviteTracker")
+            .field("guilds", &self.guilds.lock().len())
+            .finish()
+    }
+}
+
+impl EventHandler for InviteTracker {
+    #[cfg(feature = "cache")]
+    fn guild_create(&self, _: Context, guild: Guild, _: bool) {
+        self.track(&guild);
+    }
+
+    #[cfg(not(feature = "cache"))]
+    fn guild_create(&self, _: Context, guild: Guild) {
+        self.track(&guild);
+    }
+
+    #[cfg(feature = "cache")]
+    fn guild_delete(&self, _: Context, guild: PartialGuild, _: Option<Arc<RwLock<Guild>>>) {
+        self.untrack(guild.id);
+    }
+
+    #[cfg(not(feature = "cache"))]
+    fn guild_delete(&self, _: Context, guild: PartialGuild) {
+        self.untrack(guild.id);
+    }
+
+    fn guild_member_addition(&self, ctx: Context, guild_id: GuildId, member: Member) {
+        self.enqueue(guild_id, Some((ctx, member)));
+    }
+
+    #[cfg(feature = "cache")]
+    fn guild_member_update(&self, ctx: Context, _: Option<Member>, member: Member) {
+        if member.user.read().id == ctx.cache.read().user.id {
+            self.permissions_changed(member.guild_id);
+        }
+    }
+
+    fn guild_role_create(&self, _: Context, guild_id: GuildId, _: Role) {
+        self.permissions_changed(guild_id);
+    }
+
+    #[cfg(feature = "cache")]
+    fn guild_role_update(&self, _: Context, guild_id: GuildId, _: Option<Role>, _: Role) {
+        self.permissions_changed(guild_id);
+    }
+
+    #[cfg(not(feature = "cache"))]
+    fn guild_role_update(&self, _: Context, guild_id: GuildId, _: Role) {
+        self.permissions_changed(guild_id);
+    }
+
+    #[cfg(feature = "cache")]
+    fn guild_update(&self, _: Context, _: Option<Arc<RwLock<Guild>>>, guild: PartialGuild) {
+        self.permissions_changed(guild.id);
+    }
+
+    #[cfg(not(feature = "cache"))]
+    fn guild_update(&self, _: Context, guild: PartialGuild) {
+        self.permissions_changed(guild.id);
+    }
+}
+
+// Stops the processing of the queued joins of a guild if the callback
+// panicked, so that the next join processes the queue again.
+struct Fetching<'a>(&'a Mutex<Tracked>);
+
+impl<'a> Drop for Fetching<'a> {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            self.0.lock().fetching = false;
+        }
+    }
+}
+
+fn is_forbidden(why: &Error) -> bool {
+    match *why {
+        Error::Http(HttpError::UnsuccessfulRequest(ref response)) => {
+            response.status == StatusCode::Forbidden
+        },
+        _ => false,
+    }
+}
+
+fn fetch(guild_id: GuildId, has_vanity: bool) -> Result<Snapshot> {
+    let invites = http::get_guild_invites(guild_id.0)?
+        .into_iter()
+        .map(|invite| (invite.code.clone(), invite))
+        .collect();
+
+    // The vanity URL is optional, so failing to retrieve its uses does not
+    // prevent tracking the other invites.
+    let vanity_uses = if has_vanity {
+        http::get_guild_vanity_url_uses(guild_id.0).ok()
+    } else {
+        None
+    };
+
+    Ok(Snapshot {
+        invites,
+        vanity_uses,
+    })
+}
+
+// Infers the invite used from the increase of the uses between two snapshots.
+fn infer(before: &Snapshot, after: &Snapshot) -> InviteUse {
+    let mut invites = after.invites
+        .values()
+        .filter(|invite| {
+            let uses = before.invites.get(&invite.code).map_or(0, |before| before.uses);
+
+            invite.uses > uses
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+
+    // An invite is deleted once it reaches its maximum uses, so one which was
+    // one use away from it may have been used.
+    invites.extend(before.invites
+        .values()
+        .filter(|invite| {
+            !after.invites.contains_key(&invite.code)
+                && invite.max_uses != 0
+                && invite.uses + 1 == invite.max_uses
+        })
+        .map(|invite| RichInvite {
+            uses: invite.max_uses,
+            ..invite.clone()
+        }));
+
+    let vanity = match (before.vanity_uses, after.vanity_uses) {
+        (Some(before), Some(after)) => after > before,
+        _ => false,
+    };
+
+    match (invites.len(), vanity) {
+        (0, false) => InviteUse::Unknown,
+        (0, true) => InviteUse::Vanity,
+        (1, false) => InviteUse::Invite(invites.remove(0)),
+        _ => {
+            invites.sort_by(|a, b| a.code.cmp(&b.code));
+
+            InviteUse::Ambiguous {
+                invites,
+                vanity,
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use internal::{prelude::*, test_util};
+    use model::prelude::*;
+    use parking_lot::Mutex;
+    use serde_json;
+    use std::sync::Arc;
+    use super::{infer, EventHandler, InviteTracker, InviteUse, Snapshot};
+
+    fn invite(code: &str, uses: u64, max_uses: u64) -> RichInvite {
+        serde_json::from_value(json!({
+            "channel": {"id": "1", "name": "general", "type": 0},
+            "code": code,
+            "created_at": "2018-08-01T12:00:00+00:00",
+            "guild": null,
+            "inviter": {"id": "2", "username": "inviter", "discriminator": "0001", "avatar": null},
+            "max_age": 0,
+            "max_uses": max_uses,
+            "temporary": false,
+            "uses": uses,
+        })).unwrap()
+    }
+
+    fn snapshot(invites: Vec<RichInvite>, vanity_uses: Option<u64>) -> Snapshot {
+        Snapshot {
+            invites: invites.into_iter().map(|invite| (invite.code.clone(), invite)).collect(),
+            vanity_uses,
+        }
+    }
+
+    #[test]
+    fn test_infer() {
+        let before = snapshot(vec![invite("a", 1, 0), invite("b", 4, 5)], Some(10));
+
+        match infer(&before, &snapshot(vec![invite("a", 2, 0), invite("b", 4, 5)], Some(10))) {
+            InviteUse::Invite(ref invite) => assert_eq!(invite.code, "a"),
+            ref other => panic!("unexpected use: {:?}", other),
+        }
+
+        // The invite was deleted as it reached its maximum uses.
+        match infer(&before, &snapshot(vec![invite("a", 1, 0)], Some(10))) {
+            InviteUse::Invite(ref invite) => assert_eq!((&invite.code[..], invite.uses), ("b", 5)),
+            ref other => panic!("unexpected use: {:?}", other),
+        }
+
+        match infer(&before, &snapshot(vec![invite("a", 1, 0), invite("b", 4, 5)], Some(11))) {
+            InviteUse::Vanity => {},
+            ref other => panic!("unexpected use: {:?}", other),
+        }
+
+        match infer(&before, &snapshot(vec![invite("a", 2, 0), invite("c", 1, 0)], Some(11))) {
+            InviteUse::Ambiguous { ref invites, vanity } => {
+                let codes = invites.iter().map(|invite| &invite.code[..]).collect::<Vec<_>>();
+
+                assert_eq!(codes, vec!["a", "b", "c"]);
+                assert!(vanity);
+            },
+            ref other => panic!("unexpected use: {:?}", other),
+        }
+
+        match infer(&before, &snapshot(vec![invite("a", 1, 0)], None)) {
+            InviteUse::Invite(ref invite) => assert_eq!(invite.code, "b"),
+            ref other => panic!("unexpected use: {:?}", other),
+        }
+
+        match infer(&before, &before) {
+            InviteUse::Unknown => {},
+            ref other => panic!("unexpected use: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_forbidden_invites() {
+        let uses = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&uses);
+        let tracker = InviteTracker::new(move |_, guild_id, _, invite| {
+            recorded.lock().push((guild_id, invite));
+        });
+        let guild: Guild = fixture!("guild_create_1");
+        let member = guild.members.values().next().unwrap().clone();
+
+        // Joins are only queued while another thread retrieves the invites.
+        tracker.tracked(guild.id).lock().fetching = true;
+        tracker.guild_member_addition(test_util::context(), guild.id, member.clone());
+        assert_eq!(tracker.tracked(guild.id).lock().queue.len(), 1);
+        assert!(uses.lock().is_empty());
+
+        // The queued join is processed along with the next one, without
+        // retrieving the invites again once they were forbidden.
+        {
+            let tracked = tracker.tracked(guild.id);
+            let mut tracked = tracked.lock();
+            tracked.fetching = false;
+            tracked.forbidden = true;
+        }
+        tracker.guild_member_addition(test_util::context(), guild.id, member);
+
+        let uses = uses.lock();
+        assert_eq!(uses.len(), 2);
+
+        for &(guild_id, ref invite) in uses.iter() {
+            assert_eq!(guild_id, guild.id);
+
+            match *invite {
+                InviteUse::Unavailable(Error::Model(ModelError::InvalidPermissions(permissions))) => {
+                    assert_eq!(permissions, Permissions::MANAGE_GUILD);
+                },
+                ref other => panic!("unexpected use: {:?}", other),
+            }
+        }
+
+        let tracked = tracker.tracked(guild.id);
+        let tracked = tracked.lock();
+        assert!(!tracked.fetching);
+        assert!(tracked.queue.is_empty());
+    }
+}
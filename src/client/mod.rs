@@ -20,18 +20,40 @@
 #![allow(zero_ptr)]
 
 pub mod bridge;
+pub mod scheduler;
 
+mod collector;
 mod context;
+mod data_store;
 mod dispatch;
 mod error;
 mod event_handler;
+mod handler_chain;
+mod handler_error;
+mod invite_tracker;
+mod raw_event_handler;
+mod sequencer;
+mod shutdown;
+mod try_event_handler;
 
 pub use self::{
+    collector::{Collectors, MessageCollector, ReactionCollector},
     context::Context,
+    data_store::DataStore,
     error::Error as ClientError,
-    event_handler::EventHandler
+    event_handler::EventHandler,
+    handler_chain::HandlerChain,
+    handler_error::{ErrorHook, HandlerError, HandlerErrorKind, HandlerResult},
+    invite_tracker::{InviteTracker, InviteUse},
+    raw_event_handler::{RawEventHandler, RawEventStage},
+    sequencer::DispatchOrder,
+    shutdown::ShutdownHandle,
+    try_event_handler::{Fallible, TryEventHandler}
 };
 
+#[cfg(any(test, feature = "standard_framework"))]
+pub(crate) use self::handler_error::ErrorReporter;
+
 // Note: the following re-exports are here for backwards compatibility
 pub use gateway;
 pub use http as rest;
@@ -42,17 +64,43 @@ pub use CACHE;
 use http;
 use internal::prelude::*;
 use parking_lot::Mutex;
-use self::bridge::gateway::{ShardManager, ShardManagerMonitor, ShardManagerOptions};
+use self::scheduler::{Runtime, Scheduler};
+use self::bridge::gateway::{
+    EventFilter,
+    EventStream,
+    EventStreamOptions,
+    ShardManager,
+    ShardManagerMonitor,
+    ShardManagerOptions
+};
 use std::sync::Arc;
 use threadpool::ThreadPool;
 use typemap::ShareMap;
 
+#[cfg(feature = "cache")]
+use cache::Cache;
+#[cfg(feature = "cache")]
+use parking_lot::RwLock;
 #[cfg(feature = "framework")]
 use framework::Framework;
 #[cfg(feature = "voice")]
 use model::id::UserId;
 #[cfg(feature = "voice")]
 use self::bridge::voice::ClientVoiceManager;
+#[cfg(all(feature = "cluster", unix))]
+use self::bridge::{
+    cluster::{ClusterError, ClusterWorker, CoordinatorMessage},
+    gateway::ShardId
+};
+#[cfg(all(feature = "cluster", unix))]
+use std::thread;
+
+// The cache given to a client's shards, which is nothing if the cache is
+// disabled.
+#[cfg(feature = "cache")]
+type ClientCache = Arc<RwLock<Cache>>;
+#[cfg(not(feature = "cache"))]
+type ClientCache = ();
 
 /// The Client is the way to be able to start sending authenticated requests
 /// over the REST API, as well as initializing a WebSocket connection through
@@ -100,6 +148,24 @@ use self::bridge::voice::ClientVoiceManager;
 /// [`Event::MessageCreate`]: ../model/event/enum.Event.html#variant.MessageCreate
 /// [sharding docs]: ../index.html#sharding
 pub struct Client {
+    /// The cache updated by the client's shards.
+    ///
+    /// Every client owns its own cache, unless created with a shared one via
+    /// [`new_with_cache`]. Within event handlers, the cache can be accessed
+    /// through [`Context::cache`].
+    ///
+    /// [`Context::cache`]: struct.Context.html#structfield.cache
+    /// [`new_with_cache`]: #method.new_with_cache
+    #[cfg(feature = "cache")]
+    pub cache: Arc<RwLock<Cache>>,
+    /// The collectors waiting for events received by the client's shards.
+    ///
+    /// Within event handlers, the collectors can be accessed through
+    /// [`Context::collectors`]. Refer to [`Collectors`] for more information.
+    ///
+    /// [`Collectors`]: struct.Collectors.html
+    /// [`Context::collectors`]: struct.Context.html#structfield.collectors
+    pub collectors: Collectors,
     /// A ShareMap which requires types to be Send + Sync. This is a map that
     /// can be safely shared across contexts.
     ///
@@ -148,18 +214,18 @@ pub struct Client {
     ///
     /// struct Handler;
     ///
-    /// # #[cfg(all(feature = "client", feature = "standard_framework", feature = "model"))]
+    /// # #[cfg(all(feature = "cache", feature = "client", feature = "standard_framework", feature = "model"))]
     /// impl EventHandler for Handler {
     ///     fn message(&self, ctx: Context, _: Message) { reg(ctx, "MessageCreate") }
-    ///     fn message_delete(&self, ctx: Context, _: ChannelId, _: MessageId) {
+    ///     fn message_delete(&self, ctx: Context, _: ChannelId, _: MessageId, _: Option<Message>) {
     ///         reg(ctx, "MessageDelete") }
-    ///     fn message_delete_bulk(&self, ctx: Context, _: ChannelId, _: Vec<MessageId>) {
+    ///     fn message_delete_bulk(&self, ctx: Context, _: ChannelId, _: Vec<MessageId>, _: Vec<Message>) {
     ///         reg(ctx, "MessageDeleteBulk") }
-    ///     fn message_update(&self, ctx: Context, _: MessageUpdateEvent) {
+    ///     fn message_update(&self, ctx: Context, _: Option<Message>, _: Option<Message>, _: MessageUpdateEvent) {
     ///         reg(ctx, "MessageUpdate") }
     /// }
     ///
-    /// # #[cfg(all(feature = "client", feature = "standard_framework", feature = "model"))]
+    /// # #[cfg(all(feature = "cache", feature = "client", feature = "standard_framework", feature = "model"))]
     /// # fn main() {
     ///
     /// let mut client = Client::new(&env::var("DISCORD_TOKEN").expect("Could not find token."), Handler)
@@ -176,6 +242,11 @@ pub struct Client {
     ///
     /// Refer to [example 05] for an example on using the `data` field.
     ///
+    /// Every access to the map locks all of its values, so a handler using
+    /// one value blocks all other handlers using the map. Prefer
+    /// [`data_store`], which locks every value on its own.
+    ///
+    /// [`data_store`]: #structfield.data_store
     /// [`Context::data`]: struct.Context.html#structfield.data
     /// [`Event::MessageCreate`]: ../model/event/enum.Event.html#variant.MessageCreate
     /// [`Event::MessageDelete`]: ../model/event/enum.Event.html#variant.MessageDelete
@@ -183,6 +254,27 @@ pub struct Client {
     /// [`Event::MessageUpdate`]: ../model/event/enum.Event.html#variant.MessageUpdate
     /// [example 05]: https://github.com/serenity-rs/serenity/tree/current/examples/05_command_framework
     pub data: Arc<Mutex<ShareMap>>,
+    /// A store of typed data shared across contexts, where every value is
+    /// locked on its own.
+    ///
+    /// Within event handlers, the store can be accessed through
+    /// [`Context::data_store`]. Refer to [`DataStore`] for more information,
+    /// including on moving values from [`data`] into it.
+    ///
+    /// [`Context::data_store`]: struct.Context.html#structfield.data_store
+    /// [`DataStore`]: struct.DataStore.html
+    /// [`data`]: #structfield.data
+    pub data_store: DataStore,
+    /// The scheduler running jobs alongside the client.
+    ///
+    /// Jobs are run while the client is started, and the scheduler is shut
+    /// down along with the client. Within event handlers, the scheduler can be
+    /// accessed through [`Context::scheduler`]. Refer to [`Scheduler`] for
+    /// more information.
+    ///
+    /// [`Context::scheduler`]: struct.Context.html#structfield.scheduler
+    /// [`Scheduler`]: scheduler/struct.Scheduler.html
+    pub scheduler: Scheduler,
     /// A vector of all active shards that have received their [`Event::Ready`]
     /// payload, and have dispatched to [`on_ready`] if an event handler was
     /// configured.
@@ -283,6 +375,7 @@ pub struct Client {
     /// [`Client::start_shards`]: #method.start_shards
     pub shard_manager: Arc<Mutex<ShardManager>>,
     shard_manager_worker: ShardManagerMonitor,
+    shutdown: ShutdownHandle,
     /// The threadpool shared by all shards.
     ///
     /// Defaults to 5 threads, which should suffice small bots. Consider
@@ -337,6 +430,55 @@ impl Client {
     /// # }
     /// ```
     pub fn new<H>(token: &str, handler: H) -> Result<Self>
+        where H: EventHandler + Send + Sync + 'static {
+        feature_cache! {{
+            Self::build(token, handler, Arc::new(RwLock::new(Cache::default())))
+        } else {
+            Self::build(token, handler, ())
+        }}
+    }
+
+    /// Creates a Client for a bot user, which updates the given cache instead
+    /// of creating its own.
+    ///
+    /// This allows multiple clients to share a cache, or a cache to be set up
+    /// - such as with [`Settings`] - before the client starts.
+    ///
+    /// # Examples
+    ///
+    /// Create a Client which updates the global [`CACHE`], for compatibility
+    /// with code reading from it:
+    ///
+    /// ```rust,no_run
+    /// # use serenity::prelude::EventHandler;
+    /// struct Handler;
+    ///
+    /// impl EventHandler for Handler {}
+    /// # use std::error::Error;
+    /// #
+    /// # fn try_main() -> Result<(), Box<Error>> {
+    /// use serenity::{CACHE, Client};
+    /// use std::{env, sync::Arc};
+    ///
+    /// let token = env::var("DISCORD_TOKEN")?;
+    /// let client = Client::new_with_cache(&token, Handler, Arc::clone(&CACHE))?;
+    /// # Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #    try_main().unwrap();
+    /// # }
+    /// ```
+    ///
+    /// [`CACHE`]: ../struct.CACHE.html
+    /// [`Settings`]: ../cache/struct.Settings.html
+    #[cfg(feature = "cache")]
+    pub fn new_with_cache<H>(token: &str, handler: H, cache: Arc<RwLock<Cache>>) -> Result<Self>
+        where H: EventHandler + Send + Sync + 'static {
+        Self::build(token, handler, cache)
+    }
+
+    fn build<H>(token: &str, handler: H, cache: ClientCache) -> Result<Self>
         where H: EventHandler + Send + Sync + 'static {
         let token = token.trim();
 
@@ -347,12 +489,24 @@ impl Client {
         };
 
         http::set_token(&token);
+        let url = http::get_gateway()?.url;
+
+        Ok(Self::with_gateway(token, url, handler, cache))
+    }
+
+    // Creates a client connecting to the given gateway URL.
+    #[cfg_attr(not(feature = "cache"), allow(unused_variables))]
+    fn with_gateway<H>(token: String, url: String, handler: H, cache: ClientCache) -> Self
+        where H: EventHandler + Send + Sync + 'static {
         let locked = Arc::new(Mutex::new(token));
 
         let name = "serenity client".to_owned();
         let threadpool = ThreadPool::with_name(name, 5);
-        let url = Arc::new(Mutex::new(http::get_gateway()?.url));
+        let url = Arc::new(Mutex::new(url));
         let data = Arc::new(Mutex::new(ShareMap::custom()));
+        let data_store = DataStore::default();
+        let collectors = Collectors::default();
+        let scheduler = Scheduler::default();
         let event_handler = Arc::new(handler);
 
         #[cfg(feature = "framework")]
@@ -365,10 +519,15 @@ impl Client {
 
         let (shard_manager, shard_manager_worker) = {
             ShardManager::new(ShardManagerOptions {
+                #[cfg(feature = "cache")]
+                cache: &cache,
+                collectors: &collectors,
                 data: &data,
+                data_store: &data_store,
                 event_handler: &event_handler,
                 #[cfg(feature = "framework")]
                 framework: &framework,
+                scheduler: &scheduler,
                 shard_index: 0,
                 shard_init: 0,
                 shard_total: 0,
@@ -379,19 +538,26 @@ impl Client {
                 ws_url: &url,
             })
         };
+        let shutdown = ShutdownHandle::new(&shard_manager);
 
-        Ok(Client {
+        Client {
+            #[cfg(feature = "cache")]
+            cache,
             token: locked,
             ws_uri: url,
             #[cfg(feature = "framework")]
             framework,
+            collectors,
             data,
+            data_store,
+            scheduler,
             shard_manager,
             shard_manager_worker,
+            shutdown,
             threadpool,
             #[cfg(feature = "voice")]
             voice_manager,
-        })
+        }
     }
 
     /// Sets a framework to be used with the client. All message events will be
@@ -501,6 +667,70 @@ impl Client {
         *self.framework.lock() = Some(Box::new(f));
     }
 
+    /// Sets the filter deciding which events are fully processed by the
+    /// shards, allowing unneeded events to skip deserialization.
+    ///
+    /// Refer to [`EventFilter`] for more information.
+    ///
+    /// [`EventFilter`]: bridge/gateway/struct.EventFilter.html
+    pub fn set_event_filter(&mut self, filter: EventFilter) {
+        self.shard_manager.lock().set_event_filter(filter);
+    }
+
+    /// Creates an ordered stream of the events received by the client's
+    /// shards, replacing the previous stream, if any.
+    ///
+    /// Events are sent to the stream in addition to being dispatched to the
+    /// event handler. Refer to [`EventStream`] for more information.
+    ///
+    /// [`EventStream`]: bridge/gateway/struct.EventStream.html
+    pub fn event_stream(&mut self, options: EventStreamOptions) -> EventStream {
+        self.shard_manager.lock().event_stream(options)
+    }
+
+    /// Adds a handler which is given every event before and after the cache
+    /// is updated with it.
+    ///
+    /// Handlers are called in the order they were added. Refer to
+    /// [`RawEventHandler`] for more information.
+    ///
+    /// [`RawEventHandler`]: trait.RawEventHandler.html
+    pub fn add_raw_event_handler<R: RawEventHandler + 'static>(&mut self, handler: R) {
+        self.shard_manager.lock().add_raw_event_handler(handler);
+    }
+
+    /// Sets the hook which is given every failure of the event handler,
+    /// replacing the previous hook, if any.
+    ///
+    /// Refer to [`ErrorHook`] for more information.
+    ///
+    /// [`ErrorHook`]: trait.ErrorHook.html
+    pub fn set_error_hook<E: ErrorHook + 'static>(&mut self, hook: E) {
+        self.shard_manager.lock().set_error_hook(hook);
+    }
+
+    /// Sets the order in which events are handled by the event handler.
+    ///
+    /// Refer to [`DispatchOrder`] for more information.
+    ///
+    /// [`DispatchOrder`]: enum.DispatchOrder.html
+    pub fn set_dispatch_order(&mut self, order: DispatchOrder) {
+        self.shard_manager.lock().set_dispatch_order(order);
+    }
+
+    /// Returns a handle which can be used to gracefully shut down the client
+    /// from another thread.
+    ///
+    /// Once a shutdown is requested, the start method that is running returns
+    /// `Ok` after every shard has closed and every running event handler has
+    /// finished, or once the given timeout passed. Refer to
+    /// [`ShutdownHandle::shutdown`] for more information.
+    ///
+    /// [`ShutdownHandle::shutdown`]: struct.ShutdownHandle.html#method.shutdown
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shutdown.clone()
+    }
+
     /// Establish the connection and start listening for events.
     ///
     /// This will start receiving events in a loop and start dispatching the
@@ -810,6 +1040,108 @@ impl Client {
         self.start_connection([range[0], range[1], total_shards])
     }
 
+    /// Starts the shards assigned to this process by a cluster coordinator and
+    /// starts listening for events.
+    ///
+    /// Shards are only started once the coordinator allows them to IDENTIFY,
+    /// and any shards the coordinator moves to this process later on - such
+    /// as when another worker dies - are started as well.
+    ///
+    /// Refer to the [cluster documentation][cluster docs] for more
+    /// information.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use std::error::Error;
+    /// #
+    /// # fn try_main() -> Result<(), Box<Error>> {
+    /// use serenity::client::bridge::cluster::ClusterWorker;
+    /// use serenity::client::{Client, EventHandler};
+    /// use std::env;
+    ///
+    /// struct Handler;
+    ///
+    /// impl EventHandler for Handler { }
+    ///
+    /// let mut client = Client::new(&env::var("DISCORD_TOKEN")?, Handler)?;
+    /// let worker = ClusterWorker::connect("/tmp/my-bot.sock")?;
+    ///
+    /// if let Err(why) = client.start_cluster(&worker) {
+    ///     println!("Err with client: {:?}", why);
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #     try_main().unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ClusterError::AssignmentsTaken`] if the worker was already
+    /// used to start a client, or a [`ClusterError::Disconnected`] if the
+    /// coordinator closed the connection before assigning shards.
+    ///
+    /// [`ClusterError::AssignmentsTaken`]: bridge/cluster/enum.ClusterError.html#variant.AssignmentsTaken
+    /// [`ClusterError::Disconnected`]: bridge/cluster/enum.ClusterError.html#variant.Disconnected
+    /// [cluster docs]: bridge/cluster/index.html
+    #[cfg(all(feature = "cluster", unix))]
+    pub fn start_cluster(&mut self, worker: &ClusterWorker) -> Result<()> {
+        let assignments = worker.take_assignments()?;
+
+        let (shards, shard_total) = match assignments.recv() {
+            Ok(CoordinatorMessage::Assign { shards, shard_total }) => {
+                (shards, shard_total)
+            },
+            Ok(_) => return Err(Error::Cluster(ClusterError::UnexpectedMessage)),
+            Err(_) => return Err(Error::Cluster(ClusterError::Disconnected)),
+        };
+
+        #[cfg(feature = "voice")]
+        self.voice_manager.lock().set_shard_count(shard_total);
+
+        self.update_current_user()?;
+
+        #[cfg(feature = "cache")]
+        worker.set_cache(&self.cache);
+        worker.set_shard_manager(&self.shard_manager);
+
+        self.start_scheduler();
+
+        {
+            let mut manager = self.shard_manager.lock();
+
+            manager.set_shards(0, 0, shard_total);
+            manager.set_cluster(Some(worker.clone()));
+
+            debug!("Initializing cluster shards: {:?}/{}", shards, shard_total);
+
+            for shard_id in shards {
+                manager.start(ShardId(shard_id));
+            }
+        }
+
+        let shard_manager = Arc::clone(&self.shard_manager);
+
+        thread::spawn(move || {
+            for msg in assignments {
+                if let CoordinatorMessage::StartShards { shards } = msg {
+                    let mut manager = shard_manager.lock();
+
+                    for shard_id in shards {
+                        manager.start(ShardId(shard_id));
+                    }
+                }
+            }
+        });
+
+        self.run_shards();
+
+        Ok(())
+    }
+
     // Shard data layout is:
     // 0: first shard number to initialize
     // 1: shard number to initialize up to and including
@@ -827,30 +1159,9 @@ impl Client {
         #[cfg(feature = "voice")]
         self.voice_manager.lock().set_shard_count(shard_data[2]);
 
-        // This is kind of gross, but oh well.
-        //
-        // Both the framework and voice bridge need the user's ID, so we'll only
-        // retrieve it over REST if at least one of those are enabled.
-        #[cfg(any(all(feature = "standard_framework", feature = "framework"),
-                  feature = "voice"))]
-        {
-            let user = http::get_current_user()?;
+        self.update_current_user()?;
 
-            // Update the framework's current user if the feature is enabled.
-            //
-            // This also acts as a form of check to ensure the token is correct.
-            #[cfg(all(feature = "standard_framework", feature = "framework"))]
-            {
-                if let Some(ref mut framework) = *self.framework.lock() {
-                    framework.update_current_user(user.id);
-                }
-            }
-
-            #[cfg(feature = "voice")]
-            {
-                self.voice_manager.lock().set_user_id(user.id);
-            }
-        }
+        self.start_scheduler();
 
         {
             let mut manager = self.shard_manager.lock();
@@ -871,12 +1182,67 @@ impl Client {
                 info!("Shutting down all shards");
 
                 manager.shutdown_all();
+                self.scheduler.shutdown();
 
                 return Err(Error::Client(ClientError::ShardBootFailure));
             }
         }
 
+        self.run_shards();
+
+        Ok(())
+    }
+
+    // Starts running the scheduler's jobs for the shard manager's shards.
+    fn start_scheduler(&self) {
+        let manager = self.shard_manager.lock();
+
+        self.scheduler.start(Runtime {
+            #[cfg(feature = "cache")]
+            cache: Arc::clone(&self.cache),
+            collectors: self.collectors.clone(),
+            data: Arc::clone(&self.data),
+            data_store: self.data_store.clone(),
+            error_reporter: manager.error_reporter.clone(),
+            runners: Arc::clone(&manager.runners),
+            scheduler: self.scheduler.clone(),
+            threadpool: self.threadpool.clone(),
+        });
+    }
+
+    // Blocks until every shard has shut down, then stops the scheduler and
+    // waits for the remaining event handlers.
+    fn run_shards(&mut self) {
         self.shard_manager_worker.run();
+        self.scheduler.shutdown();
+        self.shutdown.drain(&self.threadpool);
+    }
+
+    fn update_current_user(&mut self) -> Result<()> {
+        // This is kind of gross, but oh well.
+        //
+        // Both the framework and voice bridge need the user's ID, so we'll only
+        // retrieve it over REST if at least one of those are enabled.
+        #[cfg(any(all(feature = "standard_framework", feature = "framework"),
+                  feature = "voice"))]
+        {
+            let user = http::get_current_user()?;
+
+            // Update the framework's current user if the feature is enabled.
+            //
+            // This also acts as a form of check to ensure the token is correct.
+            #[cfg(all(feature = "standard_framework", feature = "framework"))]
+            {
+                if let Some(ref mut framework) = *self.framework.lock() {
+                    framework.update_current_user(user.id);
+                }
+            }
+
+            #[cfg(feature = "voice")]
+            {
+                self.voice_manager.lock().set_user_id(user.id);
+            }
+        }
 
         Ok(())
     }
@@ -944,3 +1310,40 @@ pub fn validate_token(token: &str) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::{Client, EventHandler};
+
+    #[cfg(feature = "cache")]
+    use cache::Cache;
+    #[cfg(feature = "cache")]
+    use parking_lot::RwLock;
+    #[cfg(feature = "cache")]
+    use std::sync::Arc;
+
+    struct Handler;
+
+    impl EventHandler for Handler {}
+
+    // Every way of starting a client, including clusters, runs the scheduler
+    // while its shards run.
+    #[test]
+    fn test_client_stops_scheduler() {
+        let cache = feature_cache! {{
+            Arc::new(RwLock::new(Cache::default()))
+        } else {
+            ()
+        }};
+        let token = "Bot token".to_owned();
+        let url = "wss://gateway.discord.gg".to_owned();
+        let mut client = Client::with_gateway(token, url, Handler, cache);
+
+        client.start_scheduler();
+        assert!(client.scheduler.is_running());
+
+        client.shard_manager.lock().begin_shutdown();
+        client.run_shards();
+        assert!(!client.scheduler.is_running());
+    }
+}
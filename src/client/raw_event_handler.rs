@@ -0,0 +1,96 @@
+use model::event::Event;
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+use super::Context;
+
+/// The point at which an event is given to a [`RawEventHandler`].
+///
+/// [`RawEventHandler`]: trait.RawEventHandler.html
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RawEventStage {
+    /// The event was received, and the cache was not yet updated with it.
+    BeforeCache,
+    /// The cache was updated with the event, and it was queued for dispatching
+    /// to the [`EventHandler`].
+    ///
+    /// [`EventHandler`]: trait.EventHandler.html
+    AfterCache,
+}
+
+/// A handler which is given every event received by a shard in its raw form,
+/// both before and after the cache is updated with it.
+///
+/// Unlike the methods of an [`EventHandler`], a raw event handler is called
+/// synchronously on the thread of the shard which received the event, so it
+/// can inspect the cache as it was right before, and right after, the event
+/// was applied. The shard does not receive further events until the handler
+/// returns, so any lengthy work should be moved to a separate thread.
+///
+/// Events that are filtered out by the [`EventFilter`] are not given to raw
+/// event handlers, unless it is [`raw`], in which case they are given as an
+/// [`Event::Unknown`] with their name and raw payload.
+///
+/// A handler which panics is reported to the [`ErrorHook`] as the
+/// `"raw_event"` event, and the shard keeps running.
+///
+/// This is implemented for closures, and multiple handlers can be added
+/// through [`Client::add_raw_event_handler`], which are called in the order
+/// they were added.
+///
+/// # Examples
+///
+/// Log every event along with the number of guilds in the cache:
+///
+/// ```rust,no_run
+/// # use std::error::Error;
+/// #
+/// # #[cfg(feature = "cache")]
+/// # fn try_main() -> Result<(), Box<Error>> {
+/// use serenity::client::{Client, Context, EventHandler, RawEventStage};
+/// use serenity::model::event::Event;
+/// use std::env;
+///
+/// struct Handler;
+///
+/// impl EventHandler for Handler { }
+///
+/// let mut client = Client::new(&env::var("DISCORD_TOKEN")?, Handler)?;
+///
+/// client.add_raw_event_handler(|ctx: &Context, event: &Event, stage: RawEventStage| {
+///     println!("{:?} ({} guilds): {:?}", stage, ctx.cache.read().guilds.len(), event);
+/// });
+///
+/// client.start()?;
+/// #     Ok(())
+/// # }
+/// #
+/// # #[cfg(feature = "cache")]
+/// # fn main() {
+/// #     try_main().unwrap();
+/// # }
+/// #
+/// # #[cfg(not(feature = "cache"))]
+/// # fn main() {}
+/// ```
+///
+/// [`Client::add_raw_event_handler`]: struct.Client.html#method.add_raw_event_handler
+/// [`ErrorHook`]: trait.ErrorHook.html
+/// [`Event::Unknown`]: ../model/event/enum.Event.html#variant.Unknown
+/// [`EventFilter`]: bridge/gateway/struct.EventFilter.html
+/// [`EventHandler`]: trait.EventHandler.html
+/// [`raw`]: bridge/gateway/struct.EventFilter.html#method.raw
+pub trait RawEventHandler: Send + Sync {
+    /// Dispatched with an event at the given stage of its processing.
+    fn raw_event(&self, ctx: &Context, event: &Event, stage: RawEventStage);
+}
+
+impl Debug for RawEventHandler {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str("RawEventHandler")
+    }
+}
+
+impl<F> RawEventHandler for F where F: Fn(&Context, &Event, RawEventStage) + Send + Sync {
+    fn raw_event(&self, ctx: &Context, event: &Event, stage: RawEventStage) {
+        (*self)(ctx, event, stage)
+    }
+}
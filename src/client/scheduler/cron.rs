@@ -0,0 +1,197 @@
+use chrono::{DateTime, Datelike, Duration, TimeZone, Timelike, Utc};
+use internal::prelude::*;
+use std::str::FromStr;
+use super::super::ClientError;
+
+// The number of years after which a schedule is considered to never match.
+const MAX_YEARS_AHEAD: i32 = 5;
+
+/// A cron-style schedule, matching the minutes at which a recurring job runs.
+///
+/// A schedule is parsed from the usual five fields separated by whitespace:
+/// minute (`0-59`), hour (`0-23`), day of the month (`1-31`), month (`1-12`),
+/// and day of the week (`0-7`, where both `0` and `7` are Sunday). Each field
+/// is either `*`, a number, a range such as `1-5`, or a list of those
+/// separated by commas, and may be followed by a step such as `*/15`.
+///
+/// As with cron, when both the day of the month and the day of the week are
+/// restricted, a day matches if either of them matches. All times are in UTC.
+///
+/// # Examples
+///
+/// Parse a schedule matching 9:30 on every weekday:
+///
+/// ```rust
+/// use serenity::client::scheduler::CronSchedule;
+///
+/// let schedule = "30 9 * * 1-5".parse::<CronSchedule>().unwrap();
+/// ```
+///
+/// # Errors
+///
+/// Returns a [`ClientError::InvalidCronExpression`] if the expression does not
+/// have five fields, or a field is malformed or out of range.
+///
+/// [`ClientError::InvalidCronExpression`]: ../enum.ClientError.html#variant.InvalidCronExpression
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CronSchedule {
+    days_of_month: u64,
+    days_of_week: u64,
+    hours: u64,
+    minutes: u64,
+    months: u64,
+    restricts_day_of_month: bool,
+    restricts_day_of_week: bool,
+}
+
+impl CronSchedule {
+    /// Returns the first time matching the schedule which is after the given
+    /// time, if any within the next five years.
+    pub fn next_after(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut time = time.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let last_year = time.year() + MAX_YEARS_AHEAD;
+
+        while time.year() <= last_year {
+            if !contains(self.months, time.month()) {
+                let (year, month) = if time.month() == 12 {
+                    (time.year() + 1, 1)
+                } else {
+                    (time.year(), time.month() + 1)
+                };
+
+                time = Utc.ymd(year, month, 1).and_hms(0, 0, 0);
+            } else if !self.matches_day(time) {
+                time = time.date().succ().and_hms(0, 0, 0);
+            } else if !contains(self.hours, time.hour()) {
+                time = time.with_minute(0)? + Duration::hours(1);
+            } else if !contains(self.minutes, time.minute()) {
+                time = time + Duration::minutes(1);
+            } else {
+                return Some(time);
+            }
+        }
+
+        None
+    }
+
+    fn matches_day(&self, time: DateTime<Utc>) -> bool {
+        let day_of_month = contains(self.days_of_month, time.day());
+        let day_of_week = contains(self.days_of_week, time.weekday().num_days_from_sunday());
+
+        match (self.restricts_day_of_month, self.restricts_day_of_week) {
+            (true, true) => day_of_month || day_of_week,
+            _ => day_of_month && day_of_week,
+        }
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+
+        if fields.len() != 5 {
+            return Err(Error::Client(ClientError::InvalidCronExpression));
+        }
+
+        let mut days_of_week = parse_field(fields[4], 0, 7)?;
+
+        // Sunday may be given as either 0 or 7.
+        if contains(days_of_week, 7) {
+            days_of_week |= 1;
+        }
+
+        Ok(Self {
+            days_of_month: parse_field(fields[2], 1, 31)?,
+            days_of_week,
+            hours: parse_field(fields[1], 0, 23)?,
+            minutes: parse_field(fields[0], 0, 59)?,
+            months: parse_field(fields[3], 1, 12)?,
+            restricts_day_of_month: fields[2] != "*",
+            restricts_day_of_week: fields[4] != "*",
+        })
+    }
+}
+
+fn contains(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+// Parses a field into a set of the values it matches, with one bit per value.
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64> {
+    let invalid = || Error::Client(ClientError::InvalidCronExpression);
+    let mut set = 0;
+
+    for part in field.split(',') {
+        let (range, step) = match part.find('/') {
+            Some(idx) => (&part[..idx], part[idx + 1..].parse::<u32>().map_err(|_| invalid())?),
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some(idx) = range.find('-') {
+            let start = range[..idx].parse::<u32>().map_err(|_| invalid())?;
+            let end = range[idx + 1..].parse::<u32>().map_err(|_| invalid())?;
+
+            (start, end)
+        } else {
+            let value = range.parse::<u32>().map_err(|_| invalid())?;
+
+            (value, value)
+        };
+
+        if step == 0 || start < min || end > max || start > end {
+            return Err(invalid());
+        }
+
+        let mut value = start;
+
+        while value <= end {
+            set |= 1 << value;
+            value += step;
+        }
+    }
+
+    Ok(set)
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{TimeZone, Utc};
+    use super::CronSchedule;
+
+    fn parse(expr: &str) -> CronSchedule {
+        expr.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert!("* * * * *".parse::<CronSchedule>().is_ok());
+        assert!("*/15 0-6,18 1 */2 1-5".parse::<CronSchedule>().is_ok());
+        assert!("* * * *".parse::<CronSchedule>().is_err());
+        assert!("60 * * * *".parse::<CronSchedule>().is_err());
+        assert!("* * 0 * *".parse::<CronSchedule>().is_err());
+        assert!("*/0 * * * *".parse::<CronSchedule>().is_err());
+        assert!("5-1 * * * *".parse::<CronSchedule>().is_err());
+        assert!("a * * * *".parse::<CronSchedule>().is_err());
+    }
+
+    #[test]
+    fn test_next_after() {
+        // A Wednesday.
+        let time = Utc.ymd(2018, 8, 1).and_hms(12, 30, 15);
+
+        assert_eq!(parse("* * * * *").next_after(time), Some(Utc.ymd(2018, 8, 1).and_hms(12, 31, 0)));
+        assert_eq!(parse("*/15 * * * *").next_after(time), Some(Utc.ymd(2018, 8, 1).and_hms(12, 45, 0)));
+        assert_eq!(parse("0 9 * * *").next_after(time), Some(Utc.ymd(2018, 8, 2).and_hms(9, 0, 0)));
+        assert_eq!(parse("0 0 1 1 *").next_after(time), Some(Utc.ymd(2019, 1, 1).and_hms(0, 0, 0)));
+        assert_eq!(parse("0 12 * * 0").next_after(time), Some(Utc.ymd(2018, 8, 5).and_hms(12, 0, 0)));
+        assert_eq!(parse("0 12 * * 7").next_after(time), Some(Utc.ymd(2018, 8, 5).and_hms(12, 0, 0)));
+        assert_eq!(parse("0 0 29 2 *").next_after(time), Some(Utc.ymd(2020, 2, 29).and_hms(0, 0, 0)));
+        // Either the 10th or a Friday.
+        assert_eq!(parse("0 0 10 * 5").next_after(time), Some(Utc.ymd(2018, 8, 3).and_hms(0, 0, 0)));
+        assert_eq!(parse("0 0 31 2 *").next_after(time), None);
+    }
+}
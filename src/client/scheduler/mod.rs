@@ -0,0 +1,749 @@
+//! A scheduler running one-shot and recurring jobs alongside a [`Client`].
+//!
+//! Refer to [`Scheduler`] for more information.
+//!
+//! [`Client`]: ../struct.Client.html
+//! [`Scheduler`]: struct.Scheduler.html
+
+mod cron;
+
+pub use self::cron::CronSchedule;
+
+use chrono::{DateTime, Duration, Utc};
+use internal::prelude::*;
+use parking_lot::{Condvar, Mutex};
+use serde_json::Value;
+use std::{
+    cmp,
+    collections::HashMap,
+    fmt::{Debug, Display, Formatter, Result as FmtResult},
+    sync::Arc,
+    thread::{self, JoinHandle},
+    time::Duration as StdDuration,
+};
+use super::{
+    bridge::gateway::{ShardId, ShardRunnerInfo},
+    handler_error::ErrorReporter,
+    Collectors,
+    Context,
+    DataStore,
+};
+use threadpool::ThreadPool;
+use typemap::ShareMap;
+
+#[cfg(feature = "cache")]
+use cache::Cache;
+#[cfg(feature = "cache")]
+use parking_lot::RwLock;
+
+// The number of milliseconds after which a job is retried if its shard is not
+// running, or a reminder if no reminder handler is set.
+const RETRY_INTERVAL_IN_MILLIS: i64 = 1000;
+
+/// The Id of a job, unique within a [`Scheduler`].
+///
+/// [`Scheduler`]: struct.Scheduler.html
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct JobId(pub u64);
+
+impl Display for JobId {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        Display::fmt(&self.0, f)
+    }
+}
+
+/// When a job of a [`Scheduler`] is run.
+///
+/// [`Scheduler`]: struct.Scheduler.html
+#[derive(Clone, Debug)]
+pub enum Schedule {
+    /// The job is run once at the given time, or as soon as possible if the
+    /// time has passed.
+    At(DateTime<Utc>),
+    /// The job is run repeatedly, waiting the given duration before each run.
+    ///
+    /// Runs which were missed, such as while the job's shard was not running,
+    /// are skipped.
+    Every(StdDuration),
+    /// The job is run at every time matching the cron-style schedule.
+    Cron(CronSchedule),
+}
+
+impl Schedule {
+    /// Creates a schedule running a job once after the given delay.
+    pub fn after(delay: StdDuration) -> Self {
+        let delay = Duration::from_std(delay).unwrap_or_else(|_| Duration::max_value());
+
+        Schedule::At(Utc::now().checked_add_signed(delay).unwrap_or_else(Utc::now))
+    }
+
+    // The first time at which a job is run.
+    fn first(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match *self {
+            Schedule::At(time) => Some(time),
+            Schedule::Every(interval) => now.checked_add_signed(Duration::from_std(interval).ok()?),
+            Schedule::Cron(ref cron) => cron.next_after(now),
+        }
+    }
+
+    // The time at which a job is run next after it was run at the given time.
+    fn next(&self, last: DateTime<Utc>, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match *self {
+            Schedule::At(_) => None,
+            Schedule::Every(interval) => {
+                let interval = Duration::from_std(interval).ok()?;
+                let next = last.checked_add_signed(interval)?;
+
+                if next > now {
+                    Some(next)
+                } else {
+                    now.checked_add_signed(interval)
+                }
+            },
+            Schedule::Cron(ref cron) => cron.next_after(now),
+        }
+    }
+}
+
+/// A one-shot job which is persisted through a [`ReminderStore`], so that it
+/// is run even if the client was restarted in the meantime.
+///
+/// Reminders are handled by the handler set through
+/// [`Scheduler::set_reminder_handler`]. They are removed from the store once
+/// they are given to the handler.
+///
+/// [`ReminderStore`]: trait.ReminderStore.html
+/// [`Scheduler::set_reminder_handler`]: struct.Scheduler.html#method.set_reminder_handler
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Reminder {
+    /// The data given when the reminder was created, such as the channel and
+    /// content of a message to send.
+    pub data: Value,
+    /// The Id of the reminder's job.
+    pub id: JobId,
+    /// The Id of the shard whose context the reminder is handled with.
+    pub shard_id: u64,
+    /// The time at which the reminder is due.
+    pub time: DateTime<Utc>,
+}
+
+/// A storage for [`Reminder`]s, allowing them to survive restarts.
+///
+/// The store is called while the scheduler is locked, so it must not use the
+/// scheduler itself. Errors are logged, and do not prevent the scheduler from
+/// running the reminders it knows of.
+///
+/// [`Reminder`]: struct.Reminder.html
+pub trait ReminderStore: Send + Sync {
+    /// Loads every stored reminder.
+    fn load(&self) -> Result<Vec<Reminder>>;
+
+    /// Stores a new reminder.
+    fn save(&self, reminder: &Reminder) -> Result<()>;
+
+    /// Removes a stored reminder, as it was handled or cancelled.
+    fn remove(&self, id: JobId) -> Result<()>;
+}
+
+enum Task {
+    Callback(Arc<Fn(Context) + Send + Sync>),
+    Reminder(Reminder),
+}
+
+struct Job {
+    next: DateTime<Utc>,
+    schedule: Schedule,
+    shard_id: u64,
+    task: Task,
+}
+
+#[derive(Default)]
+struct State {
+    jobs: HashMap<JobId, Job>,
+    last_id: u64,
+    reminder_handler: Option<Arc<Fn(Context, Reminder) + Send + Sync>>,
+    running: bool,
+    shutdown: bool,
+    store: Option<Box<ReminderStore>>,
+}
+
+impl State {
+    fn next_id(&mut self) -> JobId {
+        self.last_id += 1;
+
+        JobId(self.last_id)
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    condvar: Condvar,
+    state: Mutex<State>,
+    thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+/// A scheduler running one-shot, interval, and cron-style jobs on the
+/// [`Client`]'s threadpool.
+///
+/// Jobs are given a [`Context`] for the shard they were scheduled for, so
+/// they can use the client's data and cache, and send messages to the shard,
+/// such as to rotate the current user's game. A job is delayed until its
+/// shard is running.
+///
+/// Every client owns a scheduler, which is accessible through
+/// [`Client::scheduler`] and [`Context::scheduler`]. Its jobs are run while
+/// the client is started, and it is shut down along with the client, after
+/// which no more jobs are run. Jobs can be cancelled by the [`JobId`]
+/// returned when scheduling them.
+///
+/// # Examples
+///
+/// Rotate the current user's game every ten minutes, and greet a channel
+/// every morning:
+///
+/// ```rust,no_run
+/// # use std::error::Error;
+/// #
+/// # fn try_main() -> Result<(), Box<Error>> {
+/// use serenity::client::{Client, EventHandler};
+/// use serenity::client::scheduler::Schedule;
+/// use serenity::model::gateway::Game;
+/// use serenity::model::id::ChannelId;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::{env, time::Duration};
+///
+/// struct Handler;
+///
+/// impl EventHandler for Handler { }
+///
+/// let mut client = Client::new(&env::var("DISCORD_TOKEN")?, Handler)?;
+/// let games = ["with the scheduler", "with cron"];
+/// let rotation = AtomicUsize::new(0);
+///
+/// client.scheduler.add(0, Schedule::Every(Duration::from_secs(600)), move |ctx| {
+///     let idx = rotation.fetch_add(1, Ordering::Relaxed) % games.len();
+///
+///     ctx.set_game(Game::playing(games[idx]));
+/// });
+///
+/// client.scheduler.add(0, Schedule::Cron("0 8 * * *".parse()?), |_| {
+///     let _ = ChannelId(381880193700069377).say("Good morning!");
+/// });
+///
+/// client.start()?;
+/// #     Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #     try_main().unwrap();
+/// # }
+/// ```
+///
+/// [`Client`]: ../struct.Client.html
+/// [`Client::scheduler`]: ../struct.Client.html#structfield.scheduler
+/// [`Context`]: ../struct.Context.html
+/// [`Context::scheduler`]: ../struct.Context.html#structfield.scheduler
+/// [`JobId`]: struct.JobId.html
+#[derive(Clone, Default)]
+pub struct Scheduler {
+    shared: Arc<Shared>,
+}
+
+impl Scheduler {
+    /// Schedules a job to be run with a context for the given shard, returning
+    /// the Id of the job.
+    ///
+    /// A cron-style schedule which never matches does not run the job.
+    pub fn add<F>(&self, shard_id: u64, schedule: Schedule, job: F) -> JobId
+        where F: Fn(Context) + Send + Sync + 'static {
+        let mut state = self.shared.state.lock();
+        let id = state.next_id();
+
+        if let Some(next) = schedule.first(Utc::now()) {
+            state.jobs.insert(id, Job {
+                next,
+                schedule,
+                shard_id,
+                task: Task::Callback(Arc::new(job)),
+            });
+
+            self.shared.condvar.notify_one();
+        }
+
+        id
+    }
+
+    /// Schedules a [`Reminder`] with the given data to be handled at the given
+    /// time with a context for the given shard, returning the Id of its job.
+    ///
+    /// The reminder is saved to the [`ReminderStore`], if one is set.
+    ///
+    /// [`Reminder`]: struct.Reminder.html
+    /// [`ReminderStore`]: trait.ReminderStore.html
+    pub fn remind(&self, shard_id: u64, time: DateTime<Utc>, data: Value) -> JobId {
+        let mut state = self.shared.state.lock();
+        let id = state.next_id();
+        let reminder = Reminder {
+            data,
+            id,
+            shard_id,
+            time,
+        };
+
+        if let Some(ref store) = state.store {
+            if let Err(why) = store.save(&reminder) {
+                warn!("[Scheduler] Error saving reminder {}: {:?}", id, why);
+            }
+        }
+
+        state.jobs.insert(id, Job {
+            next: time,
+            schedule: Schedule::At(time),
+            shard_id,
+            task: Task::Reminder(reminder),
+        });
+
+        self.shared.condvar.notify_one();
+
+        id
+    }
+
+    /// Cancels a job, returning whether it was scheduled.
+    ///
+    /// Cancelled reminders are removed from the [`ReminderStore`]. Runs of
+    /// the job which already started are not stopped.
+    ///
+    /// [`ReminderStore`]: trait.ReminderStore.html
+    pub fn cancel(&self, id: JobId) -> bool {
+        let mut state = self.shared.state.lock();
+
+        let job = match state.jobs.remove(&id) {
+            Some(job) => job,
+            None => return false,
+        };
+
+        if let Task::Reminder(_) = job.task {
+            remove_reminder(&state, id);
+        }
+
+        true
+    }
+
+    /// Returns the time at which a job is run next, if it is scheduled.
+    pub fn next_run(&self, id: JobId) -> Option<DateTime<Utc>> {
+        self.shared.state.lock().jobs.get(&id).map(|job| job.next)
+    }
+
+    /// The number of scheduled jobs.
+    pub fn len(&self) -> usize {
+        self.shared.state.lock().jobs.len()
+    }
+
+    /// Whether no jobs are scheduled.
+    pub fn is_empty(&self) -> bool {
+        self.shared.state.lock().jobs.is_empty()
+    }
+
+    /// Sets the handler which is given [`Reminder`]s once they are due.
+    ///
+    /// Reminders which are due are delayed until a handler is set.
+    ///
+    /// [`Reminder`]: struct.Reminder.html
+    pub fn set_reminder_handler<F>(&self, handler: F)
+        where F: Fn(Context, Reminder) + Send + Sync + 'static {
+        self.shared.state.lock().reminder_handler = Some(Arc::new(handler));
+        self.shared.condvar.notify_one();
+    }
+
+    /// Sets the store which [`Reminder`]s are saved to, and schedules the
+    /// reminders it contains.
+    ///
+    /// Reminders whose time has passed are handled as soon as possible. A
+    /// reminder whose Id is already used by a job scheduled before the store
+    /// was set is given a new Id, and saved to the store again.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the store if the reminders could not be loaded,
+    /// in which case the store is not set.
+    ///
+    /// [`Reminder`]: struct.Reminder.html
+    pub fn set_reminder_store<S: ReminderStore + 'static>(&self, store: S) -> Result<()> {
+        let reminders = store.load()?;
+        let mut state = self.shared.state.lock();
+
+        // New Ids are only given out after the loaded ones.
+        for reminder in &reminders {
+            state.last_id = cmp::max(state.last_id, reminder.id.0);
+        }
+
+        for mut reminder in reminders {
+            if state.jobs.contains_key(&reminder.id) {
+                let old_id = reminder.id;
+                reminder.id = state.next_id();
+
+                if let Err(why) = store.remove(old_id).and_then(|_| store.save(&reminder)) {
+                    warn!(
+                        "[Scheduler] Error saving reminder {} as {}: {:?}",
+                        old_id,
+                        reminder.id,
+                        why,
+                    );
+                }
+            }
+
+            state.jobs.insert(reminder.id, Job {
+                next: reminder.time,
+                schedule: Schedule::At(reminder.time),
+                shard_id: reminder.shard_id,
+                task: Task::Reminder(reminder),
+            });
+        }
+
+        state.store = Some(Box::new(store));
+        self.shared.condvar.notify_one();
+
+        Ok(())
+    }
+
+    // Starts running jobs on a separate thread, unless already running.
+    pub(crate) fn start(&self, runtime: Runtime) {
+        {
+            let mut state = self.shared.state.lock();
+
+            if state.running || state.shutdown {
+                return;
+            }
+
+            state.running = true;
+        }
+
+        let shared = Arc::clone(&self.shared);
+
+        let spawned = thread::Builder::new()
+            .name("serenity scheduler".to_owned())
+            .spawn(move || run(&shared, &runtime));
+
+        match spawned {
+            Ok(handle) => *self.shared.thread.lock() = Some(handle),
+            Err(why) => {
+                error!("[Scheduler] Error spawning thread: {:?}", why);
+
+                self.shared.state.lock().running = false;
+            },
+        }
+    }
+
+    // Stops running jobs, and waits for the thread running them to exit. Jobs
+    // which are already running are not stopped.
+    pub(crate) fn shutdown(&self) {
+        self.shared.state.lock().shutdown = true;
+        self.shared.condvar.notify_one();
+
+        let handle = self.shared.thread.lock().take();
+
+        if let Some(handle) = handle {
+            if handle.join().is_err() {
+                warn!("[Scheduler] Thread panicked");
+            }
+        }
+    }
+
+    // Whether a thread is running jobs.
+    #[cfg(test)]
+    pub(crate) fn is_running(&self) -> bool {
+        self.shared.state.lock().running
+    }
+}
+
+impl Debug for Scheduler {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_struct("Scheduler")
+            .field("jobs", &self.len())
+            .finish()
+    }
+}
+
+// The parts of a client needed to run jobs.
+pub(crate) struct Runtime {
+    #[cfg(feature = "cache")]
+    pub cache: Arc<RwLock<Cache>>,
+    pub collectors: Collectors,
+    pub data: Arc<Mutex<ShareMap>>,
+    pub data_store: DataStore,
+    pub error_reporter: ErrorReporter,
+    pub runners: Arc<Mutex<HashMap<ShardId, ShardRunnerInfo>>>,
+    pub scheduler: Scheduler,
+    pub threadpool: ThreadPool,
+}
+
+impl Runtime {
+    // Creates a context for a shard, if it is running.
+    fn context(&self, shard_id: u64) -> Option<Context> {
+        let runner_tx = self.runners.lock().get(&ShardId(shard_id))?.runner_tx.clone();
+        let collectors = self.collectors.clone();
+        let data = Arc::clone(&self.data);
+        let data_store = self.data_store.clone();
+        let error_reporter = self.error_reporter.clone();
+        let scheduler = self.scheduler.clone();
+
+        Some(feature_cache! {{
+            Context::new(Arc::clone(&self.cache), collectors, data, data_store, error_reporter, runner_tx, scheduler, shard_id)
+        } else {
+            Context::new(collectors, data, data_store, error_reporter, runner_tx, scheduler, shard_id)
+        }})
+    }
+}
+
+fn run(shared: &Shared, runtime: &Runtime) {
+    let mut state = shared.state.lock();
+
+    loop {
+        if state.shutdown {
+            debug!("[Scheduler] Shut down");
+            state.running = false;
+
+            return;
+        }
+
+        let now = Utc::now();
+        let due = state.jobs
+            .iter()
+            .filter(|&(_, job)| job.next <= now)
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+
+        for id in due {
+            run_job(&mut state, runtime, id, now);
+        }
+
+        let next = state.jobs.values().map(|job| job.next).min();
+
+        match next {
+            Some(next) => {
+                let timeout = next.signed_duration_since(Utc::now())
+                    .to_std()
+                    .unwrap_or_else(|_| StdDuration::from_millis(0));
+
+                shared.condvar.wait_for(&mut state, timeout);
+            },
+            None => shared.condvar.wait(&mut state),
+        }
+    }
+}
+
+// Gives a due job to the threadpool, and schedules its next run.
+fn run_job(state: &mut State, runtime: &Runtime, id: JobId, now: DateTime<Utc>) {
+    let retry = now + Duration::milliseconds(RETRY_INTERVAL_IN_MILLIS);
+
+    let next = {
+        let State { ref mut jobs, ref reminder_handler, .. } = *state;
+
+        let job = match jobs.get_mut(&id) {
+            Some(job) => job,
+            None => return,
+        };
+
+        let context = match runtime.context(job.shard_id) {
+            Some(context) => context,
+            None => {
+                job.next = retry;
+
+                return;
+            },
+        };
+
+        match job.task {
+            Task::Callback(ref callback) => {
+                let callback = Arc::clone(callback);
+
+                runtime.threadpool.execute(move || callback(context));
+            },
+            Task::Reminder(ref reminder) => {
+                let handler = match *reminder_handler {
+                    Some(ref handler) => Arc::clone(handler),
+                    None => {
+                        job.next = retry;
+
+                        return;
+                    },
+                };
+                let reminder = reminder.clone();
+
+                runtime.threadpool.execute(move || handler(context, reminder));
+            },
+        }
+
+        let next = job.schedule.next(job.next, now);
+
+        if let Some(next) = next {
+            job.next = next;
+        }
+
+        next
+    };
+
+    if next.is_none() {
+        if let Some(Job { task: Task::Reminder(_), .. }) = state.jobs.remove(&id) {
+            remove_reminder(state, id);
+        }
+    }
+}
+
+fn remove_reminder(state: &State, id: JobId) {
+    if let Some(ref store) = state.store {
+        if let Err(why) = store.remove(id) {
+            warn!("[Scheduler] Error removing reminder {}: {:?}", id, why);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Utc;
+    use gateway::ConnectionStage;
+    use internal::prelude::*;
+    use parking_lot::Mutex;
+    use std::{
+        collections::HashMap,
+        sync::{mpsc, Arc},
+        time::Duration,
+    };
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct MemoryStore(Arc<Mutex<HashMap<JobId, Reminder>>>);
+
+    impl ReminderStore for MemoryStore {
+        fn load(&self) -> Result<Vec<Reminder>> {
+            Ok(self.0.lock().values().cloned().collect())
+        }
+
+        fn save(&self, reminder: &Reminder) -> Result<()> {
+            self.0.lock().insert(reminder.id, reminder.clone());
+
+            Ok(())
+        }
+
+        fn remove(&self, id: JobId) -> Result<()> {
+            self.0.lock().remove(&id);
+
+            Ok(())
+        }
+    }
+
+    fn start(scheduler: &Scheduler) {
+        let (runner_tx, _) = mpsc::channel();
+        let mut runners = HashMap::new();
+        runners.insert(ShardId(0), ShardRunnerInfo {
+            latency: None,
+            runner_tx,
+            stage: ConnectionStage::Connected,
+        });
+
+        scheduler.start(Runtime {
+            #[cfg(feature = "cache")]
+            cache: Arc::new(RwLock::new(Cache::default())),
+            collectors: Collectors::default(),
+            data: Arc::new(Mutex::new(ShareMap::custom())),
+            data_store: DataStore::default(),
+            error_reporter: ErrorReporter::default(),
+            runners: Arc::new(Mutex::new(runners)),
+            scheduler: scheduler.clone(),
+            threadpool: ThreadPool::new(2),
+        });
+    }
+
+    #[test]
+    fn test_scheduler_jobs() {
+        let scheduler = Scheduler::default();
+        let (tx, rx) = mpsc::channel();
+        let tx = Mutex::new(tx);
+
+        let id = scheduler.add(0, Schedule::Every(Duration::from_millis(10)), move |ctx| {
+            let _ = tx.lock().send(ctx.shard_id);
+        });
+        // Jobs for shards which are not running are delayed.
+        let delayed = scheduler.add(1, Schedule::after(Duration::from_millis(0)), |_| {});
+        start(&scheduler);
+
+        for _ in 0..3 {
+            assert_eq!(rx.recv_timeout(Duration::from_secs(5)), Ok(0));
+        }
+
+        assert!(scheduler.next_run(delayed).is_some());
+        assert!(scheduler.cancel(id));
+        assert!(!scheduler.cancel(id));
+        assert_eq!(scheduler.len(), 1);
+
+        scheduler.shutdown();
+        assert!(!scheduler.is_running());
+    }
+
+    #[test]
+    fn test_scheduler_reminders() {
+        let store = MemoryStore::default();
+        let (tx, rx) = mpsc::channel();
+        let tx = Mutex::new(tx);
+
+        // A reminder which is saved before a restart.
+        let id = {
+            let scheduler = Scheduler::default();
+            scheduler.set_reminder_store(store.clone()).unwrap();
+
+            scheduler.remind(0, Utc::now(), json!("ping"))
+        };
+
+        assert!(store.0.lock().contains_key(&id));
+
+        let scheduler = Scheduler::default();
+        scheduler.set_reminder_store(store.clone()).unwrap();
+        scheduler.set_reminder_handler(move |_, reminder| {
+            let _ = tx.lock().send((reminder.id, reminder.data));
+        });
+        start(&scheduler);
+
+        assert_eq!(rx.recv_timeout(Duration::from_secs(5)), Ok((id, json!("ping"))));
+        assert!(store.0.lock().is_empty());
+
+        let id = scheduler.remind(0, Utc::now() + ::chrono::Duration::hours(1), Value::Null);
+
+        assert!(store.0.lock().contains_key(&id));
+        assert!(scheduler.cancel(id));
+        assert!(store.0.lock().is_empty());
+
+        scheduler.shutdown();
+    }
+
+    #[test]
+    fn test_scheduler_reminder_ids() {
+        let store = MemoryStore::default();
+        let later = Utc::now() + ::chrono::Duration::hours(1);
+
+        let saved = {
+            let scheduler = Scheduler::default();
+            scheduler.set_reminder_store(store.clone()).unwrap();
+
+            scheduler.remind(0, later, json!("saved"))
+        };
+
+        // A job added before the store is set uses the reminder's Id.
+        let scheduler = Scheduler::default();
+        let job = scheduler.add(0, Schedule::after(Duration::from_secs(60 * 60)), |_| {});
+        assert_eq!(job, saved);
+
+        scheduler.set_reminder_store(store.clone()).unwrap();
+        assert_eq!(scheduler.len(), 2);
+
+        let reminder = store.0.lock().values().next().cloned().unwrap();
+        assert_eq!(store.0.lock().len(), 1);
+        assert_ne!(reminder.id, job);
+        assert_eq!(reminder.data, json!("saved"));
+        assert_eq!(scheduler.next_run(reminder.id), Some(later));
+
+        let id = scheduler.add(0, Schedule::after(Duration::from_secs(60 * 60)), |_| {});
+        assert!(id != job && id != reminder.id);
+
+        scheduler.shutdown();
+    }
+}
@@ -0,0 +1,375 @@
+use model::{
+    channel::Channel,
+    event::Event,
+    id::{ChannelId, GuildId},
+};
+use parking_lot::{Mutex, RwLock};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::{Debug, Formatter, Result as FmtResult},
+    sync::Arc,
+};
+use super::{
+    dispatch::DispatchEvent,
+    handler_error::ErrorReporter,
+    Context,
+};
+use threadpool::ThreadPool;
+
+/// The order in which the tasks dispatching events to the [`EventHandler`]
+/// are run on the client's threadpool.
+///
+/// By default, the tasks of all events are run concurrently, so that events
+/// may be handled out of order. The other variants make events sharing a
+/// guild or channel be handled sequentially, in the order they were received,
+/// while those of different guilds or channels are still handled
+/// concurrently.
+///
+/// Events which neither belong to a guild nor a channel, such as [`Ready`],
+/// are always handled concurrently. The commands of a framework are not
+/// affected, and are run concurrently.
+///
+/// # Examples
+///
+/// Handle the events of each guild in the order they were received:
+///
+/// ```rust,no_run
+/// # use std::error::Error;
+/// #
+/// # fn try_main() -> Result<(), Box<Error>> {
+/// use serenity::client::{Client, DispatchOrder, EventHandler};
+/// use std::env;
+///
+/// struct Handler;
+///
+/// impl EventHandler for Handler { }
+///
+/// let mut client = Client::new(&env::var("DISCORD_TOKEN")?, Handler)?;
+///
+/// client.set_dispatch_order(DispatchOrder::Guild);
+///
+/// client.start()?;
+/// #     Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #     try_main().unwrap();
+/// # }
+/// ```
+///
+/// [`EventHandler`]: trait.EventHandler.html
+/// [`Ready`]: ../model/event/enum.Event.html#variant.Ready
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum DispatchOrder {
+    /// Events are handled concurrently.
+    ///
+    /// This is the default.
+    Concurrent,
+    /// Events of the same guild are handled sequentially.
+    ///
+    /// Some events, such as [`MessageDelete`], only contain the Id of the
+    /// channel they belong to. With the `cache` feature, their guild is
+    /// retrieved from the cache, and otherwise they are handled sequentially
+    /// with the events of the same channel instead.
+    ///
+    /// [`MessageDelete`]: ../model/event/enum.Event.html#variant.MessageDelete
+    Guild,
+    /// Events of the same channel are handled sequentially, as are events of
+    /// the same guild which do not belong to a channel.
+    Channel,
+}
+
+impl Default for DispatchOrder {
+    fn default() -> Self {
+        DispatchOrder::Concurrent
+    }
+}
+
+// The guild or channel whose events are handled sequentially.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub(crate) enum DispatchKey {
+    Channel(ChannelId),
+    Guild(GuildId),
+}
+
+trait Job: Send {
+    fn run(self: Box<Self>);
+}
+
+impl<F: FnOnce() + Send> Job for F {
+    fn run(self: Box<Self>) {
+        (*self)()
+    }
+}
+
+type Queues = Arc<Mutex<HashMap<DispatchKey, VecDeque<Box<Job>>>>>;
+
+// Runs the tasks of events sharing a key one after another on a threadpool.
+//
+// A key has a queue for as long as one of its tasks is running, and any tasks
+// received in the meantime are added to it. Once a task finished, the next one
+// of the queue is given to the threadpool.
+#[derive(Clone, Default)]
+pub(crate) struct Sequencer {
+    order: Arc<RwLock<DispatchOrder>>,
+    queues: Queues,
+}
+
+impl Sequencer {
+    pub fn order(&self) -> DispatchOrder {
+        *self.order.read()
+    }
+
+    pub fn set_order(&self, order: DispatchOrder) {
+        *self.order.write() = order;
+    }
+
+    // Determines the key of an event under the current order. This is done
+    // before the cache is updated with the event, as the event may remove
+    // the channel needed to determine its guild.
+    #[cfg_attr(not(feature = "cache"), allow(unused_variables))]
+    pub fn key(&self, event: &DispatchEvent, context: &Context) -> Option<DispatchKey> {
+        let (guild_id, channel_id) = match *event {
+            DispatchEvent::Model(ref event) => ids(event),
+            DispatchEvent::Client(_) => return None,
+        };
+
+        match self.order() {
+            DispatchOrder::Concurrent => None,
+            DispatchOrder::Guild => {
+                #[cfg(feature = "cache")]
+                let guild_id = guild_id.or_else(|| {
+                    channel_id.and_then(|channel_id| {
+                        context.cache.read().channels.get(&channel_id).map(|c| c.read().guild_id)
+                    })
+                });
+
+                guild_id.map(DispatchKey::Guild).or_else(|| channel_id.map(DispatchKey::Channel))
+            },
+            DispatchOrder::Channel => {
+                channel_id.map(DispatchKey::Channel).or_else(|| guild_id.map(DispatchKey::Guild))
+            },
+        }
+    }
+
+    pub fn execute<F>(&self, threadpool: &ThreadPool, key: Option<DispatchKey>, job: F)
+        where F: FnOnce() + Send + 'static {
+        let key = match key {
+            Some(key) => key,
+            None => return threadpool.execute(job),
+        };
+
+        {
+            let mut queues = self.queues.lock();
+
+            if let Some(queue) = queues.get_mut(&key) {
+                queue.push_back(Box::new(job));
+
+                return;
+            }
+
+            queues.insert(key, VecDeque::new());
+        }
+
+        run(key, Arc::clone(&self.queues), threadpool.clone(), Box::new(job));
+    }
+}
+
+impl Debug for Sequencer {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_struct("Sequencer")
+            .field("order", &self.order())
+            .field("queues", &self.queues.lock().len())
+            .finish()
+    }
+}
+
+// Gives a task to the threadpool, which then gives the next queued task of its
+// key to the threadpool once it finished.
+fn run(key: DispatchKey, queues: Queues, threadpool: ThreadPool, job: Box<Job>) {
+    let pool = threadpool.clone();
+
+    pool.execute(move || {
+        let _next = Next {
+            key,
+            queues,
+            threadpool,
+        };
+
+        job.run();
+    });
+}
+
+// Runs the next task of a key once the current one finished, which is done
+// on drop so that a panicking task does not stall the remaining ones.
+struct Next {
+    key: DispatchKey,
+    queues: Queues,
+    threadpool: ThreadPool,
+}
+
+impl Drop for Next {
+    fn drop(&mut self) {
+        let job = {
+            let mut queues = self.queues.lock();
+
+            let job = match queues.get_mut(&self.key) {
+                Some(queue) => queue.pop_front(),
+                None => None,
+            };
+
+            match job {
+                Some(job) => job,
+                None => {
+                    queues.remove(&self.key);
+
+                    return;
+                },
+            }
+        };
+
+        run(self.key, Arc::clone(&self.queues), self.threadpool.clone(), job);
+    }
+}
+
+// The executor used to dispatch a single event, which runs its tasks in the
+// order of the event's key, reporting the handler methods which panic.
+pub(crate) struct Executor<'a> {
+    pub error_reporter: &'a ErrorReporter,
+    pub key: Option<DispatchKey>,
+    pub sequencer: &'a Sequencer,
+    pub shard_id: u64,
+    pub threadpool: &'a ThreadPool,
+}
+
+impl<'a> Executor<'a> {
+    // Runs a call to the given handler method on the threadpool.
+    pub fn execute<F: FnOnce() + Send + 'static>(&self, event: &'static str, job: F) {
+        let error_reporter = self.error_reporter.clone();
+        let shard_id = self.shard_id;
+
+        self.sequencer.execute(self.threadpool, self.key, move || {
+            error_reporter.catch(event, shard_id, job);
+        });
+    }
+
+    // Runs a call to the given handler method on the current thread.
+    pub fn call<F: FnOnce()>(&self, event: &'static str, job: F) {
+        self.error_reporter.catch(event, self.shard_id, job);
+    }
+}
+
+// Retrieves the Ids of the guild and channel an event belongs to, if any.
+fn ids(event: &Event) -> (Option<GuildId>, Option<ChannelId>) {
+    match *event {
+        Event::ChannelCreate(ref e) => channel_ids(&e.channel),
+        Event::ChannelDelete(ref e) => channel_ids(&e.channel),
+        Event::ChannelUpdate(ref e) => channel_ids(&e.channel),
+        Event::ChannelPinsUpdate(ref e) => (None, Some(e.channel_id)),
+        Event::ChannelRecipientAdd(ref e) => (None, Some(e.channel_id)),
+        Event::ChannelRecipientRemove(ref e) => (None, Some(e.channel_id)),
+        Event::GuildBanAdd(ref e) => (Some(e.guild_id), None),
+        Event::GuildBanRemove(ref e) => (Some(e.guild_id), None),
+        Event::GuildCreate(ref e) => (Some(e.guild.id), None),
+        Event::GuildDelete(ref e) => (Some(e.guild.id), None),
+        Event::GuildEmojisUpdate(ref e) => (Some(e.guild_id), None),
+        Event::GuildIntegrationsUpdate(ref e) => (Some(e.guild_id), None),
+        Event::GuildMemberAdd(ref e) => (Some(e.guild_id), None),
+        Event::GuildMemberRemove(ref e) => (Some(e.guild_id), None),
+        Event::GuildMemberUpdate(ref e) => (Some(e.guild_id), None),
+        Event::GuildMembersChunk(ref e) => (Some(e.guild_id), None),
+        Event::GuildRoleCreate(ref e) => (Some(e.guild_id), None),
+        Event::GuildRoleDelete(ref e) => (Some(e.guild_id), None),
+        Event::GuildRoleUpdate(ref e) => (Some(e.guild_id), None),
+        Event::GuildUnavailable(ref e) => (Some(e.guild_id), None),
+        Event::GuildUpdate(ref e) => (Some(e.guild.id), None),
+        Event::MessageCreate(ref e) => (e.message.guild_id, Some(e.message.channel_id)),
+        Event::MessageDelete(ref e) => (None, Some(e.channel_id)),
+        Event::MessageDeleteBulk(ref e) => (None, Some(e.channel_id)),
+        Event::MessageUpdate(ref e) => (None, Some(e.channel_id)),
+        Event::PresenceUpdate(ref e) => (e.guild_id, None),
+        Event::ReactionAdd(ref e) => (None, Some(e.reaction.channel_id)),
+        Event::ReactionRemove(ref e) => (None, Some(e.reaction.channel_id)),
+        Event::ReactionRemoveAll(ref e) => (None, Some(e.channel_id)),
+        Event::TypingStart(ref e) => (None, Some(e.channel_id)),
+        Event::VoiceStateUpdate(ref e) => (e.guild_id, None),
+        Event::VoiceServerUpdate(ref e) => (e.guild_id, e.channel_id),
+        Event::WebhookUpdate(ref e) => (Some(e.guild_id), Some(e.channel_id)),
+        Event::PresencesReplace(_)
+        | Event::Ready(_)
+        | Event::Resumed(_)
+        | Event::UserUpdate(_)
+        | Event::Unknown(_) => (None, None),
+    }
+}
+
+fn channel_ids(channel: &Channel) -> (Option<GuildId>, Option<ChannelId>) {
+    match *channel {
+        Channel::Group(ref group) => (None, Some(group.read().channel_id)),
+        Channel::Guild(ref channel) => {
+            let channel = channel.read();
+
+            (Some(channel.guild_id), Some(channel.id))
+        },
+        Channel::Private(ref channel) => (None, Some(channel.read().id)),
+        Channel::Category(ref category) => (None, Some(category.read().id)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use model::id::GuildId;
+    use parking_lot::Mutex;
+    use std::{
+        sync::{mpsc, Arc},
+        thread,
+        time::Duration,
+    };
+    use super::{DispatchKey, Sequencer};
+    use threadpool::ThreadPool;
+
+    #[test]
+    fn test_sequencer_order() {
+        let sequencer = Sequencer::default();
+        let threadpool = ThreadPool::new(4);
+        let handled = Arc::new(Mutex::new(Vec::new()));
+        let key = Some(DispatchKey::Guild(GuildId(1)));
+
+        for i in 0..20 {
+            let handled = Arc::clone(&handled);
+
+            sequencer.execute(&threadpool, key, move || {
+                // Earlier tasks take longer, so that they would finish last if
+                // they were run concurrently.
+                thread::sleep(Duration::from_millis(20 - i));
+
+                handled.lock().push(i);
+            });
+        }
+
+        let (tx, rx) = mpsc::channel();
+
+        sequencer.execute(&threadpool, key, move || {
+            tx.send(()).unwrap();
+        });
+
+        rx.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        assert_eq!(*handled.lock(), (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_sequencer_panic() {
+        let sequencer = Sequencer::default();
+        let threadpool = ThreadPool::new(2);
+        let key = Some(DispatchKey::Guild(GuildId(1)));
+        let (tx, rx) = mpsc::channel();
+
+        sequencer.execute(&threadpool, key, || panic!("handler panicked"));
+        sequencer.execute(&threadpool, key, move || {
+            tx.send(()).unwrap();
+        });
+
+        assert!(rx.recv_timeout(Duration::from_secs(5)).is_ok());
+    }
+}
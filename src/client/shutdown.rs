@@ -0,0 +1,192 @@
+use parking_lot::Mutex;
+use std::{
+    sync::{Arc, Weak},
+    thread,
+    time::{Duration, Instant}
+};
+use super::bridge::gateway::ShardManager;
+use threadpool::ThreadPool;
+
+/// The number of milliseconds to wait between checks of whether every event
+/// handler has finished.
+const DRAIN_POLL_INTERVAL_IN_MILLIS: u64 = 50;
+
+/// A handle to gracefully stop a [`Client`] from outside of it, for example
+/// when the process receives a signal.
+///
+/// This is cheap to clone, and is retrieved via [`Client::shutdown_handle`].
+///
+/// [`Client`]: struct.Client.html
+/// [`Client::shutdown_handle`]: struct.Client.html#method.shutdown_handle
+#[derive(Clone, Debug)]
+pub struct ShutdownHandle {
+    deadline: Arc<Mutex<Option<Instant>>>,
+    manager: Weak<Mutex<ShardManager>>,
+}
+
+impl ShutdownHandle {
+    pub(crate) fn new(manager: &Arc<Mutex<ShardManager>>) -> Self {
+        Self {
+            deadline: Arc::new(Mutex::new(None)),
+            manager: Arc::downgrade(manager),
+        }
+    }
+
+    /// Gracefully shuts down the client.
+    ///
+    /// This does not block. The client stops dispatching new events and
+    /// starting shards, and closes every shard with a normal close code.
+    /// [`Client::start`] - or whichever start method was used - then waits up
+    /// to `timeout` for event handlers and framework commands that are
+    /// already running or queued to finish, before returning `Ok`.
+    ///
+    /// Only the timeout of the first call is used.
+    ///
+    /// # Examples
+    ///
+    /// Stop the client from another thread after an hour:
+    ///
+    /// ```rust,no_run
+    /// # use std::error::Error;
+    /// #
+    /// # fn try_main() -> Result<(), Box<Error>> {
+    /// use serenity::client::{Client, EventHandler};
+    /// use std::{env, thread};
+    /// use std::time::Duration;
+    ///
+    /// struct Handler;
+    ///
+    /// impl EventHandler for Handler { }
+    ///
+    /// let mut client = Client::new(&env::var("DISCORD_TOKEN")?, Handler)?;
+    /// let handle = client.shutdown_handle();
+    ///
+    /// thread::spawn(move || {
+    ///     thread::sleep(Duration::from_secs(60 * 60));
+    ///
+    ///     handle.shutdown(Duration::from_secs(10));
+    /// });
+    ///
+    /// client.start()?;
+    /// #     Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #     try_main().unwrap();
+    /// # }
+    /// ```
+    ///
+    /// [`Client::start`]: struct.Client.html#method.start
+    pub fn shutdown(&self, timeout: Duration) {
+        {
+            let mut deadline = self.deadline.lock();
+
+            if deadline.is_none() {
+                *deadline = Some(Instant::now() + timeout);
+            }
+        }
+
+        if let Some(manager) = self.manager.upgrade() {
+            manager.lock().begin_shutdown();
+        }
+    }
+
+    /// Whether a shutdown has been requested via [`shutdown`].
+    ///
+    /// [`shutdown`]: #method.shutdown
+    pub fn is_shutting_down(&self) -> bool {
+        self.deadline.lock().is_some()
+    }
+
+    // Blocks until every job of the threadpool has finished, or until the
+    // deadline has passed. Does nothing if no shutdown was requested.
+    pub(crate) fn drain(&self, threadpool: &ThreadPool) {
+        let deadline = match *self.deadline.lock() {
+            Some(deadline) => deadline,
+            None => return,
+        };
+
+        let interval = Duration::from_millis(DRAIN_POLL_INTERVAL_IN_MILLIS);
+
+        loop {
+            let remaining = threadpool.active_count() + threadpool.queued_count();
+
+            if remaining == 0 {
+                info!("All event handlers finished");
+
+                return;
+            }
+
+            let now = Instant::now();
+
+            if now >= deadline {
+                warn!(
+                    "Shutdown deadline passed with {} event handlers unfinished",
+                    remaining,
+                );
+
+                return;
+            }
+
+            thread::sleep(interval.min(deadline - now));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::Weak,
+        thread,
+        time::{Duration, Instant}
+    };
+    use super::*;
+
+    fn handle() -> ShutdownHandle {
+        ShutdownHandle {
+            deadline: Arc::new(Mutex::new(None)),
+            manager: Weak::new(),
+        }
+    }
+
+    #[test]
+    fn test_drain_without_shutdown() {
+        let threadpool = ThreadPool::new(1);
+        threadpool.execute(|| thread::sleep(Duration::from_secs(5)));
+
+        let start = Instant::now();
+        handle().drain(&threadpool);
+
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_drain_waits_for_handlers() {
+        let threadpool = ThreadPool::new(1);
+        let handle = handle();
+
+        for _ in 0..2 {
+            threadpool.execute(|| thread::sleep(Duration::from_millis(100)));
+        }
+
+        handle.shutdown(Duration::from_secs(5));
+        assert!(handle.is_shutting_down());
+
+        handle.drain(&threadpool);
+
+        assert_eq!(threadpool.active_count() + threadpool.queued_count(), 0);
+    }
+
+    #[test]
+    fn test_drain_deadline() {
+        let threadpool = ThreadPool::new(1);
+        let handle = handle();
+        threadpool.execute(|| thread::sleep(Duration::from_secs(5)));
+
+        let start = Instant::now();
+        handle.shutdown(Duration::from_millis(100));
+        handle.drain(&threadpool);
+
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+}
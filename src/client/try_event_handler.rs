@@ -0,0 +1,182 @@
+use model::prelude::*;
+use parking_lot::RwLock;
+use serde_json::Value;
+use std::{collections::HashMap, sync::Arc};
+use super::{
+    bridge::gateway::event::*,
+    Context,
+    EventHandler,
+    HandlerResult
+};
+
+// Defines the methods of `TryEventHandler`, which mirror those of
+// `EventHandler` but return a result, and implements `EventHandler` for
+// `Fallible` by reporting the errors of the wrapped handler.
+macro_rules! fallible {
+    ($($(#[$attr:meta])* fn $name:ident(&self, ctx: Context $(, $arg:ident: $kind:ty)*);)*) => {
+        /// An event handler whose methods return a result, with errors being
+        /// given to the [`ErrorHook`].
+        ///
+        /// The methods mirror those of the [`EventHandler`], and are
+        /// dispatched with the same arguments. To be given to the [`Client`],
+        /// a handler is wrapped in [`Fallible`].
+        ///
+        /// # Examples
+        ///
+        /// ```rust,no_run
+        /// # use std::error::Error;
+        /// #
+        /// # fn try_main() -> Result<(), Box<Error>> {
+        /// use serenity::client::{Client, Context, Fallible, HandlerResult, TryEventHandler};
+        /// use serenity::model::channel::Message;
+        /// use std::env;
+        ///
+        /// struct Handler;
+        ///
+        /// impl TryEventHandler for Handler {
+        ///     fn message(&self, _: Context, msg: Message) -> HandlerResult {
+        ///         if msg.content == "!ping" {
+        ///             msg.channel_id.say("Pong!")?;
+        ///         }
+        ///
+        ///         Ok(())
+        ///     }
+        /// }
+        ///
+        /// let mut client = Client::new(&env::var("DISCORD_TOKEN")?, Fallible(Handler))?;
+        ///
+        /// client.start()?;
+        /// #     Ok(())
+        /// # }
+        /// #
+        /// # fn main() {
+        /// #     try_main().unwrap();
+        /// # }
+        /// ```
+        ///
+        /// [`Client`]: struct.Client.html
+        /// [`ErrorHook`]: trait.ErrorHook.html
+        /// [`EventHandler`]: trait.EventHandler.html
+        /// [`Fallible`]: struct.Fallible.html
+        pub trait TryEventHandler {
+            $(
+                $(#[$attr])*
+                fn $name(&self, _: Context $(, _: $kind)*) -> HandlerResult {
+                    Ok(())
+                }
+            )*
+        }
+
+        impl<H: TryEventHandler> EventHandler for Fallible<H> {
+            $(
+                $(#[$attr])*
+                fn $name(&self, ctx: Context $(, $arg: $kind)*) {
+                    if let Err(why) = self.0.$name(ctx.clone() $(, $arg)*) {
+                        ctx.report_error(stringify!($name), why);
+                    }
+                }
+            )*
+        }
+    };
+}
+
+/// Wraps a [`TryEventHandler`] to be used as the [`EventHandler`] of a
+/// [`Client`], reporting the errors it returns to the [`ErrorHook`].
+///
+/// [`Client`]: struct.Client.html
+/// [`ErrorHook`]: trait.ErrorHook.html
+/// [`EventHandler`]: trait.EventHandler.html
+/// [`TryEventHandler`]: trait.TryEventHandler.html
+#[derive(Clone, Debug)]
+pub struct Fallible<H>(pub H);
+
+fallible! {
+    #[cfg(feature = "cache")]
+    fn cached(&self, ctx: Context, guilds: Vec<GuildId>);
+    fn channel_create(&self, ctx: Context, channel: Arc<RwLock<GuildChannel>>);
+    fn category_create(&self, ctx: Context, category: Arc<RwLock<ChannelCategory>>);
+    fn category_delete(&self, ctx: Context, category: Arc<RwLock<ChannelCategory>>);
+    fn private_channel_create(&self, ctx: Context, channel: Arc<RwLock<PrivateChannel>>);
+    fn channel_delete(&self, ctx: Context, channel: Arc<RwLock<GuildChannel>>);
+    fn channel_pins_update(&self, ctx: Context, pin: ChannelPinsUpdateEvent);
+    fn channel_recipient_addition(&self, ctx: Context, group_id: ChannelId, user: User);
+    fn channel_recipient_removal(&self, ctx: Context, group_id: ChannelId, user: User);
+    #[cfg(feature = "cache")]
+    fn channel_update(&self, ctx: Context, old: Option<Channel>, new: Channel);
+    #[cfg(not(feature = "cache"))]
+    fn channel_update(&self, ctx: Context, new_data: Channel);
+    fn guild_ban_addition(&self, ctx: Context, guild_id: GuildId, banned_user: User);
+    fn guild_ban_removal(&self, ctx: Context, guild_id: GuildId, unbanned_user: User);
+    #[cfg(feature = "cache")]
+    fn guild_create(&self, ctx: Context, guild: Guild, is_new: bool);
+    #[cfg(not(feature = "cache"))]
+    fn guild_create(&self, ctx: Context, guild: Guild);
+    #[cfg(feature = "cache")]
+    fn guild_delete(&self, ctx: Context, incomplete: PartialGuild, full: Option<Arc<RwLock<Guild>>>);
+    #[cfg(not(feature = "cache"))]
+    fn guild_delete(&self, ctx: Context, incomplete: PartialGuild);
+    fn guild_emojis_update(&self, ctx: Context, guild_id: GuildId, current_state: HashMap<EmojiId, Emoji>);
+    fn guild_integrations_update(&self, ctx: Context, guild_id: GuildId);
+    fn guild_member_addition(&self, ctx: Context, guild_id: GuildId, new_member: Member);
+    #[cfg(feature = "cache")]
+    fn guild_member_removal(&self, ctx: Context, guild: GuildId, user: User, member_data_if_available: Option<Member>);
+    #[cfg(not(feature = "cache"))]
+    fn guild_member_removal(&self, ctx: Context, guild_id: GuildId, kicked: User);
+    #[cfg(feature = "cache")]
+    fn guild_member_update(&self, ctx: Context, old_if_available: Option<Member>, new: Member);
+    #[cfg(not(feature = "cache"))]
+    fn guild_member_update(&self, ctx: Context, new: GuildMemberUpdateEvent);
+    fn guild_members_chunk(&self, ctx: Context, guild_id: GuildId, offline_members: HashMap<UserId, Member>);
+    fn guild_role_create(&self, ctx: Context, guild_id: GuildId, new: Role);
+    #[cfg(feature = "cache")]
+    fn guild_role_delete(&self, ctx: Context, guild_id: GuildId, removed_role_id: RoleId, removed_role_data_if_available: Option<Role>);
+    #[cfg(not(feature = "cache"))]
+    fn guild_role_delete(&self, ctx: Context, guild_id: GuildId, removed_role_id: RoleId);
+    #[cfg(feature = "cache")]
+    fn guild_role_update(&self, ctx: Context, guild_id: GuildId, old_data_if_available: Option<Role>, new: Role);
+    #[cfg(not(feature = "cache"))]
+    fn guild_role_update(&self, ctx: Context, guild_id: GuildId, new_data: Role);
+    fn guild_unavailable(&self, ctx: Context, guild_id: GuildId);
+    #[cfg(feature = "cache")]
+    fn guild_update(&self, ctx: Context, old_data_if_available: Option<Arc<RwLock<Guild>>>, new_but_incomplete: PartialGuild);
+    #[cfg(not(feature = "cache"))]
+    fn guild_update(&self, ctx: Context, new_but_incomplete_data: PartialGuild);
+    fn message(&self, ctx: Context, new_message: Message);
+    #[cfg(feature = "cache")]
+    fn message_delete(&self, ctx: Context, channel_id: ChannelId, deleted_message_id: MessageId, deleted_if_available: Option<Message>);
+    #[cfg(not(feature = "cache"))]
+    fn message_delete(&self, ctx: Context, channel_id: ChannelId, deleted_message_id: MessageId);
+    #[cfg(feature = "cache")]
+    fn message_delete_bulk(&self, ctx: Context, channel_id: ChannelId, multiple_deleted_messages_ids: Vec<MessageId>, deleted_if_available: Vec<Message>);
+    #[cfg(not(feature = "cache"))]
+    fn message_delete_bulk(&self, ctx: Context, channel_id: ChannelId, multiple_deleted_messages_ids: Vec<MessageId>);
+    fn reaction_add(&self, ctx: Context, add_reaction: Reaction);
+    fn reaction_remove(&self, ctx: Context, removed_reaction: Reaction);
+    fn reaction_remove_all(&self, ctx: Context, channel_id: ChannelId, removed_from_message_id: MessageId);
+    #[cfg(feature = "cache")]
+    fn message_update(&self, ctx: Context, old_if_available: Option<Message>, new: Option<Message>, event: MessageUpdateEvent);
+    #[cfg(not(feature = "cache"))]
+    fn message_update(&self, ctx: Context, new_data: MessageUpdateEvent);
+    fn presence_replace(&self, ctx: Context, presences: Vec<Presence>);
+    fn presence_update(&self, ctx: Context, new_data: PresenceUpdateEvent);
+    fn ready(&self, ctx: Context, data_about_bot: Ready);
+    fn resume(&self, ctx: Context, event: ResumedEvent);
+    fn shard_stage_update(&self, ctx: Context, event: ShardStageUpdateEvent);
+    fn typing_start(&self, ctx: Context, event: TypingStartEvent);
+    fn unknown(&self, ctx: Context, name: String, raw: Value);
+    #[cfg(feature = "cache")]
+    fn user_update(&self, ctx: Context, old_data: CurrentUser, new: CurrentUser);
+    #[cfg(not(feature = "cache"))]
+    fn user_update(&self, ctx: Context, new_data: CurrentUser);
+    fn voice_server_update(&self, ctx: Context, event: VoiceServerUpdateEvent);
+    fn voice_state_update(&self, ctx: Context, guild_id: Option<GuildId>, state: VoiceState);
+    #[cfg(feature = "cache")]
+    fn voice_channel_join(&self, ctx: Context, guild_id: GuildId, state: VoiceState);
+    #[cfg(feature = "cache")]
+    fn voice_channel_leave(&self, ctx: Context, guild_id: GuildId, left_channel_id: ChannelId, state: VoiceState);
+    #[cfg(feature = "cache")]
+    fn voice_channel_move(&self, ctx: Context, guild_id: GuildId, old_channel_id: ChannelId, state: VoiceState);
+    #[cfg(feature = "cache")]
+    fn voice_state_change(&self, ctx: Context, guild_id: GuildId, change: VoiceStateChange, state: VoiceState);
+    fn webhook_update(&self, ctx: Context, guild_id: GuildId, belongs_to_channel_id: ChannelId);
+}
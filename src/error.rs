@@ -20,8 +20,12 @@ use native_tls::Error as TlsError;
 use opus::Error as OpusError;
 #[cfg(feature = "websocket")]
 use websocket::result::WebSocketError;
+#[cfg(feature = "cache")]
+use cache::CacheError;
 #[cfg(feature = "client")]
 use client::ClientError;
+#[cfg(all(feature = "cluster", unix))]
+use client::bridge::cluster::ClusterError;
 #[cfg(feature = "gateway")]
 use gateway::GatewayError;
 #[cfg(feature = "http")]
@@ -81,11 +85,21 @@ pub enum Error {
     Other(&'static str),
     /// An error from the `url` crate.
     Url(String),
+    /// A [cache] error.
+    ///
+    /// [cache]: cache/index.html
+    #[cfg(feature = "cache")]
+    Cache(CacheError),
     /// A [client] error.
     ///
     /// [client]: client/index.html
     #[cfg(feature = "client")]
     Client(ClientError),
+    /// An error from the [cluster] bridge.
+    ///
+    /// [cluster]: client/bridge/cluster/index.html
+    #[cfg(all(feature = "cluster", unix))]
+    Cluster(ClusterError),
     /// An error from the `gateway` module.
     #[cfg(feature = "gateway")]
     Gateway(GatewayError),
@@ -113,6 +127,11 @@ pub enum Error {
     Voice(VoiceError),
 }
 
+#[cfg(feature = "cache")]
+impl From<CacheError> for Error {
+    fn from(e: CacheError) -> Error { Error::Cache(e) }
+}
+
 impl From<FormatError> for Error {
     fn from(e: FormatError) -> Error { Error::Format(e) }
 }
@@ -175,8 +194,12 @@ impl StdError for Error {
             Error::Model(ref inner) => inner.description(),
             Error::Num(ref inner) => inner.description(),
             Error::Url(ref inner) => inner,
+            #[cfg(feature = "cache")]
+            Error::Cache(ref inner) => inner.description(),
             #[cfg(feature = "client")]
             Error::Client(ref inner) => inner.description(),
+            #[cfg(all(feature = "cluster", unix))]
+            Error::Cluster(ref inner) => inner.description(),
             #[cfg(feature = "gateway")]
             Error::Gateway(ref inner) => inner.description(),
             #[cfg(feature = "http")]
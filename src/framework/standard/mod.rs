@@ -31,7 +31,7 @@ pub use self::create_help_command::CreateHelpCommand;
 pub use self::create_command::{CreateCommand, FnOrCommand};
 pub use self::create_group::CreateGroup;
 
-use client::Context;
+use client::{Context, ErrorReporter};
 use internal::RwLockExt;
 use model::{
     channel::Message,
@@ -49,9 +49,11 @@ use super::Framework;
 use threadpool::ThreadPool;
 
 #[cfg(feature = "cache")]
-use client::CACHE;
+use cache::Cache;
 #[cfg(feature = "cache")]
 use model::channel::Channel;
+#[cfg(feature = "cache")]
+use parking_lot::RwLock;
 
 /// A convenience macro for generating a struct fulfilling the [`Command`][command trait] trait.
 ///
@@ -65,8 +67,8 @@ use model::channel::Channel;
 /// Create a regular `ping` command which takes no arguments:
 ///
 /// ```rust,ignore
-/// command!(ping(_context, message) {
-///     if let Err(why) = message.reply("Pong!") {
+/// command!(ping(context, message) {
+///     if let Err(why) = message.reply(context, "Pong!") {
 ///         println!("Error sending pong: {:?}", why);
 ///     }
 /// });
@@ -76,12 +78,12 @@ use model::channel::Channel;
 /// them, sending the product as a reply:
 ///
 /// ```rust,ignore
-/// command!(multiply(_context, message, args) {
+/// command!(multiply(context, message, args) {
 ///     let first = args.single::<f64>().unwrap();
 ///     let second = args.single::<f64>().unwrap();
 ///     let product = first * second;
 ///
-///     if let Err(why) = message.reply(&product.to_string()) {
+///     if let Err(why) = message.reply(context, &product.to_string()) {
 ///         println!("Error sending product: {:?}", why);
 ///     }
 /// });
@@ -473,14 +475,14 @@ impl StandardFramework {
     }
 
     #[cfg(feature = "cache")]
-    fn is_blocked_guild(&self, message: &Message) -> bool {
-        if let Some(Channel::Guild(channel)) = CACHE.read().channel(message.channel_id) {
+    fn is_blocked_guild(&self, cache: &RwLock<Cache>, message: &Message) -> bool {
+        if let Some(Channel::Guild(channel)) = cache.read().channel(message.channel_id) {
             let guild_id = channel.with(|g| g.guild_id);
             if self.configuration.blocked_guilds.contains(&guild_id) {
                 return true;
             }
 
-            if let Some(guild) = guild_id.to_guild_cached() {
+            if let Some(guild) = guild_id.to_guild_cached(cache) {
                 return self.configuration
                     .blocked_users
                     .contains(&guild.with(|g| g.owner_id));
@@ -570,7 +572,7 @@ impl StandardFramework {
 
             #[cfg(feature = "cache")]
             {
-                if self.is_blocked_guild(message) {
+                if self.is_blocked_guild(&context.cache, message) {
                     return Some(DispatchError::BlockedGuild);
                 }
 
@@ -578,7 +580,7 @@ impl StandardFramework {
                     return Some(DispatchError::BlockedChannel);
                 }
 
-                if !has_correct_permissions(command, message) {
+                if !has_correct_permissions(&context.cache, command, message) {
                     return Some(DispatchError::LackOfPermissions(
                         command.required_permissions,
                     ));
@@ -608,11 +610,11 @@ impl StandardFramework {
 
                 #[cfg(feature = "cache")] {
                     if !command.allowed_roles.is_empty() {
-                        if let Some(guild) = message.guild() {
+                        if let Some(guild) = message.guild(&context.cache) {
                             let guild = guild.read();
 
                             if let Some(member) = guild.members.get(&message.author.id) {
-                                if let Ok(permissions) = member.permissions() {
+                                if let Ok(permissions) = member.permissions(&context.cache) {
 
                                     if !permissions.administrator()
                                         && !has_correct_roles(command, &guild, member) {
@@ -890,8 +892,8 @@ impl StandardFramework {
     /// use serenity::framework::StandardFramework;
     ///
     /// client.with_framework(StandardFramework::new()
-    ///     .before(|_, msg, cmd_name| {
-    ///         if let Ok(channel) = msg.channel_id.to_channel() {
+    ///     .before(|ctx, msg, cmd_name| {
+    ///         if let Ok(channel) = msg.channel_id.to_channel(ctx) {
     ///             //  Don't run unless in nsfw channel
     ///             if !channel.is_nsfw() {
     ///                 return false;
@@ -1018,6 +1020,15 @@ impl StandardFramework {
     }
 }
 
+// Runs a job of a dispatched message on the threadpool, reporting it to the
+// client's error hook if it panics.
+fn execute<F>(threadpool: &ThreadPool, error_reporter: &ErrorReporter, shard_id: u64, job: F)
+    where F: FnOnce() + Send + 'static {
+    let error_reporter = error_reporter.clone();
+
+    threadpool.execute(move || error_reporter.catch("framework", shard_id, job));
+}
+
 fn skip_chars_and_trim_to_new_string(str_to_transform_to_chars: &str, chars_to_skip: usize) -> String {
     let mut chars = str_to_transform_to_chars.chars();
 
@@ -1035,6 +1046,8 @@ impl Framework for StandardFramework {
         message: Message,
         threadpool: &ThreadPool,
     ) {
+        let error_reporter = context.error_reporter.clone();
+        let shard_id = context.shard_id;
         let res = command::positions(&mut context, &message, &self.configuration);
         let mut unrecognised_command_name = String::from("");
 
@@ -1054,7 +1067,7 @@ impl Framework for StandardFramework {
                         let before = self.before.clone();
                         let after = self.after.clone();
 
-                        threadpool.execute(move || {
+                        execute(threadpool, &error_reporter, shard_id, move || {
                             if let Some(before) = before {
                                 if !(before)(&mut context, &message, "") {
                                     return;
@@ -1087,7 +1100,7 @@ impl Framework for StandardFramework {
 
                     if !(self.configuration.ignore_bots && message.author.bot) {
                         let message_without_command = message_without_command.clone();
-                        threadpool.execute(move || {
+                        execute(threadpool, &error_reporter, shard_id, move || {
                             (message_without_command)(&mut context, &message);
                         });
                     }
@@ -1179,7 +1192,7 @@ impl Framework for StandardFramework {
                             let groups = self.groups.clone();
                             let mut args = command_and_help_args!(&message.content, position, command_length, &self.configuration.delimiters);
 
-                            threadpool.execute(move || {
+                            execute(threadpool, &error_reporter, shard_id, move || {
 
                                 if let Some(before) = before {
 
@@ -1220,7 +1233,7 @@ impl Framework for StandardFramework {
                                 return;
                             }
 
-                            threadpool.execute(move || {
+                            execute(threadpool, &error_reporter, shard_id, move || {
                                 if let Some(before) = before {
                                     if !(before)(&mut context, &message, &built) {
                                         return;
@@ -1268,7 +1281,7 @@ impl Framework for StandardFramework {
                                 return;
                             }
 
-                            threadpool.execute(move || {
+                            execute(threadpool, &error_reporter, shard_id, move || {
                                 if let Some(before) = before {
                                     if !(before)(&mut context, &message, &args.full()) {
                                         return;
@@ -1306,24 +1319,24 @@ impl Framework for StandardFramework {
                     let message_unrecognised = message.clone();
 
                     let unrecognised_command = unrecognised_command.clone();
-                    threadpool.execute(move || {
+                    execute(threadpool, &error_reporter, shard_id, move || {
                         (unrecognised_command)(&mut context_unrecognised, &message_unrecognised,
                         &unrecognised_command_name);
                     });
 
                     let message_without_command = message_without_command.clone();
-                        threadpool.execute(move || {
+                        execute(threadpool, &error_reporter, shard_id, move || {
                             (message_without_command)(&mut context, &message);
                     });
                 } else {
                     let unrecognised_command = unrecognised_command.clone();
-                    threadpool.execute(move || {
+                    execute(threadpool, &error_reporter, shard_id, move || {
                         (unrecognised_command)(&mut context, &message, &unrecognised_command_name);
                     });
                 }
             } else if let &Some(ref message_without_command) = &self.message_without_command {
                 let message_without_command = message_without_command.clone();
-                    threadpool.execute(move || {
+                    execute(threadpool, &error_reporter, shard_id, move || {
                         (message_without_command)(&mut context, &message);
                 });
             }
@@ -1336,11 +1349,15 @@ impl Framework for StandardFramework {
 }
 
 #[cfg(feature = "cache")]
-pub fn has_correct_permissions(command: &Arc<CommandOptions>, message: &Message) -> bool {
+pub fn has_correct_permissions(
+    cache: &RwLock<Cache>,
+    command: &Arc<CommandOptions>,
+    message: &Message,
+) -> bool {
     if command.required_permissions.is_empty() {
         true
     } else {
-        if let Some(guild) = message.guild() {
+        if let Some(guild) = message.guild(cache) {
             let perms = guild
                 .with(|g| g.permissions_in(message.channel_id, message.author.id));
 
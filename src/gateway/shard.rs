@@ -63,6 +63,8 @@ use websocket::{
 /// [module docs]: index.html#sharding
 pub struct Shard {
     pub client: WsClient,
+    // Every activity of the presence, the first of which is also its game.
+    activities: Vec<Game>,
     current_presence: CurrentPresence,
     /// A tuple of:
     ///
@@ -150,6 +152,7 @@ impl Shard {
 
         Ok(Shard {
             shutdown: false,
+            activities: Vec::new(),
             client,
             current_presence,
             heartbeat_instants,
@@ -170,6 +173,12 @@ impl Shard {
     pub fn current_presence(&self) -> &CurrentPresence {
         &self.current_presence
     }
+
+    /// Retrieves every activity of the shard's current presence.
+    #[inline]
+    pub fn activities(&self) -> &[Game] {
+        &self.activities
+    }
     /// Whether the shard has permanently shutdown.
     ///
     /// This should normally happen due to manual calling of [`shutdown`] or
@@ -282,9 +291,22 @@ impl Shard {
     /// ```
     #[inline]
     pub fn set_game(&mut self, game: Option<Game>) {
+        self.activities = game.iter().cloned().collect();
         self.current_presence.0 = game;
     }
 
+    /// Sets the activities of the shard's presence, replacing its game with
+    /// the first of them.
+    ///
+    /// Note that this does not send the presence to the gateway. Use
+    /// [`update_presence`] for that.
+    ///
+    /// [`update_presence`]: #method.update_presence
+    pub fn set_activities(&mut self, activities: Vec<Game>) {
+        self.current_presence.0 = activities.first().cloned();
+        self.activities = activities;
+    }
+
     #[inline]
     pub fn set_presence(&mut self, status: OnlineStatus, game: Option<Game>) {
         self.set_game(game);
@@ -828,9 +850,10 @@ impl Shard {
     }
 
     pub fn update_presence(&mut self) -> Result<()> {
-        self.client.send_presence_update(
+        self.client.send_activities_update(
             &self.shard_info,
-            &self.current_presence,
+            &self.activities,
+            self.current_presence.1,
         )
     }
 }
@@ -3,8 +3,12 @@ use constants::{self, OpCode};
 use gateway::{CurrentPresence, WsClient};
 use internal::prelude::*;
 use internal::ws_impl::SenderExt;
-use model::id::GuildId;
-use std::env::consts;
+use model::{
+    gateway::Game,
+    id::GuildId,
+    user::OnlineStatus
+};
+use std::{env::consts, slice};
 
 pub trait WebSocketGatewayClientExt {
     fn send_chunk_guilds<It>(
@@ -15,6 +19,13 @@ pub trait WebSocketGatewayClientExt {
         query: Option<&str>,
     ) -> Result<()> where It: IntoIterator<Item=GuildId>;
 
+    fn send_activities_update(
+        &mut self,
+        shard_info: &[u64; 2],
+        activities: &[Game],
+        status: OnlineStatus,
+    ) -> Result<()>;
+
     fn send_heartbeat(&mut self, shard_info: &[u64; 2], seq: Option<u64>)
         -> Result<()>;
 
@@ -56,6 +67,28 @@ impl WebSocketGatewayClientExt for WsClient {
         })).map_err(From::from)
     }
 
+    fn send_activities_update(
+        &mut self,
+        shard_info: &[u64; 2],
+        activities: &[Game],
+        status: OnlineStatus,
+    ) -> Result<()> {
+        let now = Utc::now().timestamp() as u64;
+
+        debug!("[Shard {:?}] Sending presence update", shard_info);
+
+        self.send_json(&json!({
+            "op": OpCode::StatusUpdate.num(),
+            "d": {
+                "activities": activities,
+                "afk": false,
+                "since": now,
+                "status": status.name(),
+                "game": activities.first(),
+            },
+        }))
+    }
+
     fn send_heartbeat(&mut self, shard_info: &[u64; 2], seq: Option<u64>)
         -> Result<()> {
         trace!("[Shard {:?}] Sending heartbeat d: {:?}", shard_info, seq);
@@ -92,24 +125,13 @@ impl WebSocketGatewayClientExt for WsClient {
         shard_info: &[u64; 2],
         current_presence: &CurrentPresence,
     ) -> Result<()> {
-        let &(ref game, ref status) = current_presence;
-        let now = Utc::now().timestamp() as u64;
-
-        debug!("[Shard {:?}] Sending presence update", shard_info);
+        let &(ref game, status) = current_presence;
+        let activities = match *game {
+            Some(ref game) => slice::from_ref(game),
+            None => &[],
+        };
 
-        self.send_json(&json!({
-            "op": OpCode::StatusUpdate.num(),
-            "d": {
-                "afk": false,
-                "since": now,
-                "status": status.name(),
-                "game": game.as_ref().map(|x| json!({
-                    "name": x.name,
-                    "type": x.kind,
-                    "url": x.url,
-                })),
-            },
-        }))
+        self.send_activities_update(shard_info, activities, status)
     }
 
     fn send_resume(
@@ -1091,6 +1091,25 @@ pub fn get_guild_vanity_url(guild_id: u64) -> Result<String> {
         .map_err(From::from)
 }
 
+/// Gets the number of times a guild's vanity URL was used, if it has one.
+pub fn get_guild_vanity_url_uses(guild_id: u64) -> Result<u64> {
+    #[derive(Deserialize)]
+    struct GuildVanityUrl {
+        #[serde(default)]
+        uses: u64,
+    }
+
+    let response = request(Request {
+        body: None,
+        headers: None,
+        route: RouteInfo::GetGuildVanityUrl { guild_id },
+    })?;
+
+    serde_json::from_reader::<HyperResponse, GuildVanityUrl>(response)
+        .map(|x| x.uses)
+        .map_err(From::from)
+}
+
 /// Gets the members of a guild. Optionally pass a `limit` and the Id of the
 /// user to offset the result by.
 pub fn get_guild_members(guild_id: u64,
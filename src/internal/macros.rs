@@ -104,3 +104,16 @@ macro_rules! enum_number {
         }
     }
 }
+
+// Deserializes a JSON file of the tests' resources, for the unit tests.
+#[cfg(test)]
+macro_rules! fixture {
+    ($filename:expr) => {
+        ::serde_json::from_str(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/resources/",
+            $filename,
+            ".json",
+        ))).unwrap()
+    };
+}
@@ -7,6 +7,9 @@ mod rwlock_ext;
 
 pub use self::rwlock_ext::RwLockExt;
 
+#[cfg(test)]
+pub mod test_util;
+
 #[cfg(feature = "gateway")]
 pub mod ws_impl;
 
@@ -0,0 +1,59 @@
+//! Helpers shared by the unit tests.
+
+use model::prelude::*;
+
+#[cfg(feature = "client")]
+use client::{scheduler::Scheduler, Collectors, Context, DataStore, ErrorReporter};
+#[cfg(feature = "client")]
+use parking_lot::Mutex;
+#[cfg(feature = "client")]
+use std::sync::{mpsc, Arc};
+#[cfg(feature = "client")]
+use typemap::ShareMap;
+
+#[cfg(all(feature = "client", feature = "cache"))]
+use cache::Cache;
+#[cfg(all(feature = "client", feature = "cache"))]
+use parking_lot::RwLock;
+
+/// Creates a context of shard 0 with empty data, whose shard runner is gone.
+#[cfg(feature = "client")]
+pub fn context() -> Context {
+    let (tx, _) = mpsc::channel();
+    let data = Arc::new(Mutex::new(ShareMap::custom()));
+
+    #[cfg(feature = "cache")]
+    let ctx = Context::new(
+        Arc::new(RwLock::new(Cache::default())),
+        Collectors::default(),
+        data,
+        DataStore::default(),
+        ErrorReporter::default(),
+        tx,
+        Scheduler::default(),
+        0,
+    );
+    #[cfg(not(feature = "cache"))]
+    let ctx = Context::new(
+        Collectors::default(),
+        data,
+        DataStore::default(),
+        ErrorReporter::default(),
+        tx,
+        Scheduler::default(),
+        0,
+    );
+
+    ctx
+}
+
+/// Creates a message of a guild's channel from the `message_create_1`
+/// resource.
+pub fn message(channel_id: u64, message_id: u64) -> Message {
+    let mut message: Message = fixture!("message_create_1");
+    message.channel_id = ChannelId(channel_id);
+    message.guild_id = Some(GuildId(1));
+    message.id = MessageId(message_id);
+
+    message
+}
@@ -58,8 +58,8 @@
 //!     }
 //! }
 //!
-//! command!(ping(_context, message) {
-//!     let _ = message.reply("Pong!");
+//! command!(ping(context, message) {
+//!     let _ = message.reply(context, "Pong!");
 //! });
 //! #
 //! # }
@@ -192,14 +192,23 @@ pub use client::Client;
 use cache::Cache;
 #[cfg(feature = "cache")]
 use parking_lot::RwLock;
+#[cfg(feature = "cache")]
+use std::sync::Arc;
 
 #[cfg(feature = "cache")]
 lazy_static! {
     /// A mutable and lazily-initialized static binding. It can be accessed
     /// across any function and in any context.
     ///
-    /// This [`Cache`] instance is updated for every event received, so you do
-    /// not need to maintain your own cache.
+    /// This [`Cache`] instance is only kept for compatibility. Every [`Client`]
+    /// owns its own cache, reachable via [`Client::cache`] and
+    /// [`Context::cache`], and this instance is only updated by clients
+    /// created with it via [`Client::new_with_cache`].
+    ///
+    /// Model methods which are not given a cache, such as the permission
+    /// checks performed before requests or [`ChannelId::to_channel`], search
+    /// this instance. Permission checks are skipped while it has not received
+    /// a [`Ready`] event.
     ///
     /// See the [cache module documentation] for more details.
     ///
@@ -228,8 +237,14 @@ lazy_static! {
     /// CACHE.write().settings_mut().max_messages(10);
     /// ```
     ///
+    /// [`ChannelId::to_channel`]: model/id/struct.ChannelId.html#method.to_channel
+    /// [`Client`]: client/struct.Client.html
+    /// [`Client::cache`]: client/struct.Client.html#structfield.cache
+    /// [`Client::new_with_cache`]: client/struct.Client.html#method.new_with_cache
+    /// [`Context::cache`]: client/struct.Context.html#structfield.cache
     /// [`CurrentUser`]: model/user/struct.CurrentUser.html
     /// [`Cache`]: cache/struct.Cache.html
+    /// [`Ready`]: model/gateway/struct.Ready.html
     /// [cache module documentation]: cache/index.html
-    pub static ref CACHE: RwLock<Cache> = RwLock::new(Cache::default());
+    pub static ref CACHE: Arc<RwLock<Cache>> = Arc::new(RwLock::new(Cache::default()));
 }
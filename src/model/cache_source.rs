@@ -0,0 +1,78 @@
+#[cfg(feature = "cache")]
+use cache::Cache;
+#[cfg(feature = "cache")]
+use parking_lot::RwLock;
+#[cfg(feature = "cache")]
+use std::sync::Arc;
+#[cfg(feature = "cache")]
+use CACHE;
+
+/// A source of the cache which model methods read from, such as to check the
+/// current user's permissions before performing a request.
+///
+/// This is implemented for the [`Context`] given to event handlers and, with
+/// the `cache` feature, for a [`Cache`] behind a lock, such as
+/// [`Client::cache`] or the global [`CACHE`].
+///
+/// Passing `&()` reads no cache: permissions are then not checked before
+/// performing requests, leaving this to Discord, and items are always
+/// requested over REST. Without the `cache` feature, this is the case for
+/// every source.
+///
+/// # Examples
+///
+/// Reply to a message, checking the permissions of the current user against
+/// the cache of the client which received it:
+///
+/// ```rust,no_run
+/// # use serenity::prelude::*;
+/// # use serenity::model::channel::Message;
+/// #
+/// struct Handler;
+///
+/// impl EventHandler for Handler {
+///     fn message(&self, ctx: Context, msg: Message) {
+///         if msg.content == "!ping" {
+///             let _ = msg.reply(&ctx, "Pong!");
+///         }
+///     }
+/// }
+/// ```
+///
+/// [`CACHE`]: ../struct.CACHE.html
+/// [`Cache`]: ../cache/struct.Cache.html
+/// [`Client::cache`]: ../client/struct.Client.html#structfield.cache
+/// [`Context`]: ../client/struct.Context.html
+pub trait CacheSource {
+    /// The cache to read from, if any.
+    #[cfg(feature = "cache")]
+    fn cache(&self) -> Option<&RwLock<Cache>>;
+}
+
+impl CacheSource for () {
+    #[cfg(feature = "cache")]
+    fn cache(&self) -> Option<&RwLock<Cache>> { None }
+}
+
+#[cfg(feature = "cache")]
+impl CacheSource for RwLock<Cache> {
+    fn cache(&self) -> Option<&RwLock<Cache>> { Some(self) }
+}
+
+#[cfg(feature = "cache")]
+impl CacheSource for Arc<RwLock<Cache>> {
+    fn cache(&self) -> Option<&RwLock<Cache>> { Some(&**self) }
+}
+
+#[cfg(feature = "cache")]
+impl CacheSource for CACHE {
+    fn cache(&self) -> Option<&RwLock<Cache>> { Some(&**self) }
+}
+
+// The source used by methods kept for compatibility, which read the global
+// cache if it is enabled.
+#[cfg(feature = "cache")]
+pub(crate) fn global() -> &'static CacheSource { &CACHE }
+
+#[cfg(not(feature = "cache"))]
+pub(crate) fn global() -> &'static CacheSource { &() }
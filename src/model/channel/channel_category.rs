@@ -57,14 +57,11 @@ impl ChannelCategory {
 
     /// Deletes this category.
     #[inline]
-    pub fn delete(&self) -> Result<()> {
-        #[cfg(feature = "cache")]
-        {
-            let req = Permissions::MANAGE_CHANNELS;
+    pub fn delete(&self, cache: &CacheSource) -> Result<()> {
+        let req = Permissions::MANAGE_CHANNELS;
 
-            if !utils::user_has_perms(self.id, req)? {
-                return Err(Error::Model(ModelError::InvalidPermissions(req)));
-            }
+        if !utils::user_has_perms(cache, self.id, req)? {
+            return Err(Error::Model(ModelError::InvalidPermissions(req)));
         }
 
         self.id.delete().map(|_| ())
@@ -79,18 +76,15 @@ impl ChannelCategory {
     /// Change a voice channels name and bitrate:
     ///
     /// ```rust,ignore
-    /// category.edit(|c| c.name("test").bitrate(86400));
+    /// category.edit(&ctx, |c| c.name("test").bitrate(86400));
     /// ```
     #[cfg(all(feature = "builder", feature = "model", feature = "utils"))]
-    pub fn edit<F>(&mut self, f: F) -> Result<()>
+    pub fn edit<F>(&mut self, cache: &CacheSource, f: F) -> Result<()>
         where F: FnOnce(EditChannel) -> EditChannel {
-        #[cfg(feature = "cache")]
-        {
-            let req = Permissions::MANAGE_CHANNELS;
+        let req = Permissions::MANAGE_CHANNELS;
 
-            if !utils::user_has_perms(self.id, req)? {
-                return Err(Error::Model(ModelError::InvalidPermissions(req)));
-            }
+        if !utils::user_has_perms(cache, self.id, req)? {
+            return Err(Error::Model(ModelError::InvalidPermissions(req)));
         }
 
         let mut map = VecMap::new();
@@ -1,9 +1,11 @@
 use internal::RwLockExt;
 use model::prelude::*;
 
-#[cfg(feature = "model")]
+#[cfg(all(feature = "cache", feature = "model"))]
 use std::borrow::Cow;
 #[cfg(feature = "model")]
+use model::cache_source;
+#[cfg(feature = "model")]
 use std::fmt::Write as FmtWrite;
 #[cfg(feature = "model")]
 use builder::{
@@ -288,21 +290,14 @@ impl ChannelId {
     /// Search the cache for the channel with the Id.
     #[cfg(feature = "cache")]
     #[deprecated(since = "0.5.8", note = "Use the `to_channel_cached`-method instead.")]
-    pub fn find(&self) -> Option<Channel> { self.to_channel_cached() }
+    pub fn find(&self) -> Option<Channel> { self.to_channel_cached(&CACHE) }
 
-    /// Attempts to find a [`Channel`] by its Id in the cache.
+    /// Attempts to find a [`Channel`] by its Id in the given cache.
     ///
     /// [`Channel`]: ../channel/enum.Channel.html
     #[cfg(feature = "cache")]
     #[inline]
-    pub fn to_channel_cached(self) -> Option<Channel> {
-        self._to_channel_cached(&CACHE)
-    }
-
-    /// To allow testing pass their own cache instead of using the globale one.
-    #[cfg(feature = "cache")]
-    #[inline]
-    pub(crate) fn _to_channel_cached(self, cache: &RwLock<Cache>) -> Option<Channel> {
+    pub fn to_channel_cached(self, cache: &RwLock<Cache>) -> Option<Channel> {
         cache.read().channel(self)
     }
 
@@ -311,24 +306,25 @@ impl ChannelId {
     /// requested over REST.
     #[deprecated(since = "0.5.8", note = "Use the `to_channel`-method instead.")]
     pub fn get(&self) -> Result<Channel> {
-        self.to_channel()
+        self.to_channel(cache_source::global())
     }
 
-    /// First attempts to find a [`Channel`] by its Id in the cache,
-    /// upon failure requests it via the REST API.
+    /// First attempts to find a [`Channel`] by its Id in the given cache, upon
+    /// failure requests it via the REST API.
     ///
     /// **Note**: If the cache is not enabled,
     /// REST API will be used only.
     ///
     /// [`Channel`]: ../channel/enum.Channel.html
     #[inline]
-    pub fn to_channel(self) -> Result<Channel> {
-        #[cfg(feature = "cache")]
-        {
-            if let Some(channel) = CACHE.read().channel(self) {
+    pub fn to_channel(self, cache: &CacheSource) -> Result<Channel> {
+        feature_cache! {{
+            if let Some(channel) = cache.cache().and_then(|cache| cache.read().channel(self)) {
                 return Ok(channel);
             }
-        }
+        } else {
+            let _ = cache;
+        }}
 
         http::get_channel(self.0)
     }
@@ -391,24 +387,13 @@ impl ChannelId {
         })
     }
 
-    /// Returns the name of whatever channel this id holds.
-    #[cfg(feature = "model")]
-    pub fn name(&self) -> Option<String> {
+    /// Returns the name of whatever channel this id holds, if the channel is
+    /// in the given cache.
+    #[cfg(feature = "cache")]
+    pub fn name(&self, cache: &RwLock<Cache>) -> Option<String> {
         use self::Channel::*;
 
-        let finding = feature_cache! {{
-            Some(self.to_channel_cached())
-        } else {
-            None
-        }};
-
-        let channel = if let Some(Some(c)) = finding {
-            c
-        } else {
-            return None;
-        };
-
-        Some(match channel {
+        Some(match self.to_channel_cached(cache)? {
             Guild(channel) => channel.read().name().to_string(),
             Group(channel) => match channel.read().name() {
                 Cow::Borrowed(name) => name.to_string(),
@@ -419,6 +404,12 @@ impl ChannelId {
         })
     }
 
+    /// Returns the name of whatever channel this id holds.
+    ///
+    /// This is always `None`, as the name can only be found in the cache.
+    #[cfg(not(feature = "cache"))]
+    pub fn name(&self) -> Option<String> { None }
+
     /// Pins a [`Message`] to the channel.
     ///
     /// [`Message`]: ../channel/struct.Message.html
@@ -2,7 +2,7 @@ use chrono::{DateTime, FixedOffset};
 use model::prelude::*;
 
 #[cfg(all(feature = "cache", feature = "model"))]
-use CACHE;
+use Cache;
 #[cfg(feature = "model")]
 use builder::{
     CreateInvite,
@@ -113,18 +113,15 @@ impl GuildChannel {
     /// Create an invite that can only be used 5 times:
     ///
     /// ```rust,ignore
-    /// let invite = channel.create_invite(|i| i.max_uses(5));
+    /// let invite = channel.create_invite(&ctx, |i| i.max_uses(5));
     /// ```
     #[cfg(feature = "utils")]
-    pub fn create_invite<F>(&self, f: F) -> Result<RichInvite>
+    pub fn create_invite<F>(&self, cache: &CacheSource, f: F) -> Result<RichInvite>
         where F: FnOnce(CreateInvite) -> CreateInvite {
-        #[cfg(feature = "cache")]
-        {
-            let req = Permissions::CREATE_INVITE;
+        let req = Permissions::CREATE_INVITE;
 
-            if !utils::user_has_perms(self.id, req)? {
-                return Err(Error::Model(ModelError::InvalidPermissions(req)));
-            }
+        if !utils::user_has_perms(cache, self.id, req)? {
+            return Err(Error::Model(ModelError::InvalidPermissions(req)));
         }
 
         let map = serenity_utils::vecmap_to_json_map(f(CreateInvite::default()).0);
@@ -148,11 +145,12 @@ impl GuildChannel {
     /// permissions:
     ///
     /// ```rust,no_run
+    /// # use serenity::model::CacheSource;
     /// # use serenity::model::id::{ChannelId, UserId};
     /// # use serenity::model::channel::Channel;
     /// # use std::error::Error;
     /// #
-    /// # fn try_main() -> Result<(), Box<Error>> {
+    /// # fn try_main(cache: &CacheSource) -> Result<(), Box<Error>> {
     /// #     let (channel_id, user_id) = (ChannelId(0), UserId(0));
     /// #
     /// use serenity::model::channel::{
@@ -169,7 +167,7 @@ impl GuildChannel {
     ///     kind: PermissionOverwriteType::Member(user_id),
     /// };
     ///
-    /// let channel = channel_id.to_channel().expect("Could not request channel via REST.");
+    /// let channel = channel_id.to_channel(cache).expect("Could not request channel via REST.");
     ///
     /// if let Channel::Guild(channel) = channel {
     ///     let channel = channel.read();
@@ -179,7 +177,7 @@ impl GuildChannel {
     /// # }
     /// #
     /// # fn main() {
-    /// #     try_main().unwrap();
+    /// #     try_main(&()).unwrap();
     /// # }
     /// ```
     ///
@@ -189,10 +187,11 @@ impl GuildChannel {
     /// permissions:
     ///
     /// ```rust,no_run
+    /// # use serenity::model::CacheSource;
     /// # use serenity::model::id::{ChannelId, UserId};
     /// # use std::error::Error;
     /// #
-    /// # fn try_main() -> Result<(), Box<Error>> {
+    /// # fn try_main(cache: &CacheSource) -> Result<(), Box<Error>> {
     /// #     let (channel_id, user_id) = (ChannelId(0), UserId(0));
     /// #
     /// use serenity::model::channel::{
@@ -209,7 +208,7 @@ impl GuildChannel {
     ///     kind: PermissionOverwriteType::Member(user_id),
     /// };
     ///
-    /// let channel = channel_id.to_channel().expect("Could not request channel via REST.");
+    /// let channel = channel_id.to_channel(cache).expect("Could not request channel via REST.");
     ///
     /// if let Channel::Guild(channel) = channel {
     ///     let channel = channel.read();
@@ -219,7 +218,7 @@ impl GuildChannel {
     /// # }
     /// #
     /// # fn main() {
-    /// #     try_main().unwrap();
+    /// #     try_main(&()).unwrap();
     /// # }
     /// ```
     ///
@@ -241,14 +240,11 @@ impl GuildChannel {
     }
 
     /// Deletes this channel, returning the channel on a successful deletion.
-    pub fn delete(&self) -> Result<Channel> {
-        #[cfg(feature = "cache")]
-        {
-            let req = Permissions::MANAGE_CHANNELS;
-
-            if !utils::user_has_perms(self.id, req)? {
-                return Err(Error::Model(ModelError::InvalidPermissions(req)));
-            }
+    pub fn delete(&self, cache: &CacheSource) -> Result<Channel> {
+        let req = Permissions::MANAGE_CHANNELS;
+
+        if !utils::user_has_perms(cache, self.id, req)? {
+            return Err(Error::Model(ModelError::InvalidPermissions(req)));
         }
 
         self.id.delete()
@@ -313,18 +309,15 @@ impl GuildChannel {
     /// Change a voice channels name and bitrate:
     ///
     /// ```rust,ignore
-    /// channel.edit(|c| c.name("test").bitrate(86400));
+    /// channel.edit(&ctx, |c| c.name("test").bitrate(86400));
     /// ```
     #[cfg(feature = "utils")]
-    pub fn edit<F>(&mut self, f: F) -> Result<()>
+    pub fn edit<F>(&mut self, cache: &CacheSource, f: F) -> Result<()>
         where F: FnOnce(EditChannel) -> EditChannel {
-        #[cfg(feature = "cache")]
-        {
-            let req = Permissions::MANAGE_CHANNELS;
+        let req = Permissions::MANAGE_CHANNELS;
 
-            if !utils::user_has_perms(self.id, req)? {
-                return Err(Error::Model(ModelError::InvalidPermissions(req)));
-            }
+        if !utils::user_has_perms(cache, self.id, req)? {
+            return Err(Error::Model(ModelError::InvalidPermissions(req)));
         }
 
         let mut map = VecMap::new();
@@ -368,12 +361,11 @@ impl GuildChannel {
         self.id.edit_message(message_id, f)
     }
 
-    /// Attempts to find this channel's guild in the Cache.
-    ///
-    /// **Note**: Right now this performs a clone of the guild. This will be
-    /// optimized in the future.
+    /// Attempts to find this channel's guild in the given cache.
     #[cfg(feature = "cache")]
-    pub fn guild(&self) -> Option<Arc<RwLock<Guild>>> { CACHE.read().guild(self.guild_id) }
+    pub fn guild(&self, cache: &RwLock<Cache>) -> Option<Arc<RwLock<Guild>>> {
+        cache.read().guild(self.guild_id)
+    }
 
     /// Gets all of the channel's invites.
     ///
@@ -421,7 +413,8 @@ impl GuildChannel {
     /// Returns the name of the guild channel.
     pub fn name(&self) -> &str { &self.name }
 
-    /// Calculates the permissions of a member.
+    /// Calculates the permissions of a member, using the channel's guild in the
+    /// given cache.
     ///
     /// The Id of the argument must be a [`Member`] of the [`Guild`] that the
     /// channel is in.
@@ -436,16 +429,14 @@ impl GuildChannel {
     /// use serenity::model::prelude::*;
     /// struct Handler;
     ///
-    /// use serenity::CACHE;
-    ///
     /// impl EventHandler for Handler {
-    ///     fn message(&self, _: Context, msg: Message) {
-    ///         let channel = match CACHE.read().guild_channel(msg.channel_id) {
+    ///     fn message(&self, ctx: Context, msg: Message) {
+    ///         let channel = match ctx.cache.read().guild_channel(msg.channel_id) {
     ///             Some(channel) => channel,
     ///             None => return,
     ///         };
     ///
-    ///         let permissions = channel.read().permissions_for(&msg.author).unwrap();
+    ///         let permissions = channel.read().permissions_for(&ctx.cache, &msg.author).unwrap();
     ///
     ///         println!("The user's permissions: {:?}", permissions);
     ///     }
@@ -463,18 +454,17 @@ impl GuildChannel {
     /// use serenity::prelude::*;
     /// use serenity::model::prelude::*;
     /// use serenity::model::channel::Channel;
-    /// use serenity::CACHE;
     /// use std::fs::File;
     ///
     /// struct Handler;
     ///
     /// impl EventHandler for Handler {
-    ///     fn message(&self, _: Context, msg: Message) {
-    ///         if let Ok(Channel::Guild(guild_channel)) = msg.channel_id.to_channel() {
+    ///     fn message(&self, ctx: Context, msg: Message) {
+    ///         if let Ok(Channel::Guild(guild_channel)) = msg.channel_id.to_channel(&ctx) {
     ///
-    ///             let current_user_id = CACHE.read().user.id;
+    ///             let current_user_id = ctx.cache.read().user.id;
     ///             let guild_channel = guild_channel.read();
-    ///             let permissions = guild_channel.permissions_for(current_user_id).unwrap();
+    ///             let permissions = guild_channel.permissions_for(&ctx.cache, current_user_id).unwrap();
     ///
     ///             if !permissions.contains(Permissions::ATTACH_FILES | Permissions::SEND_MESSAGES) {
     ///                 return;
@@ -515,13 +505,13 @@ impl GuildChannel {
     /// [Send Messages]: ../permissions/struct.Permissions.html#associatedconstant.SEND_MESSAGES
     #[cfg(feature = "cache")]
     #[inline]
-    pub fn permissions_for<U: Into<UserId>>(&self, user_id: U) -> Result<Permissions> {
-        self._permissions_for(user_id.into())
+    pub fn permissions_for<U: Into<UserId>>(&self, cache: &RwLock<Cache>, user_id: U) -> Result<Permissions> {
+        self._permissions_for(cache, user_id.into())
     }
 
     #[cfg(feature = "cache")]
-    fn _permissions_for(&self, user_id: UserId) -> Result<Permissions> {
-        self.guild()
+    fn _permissions_for(&self, cache: &RwLock<Cache>, user_id: UserId) -> Result<Permissions> {
+        self.guild(cache)
             .ok_or_else(|| Error::Model(ModelError::GuildNotFound))
             .map(|g| g.read().permissions_in(self.id, user_id))
     }
@@ -616,14 +606,12 @@ impl GuildChannel {
     /// [`ModelError::MessageTooLong`]: ../error/enum.Error.html#variant.MessageTooLong
     /// [`Message`]: struct.Message.html
     /// [Send Messages]: ../permissions/struct.Permissions.html#associatedconstant.SEND_MESSAGES
-    pub fn send_message<F: FnOnce(CreateMessage) -> CreateMessage>(&self, f: F) -> Result<Message> {
-        #[cfg(feature = "cache")]
-        {
-            let req = Permissions::SEND_MESSAGES;
-
-            if !utils::user_has_perms(self.id, req)? {
-                return Err(Error::Model(ModelError::InvalidPermissions(req)));
-            }
+    pub fn send_message<F>(&self, cache: &CacheSource, f: F) -> Result<Message>
+        where F: FnOnce(CreateMessage) -> CreateMessage {
+        let req = Permissions::SEND_MESSAGES;
+
+        if !utils::user_has_perms(cache, self.id, req)? {
+            return Err(Error::Model(ModelError::InvalidPermissions(req)));
         }
 
         self.id.send_message(f)
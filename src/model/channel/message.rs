@@ -7,7 +7,7 @@ use serde_json::Value;
 #[cfg(feature = "model")]
 use builder::{CreateEmbed, EditMessage};
 #[cfg(all(feature = "cache", feature = "model"))]
-use CACHE;
+use Cache;
 #[cfg(all(feature = "cache", feature = "model"))]
 use std::fmt::Write;
 #[cfg(feature = "model")]
@@ -102,30 +102,32 @@ impl Message {
     ///     .configure(|c| c.prefix("~"))
     ///     .cmd("channelname", channel_name));
     ///
-    /// command!(channel_name(_ctx, msg) {
-    ///     let _ = match msg.channel() {
-    ///         Some(Channel::Category(c)) => msg.reply(&c.read().name),
-    ///         Some(Channel::Group(c)) => msg.reply(&c.read().name()),
-    ///         Some(Channel::Guild(c)) => msg.reply(&c.read().name),
+    /// command!(channel_name(ctx, msg) {
+    ///     let _ = match msg.channel(&ctx.cache) {
+    ///         Some(Channel::Category(c)) => msg.reply(ctx, &c.read().name),
+    ///         Some(Channel::Group(c)) => msg.reply(ctx, &c.read().name()),
+    ///         Some(Channel::Guild(c)) => msg.reply(ctx, &c.read().name),
     ///         Some(Channel::Private(c)) => {
     ///             let channel = c.read();
     ///             let user = channel.recipient.read();
     ///
-    ///             msg.reply(&format!("DM with {}", user.name.clone()))
+    ///             msg.reply(ctx, &format!("DM with {}", user.name.clone()))
     ///         },
-    ///         None => msg.reply("Unknown"),
+    ///         None => msg.reply(ctx, "Unknown"),
     ///     };
     /// });
     /// # }
     /// ```
     #[cfg(feature = "cache")]
     #[inline]
-    pub fn channel(&self) -> Option<Channel> { CACHE.read().channel(self.channel_id) }
+    pub fn channel(&self, cache: &RwLock<Cache>) -> Option<Channel> {
+        cache.read().channel(self.channel_id)
+    }
 
     /// A util function for determining whether this message was sent by someone else, or the
     /// bot.
     #[cfg(all(feature = "cache", feature = "utils"))]
-    pub fn is_own(&self) -> bool { self.author.id == CACHE.read().user.id }
+    pub fn is_own(&self, cache: &RwLock<Cache>) -> bool { self.author.id == cache.read().user.id }
 
     /// Deletes the message.
     ///
@@ -141,16 +143,13 @@ impl Message {
     /// [`ModelError::InvalidPermissions`]: ../error/enum.Error.html#variant.InvalidPermissions
     /// [`ModelError::InvalidUser`]: ../error/enum.Error.html#variant.InvalidUser
     /// [Manage Messages]: ../permissions/struct.Permissions.html#associatedconstant.MANAGE_MESSAGES
-    pub fn delete(&self) -> Result<()> {
-        #[cfg(feature = "cache")]
-        {
-            let req = Permissions::MANAGE_MESSAGES;
-            let is_author = self.author.id == CACHE.read().user.id;
-            let has_perms = utils::user_has_perms(self.channel_id, req)?;
+    pub fn delete(&self, cache: &CacheSource) -> Result<()> {
+        let req = Permissions::MANAGE_MESSAGES;
+        let is_author = Some(self.author.id) == utils::current_user_id(cache);
+        let has_perms = utils::user_has_perms(cache, self.channel_id, req)?;
 
-            if !is_author && !has_perms {
-                return Err(Error::Model(ModelError::InvalidPermissions(req)));
-            }
+        if !is_author && !has_perms {
+            return Err(Error::Model(ModelError::InvalidPermissions(req)));
         }
 
         self.channel_id.delete_message(self.id)
@@ -169,14 +168,11 @@ impl Message {
     /// [`ModelError::InvalidPermissions`]: ../error/enum.Error.html#variant.InvalidPermissions
     /// [`Reaction`]: struct.Reaction.html
     /// [Manage Messages]: ../permissions/struct.Permissions.html#associatedconstant.MANAGE_MESSAGES
-    pub fn delete_reactions(&self) -> Result<()> {
-        #[cfg(feature = "cache")]
-        {
-            let req = Permissions::MANAGE_MESSAGES;
+    pub fn delete_reactions(&self, cache: &CacheSource) -> Result<()> {
+        let req = Permissions::MANAGE_MESSAGES;
 
-            if !utils::user_has_perms(self.channel_id, req)? {
-                return Err(Error::Model(ModelError::InvalidPermissions(req)));
-            }
+        if !utils::user_has_perms(cache, self.channel_id, req)? {
+            return Err(Error::Model(ModelError::InvalidPermissions(req)));
         }
 
         http::delete_message_reactions(self.channel_id.0, self.id.0)
@@ -198,7 +194,7 @@ impl Message {
     /// ```rust,ignore
     /// // assuming a `message` has already been bound
     ///
-    /// message.edit(|m| m.content("new content"));
+    /// message.edit(&ctx, |m| m.content("new content"));
     /// ```
     ///
     /// # Errors
@@ -214,13 +210,10 @@ impl Message {
     /// [`ModelError::MessageTooLong`]: ../error/enum.Error.html#variant.MessageTooLong
     /// [`EditMessage`]: ../../builder/struct.EditMessage.html
     /// [`the limit`]: ../../builder/struct.EditMessage.html#method.content
-    pub fn edit<F>(&mut self, f: F) -> Result<()>
+    pub fn edit<F>(&mut self, cache: &CacheSource, f: F) -> Result<()>
         where F: FnOnce(EditMessage) -> EditMessage {
-        #[cfg(feature = "cache")]
-        {
-            if self.author.id != CACHE.read().user.id {
-                return Err(Error::Model(ModelError::InvalidUser));
-            }
+        if utils::current_user_id(cache).map_or(false, |id| id != self.author.id) {
+            return Err(Error::Model(ModelError::InvalidUser));
         }
 
         let mut builder = EditMessage::default();
@@ -270,7 +263,7 @@ impl Message {
     /// Returns message content, but with user and role mentions replaced with
     /// names and everyone/here mentions cancelled.
     #[cfg(feature = "cache")]
-    pub fn content_safe(&self) -> String {
+    pub fn content_safe(&self, cache: &RwLock<Cache>) -> String {
         let mut result = self.content.clone();
 
         // First replace all user mentions.
@@ -287,7 +280,7 @@ impl Message {
         for id in &self.mention_roles {
             let mention = id.mention();
 
-            if let Some(role) = id.to_role_cached() {
+            if let Some(role) = id.to_role_cached(cache) {
                 result = result.replace(&mention, &format!("@{}", role.name));
             } else {
                 result = result.replace(&mention, "@deleted-role");
@@ -336,8 +329,8 @@ impl Message {
     ///
     /// [`guild_id`]: #method.guild_id
     #[cfg(feature = "cache")]
-    pub fn guild(&self) -> Option<Arc<RwLock<Guild>>> {
-        CACHE.read().guild(self.guild_id?)
+    pub fn guild(&self, cache: &RwLock<Cache>) -> Option<Arc<RwLock<Guild>>> {
+        cache.read().guild(self.guild_id?)
     }
 
     /// Retrieves the Id of the guild that the message was sent in, if sent in
@@ -356,8 +349,8 @@ impl Message {
         self.guild_id.is_none()
     }
 
-    /// Retrieves a clone of the author's Member instance, if this message was
-    /// sent in a guild.
+    /// Retrieves a clone of the author's Member instance from the given cache,
+    /// if this message was sent in a guild.
     ///
     /// Note that since this clones, it is preferable performance-wise to
     /// manually retrieve the guild from the cache and access
@@ -365,8 +358,8 @@ impl Message {
     ///
     /// [`Guild::members`]: ../guild/struct.Guild.html#structfield.members
     #[cfg(feature = "cache")]
-    pub fn member(&self) -> Option<Member> {
-        self.guild().and_then(|g| g.read().members.get(&self.author.id).cloned())
+    pub fn member(&self, cache: &RwLock<Cache>) -> Option<Member> {
+        self.guild(cache).and_then(|g| g.read().members.get(&self.author.id).cloned())
     }
 
     /// Checks the length of a string to ensure that it is within Discord's
@@ -400,15 +393,12 @@ impl Message {
     ///
     /// [`ModelError::InvalidPermissions`]: ../error/enum.Error.html#variant.InvalidPermissions
     /// [Manage Messages]: ../permissions/struct.Permissions.html#associatedconstant.MANAGE_MESSAGES.html
-    pub fn pin(&self) -> Result<()> {
-        #[cfg(feature = "cache")]
-        {
-            if self.guild_id.is_some() {
-                let req = Permissions::MANAGE_MESSAGES;
-
-                if !utils::user_has_perms(self.channel_id, req)? {
-                    return Err(Error::Model(ModelError::InvalidPermissions(req)));
-                }
+    pub fn pin(&self, cache: &CacheSource) -> Result<()> {
+        if self.guild_id.is_some() {
+            let req = Permissions::MANAGE_MESSAGES;
+
+            if !utils::user_has_perms(cache, self.channel_id, req)? {
+                return Err(Error::Model(ModelError::InvalidPermissions(req)));
             }
         }
 
@@ -431,19 +421,16 @@ impl Message {
     /// ../permissions/struct.Permissions.html#associatedconstant.ADD_REACTIONS
     /// [permissions]: ../permissions/index.html
     #[inline]
-    pub fn react<R: Into<ReactionType>>(&self, reaction_type: R) -> Result<()> {
-        self._react(&reaction_type.into())
+    pub fn react<R: Into<ReactionType>>(&self, cache: &CacheSource, reaction_type: R) -> Result<()> {
+        self._react(cache, &reaction_type.into())
     }
 
-    fn _react(&self, reaction_type: &ReactionType) -> Result<()> {
-        #[cfg(feature = "cache")]
-        {
-            if self.guild_id.is_some() {
-                let req = Permissions::ADD_REACTIONS;
+    fn _react(&self, cache: &CacheSource, reaction_type: &ReactionType) -> Result<()> {
+        if self.guild_id.is_some() {
+            let req = Permissions::ADD_REACTIONS;
 
-                if !utils::user_has_perms(self.channel_id, req)? {
-                    return Err(Error::Model(ModelError::InvalidPermissions(req)));
-                }
+            if !utils::user_has_perms(cache, self.channel_id, req)? {
+                return Err(Error::Model(ModelError::InvalidPermissions(req)));
             }
         }
 
@@ -472,19 +459,16 @@ impl Message {
     /// [`ModelError::InvalidPermissions`]: ../error/enum.Error.html#variant.InvalidPermissions
     /// [`ModelError::MessageTooLong`]: ../error/enum.Error.html#variant.MessageTooLong
     /// [Send Messages]: ../permissions/struct.Permissions.html#associatedconstant.SEND_MESSAGES
-    pub fn reply(&self, content: &str) -> Result<Message> {
+    pub fn reply(&self, cache: &CacheSource, content: &str) -> Result<Message> {
         if let Some(length_over) = Message::overflow_length(content) {
             return Err(Error::Model(ModelError::MessageTooLong(length_over)));
         }
 
-        #[cfg(feature = "cache")]
-        {
-            if self.guild_id.is_some() {
-                let req = Permissions::SEND_MESSAGES;
+        if self.guild_id.is_some() {
+            let req = Permissions::SEND_MESSAGES;
 
-                if !utils::user_has_perms(self.channel_id, req)? {
-                    return Err(Error::Model(ModelError::InvalidPermissions(req)));
-                }
+            if !utils::user_has_perms(cache, self.channel_id, req)? {
+                return Err(Error::Model(ModelError::InvalidPermissions(req)));
             }
         }
 
@@ -531,15 +515,12 @@ impl Message {
     ///
     /// [`ModelError::InvalidPermissions`]: ../error/enum.Error.html#variant.InvalidPermissions
     /// [Manage Messages]: ../permissions/struct.Permissions.html#associatedconstant.MANAGE_MESSAGES
-    pub fn unpin(&self) -> Result<()> {
-        #[cfg(feature = "cache")]
-        {
-            if self.guild_id.is_some() {
-                let req = Permissions::MANAGE_MESSAGES;
-
-                if !utils::user_has_perms(self.channel_id, req)? {
-                    return Err(Error::Model(ModelError::InvalidPermissions(req)));
-                }
+    pub fn unpin(&self, cache: &CacheSource) -> Result<()> {
+        if self.guild_id.is_some() {
+            let req = Permissions::MANAGE_MESSAGES;
+
+            if !utils::user_has_perms(cache, self.channel_id, req)? {
+                return Err(Error::Model(ModelError::InvalidPermissions(req)));
             }
         }
 
@@ -551,8 +532,8 @@ impl Message {
     /// **Note**:
     /// If message was sent in a private channel, then the function will return
     /// `None`.
-    pub fn author_nick(&self) -> Option<String> {
-        self.guild_id.as_ref().and_then(|guild_id| self.author.nick_in(*guild_id))
+    pub fn author_nick(&self, cache: &CacheSource) -> Option<String> {
+        self.guild_id.as_ref().and_then(|guild_id| self.author.nick_in(cache, *guild_id))
     }
 
     pub(crate) fn check_content_length(map: &JsonMap) -> Result<()> {
@@ -40,6 +40,8 @@ use std::str::FromStr;
 use model::misc::ChannelParseError;
 #[cfg(all(feature = "cache", feature = "model", feature = "utils"))]
 use utils::parse_channel;
+#[cfg(all(feature = "cache", feature = "model", feature = "utils"))]
+use CACHE;
 
 /// A container for any channel.
 #[derive(Clone, Debug)]
@@ -81,7 +83,7 @@ impl Channel {
     /// #
     /// # #[cfg(feature = "model")]
     /// # fn main() {
-    /// #     let channel = ChannelId(0).to_channel().unwrap();
+    /// #     let channel = ChannelId(0).to_channel(&()).unwrap();
     /// #
     /// match channel.group() {
     ///     Some(group_lock) => {
@@ -123,7 +125,7 @@ impl Channel {
     /// #
     /// # #[cfg(feature = "model")]
     /// # fn main() {
-    /// #     let channel = ChannelId(0).to_channel().unwrap();
+    /// #     let channel = ChannelId(0).to_channel(&()).unwrap();
     /// #
     /// match channel.guild() {
     ///     Some(guild_lock) => {
@@ -161,7 +163,7 @@ impl Channel {
     /// #
     /// # #[cfg(feature = "model")]
     /// # fn main() {
-    /// #     let channel = ChannelId(0).to_channel().unwrap();
+    /// #     let channel = ChannelId(0).to_channel(&()).unwrap();
     /// #
     /// match channel.private() {
     ///     Some(private_lock) => {
@@ -202,7 +204,7 @@ impl Channel {
     /// #
     /// # #[cfg(feature = "model")]
     /// # fn main() {
-    /// # let channel = ChannelId(0).to_channel().unwrap();
+    /// # let channel = ChannelId(0).to_channel(&()).unwrap();
     /// #
     /// match channel.category() {
     ///     Some(category_lock) => {
@@ -250,19 +252,19 @@ impl Channel {
     ///
     /// [`Group`]: struct.Group.html
     #[cfg(feature = "model")]
-    pub fn delete(&self) -> Result<()> {
+    pub fn delete(&self, cache: &CacheSource) -> Result<()> {
         match *self {
             Channel::Group(ref group) => {
                 let _ = group.read().leave()?;
             },
             Channel::Guild(ref public_channel) => {
-                let _ = public_channel.read().delete()?;
+                let _ = public_channel.read().delete(cache)?;
             },
             Channel::Private(ref private_channel) => {
                 let _ = private_channel.read().delete()?;
             },
             Channel::Category(ref category) => {
-                category.read().delete()?;
+                category.read().delete(cache)?;
             },
         }
 
@@ -812,13 +814,24 @@ mod test {
     }
 }
 
+/// Parses a channel mention or Id, retrieving the channel from the global
+/// [`CACHE`].
+///
+/// **Note**: A client only updates the global cache if it was created with
+/// [`Client::new_with_cache`]. Otherwise, use [`utils::parse_channel`] with
+/// [`ChannelId::to_channel_cached`] on the client's cache instead.
+///
+/// [`CACHE`]: ../../struct.CACHE.html
+/// [`ChannelId::to_channel_cached`]: ../id/struct.ChannelId.html#method.to_channel_cached
+/// [`Client::new_with_cache`]: ../../client/struct.Client.html#method.new_with_cache
+/// [`utils::parse_channel`]: ../../utils/fn.parse_channel.html
 #[cfg(all(feature = "cache", feature = "model", feature = "utils"))]
 impl FromStr for Channel {
     type Err = ChannelParseError;
 
     fn from_str(s: &str) -> StdResult<Self, Self::Err> {
         match parse_channel(s) {
-            Some(x) => match ChannelId(x).to_channel_cached() {
+            Some(x) => match ChannelId(x).to_channel_cached(&CACHE) {
                 Some(channel) => Ok(channel),
                 _ => Err(ChannelParseError::NotPresentInCache),
             },
@@ -13,8 +13,6 @@ use std::{
 };
 use internal::prelude::*;
 
-#[cfg(all(feature = "cache", feature = "model"))]
-use CACHE;
 #[cfg(feature = "model")]
 use http;
 
@@ -50,8 +48,8 @@ impl Reaction {
     ///
     /// [Read Message History]: ../permissions/struct.Permissions.html#associatedconstant.READ_MESSAGE_HISTORY
     #[inline]
-    pub fn channel(&self) -> Result<Channel> {
-        self.channel_id.to_channel()
+    pub fn channel(&self, cache: &CacheSource) -> Result<Channel> {
+        self.channel_id.to_channel(cache)
     }
 
     /// Deletes the reaction, but only if the current user is the user who made
@@ -69,34 +67,26 @@ impl Reaction {
     /// [`ModelError::InvalidPermissions`]: ../error/enum.Error.html#variant.InvalidPermissions
     /// [Manage Messages]: ../permissions/struct.Permissions.html#associatedconstant.MANAGE_MESSAGES
     /// [permissions]: ../permissions/index.html
-    pub fn delete(&self) -> Result<()> {
-        let user_id = feature_cache! {
-            {
-                let user = if self.user_id == CACHE.read().user.id {
-                    None
-                } else {
-                    Some(self.user_id.0)
-                };
-
-                // If the reaction is one _not_ made by the current user, then ensure
-                // that the current user has permission* to delete the reaction.
-                //
-                // Normally, users can only delete their own reactions.
-                //
-                // * The `Manage Messages` permission.
-                if user.is_some() {
-                    let req = Permissions::MANAGE_MESSAGES;
-
-                    if !utils::user_has_perms(self.channel_id, req).unwrap_or(true) {
-                        return Err(Error::Model(ModelError::InvalidPermissions(req)));
-                    }
-                }
+    pub fn delete(&self, cache: &CacheSource) -> Result<()> {
+        let user_id = if Some(self.user_id) == utils::current_user_id(cache) {
+            None
+        } else {
+            Some(self.user_id.0)
+        };
 
-                user
-            } else {
-                Some(self.user_id.0)
+        // If the reaction is one _not_ made by the current user, then ensure
+        // that the current user has permission* to delete the reaction.
+        //
+        // Normally, users can only delete their own reactions.
+        //
+        // * The `Manage Messages` permission.
+        if user_id.is_some() {
+            let req = Permissions::MANAGE_MESSAGES;
+
+            if !utils::user_has_perms(cache, self.channel_id, req).unwrap_or(true) {
+                return Err(Error::Model(ModelError::InvalidPermissions(req)));
             }
-        };
+        }
 
         http::delete_reaction(self.channel_id.0, self.message_id.0, user_id, &self.emoji)
     }
@@ -122,8 +112,8 @@ impl Reaction {
     /// If not - or the user was not found - this will perform a request over
     /// the REST API for the user.
     #[inline]
-    pub fn user(&self) -> Result<User> {
-        self.user_id.to_user()
+    pub fn user(&self, cache: &CacheSource) -> Result<User> {
+        self.user_id.to_user(cache)
     }
 
     /// Retrieves the list of [`User`]s who have reacted to a [`Message`] with a
@@ -331,18 +321,19 @@ impl From<char> for ReactionType {
     /// Reacting to a message with an apple:
     ///
     /// ```rust,no_run
+    /// # use serenity::model::CacheSource;
     /// # use serenity::model::id::ChannelId;
     /// # use std::error::Error;
     /// #
-    /// # fn try_main() -> Result<(), Box<Error>> {
+    /// # fn try_main(cache: &CacheSource) -> Result<(), Box<Error>> {
     /// #     let message = ChannelId(0).message(0)?;
     /// #
-    /// message.react('🍎')?;
+    /// message.react(cache, '🍎')?;
     /// #     Ok(())
     /// # }
     /// #
     /// # fn main() {
-    /// #     try_main().unwrap();
+    /// #     try_main(&()).unwrap();
     /// # }
     /// ```
     fn from(ch: char) -> ReactionType { ReactionType::Unicode(ch.to_string()) }
@@ -15,12 +15,10 @@ use constants::{OpCode, VoiceOpCode};
 use internal::prelude::*;
 
 #[cfg(feature = "cache")]
-use cache::{Cache, CacheUpdate};
+use cache::{CacheBackend, CacheUpdate, ConcurrentCacheUpdate};
 #[cfg(feature = "cache")]
 use internal::RwLockExt;
 #[cfg(feature = "cache")]
-use std::collections::hash_map::Entry;
-#[cfg(feature = "cache")]
 use std::mem;
 
 /// Event data for the channel creation event.
@@ -57,68 +55,37 @@ impl Serialize for ChannelCreateEvent {
 }
 
 #[cfg(feature = "cache")]
-impl CacheUpdate for ChannelCreateEvent {
+impl<B: CacheBackend + ?Sized> CacheUpdate<B> for ChannelCreateEvent {
     type Output = Channel;
 
-    fn update(&mut self, cache: &mut Cache) -> Option<Self::Output> {
+    fn update(&mut self, cache: &mut B) -> Option<Self::Output> {
         match self.channel {
             Channel::Group(ref group) => {
-                let group = Arc::clone(group);
-
-                let channel_id = group.with_mut(|writer| {
-                    for (recipient_id, recipient) in &mut writer.recipients {
-                        cache.update_user_entry(&recipient.read());
+                group.with_mut(|writer| {
+                    for recipient in writer.recipients.values_mut() {
+                        let user = cache.insert_user(&recipient.read());
 
-                        *recipient = Arc::clone(&cache.users[recipient_id]);
+                        *recipient = user;
                     }
-
-                    writer.channel_id
                 });
-
-                let ch = cache.groups.insert(channel_id, group);
-
-                ch.map(Channel::Group)
-            },
-            Channel::Guild(ref channel) => {
-                let (guild_id, channel_id) = channel.with(|channel| (channel.guild_id, channel.id));
-
-                cache.channels.insert(channel_id, Arc::clone(channel));
-
-                cache
-                    .guilds
-                    .get_mut(&guild_id)
-                    .and_then(|guild| {
-                        guild
-                            .with_mut(|guild| guild.channels.insert(channel_id, Arc::clone(channel)))
-                    })
-                    .map(Channel::Guild)
             },
             Channel::Private(ref channel) => {
-                if let Some(channel) = cache.private_channels.get(&channel.with(|c| c.id)) {
-                    return Some(Channel::Private(Arc::clone(&(*channel))));
-                }
+                let id = channel.with(|c| c.id);
 
-                let channel = Arc::clone(channel);
+                if let Some(channel @ Channel::Private(_)) = cache.channel(id) {
+                    return Some(channel);
+                }
 
-                let id = channel.with_mut(|writer| {
-                    let user_id = writer.recipient.with_mut(|user| {
-                        cache.update_user_entry(user);
+                channel.with_mut(|writer| {
+                    let user = cache.insert_user(&writer.recipient.read());
 
-                        user.id
-                    });
-
-                    writer.recipient = Arc::clone(&cache.users[&user_id]);
-                    writer.id
+                    writer.recipient = user;
                 });
-
-                let ch = cache.private_channels.insert(id, Arc::clone(&channel));
-                ch.map(Channel::Private)
             },
-            Channel::Category(ref category) => cache
-                .categories
-                .insert(category.read().id, Arc::clone(category))
-                .map(Channel::Category),
+            Channel::Guild(_) | Channel::Category(_) => {},
         }
+
+        cache.insert_channel(self.channel.clone())
     }
 }
 
@@ -128,32 +95,12 @@ pub struct ChannelDeleteEvent {
 }
 
 #[cfg(feature = "cache")]
-impl CacheUpdate for ChannelDeleteEvent {
+impl<B: CacheBackend + ?Sized> CacheUpdate<B> for ChannelDeleteEvent {
     type Output = ();
 
-    fn update(&mut self, cache: &mut Cache) -> Option<()> {
-        match self.channel {
-            Channel::Guild(ref channel) => {
-                let (guild_id, channel_id) = channel.with(|channel| (channel.guild_id, channel.id));
-
-                cache.channels.remove(&channel_id);
-
-                cache
-                    .guilds
-                    .get_mut(&guild_id)
-                    .and_then(|guild| guild.with_mut(|g| g.channels.remove(&channel_id)));
-            },
-            Channel::Category(ref category) => {
-                let channel_id = category.with(|cat| cat.id);
-
-                cache.categories.remove(&channel_id);
-            },
-            // We ignore these because the delete event does not fire for these.
-            Channel::Private(_) | Channel::Group(_) => unreachable!(),
-        };
-
-        // Remove the cached messages for the channel.
-        cache.messages.remove(&self.channel.id());
+    fn update(&mut self, cache: &mut B) -> Option<()> {
+        // Also removes the cached messages for the channel.
+        cache.remove_channel(self.channel.id());
 
         None
     }
@@ -181,32 +128,28 @@ pub struct ChannelPinsUpdateEvent {
 }
 
 #[cfg(feature = "cache")]
-impl CacheUpdate for ChannelPinsUpdateEvent {
+impl<B: CacheBackend + ?Sized> CacheUpdate<B> for ChannelPinsUpdateEvent {
     type Output = ();
 
-    fn update(&mut self, cache: &mut Cache) -> Option<()> {
-        if let Some(channel) = cache.channels.get(&self.channel_id) {
-            channel.with_mut(|c| {
-                c.last_pin_timestamp = self.last_pin_timestamp;
-            });
-
-            return None;
-        }
+    fn update(&mut self, cache: &mut B) -> Option<()> {
+        self.update_concurrently(&*cache)
+    }
+}
 
-        if let Some(channel) = cache.private_channels.get_mut(&self.channel_id) {
-            channel.with_mut(|c| {
+#[cfg(feature = "cache")]
+impl<B: CacheBackend + ?Sized> ConcurrentCacheUpdate<B> for ChannelPinsUpdateEvent {
+    fn update_concurrently(&mut self, cache: &B) -> Option<()> {
+        match cache.channel(self.channel_id) {
+            Some(Channel::Guild(channel)) => channel.with_mut(|c| {
                 c.last_pin_timestamp = self.last_pin_timestamp;
-            });
-
-            return None;
-        }
-
-        if let Some(group) = cache.groups.get_mut(&self.channel_id) {
-            group.with_mut(|c| {
+            }),
+            Some(Channel::Private(channel)) => channel.with_mut(|c| {
                 c.last_pin_timestamp = self.last_pin_timestamp;
-            });
-
-            return None;
+            }),
+            Some(Channel::Group(group)) => group.with_mut(|c| {
+                c.last_pin_timestamp = self.last_pin_timestamp;
+            }),
+            Some(Channel::Category(_)) | None => {},
         }
 
         None
@@ -221,14 +164,13 @@ pub struct ChannelRecipientAddEvent {
 }
 
 #[cfg(feature = "cache")]
-impl CacheUpdate for ChannelRecipientAddEvent {
+impl<B: CacheBackend + ?Sized> CacheUpdate<B> for ChannelRecipientAddEvent {
     type Output = ();
 
-    fn update(&mut self, cache: &mut Cache) -> Option<()> {
-        cache.update_user_entry(&self.user);
-        let user = Arc::clone(&cache.users[&self.user.id]);
+    fn update(&mut self, cache: &mut B) -> Option<()> {
+        let user = cache.insert_user(&self.user);
 
-        if let Some(group) = cache.groups.get_mut(&self.channel_id) {
+        if let Some(Channel::Group(group)) = cache.channel(self.channel_id) {
             group.write().recipients.insert(self.user.id, user);
         }
 
@@ -244,13 +186,20 @@ pub struct ChannelRecipientRemoveEvent {
 }
 
 #[cfg(feature = "cache")]
-impl CacheUpdate for ChannelRecipientRemoveEvent {
+impl<B: CacheBackend + ?Sized> CacheUpdate<B> for ChannelRecipientRemoveEvent {
     type Output = ();
 
-    fn update(&mut self, cache: &mut Cache) -> Option<()> {
-        cache.groups.get_mut(&self.channel_id).map(|group| {
-            group.with_mut(|g| g.recipients.remove(&self.user.id))
-        });
+    fn update(&mut self, cache: &mut B) -> Option<()> {
+        self.update_concurrently(&*cache)
+    }
+}
+
+#[cfg(feature = "cache")]
+impl<B: CacheBackend + ?Sized> ConcurrentCacheUpdate<B> for ChannelRecipientRemoveEvent {
+    fn update_concurrently(&mut self, cache: &B) -> Option<()> {
+        if let Some(Channel::Group(group)) = cache.channel(self.channel_id) {
+            group.with_mut(|g| g.recipients.remove(&self.user.id));
+        }
 
         None
     }
@@ -262,55 +211,35 @@ pub struct ChannelUpdateEvent {
 }
 
 #[cfg(feature = "cache")]
-impl CacheUpdate for ChannelUpdateEvent {
+impl<B: CacheBackend + ?Sized> CacheUpdate<B> for ChannelUpdateEvent {
     type Output = ();
 
-    fn update(&mut self, cache: &mut Cache) -> Option<()> {
-        match self.channel {
-            Channel::Group(ref group) => {
-                let (ch_id, no_recipients) =
-                    group.with(|g| (g.channel_id, g.recipients.is_empty()));
-
-                match cache.groups.entry(ch_id) {
-                    Entry::Vacant(e) => {
-                        e.insert(Arc::clone(group));
-                    },
-                    Entry::Occupied(mut e) => {
-                        let mut dest = e.get_mut().write();
-
-                        if no_recipients {
-                            let recipients = mem::replace(&mut dest.recipients, HashMap::new());
-
-                            dest.clone_from(&group.read());
-
-                            dest.recipients = recipients;
-                        } else {
-                            dest.clone_from(&group.read());
-                        }
-                    },
-                }
-            },
-            Channel::Guild(ref channel) => {
-                let (guild_id, channel_id) = channel.with(|channel| (channel.guild_id, channel.id));
+    fn update(&mut self, cache: &mut B) -> Option<()> {
+        let cached = cache.channel(self.channel.id());
+
+        match (&self.channel, cached) {
+            (&Channel::Group(ref group), Some(Channel::Group(cached))) => {
+                let no_recipients = group.with(|g| g.recipients.is_empty());
+                let mut dest = cached.write();
+
+                if no_recipients {
+                    let recipients = mem::replace(&mut dest.recipients, HashMap::new());
 
-                cache.channels.insert(channel_id, Arc::clone(channel));
+                    dest.clone_from(&group.read());
 
-                if let Some(guild) = cache.guilds.get_mut(&guild_id) {
-                    guild
-                        .with_mut(|g| g.channels.insert(channel_id, Arc::clone(channel)));
+                    dest.recipients = recipients;
+                } else {
+                    dest.clone_from(&group.read());
                 }
             },
-            Channel::Private(ref channel) => {
-                if let Some(private) = cache.private_channels.get_mut(&channel.read().id) {
-                    private.clone_from(channel);
-                }
+            (&Channel::Group(_), _) | (&Channel::Guild(_), _) => {
+                cache.insert_channel(self.channel.clone());
             },
-            Channel::Category(ref category) => {
-                cache
-                    .categories
-                    .get_mut(&category.read().id)
-                    .map(|c| c.clone_from(category));
+            (&Channel::Private(_), Some(Channel::Private(_))) |
+            (&Channel::Category(_), Some(Channel::Category(_))) => {
+                cache.insert_channel(self.channel.clone());
             },
+            (&Channel::Private(_), _) | (&Channel::Category(_), _) => {},
         }
 
         None
@@ -350,25 +279,13 @@ pub struct GuildCreateEvent {
 }
 
 #[cfg(feature = "cache")]
-impl CacheUpdate for GuildCreateEvent {
+impl<B: CacheBackend + ?Sized> CacheUpdate<B> for GuildCreateEvent {
     type Output = ();
 
-    fn update(&mut self, cache: &mut Cache) -> Option<()> {
-        cache.unavailable_guilds.remove(&self.guild.id);
-
-        let mut guild = self.guild.clone();
-
-        for (user_id, member) in &mut guild.members {
-            cache.update_user_entry(&member.user.read());
-            let user = Arc::clone(&cache.users[user_id]);
-
-            member.user = Arc::clone(&user);
-        }
-
-        cache.channels.extend(guild.channels.clone());
-        cache
-            .guilds
-            .insert(self.guild.id, Arc::new(RwLock::new(guild)));
+    fn update(&mut self, cache: &mut B) -> Option<()> {
+        cache.set_guild_unavailable(self.guild.id, false);
+        cache.set_guild_stale(self.guild.id, false);
+        cache.insert_guild(self.guild.clone());
 
         None
     }
@@ -395,22 +312,13 @@ pub struct GuildDeleteEvent {
 }
 
 #[cfg(feature = "cache")]
-impl CacheUpdate for GuildDeleteEvent {
+impl<B: CacheBackend + ?Sized> CacheUpdate<B> for GuildDeleteEvent {
     type Output = Arc<RwLock<Guild>>;
 
-    fn update(&mut self, cache: &mut Cache) -> Option<Self::Output> {
-        // Remove channel entries for the guild if the guild is found.
-        cache.guilds.remove(&self.guild.id).map(|guild| {
-            for channel_id in guild.write().channels.keys() {
-                // Remove the channel from the cache.
-                cache.channels.remove(channel_id);
-
-                // Remove the channel's cached messages.
-                cache.messages.remove(channel_id);
-            }
+    fn update(&mut self, cache: &mut B) -> Option<Self::Output> {
+        cache.set_guild_stale(self.guild.id, false);
 
-            guild
-        })
+        cache.remove_guild(self.guild.id).map(|guild| Arc::new(RwLock::new(guild)))
     }
 }
 
@@ -436,15 +344,18 @@ pub struct GuildEmojisUpdateEvent {
 }
 
 #[cfg(feature = "cache")]
-impl CacheUpdate for GuildEmojisUpdateEvent {
+impl<B: CacheBackend + ?Sized> CacheUpdate<B> for GuildEmojisUpdateEvent {
     type Output = ();
 
-    fn update(&mut self, cache: &mut Cache) -> Option<()> {
-        if let Some(guild) = cache.guilds.get_mut(&self.guild_id) {
-            guild.with_mut(|g| {
-                g.emojis.clone_from(&self.emojis)
-            });
-        }
+    fn update(&mut self, cache: &mut B) -> Option<()> {
+        self.update_concurrently(&*cache)
+    }
+}
+
+#[cfg(feature = "cache")]
+impl<B: CacheBackend + ?Sized> ConcurrentCacheUpdate<B> for GuildEmojisUpdateEvent {
+    fn update_concurrently(&mut self, cache: &B) -> Option<()> {
+        cache.set_guild_emojis(self.guild_id, self.emojis.clone());
 
         None
     }
@@ -462,21 +373,16 @@ pub struct GuildMemberAddEvent {
 }
 
 #[cfg(feature = "cache")]
-impl CacheUpdate for GuildMemberAddEvent {
+impl<B: CacheBackend + ?Sized> CacheUpdate<B> for GuildMemberAddEvent {
     type Output = ();
 
-    fn update(&mut self, cache: &mut Cache) -> Option<()> {
-        let user_id = self.member.user.with(|u| u.id);
-        cache.update_user_entry(&self.member.user.read());
+    fn update(&mut self, cache: &mut B) -> Option<()> {
+        let user = cache.insert_user(&self.member.user.read());
 
-        // Always safe due to being inserted above.
-        self.member.user = Arc::clone(&cache.users[&user_id]);
+        self.member.user = user;
 
-        if let Some(guild) = cache.guilds.get_mut(&self.guild_id) {
-            guild.with_mut(|guild| {
-                guild.member_count += 1;
-                guild.members.insert(user_id, self.member.clone());
-            });
+        if cache.add_member_count(self.guild_id, 1).is_some() {
+            cache.insert_member(self.guild_id, self.member.clone());
         }
 
         None
@@ -507,16 +413,13 @@ pub struct GuildMemberRemoveEvent {
 }
 
 #[cfg(feature = "cache")]
-impl CacheUpdate for GuildMemberRemoveEvent {
+impl<B: CacheBackend + ?Sized> CacheUpdate<B> for GuildMemberRemoveEvent {
     type Output = Member;
 
-    fn update(&mut self, cache: &mut Cache) -> Option<Self::Output> {
-        cache.guilds.get_mut(&self.guild_id).and_then(|guild| {
-            guild.with_mut(|guild| {
-                guild.member_count -= 1;
-                guild.members.remove(&self.user.id)
-            })
-        })
+    fn update(&mut self, cache: &mut B) -> Option<Self::Output> {
+        cache.add_member_count(self.guild_id, -1)?;
+
+        cache.remove_member(self.guild_id, self.user.id)
     }
 }
 
@@ -529,50 +432,34 @@ pub struct GuildMemberUpdateEvent {
 }
 
 #[cfg(feature = "cache")]
-impl CacheUpdate for GuildMemberUpdateEvent {
+impl<B: CacheBackend + ?Sized> CacheUpdate<B> for GuildMemberUpdateEvent {
     type Output = Member;
 
-    fn update(&mut self, cache: &mut Cache) -> Option<Self::Output> {
-        cache.update_user_entry(&self.user);
-
-        if let Some(guild) = cache.guilds.get_mut(&self.guild_id) {
-            let mut guild = guild.write();
-
-            let mut found = false;
-
-            let item = if let Some(member) = guild.members.get_mut(&self.user.id) {
-                let item = Some(member.clone());
+    fn update(&mut self, cache: &mut B) -> Option<Self::Output> {
+        let user = cache.insert_user(&self.user);
+        let item = cache.member(self.guild_id, self.user.id);
 
+        let member = match item.clone() {
+            Some(mut member) => {
                 member.nick.clone_from(&self.nick);
                 member.roles.clone_from(&self.roles);
-                member.user.write().clone_from(&self.user);
 
-                found = true;
-
-                item
-            } else {
-                None
-            };
+                member
+            },
+            None => Member {
+                deaf: false,
+                guild_id: self.guild_id,
+                joined_at: None,
+                mute: false,
+                nick: self.nick.clone(),
+                roles: self.roles.clone(),
+                user,
+            },
+        };
 
-            if !found {
-                guild.members.insert(
-                    self.user.id,
-                    Member {
-                        deaf: false,
-                        guild_id: self.guild_id,
-                        joined_at: None,
-                        mute: false,
-                        nick: self.nick.clone(),
-                        roles: self.roles.clone(),
-                        user: Arc::new(RwLock::new(self.user.clone())),
-                    },
-                );
-            }
+        cache.insert_member(self.guild_id, member);
 
-            item
-        } else {
-            None
-        }
+        item
     }
 }
 
@@ -583,16 +470,18 @@ pub struct GuildMembersChunkEvent {
 }
 
 #[cfg(feature = "cache")]
-impl CacheUpdate for GuildMembersChunkEvent {
+impl<B: CacheBackend + ?Sized> CacheUpdate<B> for GuildMembersChunkEvent {
     type Output = ();
 
-    fn update(&mut self, cache: &mut Cache) -> Option<()> {
-        for member in self.members.values() {
-            cache.update_user_entry(&member.user.read());
+    fn update(&mut self, cache: &mut B) -> Option<()> {
+        for member in self.members.values_mut() {
+            let user = cache.insert_user(&member.user.read());
+
+            member.user = user;
         }
 
-        if let Some(guild) = cache.guilds.get_mut(&self.guild_id) {
-            guild.with_mut(|g| g.members.extend(self.members.clone()))
+        for member in self.members.values() {
+            cache.insert_member(self.guild_id, member.clone());
         }
 
         None
@@ -647,16 +536,18 @@ pub struct GuildRoleCreateEvent {
 }
 
 #[cfg(feature = "cache")]
-impl CacheUpdate for GuildRoleCreateEvent {
+impl<B: CacheBackend + ?Sized> CacheUpdate<B> for GuildRoleCreateEvent {
     type Output = ();
 
-    fn update(&mut self, cache: &mut Cache) -> Option<()> {
-        cache.guilds.get_mut(&self.guild_id).map(|guild| {
-            guild
-                .write()
-                .roles
-                .insert(self.role.id, self.role.clone())
-        });
+    fn update(&mut self, cache: &mut B) -> Option<()> {
+        self.update_concurrently(&*cache)
+    }
+}
+
+#[cfg(feature = "cache")]
+impl<B: CacheBackend + ?Sized> ConcurrentCacheUpdate<B> for GuildRoleCreateEvent {
+    fn update_concurrently(&mut self, cache: &B) -> Option<()> {
+        cache.insert_role(self.guild_id, self.role.clone());
 
         None
     }
@@ -669,14 +560,18 @@ pub struct GuildRoleDeleteEvent {
 }
 
 #[cfg(feature = "cache")]
-impl CacheUpdate for GuildRoleDeleteEvent {
+impl<B: CacheBackend + ?Sized> CacheUpdate<B> for GuildRoleDeleteEvent {
     type Output = Role;
 
-    fn update(&mut self, cache: &mut Cache) -> Option<Self::Output> {
-        cache
-            .guilds
-            .get_mut(&self.guild_id)
-            .and_then(|guild| guild.with_mut(|g| g.roles.remove(&self.role_id)))
+    fn update(&mut self, cache: &mut B) -> Option<Self::Output> {
+        self.update_concurrently(&*cache)
+    }
+}
+
+#[cfg(feature = "cache")]
+impl<B: CacheBackend + ?Sized> ConcurrentCacheUpdate<B> for GuildRoleDeleteEvent {
+    fn update_concurrently(&mut self, cache: &B) -> Option<Self::Output> {
+        cache.remove_role(self.guild_id, self.role_id)
     }
 }
 
@@ -687,17 +582,20 @@ pub struct GuildRoleUpdateEvent {
 }
 
 #[cfg(feature = "cache")]
-impl CacheUpdate for GuildRoleUpdateEvent {
+impl<B: CacheBackend + ?Sized> CacheUpdate<B> for GuildRoleUpdateEvent {
     type Output = Role;
 
-    fn update(&mut self, cache: &mut Cache) -> Option<Self::Output> {
-        cache.guilds.get_mut(&self.guild_id).and_then(|guild| {
-            guild.with_mut(|g| {
-                g.roles
-                    .get_mut(&self.role.id)
-                    .map(|role| mem::replace(role, self.role.clone()))
-            })
-        })
+    fn update(&mut self, cache: &mut B) -> Option<Self::Output> {
+        self.update_concurrently(&*cache)
+    }
+}
+
+#[cfg(feature = "cache")]
+impl<B: CacheBackend + ?Sized> ConcurrentCacheUpdate<B> for GuildRoleUpdateEvent {
+    fn update_concurrently(&mut self, cache: &B) -> Option<Self::Output> {
+        cache.role(self.guild_id, self.role.id)?;
+
+        cache.insert_role(self.guild_id, self.role.clone())
     }
 }
 
@@ -707,12 +605,13 @@ pub struct GuildUnavailableEvent {
 }
 
 #[cfg(feature = "cache")]
-impl CacheUpdate for GuildUnavailableEvent {
+impl<B: CacheBackend + ?Sized> CacheUpdate<B> for GuildUnavailableEvent {
     type Output = ();
 
-    fn update(&mut self, cache: &mut Cache) -> Option<()> {
-        cache.unavailable_guilds.insert(self.guild_id);
-        cache.guilds.remove(&self.guild_id);
+    fn update(&mut self, cache: &mut B) -> Option<()> {
+        cache.set_guild_unavailable(self.guild_id, true);
+        cache.set_guild_stale(self.guild_id, false);
+        cache.remove_unavailable_guild(self.guild_id);
 
         None
     }
@@ -724,22 +623,18 @@ pub struct GuildUpdateEvent {
 }
 
 #[cfg(feature = "cache")]
-impl CacheUpdate for GuildUpdateEvent {
+impl<B: CacheBackend + ?Sized> CacheUpdate<B> for GuildUpdateEvent {
     type Output = ();
 
-    fn update(&mut self, cache: &mut Cache) -> Option<()> {
-        if let Some(guild) = cache.guilds.get_mut(&self.guild.id) {
-            let mut guild = guild.write();
-
-            guild.afk_timeout = self.guild.afk_timeout;
-            guild.afk_channel_id.clone_from(&self.guild.afk_channel_id);
-            guild.icon.clone_from(&self.guild.icon);
-            guild.name.clone_from(&self.guild.name);
-            guild.owner_id.clone_from(&self.guild.owner_id);
-            guild.region.clone_from(&self.guild.region);
-            guild.roles.clone_from(&self.guild.roles);
-            guild.verification_level = self.guild.verification_level;
-        }
+    fn update(&mut self, cache: &mut B) -> Option<()> {
+        self.update_concurrently(&*cache)
+    }
+}
+
+#[cfg(feature = "cache")]
+impl<B: CacheBackend + ?Sized> ConcurrentCacheUpdate<B> for GuildUpdateEvent {
+    fn update_concurrently(&mut self, cache: &B) -> Option<()> {
+        cache.update_guild(&self.guild);
 
         None
     }
@@ -766,36 +661,19 @@ pub struct MessageCreateEvent {
 }
 
 #[cfg(feature = "cache")]
-impl CacheUpdate for MessageCreateEvent {
+impl<B: CacheBackend + ?Sized> CacheUpdate<B> for MessageCreateEvent {
     /// The oldest message, if the channel's message cache was already full.
     type Output = Message;
 
-    fn update(&mut self, cache: &mut Cache) -> Option<Self::Output> {
-        let max = cache.settings().max_messages;
-
-        if max == 0 {
-            return None;
-        }
-
-        let messages = cache.messages
-            .entry(self.message.channel_id)
-            .or_insert_with(Default::default);
-        let queue = cache.message_queue
-            .entry(self.message.channel_id)
-            .or_insert_with(Default::default);
-
-        let mut removed_msg = None;
-
-        if messages.len() == max {
-            if let Some(id) = queue.pop_front() {
-                removed_msg = messages.remove(&id);
-            }
-        }
-
-        queue.push_back(self.message.id);
-        messages.insert(self.message.id, self.message.clone());
+    fn update(&mut self, cache: &mut B) -> Option<Self::Output> {
+        self.update_concurrently(&*cache)
+    }
+}
 
-        removed_msg
+#[cfg(feature = "cache")]
+impl<B: CacheBackend + ?Sized> ConcurrentCacheUpdate<B> for MessageCreateEvent {
+    fn update_concurrently(&mut self, cache: &B) -> Option<Self::Output> {
+        cache.insert_message(self.message.clone())
     }
 }
 
@@ -820,12 +698,51 @@ pub struct MessageDeleteBulkEvent {
     pub ids: Vec<MessageId>,
 }
 
+#[cfg(feature = "cache")]
+impl<B: CacheBackend + ?Sized> CacheUpdate<B> for MessageDeleteBulkEvent {
+    /// The deleted messages which were in the cache.
+    type Output = Vec<Message>;
+
+    fn update(&mut self, cache: &mut B) -> Option<Self::Output> {
+        self.update_concurrently(&*cache)
+    }
+}
+
+#[cfg(feature = "cache")]
+impl<B: CacheBackend + ?Sized> ConcurrentCacheUpdate<B> for MessageDeleteBulkEvent {
+    fn update_concurrently(&mut self, cache: &B) -> Option<Self::Output> {
+        let removed = self.ids
+            .iter()
+            .filter_map(|id| cache.remove_message(self.channel_id, *id))
+            .collect::<Vec<_>>();
+
+        Some(removed)
+    }
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Serialize)]
 pub struct MessageDeleteEvent {
     pub channel_id: ChannelId,
     #[serde(rename = "id")] pub message_id: MessageId,
 }
 
+#[cfg(feature = "cache")]
+impl<B: CacheBackend + ?Sized> CacheUpdate<B> for MessageDeleteEvent {
+    /// The deleted message, if it was in the cache.
+    type Output = Message;
+
+    fn update(&mut self, cache: &mut B) -> Option<Self::Output> {
+        self.update_concurrently(&*cache)
+    }
+}
+
+#[cfg(feature = "cache")]
+impl<B: CacheBackend + ?Sized> ConcurrentCacheUpdate<B> for MessageDeleteEvent {
+    fn update_concurrently(&mut self, cache: &B) -> Option<Self::Output> {
+        cache.remove_message(self.channel_id, self.message_id)
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct MessageUpdateEvent {
     pub id: MessageId,
@@ -846,12 +763,20 @@ pub struct MessageUpdateEvent {
 }
 
 #[cfg(feature = "cache")]
-impl CacheUpdate for MessageUpdateEvent {
-    type Output = ();
+impl<B: CacheBackend + ?Sized> CacheUpdate<B> for MessageUpdateEvent {
+    /// The message as it was before the update, if it was in the cache.
+    type Output = Message;
+
+    fn update(&mut self, cache: &mut B) -> Option<Self::Output> {
+        self.update_concurrently(&*cache)
+    }
+}
 
-    fn update(&mut self, cache: &mut Cache) -> Option<Self::Output> {
-        let messages = cache.messages.get_mut(&self.channel_id)?;
-        let message = messages.get_mut(&self.id)?;
+#[cfg(feature = "cache")]
+impl<B: CacheBackend + ?Sized> ConcurrentCacheUpdate<B> for MessageUpdateEvent {
+    fn update_concurrently(&mut self, cache: &B) -> Option<Self::Output> {
+        let mut message = cache.message(self.channel_id, self.id)?;
+        let old = message.clone();
 
         if let Some(attachments) = self.attachments.clone() {
             message.attachments = attachments;
@@ -881,7 +806,9 @@ impl CacheUpdate for MessageUpdateEvent {
             message.pinned = pinned;
         }
 
-        None
+        cache.update_message(message);
+
+        Some(old)
     }
 }
 
@@ -893,55 +820,43 @@ pub struct PresenceUpdateEvent {
 }
 
 #[cfg(feature = "cache")]
-impl CacheUpdate for PresenceUpdateEvent {
+impl<B: CacheBackend + ?Sized> CacheUpdate<B> for PresenceUpdateEvent {
     type Output = ();
 
-    fn update(&mut self, cache: &mut Cache) -> Option<()> {
+    fn update(&mut self, cache: &mut B) -> Option<()> {
         let user_id = self.presence.user_id;
 
         if let Some(user) = self.presence.user.as_mut() {
-            cache.update_user_entry(&user.read());
-            *user = Arc::clone(&cache.users[&user_id]);
+            let cached = cache.insert_user(&user.read());
+
+            *user = cached;
         }
 
-        if let Some(guild_id) = self.guild_id {
-            if let Some(guild) = cache.guilds.get_mut(&guild_id) {
-                let mut guild = guild.write();
+        // If the user went offline, remove them from the presence list.
+        if self.presence.status == OnlineStatus::Offline {
+            cache.remove_presence(self.guild_id, user_id);
+        } else {
+            cache.insert_presence(self.guild_id, self.presence.clone());
+        }
 
-                // If the member went offline, remove them from the presence list.
-                if self.presence.status == OnlineStatus::Offline {
-                    guild.presences.remove(&self.presence.user_id);
-                } else {
-                    guild
-                        .presences
-                        .insert(self.presence.user_id, self.presence.clone());
-                }
+        // Create a partial member instance out of the presence update data.
+        // This includes everything but `deaf`, `mute`, and `joined_at`.
+        if let Some(guild_id) = self.guild_id {
+            if cache.member(guild_id, user_id).is_none() {
+                if let Some(user) = self.presence.user.as_ref() {
+                    let roles = self.roles.clone().unwrap_or_default();
 
-                // Create a partial member instance out of the presence update
-                // data. This includes everything but `deaf`, `mute`, and
-                // `joined_at`.
-                if !guild.members.contains_key(&self.presence.user_id) {
-                    if let Some(user) = self.presence.user.as_ref() {
-                        let roles = self.roles.clone().unwrap_or_default();
-
-                        guild.members.insert(self.presence.user_id, Member {
-                            deaf: false,
-                            guild_id,
-                            joined_at: None,
-                            mute: false,
-                            nick: self.presence.nick.clone(),
-                            user: Arc::clone(&user),
-                            roles,
-                        });
-                    }
+                    cache.insert_member(guild_id, Member {
+                        deaf: false,
+                        guild_id,
+                        joined_at: None,
+                        mute: false,
+                        nick: self.presence.nick.clone(),
+                        user: Arc::clone(user),
+                        roles,
+                    });
                 }
             }
-        } else if self.presence.status == OnlineStatus::Offline {
-            cache.presences.remove(&self.presence.user_id);
-        } else {
-            cache
-                .presences
-                .insert(self.presence.user_id, self.presence.clone());
         }
 
         None
@@ -979,19 +894,13 @@ pub struct PresencesReplaceEvent {
 }
 
 #[cfg(feature = "cache")]
-impl CacheUpdate for PresencesReplaceEvent {
+impl<B: CacheBackend + ?Sized> CacheUpdate<B> for PresencesReplaceEvent {
     type Output = ();
 
-    fn update(&mut self, cache: &mut Cache) -> Option<()> {
-        cache.presences.extend({
-            let mut p: HashMap<UserId, Presence> = HashMap::default();
-
-            for presence in &self.presences {
-                p.insert(presence.user_id, presence.clone());
-            }
-
-            p
-        });
+    fn update(&mut self, cache: &mut B) -> Option<()> {
+        for presence in &self.presences {
+            cache.insert_presence(None, presence.clone());
+        }
 
         None
     }
@@ -1073,21 +982,47 @@ pub struct ReadyEvent {
 }
 
 #[cfg(feature = "cache")]
-impl CacheUpdate for ReadyEvent {
+impl<B: CacheBackend + ?Sized> CacheUpdate<B> for ReadyEvent {
     type Output = ();
 
-    fn update(&mut self, cache: &mut Cache) -> Option<()> {
-        let mut ready = self.ready.clone();
+    fn update(&mut self, cache: &mut B) -> Option<()> {
+        let ready = self.ready.clone();
+
+        cache.set_shard_count(ready.shard.map_or(1, |s| s[1]));
+        cache.set_current_user(ready.user.clone());
+
+        // Guilds loaded from a snapshot which belong to this shard, but are
+        // not sent in the ready, were left while the bot was offline.
+        let shard = ready.shard.unwrap_or([0, 1]);
+        let stale = cache.stale_guilds();
+        let left = stale
+            .iter()
+            .filter(|id| (id.0 >> 22) % shard[1] == shard[0])
+            .filter(|id| !ready.guilds.iter().any(|guild| match *guild {
+                GuildStatus::Offline(ref guild) => guild.id == **id,
+                GuildStatus::OnlineGuild(ref guild) => guild.id == **id,
+                GuildStatus::OnlinePartialGuild(ref guild) => guild.id == **id,
+            }));
+
+        for guild_id in left {
+            cache.set_guild_stale(*guild_id, false);
+            cache.remove_guild(*guild_id);
+        }
 
         for guild in ready.guilds {
             match guild {
                 GuildStatus::Offline(unavailable) => {
-                    cache.guilds.remove(&unavailable.id);
-                    cache.unavailable_guilds.insert(unavailable.id);
+                    // Keep guilds loaded from a snapshot until they are
+                    // received.
+                    if !stale.contains(&unavailable.id) {
+                        cache.remove_guild(unavailable.id);
+                    }
+
+                    cache.set_guild_unavailable(unavailable.id, true);
                 },
                 GuildStatus::OnlineGuild(guild) => {
-                    cache.unavailable_guilds.remove(&guild.id);
-                    cache.guilds.insert(guild.id, Arc::new(RwLock::new(guild)));
+                    cache.set_guild_unavailable(guild.id, false);
+                    cache.insert_guild(guild);
                 },
                 GuildStatus::OnlinePartialGuild(_) => {},
             }
@@ -1096,18 +1031,15 @@ impl CacheUpdate for ReadyEvent {
         // `ready.private_channels` will always be empty, and possibly be removed in the future.
         // So don't handle it at all.
 
-        for (user_id, presence) in &mut ready.presences {
-            if let Some(ref user) = presence.user {
-                cache.update_user_entry(&user.read());
-            }
+        for (user_id, mut presence) in ready.presences {
+            presence.user = match presence.user.take() {
+                Some(user) => Some(cache.insert_user(&user.read())),
+                None => cache.user(user_id).map(|user| cache.insert_user(&user)),
+            };
 
-            presence.user = cache.users.get(user_id).cloned();
+            cache.insert_presence(None, presence);
         }
 
-        cache.presences.extend(ready.presences);
-        cache.shard_count = ready.shard.map_or(1, |s| s[1]);
-        cache.user = ready.user;
-
         None
     }
 }
@@ -1151,11 +1083,11 @@ pub struct UserUpdateEvent {
 }
 
 #[cfg(feature = "cache")]
-impl CacheUpdate for UserUpdateEvent {
+impl<B: CacheBackend + ?Sized> CacheUpdate<B> for UserUpdateEvent {
     type Output = CurrentUser;
 
-    fn update(&mut self, cache: &mut Cache) -> Option<Self::Output> {
-        Some(mem::replace(&mut cache.user, self.current_user.clone()))
+    fn update(&mut self, cache: &mut B) -> Option<Self::Output> {
+        Some(cache.set_current_user(self.current_user.clone()))
     }
 }
 
@@ -1189,39 +1121,24 @@ pub struct VoiceStateUpdateEvent {
 }
 
 #[cfg(feature = "cache")]
-impl CacheUpdate for VoiceStateUpdateEvent {
-    type Output = ();
-
-    fn update(&mut self, cache: &mut Cache) -> Option<()> {
-        if let Some(guild_id) = self.guild_id {
-            if let Some(guild) = cache.guilds.get_mut(&guild_id) {
-                let mut guild = guild.write();
+impl<B: CacheBackend + ?Sized> CacheUpdate<B> for VoiceStateUpdateEvent {
+    type Output = VoiceState;
 
-                if self.voice_state.channel_id.is_some() {
-                    // Update or add to the voice state list
-                    {
-                        let finding = guild.voice_states.get_mut(&self.voice_state.user_id);
-
-                        if let Some(srv_state) = finding {
-                            srv_state.clone_from(&self.voice_state);
-
-                            return None;
-                        }
-                    }
+    fn update(&mut self, cache: &mut B) -> Option<VoiceState> {
+        self.update_concurrently(&*cache)
+    }
+}
 
-                    guild
-                        .voice_states
-                        .insert(self.voice_state.user_id, self.voice_state.clone());
-                } else {
-                    // Remove the user from the voice state list
-                    guild.voice_states.remove(&self.voice_state.user_id);
-                }
-            }
+#[cfg(feature = "cache")]
+impl<B: CacheBackend + ?Sized> ConcurrentCacheUpdate<B> for VoiceStateUpdateEvent {
+    fn update_concurrently(&mut self, cache: &B) -> Option<VoiceState> {
+        let guild_id = self.guild_id?;
 
-            return None;
+        if self.voice_state.channel_id.is_some() {
+            cache.insert_voice_state(guild_id, self.voice_state.clone())
+        } else {
+            cache.remove_voice_state(guild_id, self.voice_state.user_id)
         }
-
-        None
     }
 }
 
@@ -1446,6 +1363,54 @@ pub enum Event {
     Unknown(UnknownEvent),
 }
 
+impl Event {
+    /// Retrieves the [`EventType`] of the event.
+    ///
+    /// [`EventType`]: enum.EventType.html
+    pub fn event_type(&self) -> EventType {
+        match *self {
+            Event::ChannelCreate(_) => EventType::ChannelCreate,
+            Event::ChannelDelete(_) => EventType::ChannelDelete,
+            Event::ChannelPinsUpdate(_) => EventType::ChannelPinsUpdate,
+            Event::ChannelRecipientAdd(_) => EventType::ChannelRecipientAdd,
+            Event::ChannelRecipientRemove(_) => EventType::ChannelRecipientRemove,
+            Event::ChannelUpdate(_) => EventType::ChannelUpdate,
+            Event::GuildBanAdd(_) => EventType::GuildBanAdd,
+            Event::GuildBanRemove(_) => EventType::GuildBanRemove,
+            Event::GuildCreate(_) => EventType::GuildCreate,
+            Event::GuildDelete(_) => EventType::GuildDelete,
+            Event::GuildEmojisUpdate(_) => EventType::GuildEmojisUpdate,
+            Event::GuildIntegrationsUpdate(_) => EventType::GuildIntegrationsUpdate,
+            Event::GuildMemberAdd(_) => EventType::GuildMemberAdd,
+            Event::GuildMemberRemove(_) => EventType::GuildMemberRemove,
+            Event::GuildMemberUpdate(_) => EventType::GuildMemberUpdate,
+            Event::GuildMembersChunk(_) => EventType::GuildMembersChunk,
+            Event::GuildRoleCreate(_) => EventType::GuildRoleCreate,
+            Event::GuildRoleDelete(_) => EventType::GuildRoleDelete,
+            Event::GuildRoleUpdate(_) => EventType::GuildRoleUpdate,
+            Event::GuildUnavailable(_) => EventType::GuildUnavailable,
+            Event::GuildUpdate(_) => EventType::GuildUpdate,
+            Event::MessageCreate(_) => EventType::MessageCreate,
+            Event::MessageDelete(_) => EventType::MessageDelete,
+            Event::MessageDeleteBulk(_) => EventType::MessageDeleteBulk,
+            Event::MessageUpdate(_) => EventType::MessageUpdate,
+            Event::PresenceUpdate(_) => EventType::PresenceUpdate,
+            Event::PresencesReplace(_) => EventType::PresencesReplace,
+            Event::ReactionAdd(_) => EventType::ReactionAdd,
+            Event::ReactionRemove(_) => EventType::ReactionRemove,
+            Event::ReactionRemoveAll(_) => EventType::ReactionRemoveAll,
+            Event::Ready(_) => EventType::Ready,
+            Event::Resumed(_) => EventType::Resumed,
+            Event::TypingStart(_) => EventType::TypingStart,
+            Event::UserUpdate(_) => EventType::UserUpdate,
+            Event::VoiceStateUpdate(_) => EventType::VoiceStateUpdate,
+            Event::VoiceServerUpdate(_) => EventType::VoiceServerUpdate,
+            Event::WebhookUpdate(_) => EventType::WebhookUpdate,
+            Event::Unknown(ref unknown) => EventType::Other(unknown.kind.clone()),
+        }
+    }
+}
+
 /// Deserializes a `serde_json::Value` into an `Event`.
 ///
 /// The given `EventType` is used to determine what event to deserialize into.
@@ -1580,7 +1545,7 @@ pub fn deserialize_event_with_type(kind: EventType, v: Value) -> Result<Event> {
 /// [`EventType::ChannelCreate`].
 ///
 /// [`EventType::ChannelCreate`]: enum.EventType.html#variant.ChannelCreate
-#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
 pub enum EventType {
     /// Indicator that a channel create payload was received.
     ///
@@ -29,14 +29,49 @@ pub struct BotGateway {
 /// Representation of a game that a [`User`] is playing -- or streaming in the
 /// case that a stream URL is provided.
 ///
+/// Besides its name, a game may carry rich presence information, such as the
+/// current [`details`] and [`state`] of the game, its [`party`], or its
+/// [`assets`]. Only the fields that are set are sent to the gateway.
+///
 /// [`User`]: ../user/struct.User.html
-#[derive(Clone, Debug, Serialize)]
+/// [`assets`]: #structfield.assets
+/// [`details`]: #structfield.details
+/// [`party`]: #structfield.party
+/// [`state`]: #structfield.state
+#[derive(Clone, Debug, Default, Serialize)]
 pub struct Game {
+    /// The Id of the application that the game belongs to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub application_id: Option<ApplicationId>,
+    /// The images of the game and their hover texts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assets: Option<ActivityAssets>,
+    /// What the user is currently doing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    /// The flags describing what the payload of the game includes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flags: Option<ActivityFlags>,
+    /// Whether the game is an instanced game session.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<bool>,
     /// The type of game status.
     #[serde(default, rename = "type")]
     pub kind: GameType,
     /// The name of the game being played.
     pub name: String,
+    /// Information about the party of the user.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub party: Option<ActivityParty>,
+    /// The secrets used to join or spectate the user's game.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secrets: Option<ActivitySecrets>,
+    /// The user's current party status.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    /// The times at which the game started and will end.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamps: Option<ActivityTimestamps>,
     /// The Stream URL if [`kind`] is [`GameType::Streaming`].
     ///
     /// [`GameType::Streaming`]: enum.GameType.html#variant.Streaming
@@ -74,6 +109,7 @@ impl Game {
             kind: GameType::Playing,
             name: name.to_string(),
             url: None,
+            ..Default::default()
         }
     }
 
@@ -107,6 +143,7 @@ impl Game {
             kind: GameType::Streaming,
             name: name.to_string(),
             url: Some(url.to_string()),
+            ..Default::default()
         }
     }
 
@@ -138,6 +175,7 @@ impl Game {
             kind: GameType::Listening,
             name: name.to_string(),
             url: None,
+            ..Default::default()
         }
     }
 }
@@ -148,6 +186,7 @@ impl<'a> From<&'a str> for Game {
             kind: GameType::Playing,
             name: name.to_owned(),
             url: None,
+            ..Default::default()
         }
     }
 }
@@ -158,6 +197,7 @@ impl From<String> for Game {
             kind: GameType::Playing,
             url: None,
             name,
+            ..Default::default()
         }
     }
 }
@@ -168,6 +208,7 @@ impl<'a> From<(String, GameType)> for Game {
             url: None,
             kind,
             name,
+            ..Default::default()
         }
     }
 }
@@ -178,6 +219,7 @@ impl<'a> From<(&'a str, &'a str)> for Game {
             kind: GameType::Streaming,
             name: name.to_owned(),
             url: Some(url.to_owned()),
+            ..Default::default()
         }
     }
 }
@@ -188,6 +230,7 @@ impl From<(String, String)> for Game {
             kind: GameType::Streaming,
             url: Some(url),
             name,
+            ..Default::default()
         }
     }
 }
@@ -198,6 +241,7 @@ impl From<(String, GameType, String)> for Game {
             url: Some(url),
             kind,
             name,
+            ..Default::default()
         }
     }
 }
@@ -205,24 +249,146 @@ impl From<(String, GameType, String)> for Game {
 impl<'de> Deserialize<'de> for Game {
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> StdResult<Self, D::Error> {
         let mut map = JsonMap::deserialize(deserializer)?;
+        let application_id = map.remove("application_id")
+            .and_then(|v| serde_json::from_value::<ApplicationId>(v).ok());
+        let assets = map.remove("assets")
+            .and_then(|v| serde_json::from_value::<ActivityAssets>(v).ok());
+        let details = map.remove("details")
+            .and_then(|v| serde_json::from_value::<String>(v).ok());
+        let flags = map.remove("flags")
+            .and_then(|v| serde_json::from_value::<ActivityFlags>(v).ok());
+        let instance = map.remove("instance")
+            .and_then(|v| serde_json::from_value::<bool>(v).ok());
         let kind = map.remove("type")
             .and_then(|v| GameType::deserialize(v).ok())
             .unwrap_or(GameType::Playing);
         let name = map.remove("name")
             .and_then(|v| String::deserialize(v).ok())
             .unwrap_or_else(String::new);
+        let party = map.remove("party")
+            .and_then(|v| serde_json::from_value::<ActivityParty>(v).ok());
+        let secrets = map.remove("secrets")
+            .and_then(|v| serde_json::from_value::<ActivitySecrets>(v).ok());
+        let state = map.remove("state")
+            .and_then(|v| serde_json::from_value::<String>(v).ok());
+        let timestamps = map.remove("timestamps")
+            .and_then(|v| serde_json::from_value::<ActivityTimestamps>(v).ok());
         let url = map.remove("url")
             .and_then(|v| serde_json::from_value::<String>(v).ok());
 
         Ok(Game {
+            application_id,
+            assets,
+            details,
+            flags,
+            instance,
             kind,
             name,
+            party,
+            secrets,
+            state,
+            timestamps,
             url,
         })
     }
 }
 
+/// The images of a [`Game`] and their hover texts.
+///
+/// [`Game`]: struct.Game.html
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ActivityAssets {
+    /// The Id of the large image.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub large_image: Option<String>,
+    /// The text displayed when hovering over the large image.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub large_text: Option<String>,
+    /// The Id of the small image.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub small_image: Option<String>,
+    /// The text displayed when hovering over the small image.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub small_text: Option<String>,
+}
+
+bitflags! {
+    /// A set of flags describing what the payload of a [`Game`] includes.
+    ///
+    /// [`Game`]: struct.Game.html
+    pub struct ActivityFlags: u64 {
+        /// Whether the game is an instanced game session.
+        const INSTANCE = 1;
+        /// Whether the game can be joined.
+        const JOIN = 1 << 1;
+        /// Whether the game can be spectated.
+        const SPECTATE = 1 << 2;
+        /// Whether a request to join the game can be sent.
+        const JOIN_REQUEST = 1 << 3;
+        /// Whether the game is synced, such as a song.
+        const SYNC = 1 << 4;
+        /// Whether the game can be played.
+        const PLAY = 1 << 5;
+    }
+}
 
+impl<'de> Deserialize<'de> for ActivityFlags {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> StdResult<Self, D::Error> {
+        Ok(ActivityFlags::from_bits_truncate(
+            deserializer.deserialize_u64(U64Visitor)?,
+        ))
+    }
+}
+
+impl Serialize for ActivityFlags {
+    fn serialize<S>(&self, serializer: S) -> StdResult<S::Ok, S::Error>
+        where S: Serializer {
+        serializer.serialize_u64(self.bits())
+    }
+}
+
+/// Information about the party of a [`Game`].
+///
+/// [`Game`]: struct.Game.html
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ActivityParty {
+    /// The Id of the party.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// The current and maximum size of the party.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<[u64; 2]>,
+}
+
+/// The secrets used to join or spectate a [`Game`].
+///
+/// [`Game`]: struct.Game.html
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ActivitySecrets {
+    /// The secret for joining the party.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub join: Option<String>,
+    /// The secret for a specific instanced match.
+    #[serde(rename = "match", skip_serializing_if = "Option::is_none")]
+    pub match_: Option<String>,
+    /// The secret for spectating the game.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spectate: Option<String>,
+}
+
+/// The times at which a [`Game`] started and will end, as Unix timestamps in
+/// milliseconds.
+///
+/// [`Game`]: struct.Game.html
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub struct ActivityTimestamps {
+    /// When the game ends.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<u64>,
+    /// When the game started.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<u64>,
+}
 
 /// The type of activity that is being performed when playing a game.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
@@ -275,6 +441,11 @@ pub struct Gateway {
 /// [`User`]: ../user/struct.User.html
 #[derive(Clone, Debug)]
 pub struct Presence {
+    /// Every activity that a [`User`] is currently engaged in, in the order
+    /// given by Discord.
+    ///
+    /// [`User`]: ../user/struct.User.html
+    pub activities: Vec<Game>,
     /// The game that a [`User`] is current playing.
     ///
     /// [`User`]: ../user/struct.User.html
@@ -315,13 +486,29 @@ impl<'de> Deserialize<'de> for Presence {
             (user_id, None)
         };
 
-        let game = match map.remove("game") {
+        let mut game = match map.remove("game") {
             Some(v) => serde_json::from_value::<Option<Game>>(v)
                 .map_err(DeError::custom)?,
             None => None,
         };
+        let mut activities = match map.remove("activities") {
+            Some(v) => serde_json::from_value::<Option<Vec<Game>>>(v)
+                .map_err(DeError::custom)?
+                .unwrap_or_else(Vec::new),
+            None => Vec::new(),
+        };
+
+        // Older payloads only include the game, and newer ones may only
+        // include the activities, so fill in whichever is missing.
+        if activities.is_empty() {
+            activities.extend(game.iter().cloned());
+        } else if game.is_none() {
+            game = activities.first().cloned();
+        }
+
         let last_modified = match map.remove("last_modified") {
-            Some(v) => Some(u64::deserialize(v).map_err(DeError::custom)?),
+            Some(v) => serde_json::from_value::<Option<u64>>(v)
+                .map_err(DeError::custom)?,
             None => None,
         };
         let nick = match map.remove("nick") {
@@ -335,6 +522,7 @@ impl<'de> Deserialize<'de> for Presence {
             .map_err(DeError::custom)?;
 
         Ok(Presence {
+            activities,
             game,
             last_modified,
             nick,
@@ -353,7 +541,8 @@ impl Serialize for Presence {
             id: u64,
         }
 
-        let mut state = serializer.serialize_struct("Presence", 5)?;
+        let mut state = serializer.serialize_struct("Presence", 6)?;
+        state.serialize_field("activities", &self.activities)?;
         state.serialize_field("game", &self.game)?;
         state.serialize_field("last_modified", &self.last_modified)?;
         state.serialize_field("nick", &self.nick)?;
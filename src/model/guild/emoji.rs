@@ -15,7 +15,9 @@ use super::super::ModelError;
 #[cfg(all(feature = "cache", feature = "model"))]
 use super::super::id::GuildId;
 #[cfg(all(feature = "cache", feature = "model"))]
-use {CACHE, http};
+use parking_lot::RwLock;
+#[cfg(all(feature = "cache", feature = "model"))]
+use {Cache, http};
 
 /// Represents a custom guild emoji, which can either be created using the API,
 /// or via an integration. Emojis created using the API only work within the
@@ -46,7 +48,7 @@ pub struct Emoji {
 
 #[cfg(feature = "model")]
 impl Emoji {
-    /// Deletes the emoji.
+    /// Deletes the emoji, searching the given cache for the guild that owns it.
     ///
     /// **Note**: The [Manage Emojis] permission is required.
     ///
@@ -60,6 +62,7 @@ impl Emoji {
     /// Delete a given emoji:
     ///
     /// ```rust,no_run
+    /// # use serenity::CACHE;
     /// # use serenity::model::guild::Emoji;
     /// # use serenity::model::id::EmojiId;
     /// #
@@ -73,20 +76,21 @@ impl Emoji {
     /// # };
     /// #
     /// // assuming emoji has been set already
-    /// match emoji.delete() {
+    /// match emoji.delete(&CACHE) {
     ///     Ok(()) => println!("Emoji deleted."),
     ///     Err(_) => println!("Could not delete emoji.")
     /// }
     /// ```
     #[cfg(feature = "cache")]
-    pub fn delete(&self) -> Result<()> {
-        match self.find_guild_id() {
+    pub fn delete(&self, cache: &RwLock<Cache>) -> Result<()> {
+        match self.find_guild_id(cache) {
             Some(guild_id) => http::delete_emoji(guild_id.0, self.id.0),
             None => Err(Error::Model(ModelError::ItemMissing)),
         }
     }
 
-    /// Edits the emoji by updating it with a new name.
+    /// Edits the emoji by updating it with a new name, searching the given
+    /// cache for the guild that owns it.
     ///
     /// **Note**: The [Manage Emojis] permission is required.
     ///
@@ -99,6 +103,7 @@ impl Emoji {
     /// Change the name of an emoji:
     ///
     /// ```rust,no_run
+    /// # use serenity::CACHE;
     /// # use serenity::model::guild::Emoji;
     /// # use serenity::model::id::EmojiId;
     /// #
@@ -112,12 +117,12 @@ impl Emoji {
     /// # };
     /// #
     /// // assuming emoji has been set already
-    /// let _ = emoji.edit("blobuwu");
+    /// let _ = emoji.edit(&CACHE, "blobuwu");
     /// assert_eq!(emoji.name, "blobuwu");
     /// ```
     #[cfg(feature = "cache")]
-    pub fn edit(&mut self, name: &str) -> Result<()> {
-        match self.find_guild_id() {
+    pub fn edit(&mut self, cache: &RwLock<Cache>, name: &str) -> Result<()> {
+        match self.find_guild_id(cache) {
             Some(guild_id) => {
                 let map = json!({
                     "name": name,
@@ -136,7 +141,8 @@ impl Emoji {
         }
     }
 
-    /// Finds the [`Guild`] that owns the emoji by looking through the Cache.
+    /// Finds the [`Guild`] that owns the emoji by looking through the given
+    /// cache.
     ///
     /// [`Guild`]: struct.Guild.html
     ///
@@ -145,6 +151,7 @@ impl Emoji {
     /// Print the guild id that owns this emoji:
     ///
     /// ```rust,no_run
+    /// # use serenity::CACHE;
     /// # use serenity::model::guild::Emoji;
     /// # use serenity::model::id::EmojiId;
     /// #
@@ -158,13 +165,13 @@ impl Emoji {
     /// # };
     /// #
     /// // assuming emoji has been set already
-    /// if let Some(guild_id) = emoji.find_guild_id() {
+    /// if let Some(guild_id) = emoji.find_guild_id(&CACHE) {
     ///     println!("{} is owned by {}", emoji.name, guild_id);
     /// }
     /// ```
     #[cfg(feature = "cache")]
-    pub fn find_guild_id(&self) -> Option<GuildId> {
-        for guild in CACHE.read().guilds.values() {
+    pub fn find_guild_id(&self, cache: &RwLock<Cache>) -> Option<GuildId> {
+        for guild in cache.read().guilds.values() {
             let guild = guild.read();
 
             if guild.emojis.contains_key(&self.id) {
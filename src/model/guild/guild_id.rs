@@ -1,7 +1,7 @@
 use model::prelude::*;
 
 #[cfg(all(feature = "cache", feature = "model"))]
-use CACHE;
+use {CACHE, Cache};
 #[cfg(feature = "model")]
 use builder::{EditGuild, EditMember, EditRole};
 #[cfg(feature = "model")]
@@ -406,14 +406,16 @@ impl GuildId {
     /// Search the cache for the guild.
     #[cfg(feature = "cache")]
     #[deprecated(since = "0.5.8", note = "Use the `to_guild_cached`-method instead.")]
-    pub fn find(&self) -> Option<Arc<RwLock<Guild>>> { self.to_guild_cached() }
+    pub fn find(&self) -> Option<Arc<RwLock<Guild>>> { self.to_guild_cached(&CACHE) }
 
-    /// Tries to find the [`Guild`] by its Id in the cache.
+    /// Tries to find the [`Guild`] by its Id in the given cache.
     ///
     /// [`Guild`]: ../guild/struct.Guild.html
     #[cfg(feature = "cache")]
     #[inline]
-    pub fn to_guild_cached(self) -> Option<Arc<RwLock<Guild>>> { CACHE.read().guild(self) }
+    pub fn to_guild_cached(self, cache: &RwLock<Cache>) -> Option<Arc<RwLock<Guild>>> {
+        cache.read().guild(self)
+    }
 
     /// Requests the guild over REST.
     ///
@@ -464,23 +466,26 @@ impl GuildId {
 
     /// Gets a user's [`Member`] for the guild by Id.
     ///
-    /// If the cache feature is enabled the cache will be checked
-    /// first. If not found it will resort to an http request.
+    /// If the cache feature is enabled the given cache will be checked first.
+    /// If not found it will resort to an http request.
     ///
     /// [`Guild`]: ../guild/struct.Guild.html
     /// [`Member`]: ../guild/struct.Member.html
     #[inline]
-    pub fn member<U: Into<UserId>>(&self, user_id: U) -> Result<Member> {
-        self._member(user_id.into())
+    pub fn member<U: Into<UserId>>(&self, cache: &CacheSource, user_id: U) -> Result<Member> {
+        self._member(cache, user_id.into())
     }
 
-    fn _member(&self, user_id: UserId) -> Result<Member> {
-        #[cfg(feature = "cache")]
-        {
-            if let Some(member) = CACHE.read().member(self.0, user_id) {
+    fn _member(&self, cache: &CacheSource, user_id: UserId) -> Result<Member> {
+        feature_cache! {{
+            let member = cache.cache().and_then(|cache| cache.read().member(self.0, user_id));
+
+            if let Some(member) = member {
                 return Ok(member);
             }
-        }
+        } else {
+            let _ = cache;
+        }}
 
         http::get_member(self.0, user_id.0)
     }
@@ -568,16 +573,18 @@ impl GuildId {
     /// Returns the Id of the shard associated with the guild.
     ///
     /// When the cache is enabled this will automatically retrieve the total
-    /// number of shards.
+    /// number of shards from the given cache.
     ///
-    /// **Note**: When the cache is enabled, this function unlocks the cache to
-    /// retrieve the total number of shards in use. If you already have the
-    /// total, consider using [`utils::shard_id`].
+    /// **Note**: This function unlocks the given cache to retrieve the total
+    /// number of shards in use. If you already have the total, consider using
+    /// [`utils::shard_id`].
     ///
     /// [`utils::shard_id`]: ../../utils/fn.shard_id.html
     #[cfg(all(feature = "cache", feature = "utils"))]
     #[inline]
-    pub fn shard_id(&self) -> u64 { ::utils::shard_id(self.0, CACHE.read().shard_count) }
+    pub fn shard_id(&self, cache: &RwLock<Cache>) -> u64 {
+        ::utils::shard_id(self.0, cache.read().shard_count)
+    }
 
     /// Returns the Id of the shard associated with the guild.
     ///
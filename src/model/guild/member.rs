@@ -16,7 +16,7 @@ use std::borrow::Cow;
 #[cfg(all(feature = "cache", feature = "model", feature = "utils"))]
 use utils::Colour;
 #[cfg(all(feature = "cache", feature = "model"))]
-use {CACHE, http, utils};
+use {Cache, http, utils};
 
 /// A trait for allowing both u8 or &str or (u8, &str) to be passed into the `ban` methods in `Guild` and `Member`.
 pub trait BanOptions {
@@ -166,10 +166,11 @@ impl Member {
         )
     }
 
-    /// Determines the member's colour.
+    /// Determines the member's colour, using the roles of its guild in the
+    /// given cache.
     #[cfg(all(feature = "cache", feature = "utils"))]
-    pub fn colour(&self) -> Option<Colour> {
-        let cache = CACHE.read();
+    pub fn colour(&self, cache: &RwLock<Cache>) -> Option<Colour> {
+        let cache = cache.read();
         let guild = cache.guilds.get(&self.guild_id)?.read();
 
         let mut roles = self.roles
@@ -190,8 +191,8 @@ impl Member {
     /// (This returns the first channel that can be read by the member, if there isn't
     /// one returns `None`)
     #[cfg(feature = "cache")]
-    pub fn default_channel(&self) -> Option<Arc<RwLock<GuildChannel>>> {
-        let guild = match self.guild_id.to_guild_cached() {
+    pub fn default_channel(&self, cache: &RwLock<Cache>) -> Option<Arc<RwLock<GuildChannel>>> {
+        let guild = match self.guild_id.to_guild_cached(cache) {
             Some(guild) => guild,
             None => return None,
         };
@@ -244,7 +245,7 @@ impl Member {
     }
 
     /// Retrieves the ID and position of the member's highest role in the
-    /// hierarchy, if they have one.
+    /// hierarchy, if they have one, looking up its guild in the given cache.
     ///
     /// This _may_ return `None` if:
     ///
@@ -256,14 +257,21 @@ impl Member {
     /// position. If two or more roles have the same highest position, then the
     /// role with the lowest ID is the highest.
     #[cfg(feature = "cache")]
-    pub fn highest_role_info(&self) -> Option<(RoleId, i64)> {
-        let guild = self.guild_id.to_guild_cached()?;
+    pub fn highest_role_info(&self, cache: &RwLock<Cache>) -> Option<(RoleId, i64)> {
+        let guild = self.guild_id.to_guild_cached(cache)?;
         let reader = guild.try_read()?;
 
+        self.highest_role_in(&reader)
+    }
+
+    /// Retrieves the ID and position of the member's highest role out of the
+    /// roles of the given guild.
+    #[cfg(feature = "cache")]
+    pub(crate) fn highest_role_in(&self, guild: &Guild) -> Option<(RoleId, i64)> {
         let mut highest = None;
 
         for role_id in &self.roles {
-            if let Some(role) = reader.roles.get(&role_id) {
+            if let Some(role) = guild.roles.get(&role_id) {
                 // Skip this role if this role in iteration has:
                 //
                 // - a position less than the recorded highest
@@ -291,7 +299,7 @@ impl Member {
     ///
     /// ```rust,ignore
     /// // assuming a `member` has already been bound
-    /// match member.kick() {
+    /// match member.kick(&ctx) {
     ///     Ok(()) => println!("Successfully kicked member"),
     ///     Err(Error::Model(ModelError::GuildNotFound)) => {
     ///         println!("Couldn't determine guild of member");
@@ -314,34 +322,38 @@ impl Member {
     /// [`ModelError::GuildNotFound`]: ../error/enum.Error.html#variant.GuildNotFound
     /// [`ModelError::InvalidPermissions`]: ../error/enum.Error.html#variant.InvalidPermissions
     /// [Kick Members]: ../permissions/struct.Permissions.html#associatedconstant.KICK_MEMBERS
-    pub fn kick(&self) -> Result<()> {
-        #[cfg(feature = "cache")]
-        {
-            let cache = CACHE.read();
+    pub fn kick(&self, cache: &CacheSource) -> Result<()> {
+        feature_cache! {{
+            let guild = cache.cache().and_then(|cache| {
+                cache.read().guilds.get(&self.guild_id).cloned()
+            });
 
-            if let Some(guild) = cache.guilds.get(&self.guild_id) {
+            if let Some(guild) = guild {
                 let req = Permissions::KICK_MEMBERS;
                 let reader = guild.read();
 
-                if !reader.has_perms(req) {
+                if !reader.has_perms(cache, req) {
                     return Err(Error::Model(ModelError::InvalidPermissions(req)));
                 }
 
-                reader.check_hierarchy(self.user.read().id)?;
+                reader.check_hierarchy(cache, self.user.read().id)?;
             }
-        }
+        } else {
+            let _ = cache;
+        }}
 
         self.guild_id.kick(self.user.read().id)
     }
 
-    /// Returns the guild-level permissions for the member.
+    /// Returns the guild-level permissions for the member, calculated from its
+    /// guild in the given cache.
     ///
     /// # Examples
     ///
     /// ```rust,ignore
     /// // assuming there's a `member` variable gotten from anything.
     /// println!("The permission bits for the member are: {}",
-    /// member.permissions().expect("permissions").bits);
+    /// member.permissions(&ctx.cache).expect("permissions").bits);
     /// ```
     ///
     /// # Errors
@@ -355,8 +367,8 @@ impl Member {
     /// [`ModelError::GuildNotFound`]: ../error/enum.Error.html#variant.GuildNotFound
     /// [`ModelError::ItemMissing`]: ../error/enum.Error.html#variant.ItemMissing
     #[cfg(feature = "cache")]
-    pub fn permissions(&self) -> Result<Permissions> {
-        let guild = match self.guild_id.to_guild_cached() {
+    pub fn permissions(&self, cache: &RwLock<Cache>) -> Result<Permissions> {
+        let guild = match self.guild_id.to_guild_cached(cache) {
             Some(guild) => guild,
             None => return Err(From::from(ModelError::GuildNotFound)),
         };
@@ -420,14 +432,14 @@ impl Member {
 
     /// Retrieves the full role data for the user's roles.
     ///
-    /// This is shorthand for manually searching through the CACHE.
+    /// This is shorthand for manually searching through the given cache.
     ///
     /// If role data can not be found for the member, then `None` is returned.
     #[cfg(feature = "cache")]
-    pub fn roles(&self) -> Option<Vec<Role>> {
+    pub fn roles(&self, cache: &RwLock<Cache>) -> Option<Vec<Role>> {
         self
             .guild_id
-            .to_guild_cached()
+            .to_guild_cached(cache)
             .map(|g| g
                 .read()
                 .roles
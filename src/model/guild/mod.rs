@@ -23,7 +23,7 @@ use serde_json;
 use super::utils::*;
 
 #[cfg(all(feature = "cache", feature = "model"))]
-use CACHE;
+use Cache;
 #[cfg(feature = "model")]
 use http;
 #[cfg(feature = "model")]
@@ -146,8 +146,11 @@ pub struct Guild {
 #[cfg(feature = "model")]
 impl Guild {
     #[cfg(feature = "cache")]
-    fn check_hierarchy(&self, other_user: UserId) -> Result<()> {
-        let current_id = CACHE.read().user.id;
+    fn check_hierarchy(&self, cache: &CacheSource, other_user: UserId) -> Result<()> {
+        let current_id = match utils::current_user_id(cache) {
+            Some(id) => id,
+            None => return Ok(()),
+        };
 
         if let Some(higher) = self.greater_member_hierarchy(other_user, current_id) {
             if higher != current_id {
@@ -158,6 +161,9 @@ impl Guild {
         Ok(())
     }
 
+    #[cfg(not(feature = "cache"))]
+    fn check_hierarchy(&self, _: &CacheSource, _: UserId) -> Result<()> { Ok(()) }
+
     /// Returns the "default" channel of the guild for the passed user id.
     /// (This returns the first channel that can be read by the user, if there isn't one,
     /// returns `None`)
@@ -188,9 +194,11 @@ impl Guild {
         None
     }
 
-    #[cfg(feature = "cache")]
-    fn has_perms(&self, mut permissions: Permissions) -> bool {
-        let user_id = CACHE.read().user.id;
+    fn has_perms(&self, cache: &CacheSource, mut permissions: Permissions) -> bool {
+        let user_id = match utils::current_user_id(cache) {
+            Some(id) => id,
+            None => return true,
+        };
 
         let perms = self.member_permissions(user_id);
         permissions.remove(perms);
@@ -210,8 +218,8 @@ impl Guild {
     /// Ban a member and remove all messages they've sent in the last 4 days:
     ///
     /// ```rust,ignore
-    /// // assumes a `user` and `guild` have already been bound
-    /// let _ = guild.ban(user, 4);
+    /// // assumes a `ctx`, `user` and `guild` have already been bound
+    /// let _ = guild.ban(&ctx, user, &4);
     /// ```
     ///
     /// # Errors
@@ -228,22 +236,20 @@ impl Guild {
     /// [`User`]: ../user/struct.User.html
     /// [Ban Members]: ../permissions/struct.Permissions.html#associatedconstant.BAN_MEMBERS
     #[inline]
-    pub fn ban<U: Into<UserId>, BO: BanOptions>(&self, user: U, options: &BO) -> Result<()> {
-        self._ban(user.into(), options)
+    pub fn ban<U, BO>(&self, cache: &CacheSource, user: U, options: &BO) -> Result<()>
+        where U: Into<UserId>, BO: BanOptions {
+        self._ban(cache, user.into(), options)
     }
 
-    fn _ban<BO: BanOptions>(&self, user: UserId, options: &BO) -> Result<()> {
-        #[cfg(feature = "cache")]
-        {
-            let req = Permissions::BAN_MEMBERS;
-
-            if !self.has_perms(req) {
-                return Err(Error::Model(ModelError::InvalidPermissions(req)));
-            }
+    fn _ban<BO: BanOptions>(&self, cache: &CacheSource, user: UserId, options: &BO) -> Result<()> {
+        let req = Permissions::BAN_MEMBERS;
 
-            self.check_hierarchy(user)?;
+        if !self.has_perms(cache, req) {
+            return Err(Error::Model(ModelError::InvalidPermissions(req)));
         }
 
+        self.check_hierarchy(cache, user)?;
+
         self.id.ban(user, options)
     }
 
@@ -259,14 +265,11 @@ impl Guild {
     /// [`Ban`]: struct.Ban.html
     /// [`ModelError::InvalidPermissions`]: ../error/enum.Error.html#variant.InvalidPermissions
     /// [Ban Members]: ../permissions/struct.Permissions.html#associatedconstant.BAN_MEMBERS
-    pub fn bans(&self) -> Result<Vec<Ban>> {
-        #[cfg(feature = "cache")]
-        {
-            let req = Permissions::BAN_MEMBERS;
+    pub fn bans(&self, cache: &CacheSource) -> Result<Vec<Ban>> {
+        let req = Permissions::BAN_MEMBERS;
 
-            if !self.has_perms(req) {
-                return Err(Error::Model(ModelError::InvalidPermissions(req)));
-            }
+        if !self.has_perms(cache, req) {
+            return Err(Error::Model(ModelError::InvalidPermissions(req)));
         }
 
         self.id.bans()
@@ -333,9 +336,9 @@ impl Guild {
     /// ```rust,ignore
     /// use serenity::model::ChannelType;
     ///
-    /// // assuming a `guild` has already been bound
+    /// // assuming a `ctx` and `guild` have already been bound
     ///
-    /// let _ = guild.create_channel("my-test-channel", ChannelType::Text, None);
+    /// let _ = guild.create_channel(&ctx, "my-test-channel", ChannelType::Text, None);
     /// ```
     ///
     /// # Errors
@@ -346,15 +349,17 @@ impl Guild {
     /// [`Channel`]: ../channel/enum.Channel.html
     /// [`ModelError::InvalidPermissions`]: ../error/enum.Error.html#variant.InvalidPermissions
     /// [Manage Channels]: ../permissions/struct.Permissions.html#associatedconstant.MANAGE_CHANNELS
-    pub fn create_channel<C>(&self, name: &str, kind: ChannelType, category: C) -> Result<GuildChannel>
-        where C: Into<Option<ChannelId>> {
-        #[cfg(feature = "cache")]
-        {
-            let req = Permissions::MANAGE_CHANNELS;
-
-            if !self.has_perms(req) {
-                return Err(Error::Model(ModelError::InvalidPermissions(req)));
-            }
+    pub fn create_channel<C>(
+        &self,
+        cache: &CacheSource,
+        name: &str,
+        kind: ChannelType,
+        category: C,
+    ) -> Result<GuildChannel> where C: Into<Option<ChannelId>> {
+        let req = Permissions::MANAGE_CHANNELS;
+
+        if !self.has_perms(cache, req) {
+            return Err(Error::Model(ModelError::InvalidPermissions(req)));
         }
 
         self.id.create_channel(name, kind, category)
@@ -404,9 +409,9 @@ impl Guild {
     /// Create a role which can be mentioned, with the name 'test':
     ///
     /// ```rust,ignore
-    /// // assuming a `guild` has been bound
+    /// // assuming a `ctx` and `guild` have been bound
     ///
-    /// let role = guild.create_role(|r| r.hoist(true).name("role"));
+    /// let role = guild.create_role(&ctx, |r| r.hoist(true).name("role"));
     /// ```
     ///
     /// # Errors
@@ -417,15 +422,12 @@ impl Guild {
     /// [`ModelError::InvalidPermissions`]: ../error/enum.Error.html#variant.InvalidPermissions
     /// [`Role`]: struct.Role.html
     /// [Manage Roles]: ../permissions/struct.Permissions.html#associatedconstant.MANAGE_ROLES
-    pub fn create_role<F>(&self, f: F) -> Result<Role>
+    pub fn create_role<F>(&self, cache: &CacheSource, f: F) -> Result<Role>
         where F: FnOnce(EditRole) -> EditRole {
-        #[cfg(feature = "cache")]
-        {
-            let req = Permissions::MANAGE_ROLES;
+        let req = Permissions::MANAGE_ROLES;
 
-            if !self.has_perms(req) {
-                return Err(Error::Model(ModelError::InvalidPermissions(req)));
-            }
+        if !self.has_perms(cache, req) {
+            return Err(Error::Model(ModelError::InvalidPermissions(req)));
         }
 
         self.id.create_role(f)
@@ -442,14 +444,11 @@ impl Guild {
     /// if the current user is not the guild owner.
     ///
     /// [`ModelError::InvalidUser`]: ../error/enum.Error.html#variant.InvalidUser
-    pub fn delete(&self) -> Result<PartialGuild> {
-        #[cfg(feature = "cache")]
-        {
-            if self.owner_id != CACHE.read().user.id {
-                let req = Permissions::MANAGE_GUILD;
+    pub fn delete(&self, cache: &CacheSource) -> Result<PartialGuild> {
+        if utils::current_user_id(cache).map_or(false, |id| id != self.owner_id) {
+            let req = Permissions::MANAGE_GUILD;
 
-                return Err(Error::Model(ModelError::InvalidPermissions(req)));
-            }
+            return Err(Error::Model(ModelError::InvalidPermissions(req)));
         }
 
         self.id.delete()
@@ -509,7 +508,7 @@ impl Guild {
     /// let base64_icon = utils::read_image("./icon.png")
     ///     .expect("Failed to read image");
     ///
-    /// guild.edit(|g| g.icon(base64_icon));
+    /// guild.edit(&ctx, |g| g.icon(base64_icon));
     /// ```
     ///
     /// # Errors
@@ -519,15 +518,12 @@ impl Guild {
     ///
     /// [`ModelError::InvalidPermissions`]: ../error/enum.Error.html#variant.InvalidPermissions
     /// [Manage Guild]: ../permissions/struct.Permissions.html#associatedconstant.MANAGE_GUILD
-    pub fn edit<F>(&mut self, f: F) -> Result<()>
+    pub fn edit<F>(&mut self, cache: &CacheSource, f: F) -> Result<()>
         where F: FnOnce(EditGuild) -> EditGuild {
-        #[cfg(feature = "cache")]
-        {
-            let req = Permissions::MANAGE_GUILD;
+        let req = Permissions::MANAGE_GUILD;
 
-            if !self.has_perms(req) {
-                return Err(Error::Model(ModelError::InvalidPermissions(req)));
-            }
+        if !self.has_perms(cache, req) {
+            return Err(Error::Model(ModelError::InvalidPermissions(req)));
         }
 
         match self.id.edit(f) {
@@ -600,14 +596,11 @@ impl Guild {
     ///
     /// [`ModelError::InvalidPermissions`]: ../error/enum.Error.html#variant.InvalidPermissions
     /// [Change Nickname]: ../permissions/struct.Permissions.html#associatedconstant.CHANGE_NICKNAME
-    pub fn edit_nickname(&self, new_nickname: Option<&str>) -> Result<()> {
-        #[cfg(feature = "cache")]
-        {
-            let req = Permissions::CHANGE_NICKNAME;
+    pub fn edit_nickname(&self, cache: &CacheSource, new_nickname: Option<&str>) -> Result<()> {
+        let req = Permissions::CHANGE_NICKNAME;
 
-            if !self.has_perms(req) {
-                return Err(Error::Model(ModelError::InvalidPermissions(req)));
-            }
+        if !self.has_perms(cache, req) {
+            return Err(Error::Model(ModelError::InvalidPermissions(req)));
         }
 
         self.id.edit_nickname(new_nickname)
@@ -697,10 +690,10 @@ impl Guild {
         }
 
         let lhs = self.members.get(&lhs_id)?
-            .highest_role_info()
+            .highest_role_in(self)
             .unwrap_or((RoleId(0), 0));
         let rhs = self.members.get(&rhs_id)?
-            .highest_role_info()
+            .highest_role_in(self)
             .unwrap_or((RoleId(0), 0));
 
         // If LHS and RHS both have no top position or have the same role ID,
@@ -754,14 +747,11 @@ impl Guild {
     ///
     /// [`ModelError::InvalidPermissions`]: ../error/enum.Error.html#variant.InvalidPermissions
     /// [Manage Guild]: ../permissions/struct.Permissions.html#associatedconstant.MANAGE_GUILD
-    pub fn invites(&self) -> Result<Vec<RichInvite>> {
-        #[cfg(feature = "cache")]
-        {
-            let req = Permissions::MANAGE_GUILD;
+    pub fn invites(&self, cache: &CacheSource) -> Result<Vec<RichInvite>> {
+        let req = Permissions::MANAGE_GUILD;
 
-            if !self.has_perms(req) {
-                return Err(Error::Model(ModelError::InvalidPermissions(req)));
-            }
+        if !self.has_perms(cache, req) {
+            return Err(Error::Model(ModelError::InvalidPermissions(req)));
         }
 
         self.id.invites()
@@ -790,7 +780,9 @@ impl Guild {
     /// [`Guild`]: ../guild/struct.Guild.html
     /// [`Member`]: struct.Member.html
     #[inline]
-    pub fn member<U: Into<UserId>>(&self, user_id: U) -> Result<Member> { self.id.member(user_id) }
+    pub fn member<U: Into<UserId>>(&self, cache: &CacheSource, user_id: U) -> Result<Member> {
+        self.id.member(cache, user_id)
+    }
 
     /// Gets a list of the guild's members.
     ///
@@ -1350,14 +1342,11 @@ impl Guild {
     /// [`GuildPrune`]: struct.GuildPrune.html
     /// [`Member`]: struct.Member.html
     /// [Kick Members]: ../permissions/struct.Permissions.html#associatedconstant.KICK_MEMBERS
-    pub fn prune_count(&self, days: u16) -> Result<GuildPrune> {
-        #[cfg(feature = "cache")]
-        {
-            let req = Permissions::KICK_MEMBERS;
+    pub fn prune_count(&self, cache: &CacheSource, days: u16) -> Result<GuildPrune> {
+        let req = Permissions::KICK_MEMBERS;
 
-            if !self.has_perms(req) {
-                return Err(Error::Model(ModelError::InvalidPermissions(req)));
-            }
+        if !self.has_perms(cache, req) {
+            return Err(Error::Model(ModelError::InvalidPermissions(req)));
         }
 
         self.id.prune_count(days)
@@ -1376,16 +1365,16 @@ impl Guild {
     /// Returns the Id of the shard associated with the guild.
     ///
     /// When the cache is enabled this will automatically retrieve the total
-    /// number of shards.
+    /// number of shards from the given cache.
     ///
-    /// **Note**: When the cache is enabled, this function unlocks the cache to
-    /// retrieve the total number of shards in use. If you already have the
-    /// total, consider using [`utils::shard_id`].
+    /// **Note**: This function unlocks the given cache to retrieve the total
+    /// number of shards in use. If you already have the total, consider using
+    /// [`utils::shard_id`].
     ///
     /// [`utils::shard_id`]: ../../utils/fn.shard_id.html
     #[cfg(all(feature = "cache", feature = "utils"))]
     #[inline]
-    pub fn shard_id(&self) -> u64 { self.id.shard_id() }
+    pub fn shard_id(&self, cache: &RwLock<Cache>) -> u64 { self.id.shard_id(cache) }
 
     /// Returns the Id of the shard associated with the guild.
     ///
@@ -1443,14 +1432,11 @@ impl Guild {
     /// [`GuildPrune`]: struct.GuildPrune.html
     /// [`Member`]: struct.Member.html
     /// [Kick Members]: ../permissions/struct.Permissions.html#associatedconstant.KICK_MEMBERS
-    pub fn start_prune(&self, days: u16) -> Result<GuildPrune> {
-        #[cfg(feature = "cache")]
-        {
-            let req = Permissions::KICK_MEMBERS;
+    pub fn start_prune(&self, cache: &CacheSource, days: u16) -> Result<GuildPrune> {
+        let req = Permissions::KICK_MEMBERS;
 
-            if !self.has_perms(req) {
-                return Err(Error::Model(ModelError::InvalidPermissions(req)));
-            }
+        if !self.has_perms(cache, req) {
+            return Err(Error::Model(ModelError::InvalidPermissions(req)));
         }
 
         self.id.start_prune(days)
@@ -1468,14 +1454,11 @@ impl Guild {
     /// [`ModelError::InvalidPermissions`]: ../error/enum.Error.html#variant.InvalidPermissions
     /// [`User`]: ../user/struct.User.html
     /// [Ban Members]: ../permissions/struct.Permissions.html#associatedconstant.BAN_MEMBERS
-    pub fn unban<U: Into<UserId>>(&self, user_id: U) -> Result<()> {
-        #[cfg(feature = "cache")]
-        {
-            let req = Permissions::BAN_MEMBERS;
+    pub fn unban<U: Into<UserId>>(&self, cache: &CacheSource, user_id: U) -> Result<()> {
+        let req = Permissions::BAN_MEMBERS;
 
-            if !self.has_perms(req) {
-                return Err(Error::Model(ModelError::InvalidPermissions(req)));
-            }
+        if !self.has_perms(cache, req) {
+            return Err(Error::Model(ModelError::InvalidPermissions(req)));
         }
 
         self.id.unban(user_id)
@@ -3,6 +3,8 @@ use super::super::utils::{deserialize_emojis, deserialize_roles};
 
 #[cfg(feature = "model")]
 use builder::{EditGuild, EditMember, EditRole};
+#[cfg(all(feature = "cache", feature = "model", feature = "utils"))]
+use Cache;
 
 /// Partial information about a [`Guild`]. This does not include information
 /// like member data.
@@ -332,7 +334,9 @@ impl PartialGuild {
     ///
     /// [`Guild`]: struct.Guild.html
     /// [`Member`]: struct.Member.html
-    pub fn member<U: Into<UserId>>(&self, user_id: U) -> Result<Member> { self.id.member(user_id) }
+    pub fn member<U: Into<UserId>>(&self, cache: &CacheSource, user_id: U) -> Result<Member> {
+        self.id.member(cache, user_id)
+    }
 
     /// Gets a list of the guild's members.
     ///
@@ -370,16 +374,16 @@ impl PartialGuild {
     /// Returns the Id of the shard associated with the guild.
     ///
     /// When the cache is enabled this will automatically retrieve the total
-    /// number of shards.
+    /// number of shards from the given cache.
     ///
-    /// **Note**: When the cache is enabled, this function unlocks the cache to
-    /// retrieve the total number of shards in use. If you already have the
-    /// total, consider using [`utils::shard_id`].
+    /// **Note**: This function unlocks the given cache to retrieve the total
+    /// number of shards in use. If you already have the total, consider using
+    /// [`utils::shard_id`].
     ///
     /// [`utils::shard_id`]: ../../utils/fn.shard_id.html
     #[cfg(all(feature = "cache", feature = "utils"))]
     #[inline]
-    pub fn shard_id(&self) -> u64 { self.id.shard_id() }
+    pub fn shard_id(&self, cache: &RwLock<Cache>) -> u64 { self.id.shard_id(cache) }
 
     /// Returns the Id of the shard associated with the guild.
     ///
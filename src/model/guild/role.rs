@@ -68,16 +68,19 @@ pub struct Role {
 
 #[cfg(feature = "model")]
 impl Role {
-    /// Deletes the role.
+    /// Deletes the role, searching the given cache for the guild that owns it.
     ///
     /// **Note** Requires the [Manage Roles] permission.
     ///
     /// [Manage Roles]: ../permissions/struct.Permissions.html#associatedconstant.MANAGE_ROLES
     #[cfg(feature = "cache")]
     #[inline]
-    pub fn delete(&self) -> Result<()> { http::delete_role(self.find_guild()?.0, self.id.0) }
+    pub fn delete(&self, cache: &RwLock<Cache>) -> Result<()> {
+        http::delete_role(self.find_guild(cache)?.0, self.id.0)
+    }
 
-    /// Edits a [`Role`], optionally setting its new fields.
+    /// Edits a [`Role`], optionally setting its new fields. The given cache is
+    /// searched for the guild that owns the role.
     ///
     /// Requires the [Manage Roles] permission.
     ///
@@ -86,22 +89,24 @@ impl Role {
     /// Make a role hoisted:
     ///
     /// ```rust,no_run
+    /// # use serenity::CACHE;
     /// # use serenity::model::id::RoleId;
-    /// # let role = RoleId(7).to_role_cached().unwrap();
+    /// # let role = RoleId(7).to_role_cached(&CACHE).unwrap();
     /// // assuming a `role` has already been bound
     //
-    /// role.edit(|r| r.hoist(true));
+    /// role.edit(&CACHE, |r| r.hoist(true));
     /// ```
     ///
     /// [`Role`]: struct.Role.html
     /// [Manage Roles]: ../permissions/struct.Permissions.html#associatedconstant.MANAGE_ROLES
     #[cfg(all(feature = "builder", feature = "cache"))]
-    pub fn edit<F: FnOnce(EditRole) -> EditRole>(&self, f: F) -> Result<Role> {
-        self.find_guild()
+    pub fn edit<F>(&self, cache: &RwLock<Cache>, f: F) -> Result<Role>
+        where F: FnOnce(EditRole) -> EditRole {
+        self.find_guild(cache)
             .and_then(|guild_id| guild_id.edit_role(self.id, f))
     }
 
-    /// Searches the cache for the guild that owns the role.
+    /// Searches the given cache for the guild that owns the role.
     ///
     /// # Errors
     ///
@@ -110,8 +115,8 @@ impl Role {
     ///
     /// [`ModelError::GuildNotFound`]: ../error/enum.Error.html#variant.GuildNotFound
     #[cfg(feature = "cache")]
-    pub fn find_guild(&self) -> Result<GuildId> {
-        for guild in CACHE.read().guilds.values() {
+    pub fn find_guild(&self, cache: &RwLock<Cache>) -> Result<GuildId> {
+        for guild in cache.read().guilds.values() {
             let guild = guild.read();
 
             if guild.roles.contains_key(&RoleId(self.id.0)) {
@@ -174,19 +179,14 @@ impl RoleId {
     #[cfg(feature = "cache")]
     #[deprecated(since = "0.5.8", note = "Use the `to_role_cached`-method instead.")]
     pub fn find(&self) -> Option<Role> {
-        self.to_role_cached()
+        self.to_role_cached(&CACHE)
     }
 
-    /// Tries to find the [`Role`] by its Id in the cache.
+    /// Tries to find the [`Role`] by its Id in the given cache.
     ///
     /// [`Role`]: ../guild/struct.Role.html
     #[cfg(feature = "cache")]
-    pub fn to_role_cached(self) -> Option<Role> {
-        self._to_role_cached(&CACHE)
-    }
-
-    #[cfg(feature = "cache")]
-    pub(crate) fn _to_role_cached(self, cache: &RwLock<Cache>) -> Option<Role> {
+    pub fn to_role_cached(self, cache: &RwLock<Cache>) -> Option<Role> {
         for guild in cache.read().guilds.values() {
             let guild = guild.read();
 
@@ -213,13 +213,24 @@ impl<'a> From<&'a Role> for RoleId {
     fn from(role: &Role) -> RoleId { role.id }
 }
 
+/// Parses a role mention or Id, retrieving the role from the global
+/// [`CACHE`].
+///
+/// **Note**: A client only updates the global cache if it was created with
+/// [`Client::new_with_cache`]. Otherwise, use [`utils::parse_role`] with
+/// [`RoleId::to_role_cached`] on the client's cache instead.
+///
+/// [`CACHE`]: ../../struct.CACHE.html
+/// [`Client::new_with_cache`]: ../../client/struct.Client.html#method.new_with_cache
+/// [`RoleId::to_role_cached`]: ../id/struct.RoleId.html#method.to_role_cached
+/// [`utils::parse_role`]: ../../utils/fn.parse_role.html
 #[cfg(all(feature = "cache", feature = "model", feature = "utils"))]
 impl FromStr for Role {
     type Err = RoleParseError;
 
     fn from_str(s: &str) -> StdResult<Self, Self::Err> {
         match parse_role(s) {
-            Some(x) => match RoleId(x).to_role_cached() {
+            Some(x) => match RoleId(x).to_role_cached(&CACHE) {
                 Some(role) => Ok(role),
                 _ => Err(RoleParseError::NotPresentInCache),
             },
@@ -7,10 +7,12 @@ use super::prelude::*;
 use builder::CreateInvite;
 #[cfg(feature = "model")]
 use internal::prelude::*;
-#[cfg(all(feature = "cache", feature = "model"))]
+#[cfg(feature = "model")]
 use super::{Permissions, utils as model_utils};
 #[cfg(feature = "model")]
 use {http, utils};
+#[cfg(all(feature = "cache", feature = "model", feature = "utils"))]
+use Cache;
 
 /// Information about an invite code.
 ///
@@ -70,20 +72,17 @@ impl Invite {
     /// [`GuildChannel`]: ../channel/struct.GuildChannel.html
     /// [Create Invite]: ../permissions/struct.Permissions.html#associatedconstant.CREATE_INVITE
     /// [permission]: ../permissions/index.html
-    pub fn create<C, F>(channel_id: C, f: F) -> Result<RichInvite>
+    pub fn create<C, F>(cache: &CacheSource, channel_id: C, f: F) -> Result<RichInvite>
         where C: Into<ChannelId>, F: FnOnce(CreateInvite) -> CreateInvite {
-        Self::_create(channel_id.into(), f)
+        Self::_create(cache, channel_id.into(), f)
     }
 
-    fn _create<F>(channel_id: ChannelId, f: F) -> Result<RichInvite>
+    fn _create<F>(cache: &CacheSource, channel_id: ChannelId, f: F) -> Result<RichInvite>
         where F: FnOnce(CreateInvite) -> CreateInvite {
-        #[cfg(feature = "cache")]
-        {
-            let req = Permissions::CREATE_INVITE;
+        let req = Permissions::CREATE_INVITE;
 
-            if !model_utils::user_has_perms(channel_id, req)? {
-                return Err(Error::Model(ModelError::InvalidPermissions(req)));
-            }
+        if !model_utils::user_has_perms(cache, channel_id, req)? {
+            return Err(Error::Model(ModelError::InvalidPermissions(req)));
         }
 
         let map = utils::vecmap_to_json_map(f(CreateInvite::default()).0);
@@ -103,14 +102,11 @@ impl Invite {
     /// [`ModelError::InvalidPermissions`]: ../error/enum.Error.html#variant.InvalidPermissions
     /// [Manage Guild]: ../permissions/struct.Permissions.html#associatedconstant.MANAGE_GUILD
     /// [permission]: ../permissions/index.html
-    pub fn delete(&self) -> Result<Invite> {
-        #[cfg(feature = "cache")]
-        {
-            let req = Permissions::MANAGE_GUILD;
+    pub fn delete(&self, cache: &CacheSource) -> Result<Invite> {
+        let req = Permissions::MANAGE_GUILD;
 
-            if !model_utils::user_has_perms(self.channel.id, req)? {
-                return Err(Error::Model(ModelError::InvalidPermissions(req)));
-            }
+        if !model_utils::user_has_perms(cache, self.channel.id, req)? {
+            return Err(Error::Model(ModelError::InvalidPermissions(req)));
         }
 
         http::delete_invite(&self.code)
@@ -186,16 +182,16 @@ impl InviteGuild {
     /// Returns the Id of the shard associated with the guild.
     ///
     /// When the cache is enabled this will automatically retrieve the total
-    /// number of shards.
+    /// number of shards from the given cache.
     ///
-    /// **Note**: When the cache is enabled, this function unlocks the cache to
-    /// retrieve the total number of shards in use. If you already have the
-    /// total, consider using [`utils::shard_id`].
+    /// **Note**: This function unlocks the given cache to retrieve the total
+    /// number of shards in use. If you already have the total, consider using
+    /// [`utils::shard_id`].
     ///
     /// [`utils::shard_id`]: ../../utils/fn.shard_id.html
     #[cfg(all(feature = "cache", feature = "utils"))]
     #[inline]
-    pub fn shard_id(&self) -> u64 { self.id.shard_id() }
+    pub fn shard_id(&self, cache: &RwLock<Cache>) -> u64 { self.id.shard_id(cache) }
 
     /// Returns the Id of the shard associated with the guild.
     ///
@@ -288,14 +284,11 @@ impl RichInvite {
     /// [`http::delete_invite`]: ../../http/fn.delete_invite.html
     /// [Manage Guild]: ../permissions/struct.Permissions.html#associatedconstant.MANAGE_GUILD.html
     /// [permission]: ../permissions/index.html
-    pub fn delete(&self) -> Result<Invite> {
-        #[cfg(feature = "cache")]
-        {
-            let req = Permissions::MANAGE_GUILD;
+    pub fn delete(&self, cache: &CacheSource) -> Result<Invite> {
+        let req = Permissions::MANAGE_GUILD;
 
-            if !model_utils::user_has_perms(self.channel.id, req)? {
-                return Err(Error::Model(ModelError::InvalidPermissions(req)));
-            }
+        if !model_utils::user_has_perms(cache, self.channel.id, req)? {
+            return Err(Error::Model(ModelError::InvalidPermissions(req)));
         }
 
         http::delete_invite(&self.code)
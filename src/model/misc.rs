@@ -3,6 +3,8 @@
 use super::prelude::*;
 use internal::RwLockExt;
 
+#[cfg(all(feature = "model", feature = "utils"))]
+use model::cache_source;
 #[cfg(all(feature = "model", feature = "utils"))]
 use std::error::Error as StdError;
 #[cfg(all(feature = "model", feature = "utils"))]
@@ -119,7 +121,7 @@ impl FromStr for User {
     fn from_str(s: &str) -> StdResult<Self, Self::Err> {
         match utils::parse_username(s) {
             Some(x) => UserId(x as u64)
-                .to_user()
+                .to_user(cache_source::global())
                 .map_err(|e| UserParseError::Rest(Box::new(e))),
             _ => Err(UserParseError::InvalidUsername),
         }
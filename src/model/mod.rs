@@ -21,6 +21,8 @@
 
 #[macro_use]
 mod utils;
+#[cfg(feature = "model")]
+mod cache_source;
 
 pub mod application;
 pub mod channel;
@@ -37,6 +39,8 @@ pub mod user;
 pub mod voice;
 pub mod webhook;
 
+#[cfg(feature = "model")]
+pub use self::cache_source::CacheSource;
 pub use self::error::Error as ModelError;
 pub use self::permissions::Permissions;
 
@@ -8,7 +8,9 @@ use internal::prelude::*;
 use model::misc::Mentionable;
 
 #[cfg(all(feature = "cache", feature = "model"))]
-use CACHE;
+use {CACHE, Cache};
+#[cfg(feature = "model")]
+use model::cache_source;
 #[cfg(feature = "model")]
 use builder::{CreateMessage, EditProfile};
 #[cfg(feature = "model")]
@@ -52,7 +54,7 @@ impl CurrentUser {
     /// ```rust,no_run
     /// # use serenity::model::id::UserId;
     /// #
-    /// # let user = UserId(0).to_user().unwrap();
+    /// # let user = UserId(0).to_user(&()).unwrap();
     /// #
     /// match user.avatar_url() {
     ///     Some(url) => println!("{}'s avatar can be found at {}", user.name, url),
@@ -82,7 +84,7 @@ impl CurrentUser {
     /// # extern crate serenity;
     /// # use serenity::model::id::UserId;
     /// #
-    /// # let user = UserId(0).to_user().unwrap();
+    /// # let user = UserId(0).to_user(&()).unwrap();
     /// #
     /// let avatar = serenity::utils::read_image("./avatar.png").unwrap();
     ///
@@ -235,7 +237,7 @@ impl CurrentUser {
     /// ```rust,no_run
     /// # use serenity::model::id::UserId;
     /// #
-    /// # let user = UserId(0).to_user().unwrap();
+    /// # let user = UserId(0).to_user(&()).unwrap();
     /// #
     /// match user.static_avatar_url() {
     ///     Some(url) => println!("{}'s static avatar can be found at {}", user.name, url),
@@ -256,7 +258,7 @@ impl CurrentUser {
     /// ```rust,no_run
     /// # use serenity::model::id::UserId;
     /// #
-    /// # let user = UserId(0).to_user().unwrap();
+    /// # let user = UserId(0).to_user(&()).unwrap();
     /// #
     /// println!("The current user's distinct identifier is {}", user.tag());
     /// ```
@@ -412,7 +414,7 @@ impl User {
     /// struct Handler;
     ///
     /// impl EventHandler for Handler {
-    ///     fn message(&self, _: Context, msg: Message) {
+    ///     fn message(&self, ctx: Context, msg: Message) {
     ///         if msg.content == "~help" {
     ///
     ///             let user = match get_current_user() {
@@ -438,14 +440,14 @@ impl User {
     ///                 url,
     ///             );
     ///
-    ///             match msg.author.direct_message(|m| m.content(&help)) {
+    ///             match msg.author.direct_message(&ctx, |m| m.content(&help)) {
     ///                 Ok(_) => {
-    ///                     let _ = msg.react('👌');
+    ///                     let _ = msg.react(&ctx, '👌');
     ///                 },
     ///                 Err(why) => {
     ///                     println!("Err sending help: {:?}", why);
     ///
-    ///                     let _ = msg.reply("There was an error DMing you help.");
+    ///                     let _ = msg.reply(&ctx, "There was an error DMing you help.");
     ///                 },
     ///             };
     ///         }
@@ -480,7 +482,7 @@ impl User {
     // (AKA: Clippy is wrong and so we have to mark as allowing this lint.)
     #[allow(let_and_return)]
     #[cfg(feature = "builder")]
-    pub fn direct_message<F>(&self, f: F) -> Result<Message>
+    pub fn direct_message<F>(&self, cache: &CacheSource, f: F) -> Result<Message>
         where F: FnOnce(CreateMessage) -> CreateMessage {
         if self.bot {
             return Err(Error::Model(ModelError::MessagingBot));
@@ -488,8 +490,8 @@ impl User {
 
         let private_channel_id = feature_cache! {
             {
-                let finding = {
-                    let cache = CACHE.read();
+                let finding = cache.cache().and_then(|cache| {
+                    let cache = cache.read();
 
                     let finding = cache.private_channels
                         .values()
@@ -498,7 +500,7 @@ impl User {
                         .map(|ch| ch.id);
 
                     finding
-                };
+                });
 
                 if let Some(finding) = finding {
                     finding
@@ -510,6 +512,7 @@ impl User {
                     http::create_private_channel(&map)?.id
                 }
             } else {
+                let _ = cache;
                 let map = json!({
                     "recipient_id": self.id.0,
                 });
@@ -530,7 +533,7 @@ impl User {
     /// ```rust,ignore
     /// // assuming you are in a context
     ///
-    /// let _ = message.author.dm("Hello!");
+    /// let _ = message.author.dm(&ctx, |m| m.content("Hello!"));
     /// ```
     ///
     /// # Examples
@@ -542,8 +545,9 @@ impl User {
     /// [direct_message]: #method.direct_message
     #[cfg(feature = "builder")]
     #[inline]
-    pub fn dm<F: FnOnce(CreateMessage) -> CreateMessage>(&self, f: F) -> Result<Message> {
-        self.direct_message(f)
+    pub fn dm<F>(&self, cache: &CacheSource, f: F) -> Result<Message>
+        where F: FnOnce(CreateMessage) -> CreateMessage {
+        self.direct_message(cache, f)
     }
 
     /// Retrieves the URL to the user's avatar, falling back to the default
@@ -560,8 +564,8 @@ impl User {
     }
 
     /// Check if a user has a [`Role`]. This will retrieve the [`Guild`] from
-    /// the [`Cache`] if it is available, and then check if that guild has the
-    /// given [`Role`].
+    /// the given cache if it is available, and then check if that guild has
+    /// the given [`Role`].
     ///
     /// Three forms of data may be passed in to the guild parameter: either a
     /// [`PartialGuild`], a [`GuildId`], or a `u64`.
@@ -572,82 +576,51 @@ impl User {
     ///
     /// ```rust,ignore
     /// // Assumes a 'guild_id' and `role_id` have already been bound
-    /// let _ = message.author.has_role(guild_id, role_id);
+    /// let _ = message.author.has_role(&ctx, guild_id, role_id);
     /// ```
     ///
     /// # Note
     ///
-    /// Currently if Cache is disabled it will get the member with http, if it
-    /// fails it will only log a warning and return false, if both cache and
-    /// http is disabled it will always return false.
+    /// If the guild is not cached, it will get the member with http, if it
+    /// fails it will only log a warning and return false.
     ///
     /// [`Guild`]: ../guild/struct.Guild.html
     /// [`GuildId`]: ../id/struct.GuildId.html
     /// [`PartialGuild`]: ../guild/struct.PartialGuild.html
     /// [`Role`]: ../guild/struct.Role.html
-    /// [`Cache`]: ../../cache/struct.Cache.html
     // no-cache would warn on guild_id.
-    pub fn has_role<G, R>(&self, guild: G, role: R) -> bool
+    pub fn has_role<G, R>(&self, cache: &CacheSource, guild: G, role: R) -> bool
         where G: Into<GuildContainer>, R: Into<RoleId> {
-        self._has_role(guild.into(), role.into())
+        self._has_role(cache, guild.into(), role.into())
     }
 
-    fn _has_role(&self, guild: GuildContainer, role: RoleId) -> bool {
-        match guild {
-            GuildContainer::Guild(partial_guild) => {
-                feature_cache! {{
-                    self.has_role(partial_guild.id.0, role)
-                } else {
-                    #[cfg(feature = "http")]
-                    {
-                        info!("[has_role] Getting the member with http");
-                        #[cfg(feature = "http")]
-                        use http::raw::get_member;
-                        if let Ok(m) = get_member(partial_guild.id.0, self.id.0) {
-                            m.roles.contains(&role)
-                        } else {
-                            error!("[has_role] Error getting the member");
-                            false
-                        }
-                    }
-                    #[cfg(not(feature = "http"))]
-                    {
-                        warn!("[has_role] Does not work without either cache or http.");
-                        false
-                    }
-                }}
-            },
-            GuildContainer::Id(guild_id) => {
-                feature_cache! {{
-                    CACHE.read()
-                        .guilds
-                        .get(&guild_id)
-                        .map(|g| {
-                            g.read().members.get(&self.id)
-                                .map(|m| m.roles.contains(&role))
-                                .unwrap_or(false)
-                        })
-                        .unwrap_or(false)
-                } else {
-                    #[cfg(feature = "http")]
-                    {
-                        info!("[has_role] Getting the member with http");
-                        #[cfg(feature = "http")]
-                        use http::raw::get_member;
-                        if let Ok(m) = get_member(guild_id.0, self.id.0) {
-                            m.roles.contains(&role)
-                        } else {
-                            error!("[has_role] Error getting the member");
-                            false
-                        }
-                    }
-                    #[cfg(not(feature = "http"))]
-                    {
-                        warn!("[has_role] Does not work without either cache or http.");
-                        false
-                    }
-                }}
-            },
+    fn _has_role(&self, cache: &CacheSource, guild: GuildContainer, role: RoleId) -> bool {
+        let guild_id = match guild {
+            GuildContainer::Guild(partial_guild) => partial_guild.id,
+            GuildContainer::Id(guild_id) => guild_id,
+        };
+
+        feature_cache! {{
+            let guild = cache.cache().and_then(|cache| cache.read().guilds.get(&guild_id).cloned());
+
+            if let Some(guild) = guild {
+                return guild
+                    .read()
+                    .members
+                    .get(&self.id)
+                    .map_or(false, |m| m.roles.contains(&role));
+            }
+        } else {
+            let _ = cache;
+        }}
+
+        info!("[has_role] Getting the member with http");
+
+        if let Ok(m) = http::get_member(guild_id.0, self.id.0) {
+            m.roles.contains(&role)
+        } else {
+            error!("[has_role] Error getting the member");
+            false
         }
     }
 
@@ -676,11 +649,12 @@ impl User {
     ///
     /// let mut client = Client::new("token", Handler).unwrap();
     /// use serenity::model::id::UserId;
-    /// use serenity::CACHE;
+    /// use std::sync::Arc;
     /// use std::thread;
     /// use std::time::Duration;
     ///
     /// let special_users = vec![UserId(114941315417899012), UserId(87600987040120832)];
+    /// let cache = Arc::clone(&client.cache);
     ///
     /// // start a new thread to periodically refresh the special users' data
     /// // every 12 hours
@@ -691,7 +665,7 @@ impl User {
     ///     loop {
     ///         thread::sleep(duration);
     ///
-    ///         let cache = CACHE.read();
+    ///         let cache = cache.read();
     ///
     ///         for id in &special_users {
     ///             if let Some(user) = cache.user(*id) {
@@ -707,7 +681,7 @@ impl User {
     /// # }
     /// ```
     pub fn refresh(&mut self) -> Result<()> {
-        self.id.to_user().map(|replacement| {
+        http::get_user(self.id.0).map(|replacement| {
             mem::replace(self, replacement);
 
             ()
@@ -761,27 +735,18 @@ impl User {
     #[inline]
     pub fn tag(&self) -> String { tag(&self.name, self.discriminator) }
 
-    /// Returns the user's nickname in the given `guild_id`.
+    /// Returns the user's nickname in the given `guild_id`, searching the
+    /// given cache for the member before requesting it over REST.
     ///
     /// If none is used, it returns `None`.
     #[inline]
-    pub fn nick_in<G>(&self, guild_id: G) -> Option<String>
+    pub fn nick_in<G>(&self, cache: &CacheSource, guild_id: G) -> Option<String>
     where G: Into<GuildId> {
-        self._nick_in(guild_id.into())
+        self._nick_in(cache, guild_id.into())
     }
 
-    fn _nick_in(&self, guild_id: GuildId) -> Option<String> {
-        #[cfg(feature = "cache")]
-        {
-            guild_id.to_guild_cached().and_then(|guild| {
-                guild.read().members.get(&self.id).and_then(|member| member.nick.clone())
-            })
-        }
-
-        #[cfg(not(feature = "cache"))]
-        {
-            guild_id.member(&self.id).ok().and_then(|member| member.nick.clone())
-        }
+    fn _nick_in(&self, cache: &CacheSource, guild_id: GuildId) -> Option<String> {
+        guild_id.member(cache, &self.id).ok().and_then(|member| member.nick.clone())
     }
 }
 
@@ -810,14 +775,16 @@ impl UserId {
     /// Search the cache for the user with the Id.
     #[cfg(feature = "cache")]
     #[deprecated(since = "0.5.8", note = "Use the `to_user_cached`-method instead.")]
-    pub fn find(&self) -> Option<Arc<RwLock<User>>> { self.to_user_cached() }
+    pub fn find(&self) -> Option<Arc<RwLock<User>>> { self.to_user_cached(&CACHE) }
 
-    /// Attempts to find a [`User`] by its Id in the cache.
+    /// Attempts to find a [`User`] by its Id in the given cache.
     ///
     /// [`User`]: ../user/struct.User.html
     #[cfg(feature = "cache")]
     #[inline]
-    pub fn to_user_cached(self) -> Option<Arc<RwLock<User>>> { CACHE.read().user(self) }
+    pub fn to_user_cached(self, cache: &RwLock<Cache>) -> Option<Arc<RwLock<User>>> {
+        cache.read().user(self)
+    }
 
     /// Gets a user by its Id from either the cache or the REST API.
     ///
@@ -826,24 +793,25 @@ impl UserId {
     #[inline]
     #[deprecated(since = "0.5.8", note = "Use the `to_user`-method instead.")]
     pub fn get(&self) -> Result<User> {
-        self.to_user()
+        self.to_user(cache_source::global())
     }
 
-    /// First attempts to find a [`User`] by its Id in the cache,
-    /// upon failure requests it via the REST API.
+    /// First attempts to find a [`User`] by its Id in the given cache, upon
+    /// failure requests it via the REST API.
     ///
     /// **Note**: If the cache is not enabled,
     /// REST API will be used only.
     ///
     /// [`User`]: ../user/struct.User.html
     #[inline]
-    pub fn to_user(self) -> Result<User> {
-        #[cfg(feature = "cache")]
-        {
-            if let Some(user) = CACHE.read().user(self) {
+    pub fn to_user(self, cache: &CacheSource) -> Result<User> {
+        feature_cache! {{
+            if let Some(user) = cache.cache().and_then(|cache| cache.read().user(self)) {
                 return Ok(user.read().clone());
             }
-        }
+        } else {
+            let _ = cache;
+        }}
 
         http::get_user(self.0)
     }
@@ -9,13 +9,13 @@ use std::{
 };
 use super::prelude::*;
 
-#[cfg(feature = "cache")]
+#[cfg(any(feature = "cache", feature = "model"))]
 use internal::prelude::*;
 
-#[cfg(all(feature = "cache", feature = "model"))]
+#[cfg(feature = "model")]
 use super::permissions::Permissions;
-#[cfg(all(feature = "cache", feature = "model"))]
-use CACHE;
+#[cfg(feature = "model")]
+use super::CacheSource;
 
 pub fn default_true() -> bool {
     true
@@ -206,9 +206,30 @@ pub fn serialize_gen_locked_map<K: Eq + Hash, S: Serializer, V: Serialize>(
     seq.end()
 }
 
+/// Retrieves the Id of the current user from the given cache source, if it
+/// has a cache.
+#[cfg(all(feature = "cache", feature = "model"))]
+pub fn current_user_id(source: &CacheSource) -> Option<UserId> {
+    source.cache().map(|cache| cache.read().user.id)
+}
+
+#[cfg(all(not(feature = "cache"), feature = "model"))]
+pub fn current_user_id(_: &CacheSource) -> Option<UserId> { None }
+
+/// Checks whether the current user has the given permissions in a channel,
+/// according to the given cache source.
+///
+/// The permissions are assumed to be granted if the source has no cache.
 #[cfg(all(feature = "cache", feature = "model"))]
-pub fn user_has_perms(channel_id: ChannelId, mut permissions: Permissions) -> Result<bool> {
-    let cache = CACHE.read();
+pub fn user_has_perms(
+    source: &CacheSource,
+    channel_id: ChannelId,
+    mut permissions: Permissions,
+) -> Result<bool> {
+    let cache = match source.cache() {
+        Some(cache) => cache.read(),
+        None => return Ok(true),
+    };
     let current_user = &cache.user;
 
     let channel = match cache.channel(channel_id) {
@@ -247,6 +268,9 @@ pub fn user_has_perms(channel_id: ChannelId, mut permissions: Permissions) -> Re
     Ok(permissions.is_empty())
 }
 
+#[cfg(all(not(feature = "cache"), feature = "model"))]
+pub fn user_has_perms(_: &CacheSource, _: ChannelId, _: Permissions) -> Result<bool> { Ok(true) }
+
 macro_rules! num_visitors {
     ($($visitor:ident: $type:ty),*) => {
         $(
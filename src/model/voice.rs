@@ -31,8 +31,148 @@ pub struct VoiceState {
     pub mute: bool,
     pub self_deaf: bool,
     pub self_mute: bool,
+    /// Whether the user is streaming using "Go Live".
+    #[serde(default)]
+    pub self_stream: bool,
     pub session_id: String,
     pub suppress: bool,
     pub token: Option<String>,
     pub user_id: UserId,
 }
+
+/// A change of one of the flags of a user's [`VoiceState`], along with its
+/// new value.
+///
+/// [`VoiceState`]: struct.VoiceState.html
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum VoiceStateChange {
+    /// The user was deafened or undeafened by the guild.
+    Deaf(bool),
+    /// The user was muted or unmuted by the guild.
+    Mute(bool),
+    /// The user deafened or undeafened themselves.
+    SelfDeaf(bool),
+    /// The user muted or unmuted themselves.
+    SelfMute(bool),
+    /// The user started or stopped streaming.
+    SelfStream(bool),
+    /// The user was suppressed or unsuppressed.
+    Suppress(bool),
+}
+
+/// A transition between two [`VoiceState`]s of a user.
+///
+/// [`VoiceState`]: struct.VoiceState.html
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum VoiceTransition {
+    /// The user joined a voice channel.
+    Join(ChannelId),
+    /// The user left a voice channel.
+    Leave(ChannelId),
+    /// The user moved from one voice channel to another.
+    Move {
+        from: ChannelId,
+        to: ChannelId,
+    },
+    /// A flag of the user's state changed while they stayed connected.
+    Change(VoiceStateChange),
+}
+
+impl VoiceTransition {
+    /// Computes the transitions from the previous state of a user to their
+    /// new state.
+    ///
+    /// A user without a previous state is considered to not have been
+    /// connected. Changes of flags are only computed while the user stays
+    /// connected, as the flags of a user who joins or leaves are part of
+    /// their state.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # extern crate serde_json;
+    /// # extern crate serenity;
+    /// #
+    /// # use serenity::model::voice::VoiceState;
+    /// #
+    /// # fn main() {
+    /// # let mut old: VoiceState = serde_json::from_str(r#"{"channel_id":"1","deaf":false,"mute":false,"self_deaf":false,"self_mute":false,"session_id":"","suppress":false,"user_id":"3"}"#).unwrap();
+    /// use serenity::model::id::ChannelId;
+    /// use serenity::model::voice::{VoiceStateChange, VoiceTransition};
+    ///
+    /// let mut new = old.clone();
+    /// new.channel_id = Some(ChannelId(2));
+    /// new.self_mute = true;
+    ///
+    /// assert_eq!(VoiceTransition::between(Some(&old), &new), vec![
+    ///     VoiceTransition::Move { from: ChannelId(1), to: ChannelId(2) },
+    ///     VoiceTransition::Change(VoiceStateChange::SelfMute(true)),
+    /// ]);
+    /// # }
+    /// ```
+    pub fn between(old: Option<&VoiceState>, new: &VoiceState) -> Vec<Self> {
+        let (old, from) = match old.and_then(|old| old.channel_id.map(|id| (old, id))) {
+            Some(connected) => connected,
+            None => return new.channel_id.map(VoiceTransition::Join).into_iter().collect(),
+        };
+
+        let to = match new.channel_id {
+            Some(to) => to,
+            None => return vec![VoiceTransition::Leave(from)],
+        };
+
+        let mut transitions = Vec::new();
+
+        if from != to {
+            transitions.push(VoiceTransition::Move { from, to });
+        }
+
+        let changes = [
+            (old.deaf, new.deaf, VoiceStateChange::Deaf as fn(bool) -> VoiceStateChange),
+            (old.mute, new.mute, VoiceStateChange::Mute),
+            (old.self_deaf, new.self_deaf, VoiceStateChange::SelfDeaf),
+            (old.self_mute, new.self_mute, VoiceStateChange::SelfMute),
+            (old.self_stream, new.self_stream, VoiceStateChange::SelfStream),
+            (old.suppress, new.suppress, VoiceStateChange::Suppress),
+        ];
+
+        for &(old, new, change) in &changes {
+            if old != new {
+                transitions.push(VoiceTransition::Change(change(new)));
+            }
+        }
+
+        transitions
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use model::id::ChannelId;
+    use super::{VoiceState, VoiceStateChange, VoiceTransition};
+
+    fn state(channel_id: Option<u64>) -> VoiceState {
+        let mut state: VoiceState = fixture!("voice_state_update_1");
+        state.channel_id = channel_id.map(ChannelId);
+
+        state
+    }
+
+    #[test]
+    fn test_voice_transitions() {
+        let connected = state(Some(1));
+        let mut deafened = connected.clone();
+        deafened.self_deaf = true;
+        deafened.self_stream = true;
+
+        assert_eq!(VoiceTransition::between(None, &connected), vec![VoiceTransition::Join(ChannelId(1))]);
+        assert_eq!(VoiceTransition::between(Some(&state(None)), &deafened), vec![VoiceTransition::Join(ChannelId(1))]);
+        assert_eq!(VoiceTransition::between(Some(&deafened), &state(None)), vec![VoiceTransition::Leave(ChannelId(1))]);
+        assert!(VoiceTransition::between(None, &state(None)).is_empty());
+        assert!(VoiceTransition::between(Some(&connected), &connected).is_empty());
+        assert_eq!(VoiceTransition::between(Some(&connected), &deafened), vec![
+            VoiceTransition::Change(VoiceStateChange::SelfDeaf(true)),
+            VoiceTransition::Change(VoiceStateChange::SelfStream(true)),
+        ]);
+    }
+}
@@ -5,12 +5,18 @@ mod colour;
 mod message_builder;
 mod vec_map;
 
+#[cfg(all(feature = "client", feature = "model"))]
+mod paginator;
+
 pub use self::{
     colour::Colour,
     message_builder::{Content, ContentModifier, MessageBuilder},
     vec_map::VecMap
 };
 
+#[cfg(all(feature = "client", feature = "model"))]
+pub use self::paginator::{Page, Paginator};
+
 use base64;
 use internal::prelude::*;
 use prelude::RwLock;
@@ -500,6 +506,9 @@ pub fn shard_id(guild_id: u64, shard_count: u64) -> u64 { (guild_id >> 22) % sha
 /// This is particularly useful if you just want to use the cache for this one time,
 /// or don't want to be messing with the `RwLock` directly.
 ///
+/// **Note**: This operates on the global [`CACHE`], which a [`Client`] only
+/// populates when created via [`Client::new_with_cache`].
+///
 /// # Examples
 ///
 /// Return the bot's id
@@ -510,7 +519,12 @@ pub fn shard_id(guild_id: u64, shard_count: u64) -> u64 { (guild_id >> 22) % sha
 /// // assuming that the id is `1234`:
 /// assert_eq!(1234, utils::with_cache(|cache| cache.user.id));
 /// ```
+///
+/// [`CACHE`]: ../struct.CACHE.html
+/// [`Client`]: ../client/struct.Client.html
+/// [`Client::new_with_cache`]: ../client/struct.Client.html#method.new_with_cache
 #[cfg(feature = "cache")]
+#[deprecated(since = "0.5.15", note = "Read the client's cache, such as `Context::cache`, instead.")]
 pub fn with_cache<T, F>(f: F) -> T
     where F: Fn(&Cache) -> T {
     let cache = CACHE.read();
@@ -519,6 +533,9 @@ pub fn with_cache<T, F>(f: F) -> T
 
 /// Like [`with_cache`] but as the name says, allows for modifications to be done.
 ///
+/// **Note**: This operates on the global [`CACHE`], which a [`Client`] only
+/// populates when created via [`Client::new_with_cache`].
+///
 /// # Examples
 ///
 /// Return the bot's id, and changes the shard count
@@ -530,8 +547,12 @@ pub fn with_cache<T, F>(f: F) -> T
 /// assert_eq!(1234, utils::with_cache_mut(|cache| { cache.shard_count = 8; cache.user.id }));
 /// ```
 ///
+/// [`CACHE`]: ../struct.CACHE.html
+/// [`Client`]: ../client/struct.Client.html
+/// [`Client::new_with_cache`]: ../client/struct.Client.html#method.new_with_cache
 /// [`with_cache`]: #fn.with_cache
 #[cfg(feature = "cache")]
+#[deprecated(since = "0.5.15", note = "Write to the client's cache, such as `Context::cache`, instead.")]
 pub fn with_cache_mut<T, F>(mut f: F) -> T
     where F: FnMut(&mut Cache) -> T {
     let mut cache = CACHE.write();
@@ -666,7 +687,7 @@ fn clean_roles(cache: &RwLock<Cache>, s: &mut String) {
             if let Ok(id) = RoleId::from_str(&s[mention_start..mention_end]) {
                 let to_replace = format!("<@&{}>", &s[mention_start..mention_end]);
 
-                *s = if let Some(role) = id._to_role_cached(&cache) {
+                *s = if let Some(role) = id.to_role_cached(&cache) {
                     s.replace(&to_replace, &format!("@{}", &role.name))
                 } else {
                     s.replace(&to_replace, &"@deleted-role")
@@ -704,7 +725,7 @@ fn clean_channels(cache: &RwLock<Cache>, s: &mut String) {
             if let Ok(id) = ChannelId::from_str(&s[mention_start..mention_end]) {
                 let to_replace = format!("<#{}>", &s[mention_start..mention_end]);
 
-                *s = if let Some(Channel::Guild(channel)) = id._to_channel_cached(&cache) {
+                *s = if let Some(Channel::Guild(channel)) = id.to_channel_cached(&cache) {
                     let replacement = format!("#{}", &channel.read().name);
                     s.replace(&to_replace, &replacement)
                 } else {
@@ -803,7 +824,7 @@ fn clean_users(cache: &RwLock<Cache>, s: &mut String, show_discriminator: bool,
 }
 
 /// Transforms role, channel, user, `@everyone` and `@here` mentions
-/// into raw text by using the [`Cache`] only.
+/// into raw text by using the given [`Cache`] only.
 ///
 /// [`ContentSafeOptions`] decides what kind of mentions should be filtered
 /// and how the raw-text will be displayed.
@@ -813,28 +834,21 @@ fn clean_users(cache: &RwLock<Cache>, s: &mut String, show_discriminator: bool,
 /// Sanitise an `@everyone` mention.
 ///
 /// ```rust
+/// use serenity::CACHE;
 /// use serenity::utils::{
 ///     content_safe,
 ///     ContentSafeOptions,
 /// };
 ///
 /// let with_mention = "@everyone";
-/// let without_mention = content_safe(&with_mention, &ContentSafeOptions::default());
+/// let without_mention = content_safe(&CACHE, &with_mention, &ContentSafeOptions::default());
 ///
 /// assert_eq!("@\u{200B}everyone".to_string(), without_mention);
 /// ```
 /// [`ContentSafeOptions`]: struct.ContentSafeOptions.html
 /// [`Cache`]: ../cache/struct.Cache.html
 #[cfg(feature = "cache")]
-pub fn content_safe(s: &str, options: &ContentSafeOptions) -> String {
-    let cache = &CACHE;
-
-    _content_safe(&cache, s, options)
-}
-
-
-#[cfg(feature = "cache")]
-fn _content_safe(cache: &RwLock<Cache>, s: &str, options: &ContentSafeOptions) -> String {
+pub fn content_safe(cache: &RwLock<Cache>, s: &str, options: &ContentSafeOptions) -> String {
     let mut s = s.to_string();
 
     if options.clean_role {
@@ -1024,31 +1038,31 @@ mod test {
 
         // User mentions
         let options = ContentSafeOptions::default();
-        assert_eq!(without_user_mentions, _content_safe(&cache, with_user_metions, &options));
+        assert_eq!(without_user_mentions, content_safe(&cache, with_user_metions, &options));
 
         let options = ContentSafeOptions::default();
         assert_eq!(format!("@{}#{:04}", user.name, user.discriminator),
-            _content_safe(&cache, "<@!100000000000000000>", &options));
+            content_safe(&cache, "<@!100000000000000000>", &options));
 
         let options = ContentSafeOptions::default();
         assert_eq!(format!("@{}#{:04}", user.name, user.discriminator),
-            _content_safe(&cache, "<@100000000000000000>", &options));
+            content_safe(&cache, "<@100000000000000000>", &options));
 
         let options = options.show_discriminator(false);
         assert_eq!(format!("@{}", user.name),
-            _content_safe(&cache, "<@!100000000000000000>", &options));
+            content_safe(&cache, "<@!100000000000000000>", &options));
 
         let options = options.show_discriminator(false);
         assert_eq!(format!("@{}", user.name),
-            _content_safe(&cache, "<@100000000000000000>", &options));
+            content_safe(&cache, "<@100000000000000000>", &options));
 
         let options = options.display_as_member_from(guild.id);
         assert_eq!(format!("@{}", member.nick.unwrap()),
-            _content_safe(&cache, "<@!100000000000000000>", &options));
+            content_safe(&cache, "<@!100000000000000000>", &options));
 
         let options = options.clean_user(false);
         assert_eq!(with_user_metions,
-            _content_safe(&cache, with_user_metions, &options));
+            content_safe(&cache, with_user_metions, &options));
 
         // Channel mentions
         let with_channel_mentions = "<#> <#deleted-channel> #deleted-channel <#0> \
@@ -1060,11 +1074,11 @@ mod test {
         #deleted-channel";
 
         assert_eq!(without_channel_mentions,
-            _content_safe(&cache, with_channel_mentions, &options));
+            content_safe(&cache, with_channel_mentions, &options));
 
         let options = options.clean_channel(false);
         assert_eq!(with_channel_mentions,
-            _content_safe(&cache, with_channel_mentions, &options));
+            content_safe(&cache, with_channel_mentions, &options));
 
         // Role mentions
         let with_role_mentions = "<@&> @deleted-role <@&9829> \
@@ -1074,11 +1088,11 @@ mod test {
         @ferris-club-member @deleted-role";
 
         assert_eq!(without_role_mentions,
-            _content_safe(&cache, with_role_mentions, &options));
+            content_safe(&cache, with_role_mentions, &options));
 
         let options = options.clean_role(false);
         assert_eq!(with_role_mentions,
-            _content_safe(&cache, with_role_mentions, &options));
+            content_safe(&cache, with_role_mentions, &options));
 
         // Everyone mentions
         let with_everyone_mention = "@everyone";
@@ -1086,11 +1100,11 @@ mod test {
         let without_everyone_mention = "@\u{200B}everyone";
 
         assert_eq!(without_everyone_mention,
-            _content_safe(&cache, with_everyone_mention, &options));
+            content_safe(&cache, with_everyone_mention, &options));
 
         let options = options.clean_everyone(false);
         assert_eq!(with_everyone_mention,
-            _content_safe(&cache, with_everyone_mention, &options));
+            content_safe(&cache, with_everyone_mention, &options));
 
         // Here mentions
         let with_here_mention = "@here";
@@ -1098,10 +1112,10 @@ mod test {
         let without_here_mention = "@\u{200B}here";
 
         assert_eq!(without_here_mention,
-            _content_safe(&cache, with_here_mention, &options));
+            content_safe(&cache, with_here_mention, &options));
 
         let options = options.clean_here(false);
         assert_eq!(with_here_mention,
-            _content_safe(&cache, with_here_mention, &options));
+            content_safe(&cache, with_here_mention, &options));
     }
 }
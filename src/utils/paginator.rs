@@ -0,0 +1,268 @@
+use builder::{CreateEmbed, CreateMessage, EditMessage};
+use client::Context;
+use internal::prelude::*;
+use model::{
+    channel::ReactionType,
+    id::{ChannelId, UserId},
+};
+use std::{
+    cmp,
+    fmt::{Debug, Display, Formatter, Result as FmtResult},
+    time::Duration,
+};
+
+const CLOSE: &str = "\u{274c}";
+const NEXT: &str = "\u{27a1}\u{fe0f}";
+const PREVIOUS: &str = "\u{2b05}\u{fe0f}";
+
+/// A page shown by a [`Paginator`].
+///
+/// [`Paginator`]: struct.Paginator.html
+pub enum Page {
+    /// A page shown as the content of the message.
+    Content(String),
+    /// A page shown as the embed of the message, built by the function.
+    Embed(Box<Fn(CreateEmbed) -> CreateEmbed + Send + Sync>),
+}
+
+impl Page {
+    fn create(&self, m: CreateMessage) -> CreateMessage {
+        match *self {
+            Page::Content(ref content) => m.content(content),
+            Page::Embed(ref f) => m.embed(&**f),
+        }
+    }
+
+    fn edit(&self, m: EditMessage) -> EditMessage {
+        match *self {
+            Page::Content(ref content) => {
+                let mut m = m.content(content);
+                m.0.insert("embed", Value::Null);
+
+                m
+            },
+            Page::Embed(ref f) => m.content("").embed(|e| f(e)),
+        }
+    }
+}
+
+impl Debug for Page {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match *self {
+            Page::Content(ref content) => f.debug_tuple("Content").field(content).finish(),
+            Page::Embed(_) => f.write_str("Embed"),
+        }
+    }
+}
+
+/// A message showing one of multiple pages at a time, which a user flips
+/// through by reacting to it.
+///
+/// The first page is sent along with reactions to show the previous page
+/// (⬅️), the next page (➡️), and to close the message (❌), which deletes it.
+/// Only the reactions of the given user are followed. Their reactions are
+/// removed again if the current user has permission to, so that the same
+/// reaction can be used repeatedly.
+///
+/// The paginator stops once no reaction was added within its [`timeout`],
+/// removing all reactions from the message.
+///
+/// Pages are flipped through [`Collectors`], so the thread showing the
+/// paginator is blocked until it stops. When called from an event handler,
+/// this occupies one of the client's threads for that time.
+///
+/// # Examples
+///
+/// Show a help message spanning two pages:
+///
+/// ```rust,no_run
+/// # use serenity::prelude::*;
+/// # use serenity::model::channel::Message;
+/// #
+/// use serenity::utils::Paginator;
+/// use std::time::Duration;
+///
+/// struct Handler;
+///
+/// impl EventHandler for Handler {
+///     fn message(&self, ctx: Context, msg: Message) {
+///         if msg.content != "!help" {
+///             return;
+///         }
+///
+///         let paginator = Paginator::new()
+///             .page("`!help`: Shows this message.")
+///             .embed_page(|e| e.title("Moderation").description("`!ban`: Bans a user."))
+///             .timeout(Duration::from_secs(60));
+///
+///         if let Err(why) = paginator.send(&ctx, msg.channel_id, msg.author.id) {
+///             println!("Error showing help: {:?}", why);
+///         }
+///     }
+/// }
+///
+/// let mut client = Client::new("token", Handler).unwrap();
+///
+/// client.start().unwrap();
+/// ```
+///
+/// [`Collectors`]: ../client/struct.Collectors.html
+/// [`timeout`]: #method.timeout
+pub struct Paginator {
+    pages: Vec<Page>,
+    timeout: Duration,
+}
+
+impl Paginator {
+    /// Creates a paginator without pages, which stops after two minutes
+    /// without a reaction.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a page shown as the content of the message.
+    pub fn page<D: Display>(mut self, content: D) -> Self {
+        self.pages.push(Page::Content(content.to_string()));
+
+        self
+    }
+
+    /// Adds a page shown as the embed of the message.
+    pub fn embed_page<F>(mut self, f: F) -> Self
+        where F: Fn(CreateEmbed) -> CreateEmbed + Send + Sync + 'static {
+        self.pages.push(Page::Embed(Box::new(f)));
+
+        self
+    }
+
+    /// Sets the duration without a reaction after which the paginator stops.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+
+        self
+    }
+
+    /// The number of pages.
+    pub fn len(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Whether there are no pages.
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+
+    /// Sends the first page to a channel, and flips through the pages
+    /// following the reactions of a user, blocking until the message is
+    /// closed or the timeout elapsed.
+    ///
+    /// Nothing is sent if there are no pages.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message could not be sent, reacted to, or
+    /// edited. Failing to remove reactions is ignored.
+    pub fn send(&self, ctx: &Context, channel_id: ChannelId, user_id: UserId) -> Result<()> {
+        let first = match self.pages.first() {
+            Some(first) => first,
+            None => return Ok(()),
+        };
+
+        let mut message = channel_id.send_message(|m| first.create(m))?;
+
+        // Subscribe before reacting, so that no reactions are missed.
+        let reactions = ctx.collectors
+            .reaction()
+            .message_id(message.id)
+            .user_id(user_id)
+            .subscribe();
+
+        if self.pages.len() > 1 {
+            message.react(ctx, PREVIOUS)?;
+            message.react(ctx, NEXT)?;
+        }
+
+        message.react(ctx, CLOSE)?;
+
+        let mut index = 0;
+
+        while let Some(reaction) = reactions.recv_timeout(self.timeout) {
+            let action = match reaction.emoji {
+                ReactionType::Unicode(ref name) => navigate(index, self.pages.len(), name),
+                _ => None,
+            };
+
+            match action {
+                Some(Action::Close) => return message.delete(ctx),
+                Some(Action::Show(page)) => {
+                    let _ = reaction.delete(ctx);
+
+                    if page != index {
+                        index = page;
+                        message.edit(ctx, |m| self.pages[index].edit(m))?;
+                    }
+                },
+                None => {},
+            }
+        }
+
+        let _ = message.delete_reactions(ctx);
+
+        Ok(())
+    }
+}
+
+impl Default for Paginator {
+    fn default() -> Self {
+        Paginator {
+            pages: Vec::new(),
+            timeout: Duration::from_secs(120),
+        }
+    }
+}
+
+impl Debug for Paginator {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_struct("Paginator")
+            .field("pages", &self.pages)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Action {
+    Close,
+    Show(usize),
+}
+
+// Determines the action of a reaction while showing a page, ignoring the
+// variation selectors which clients may or may not add to the emojis.
+fn navigate(index: usize, len: usize, emoji: &str) -> Option<Action> {
+    let emoji = emoji.trim_end_matches('\u{fe0f}');
+
+    if emoji == CLOSE {
+        Some(Action::Close)
+    } else if emoji == PREVIOUS.trim_end_matches('\u{fe0f}') {
+        Some(Action::Show(index.saturating_sub(1)))
+    } else if emoji == NEXT.trim_end_matches('\u{fe0f}') {
+        Some(Action::Show(cmp::min(index + 1, len - 1)))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{navigate, Action};
+
+    #[test]
+    fn test_navigate() {
+        assert_eq!(navigate(0, 3, "\u{27a1}\u{fe0f}"), Some(Action::Show(1)));
+        assert_eq!(navigate(2, 3, "\u{27a1}"), Some(Action::Show(2)));
+        assert_eq!(navigate(1, 3, "\u{2b05}\u{fe0f}"), Some(Action::Show(0)));
+        assert_eq!(navigate(0, 3, "\u{2b05}"), Some(Action::Show(0)));
+        assert_eq!(navigate(1, 3, "\u{274c}"), Some(Action::Close));
+        assert_eq!(navigate(1, 3, "\u{1f44d}"), None);
+    }
+}
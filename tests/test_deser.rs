@@ -159,6 +159,21 @@ fn ready() {
     p!(ReadyEvent, "ready_1");
 }
 
+// A presence with multiple rich activities and no game.
+#[test]
+fn presence_update_rich() {
+    let event = p!(PresenceUpdateEvent, "presence_update_rich_1");
+
+    assert_eq!(event.presence.activities.len(), 2);
+
+    let game = event.presence.game.unwrap();
+    assert_eq!(game.name, "Heroes of the Storm");
+    assert_eq!(game.details.as_ref().map(|s| &s[..]), Some("Ranked match"));
+    assert_eq!(game.party.unwrap().size, Some([2, 5]));
+    assert_eq!(game.flags, Some(ActivityFlags::INSTANCE | ActivityFlags::JOIN));
+    assert_eq!(game.timestamps.unwrap().start, Some(1_532_000_000_000));
+}
+
 #[test]
 fn typing_start() {
     p!(TypingStartEvent, "typing_start_1");